@@ -195,28 +195,30 @@ class AIAssistant:
                 "context": context
             }
 
-    @handle_errors(error_types=ProcessingError)
-    def trace_code_flow(self, entry_point: str, repo_id: int) -> list:
+    @handle_async_errors(error_types=ProcessingError)
+    async def trace_code_flow(self, entry_point: str, repo_id: int, max_depth: int = 5) -> list:
         """
         Traces the code flow starting from a given entry point.
 
         Args:
-            entry_point: The start file path for code flow analysis.
+            entry_point: A file path, or the (qualified) name of a function or
+                method, optionally as path:name.
             repo_id: Repository identifier.
+            max_depth: How many calls deep to follow.
 
         Returns:
-            A list of code paths and their relationships.
+            One call tree per matching entry point (GraphAnalysis.trace_code_flow [4.3.3]).
         """
         try:
-            return self.graph_analysis.trace_code_flow(entry_point, repo_id)
+            return await self.graph_analysis.trace_code_flow(entry_point, repo_id, max_depth=max_depth)
         except (ValueError, KeyError) as e:
             # Handle expected errors
-            log(f"Error tracing code flow from {entry_point}: {e}", level="error")
+            await log(f"Error tracing code flow from {entry_point}: {e}", level="error")
             return []
         except Exception as e:
             # Handle unexpected errors
             import traceback
-            log(f"Unexpected error tracing code flow from {entry_point}: {e}\n{traceback.format_exc()}", level="error")
+            await log(f"Unexpected error tracing code flow from {entry_point}: {e}\n{traceback.format_exc()}", level="error")
             raise ProcessingError(f"Failed to trace code flow: {e}")
 
     @handle_async_errors(error_types=ProcessingError)
@@ -225,7 +227,7 @@ class AIAssistant:
         # Import locally to avoid circular dependencies
         from semantic.search import search_code
         try:
-            return await search_code(query, repo_id, limit=limit)
+            return await search_code(query, repo_id=repo_id, limit=limit)
         except ImportError as e:
             log(f"Search module not available: {e}", level="error")
             return []
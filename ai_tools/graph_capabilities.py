@@ -5,8 +5,11 @@ Flow:
    - Code metrics calculation
    - Structure analysis
    - Similarity detection
+   - Call trees from an entry point
 
 2. Integration Points:
+   - StorageBackend [6.9]: Dependency, reference and call traversal
+   - Symbol Table [1.6]: Symbol nodes with REFERENCES and CALLS edges
    - Neo4jTools [6.2]: Graph operations
    - GraphSync [6.3]: Graph projections
    - GDS Library: Graph algorithms
@@ -16,7 +19,8 @@ Flow:
    - DatabaseError: Graph operations
 """
 
-from typing import Dict, List, Optional, Any, Set
+from typing import Dict, List, Optional, Any, Set, Tuple
+import os
 import asyncio
 from db.neo4j_ops import run_query, Neo4jTools, get_neo4j_tools
 from utils.logger import log, log_sync
@@ -38,6 +42,7 @@ from parsers.models import (
 )
 from config import Neo4jConfig
 from db.graph_sync import get_graph_sync
+from db.storage import get_storage_backend
 from utils.shutdown import register_shutdown_handler
 
 class GraphAnalysis:
@@ -237,19 +242,128 @@ class GraphAnalysis:
     
     @handle_async_errors(error_types=(ProcessingError, DatabaseError))
     async def get_references(self, repo_id: int, file_path: str) -> list:
-        """Retrieve code references for a given file via Neo4j."""
+        """Retrieve the files a given file references, with the symbols it uses from each.
+
+        Symbol REFERENCES edges [1.6] from the file and its definitions are
+        followed; RELATED_TO links between Code nodes are kept as well.
+        """
         async with AsyncErrorBoundary("reference retrieval", severity=ErrorSeverity.ERROR):
-            query = """
-            MATCH (n:Code {repo_id: $repo_id, file_path: $file_path})-[:RELATED_TO]->(m:Code)
-            RETURN m.file_path as file_path
-            """
-            future = asyncio.create_task(run_query(query, {"repo_id": repo_id, "file_path": file_path}))
-            self._pending_tasks.add(future)
+            backend = await get_storage_backend()
+            starts = [("Code", {"repo_id": repo_id, "file_path": file_path})] + [
+                ("Symbol", {"repo_id": repo_id, "id": symbol["id"]})
+                for symbol in await backend.find_nodes("Symbol", {"repo_id": repo_id, "file_path": file_path})
+            ]
+            task = asyncio.gather(*(
+                backend.traverse(label, match, ["REFERENCES", "RELATED_TO"], direction="out", max_depth=1)
+                for label, match in starts
+            ))
+            self._pending_tasks.add(task)
             try:
-                results = await future
-                return results if results else []
+                results = await task
             finally:
-                self._pending_tasks.remove(future)
+                self._pending_tasks.remove(task)
+
+            references: Dict[str, Dict[str, Any]] = {}
+            for edges in results:
+                for edge in edges:
+                    target = edge["target"].get("file_path")
+                    if not target or target == file_path:
+                        continue
+                    entry = references.setdefault(target, {"file_path": target, "symbols": []})
+                    name = edge["target"].get("qualified_name")
+                    if name and name not in entry["symbols"]:
+                        entry["symbols"].append(name)
+            return sorted(references.values(), key=lambda entry: entry["file_path"])
+
+    @handle_async_errors(error_types=(ProcessingError, DatabaseError), default_return=[])
+    async def trace_code_flow(
+        self,
+        entry_point: str,
+        repo_id: int,
+        max_depth: int = 5,
+        max_nodes: int = 500
+    ) -> List[Dict[str, Any]]:
+        """[4.3.3] Call trees rooted at an entry point, following CALLS edges [1.6].
+
+        entry_point is a file path (the calls its module-level code makes), a
+        qualified name (Parser.parse or Parser::parse), a plain name, or
+        path:name for the definition in one file; every matching definition
+        gets a tree. Each node lists its calls in line order. A call back
+        into a symbol on the current path is marked cycle, a symbol already
+        expanded elsewhere in the tree repeated, and a node whose calls lie
+        below max_depth or beyond max_nodes truncated. Calls that did not
+        resolve to a definition in the repository are unresolved leaves.
+        """
+        async with AsyncErrorBoundary("code flow tracing", severity=ErrorSeverity.ERROR):
+            backend = await get_storage_backend()
+            roots = await self._flow_entries(backend, repo_id, entry_point)
+            trees = []
+            for label, props in roots:
+                trees.append(await self._flow_tree(backend, repo_id, label, props, [], set(), max_depth, [max_nodes]))
+            return trees
+
+    async def _flow_entries(self, backend, repo_id: int, entry_point: str) -> List[Tuple[str, Dict[str, Any]]]:
+        """The Code or Symbol nodes an entry point names."""
+        path = os.path.abspath(entry_point)
+        code = await backend.find_nodes("Code", {"repo_id": repo_id, "file_path": path}, limit=1)
+        if code:
+            return [("Code", code[0])]
+
+        file_path, name = None, entry_point
+        head, sep, tail = entry_point.rpartition(":")
+        if sep and head and not head.endswith(":") and tail:
+            file_path, name = os.path.abspath(head), tail
+        name = name.replace("::", ".")
+        match = {"repo_id": repo_id, ("qualified_name" if "." in name else "name"): name}
+        if file_path:
+            match["file_path"] = file_path
+        symbols = await backend.find_nodes("Symbol", match)
+        return [("Symbol", symbol) for symbol in sorted(symbols, key=lambda s: (s.get("file_path", ""), s.get("line", 0)))]
+
+    async def _flow_tree(
+        self,
+        backend,
+        repo_id: int,
+        label: str,
+        props: Dict[str, Any],
+        path: List[str],
+        expanded: Set[str],
+        depth_left: int,
+        budget: List[int]
+    ) -> Dict[str, Any]:
+        key = props.get("id") or props.get("file_path")
+        node = {
+            "name": props.get("qualified_name") or props.get("file_path"),
+            "kind": props.get("kind", "file"),
+            "file_path": props.get("file_path"),
+            "line": props.get("line"),
+            "calls": []
+        }
+        if key in path:
+            node["cycle"] = True
+            return node
+        if key in expanded:
+            node["repeated"] = True
+            return node
+        expanded.add(key)
+        budget[0] -= 1
+
+        match = {"repo_id": repo_id, "id": key} if label == "Symbol" else {"repo_id": repo_id, "file_path": key}
+        edges = await backend.traverse(label, match, ["CALLS"], direction="out", max_depth=1)
+        unresolved = props.get("unresolved_calls") or []
+        if depth_left <= 0 or budget[0] <= 0:
+            if edges or unresolved:
+                node["truncated"] = True
+            return node
+
+        for edge in sorted(edges, key=lambda edge: (edge["properties"].get("lines") or [0])[0]):
+            child = await self._flow_tree(
+                backend, repo_id, "Symbol", edge["target"], path + [key], expanded, depth_left - 1, budget
+            )
+            child["lines"] = edge["properties"].get("lines", [])
+            node["calls"].append(child)
+        node["calls"].extend({"name": name, "unresolved": True} for name in unresolved)
+        return node
     
     @handle_async_errors(error_types=(ProcessingError, DatabaseError))
     async def analyze_code_patterns(self, repo_id: int) -> Dict[str, Any]:
@@ -310,23 +424,29 @@ class GraphAnalysis:
     
     @handle_async_errors(error_types=(ProcessingError, DatabaseError))
     async def get_dependencies(self, repo_id: int, file_path: str, depth: int = 3) -> list:
-        """Retrieve code dependencies up to a specified depth using variable-length relationships."""
+        """Retrieve code dependencies up to a specified depth."""
         async with AsyncErrorBoundary("dependency retrieval", severity=ErrorSeverity.ERROR):
-            # Ensure code projection exists
-            graph_sync = await get_graph_sync()
-            await graph_sync.ensure_projection(repo_id)
-            
-            query = """
-            MATCH (n:Code {repo_id: $repo_id, file_path: $file_path})-[:DEPENDS_ON*1..$depth]->(dep:Code)
-            RETURN dep.file_path as file_path
-            """
-            task = asyncio.create_task(run_query(query, {"repo_id": repo_id, "file_path": file_path, "depth": depth}))
+            backend = await get_storage_backend()
+            task = asyncio.create_task(backend.traverse(
+                "Code",
+                {"repo_id": repo_id, "file_path": file_path},
+                ["DEPENDS_ON", "IMPORTS"],
+                direction="out",
+                max_depth=depth
+            ))
             self._pending_tasks.add(task)
             try:
-                results = await task
-                return results if results else []
+                edges = await task
             finally:
                 self._pending_tasks.remove(task)
+            
+            # Report each dependency once, at the shallowest depth it was reached
+            dependencies = {}
+            for edge in edges:
+                dep_path = edge["target"].get("file_path")
+                if dep_path and dep_path != file_path and dep_path not in dependencies:
+                    dependencies[dep_path] = {"file_path": dep_path, "depth": edge["depth"]}
+            return list(dependencies.values())
     
     async def cleanup(self):
         """Clean up all resources."""
@@ -357,4 +477,11 @@ class GraphAnalysis:
             raise ProcessingError(f"Failed to cleanup graph analysis: {e}")
 
 # Do not create global instance until implementation is ready
-graph_analysis = None 
\ No newline at end of file
+graph_analysis = None
+
+async def get_graph_analysis() -> GraphAnalysis:
+    """Get the graph analysis instance, creating it on first use."""
+    global graph_analysis
+    if graph_analysis is None:
+        graph_analysis = await GraphAnalysis.create()
+    return graph_analysis 
\ No newline at end of file
@@ -13,9 +13,10 @@ Available Methods:
    - Finds similar code components using node2vec embeddings
    - Returns list of similar components with similarity scores
 
-3. trace_code_flow(entry_point: str, repo_id: int) -> List[Dict]
-   - Traces code flow from an entry point
-   - Returns list of code paths and relationships
+3. trace_code_flow(entry_point: str, repo_id: int, max_depth: int = 5) -> List[Dict]
+   - Follows CALLS edges from a file, or a function or method by name
+   - Returns one call tree per matching entry point; nodes are marked
+     cycle, repeated or truncated, and unresolved calls are leaves
 
 4. get_code_metrics(repo_id: int) -> Dict[str, Any]
    - Gets comprehensive code metrics
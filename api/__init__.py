@@ -0,0 +1,24 @@
+"""[8.0] Local API package.
+
+Flow:
+1. Components:
+   - ApiServer [8.1]: JSON over HTTP for editor plugins and dashboards
+   - LspServer [8.2]: Language Server Protocol over stdio for editors
+
+2. Integration Points:
+   - SearchEngine [5.0]: Code and doc search
+   - GraphAnalysis [4.3]: Dependencies and references
+   - PatternStorageCoordinator: Stored patterns
+   - UpsertCoordinator [6.5]: Repository listing and lookup
+   - TreeSitterParser: Incremental re-parsing of open buffers
+"""
+
+from .server import ApiServer, ApiError
+from .lsp import LspServer, LspError
+
+__all__ = [
+    'ApiServer',
+    'ApiError',
+    'LspServer',
+    'LspError'
+]
@@ -0,0 +1,998 @@
+"""[8.2] Language Server Protocol front end backed by the index.
+
+Flow:
+1. Transport:
+   - JSON-RPC 2.0 on stdin/stdout with Content-Length framing
+   - stdout carries protocol messages only; logs stay on stderr
+
+2. Requests:
+   - textDocument/definition   Definitions of the name under the cursor
+   - textDocument/references   Occurrences of that name across the workspace
+   - workspace/symbol          Definitions whose name contains the query
+   - textDocument/hover        Definition line, location and graph neighbours
+
+3. Symbol Sources:
+   - Tree-sitter languages: TreeSitterParser.parse_incremental; didChange
+     hands it the previous tree so only the edited region is re-parsed
+   - Custom languages (Cobalt, INI, AsciiDoc, EditorConfig): the regex
+     QueryPatterns from parsers/query_patterns that back parsers/custom_parsers
+   - Workspace files: code_snippets and repo_docs rows of the repository
+     named after the client's root folder
+   - Code nodes in the graph rank definitions (files the current file
+     depends on win) and add dependency counts to hovers
+"""
+
+import asyncio
+import importlib
+import json
+import os
+import re
+import sys
+from dataclasses import dataclass, field
+from enum import IntEnum
+from pathlib import Path
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Sequence, Set, Tuple
+from urllib.parse import unquote, urlsplit
+
+from config import FileConfig
+from utils.logger import log
+from utils.cli_output import json_default
+from utils.error_handling import AsyncErrorBoundary, ProcessingError, ErrorSeverity
+from utils.shutdown import register_shutdown_handler
+from utils.health_monitor import global_health_monitor, ComponentStatus
+from db.storage import get_storage_backend
+from db.upsert_ops import UpsertCoordinator
+
+file_config = FileConfig()
+
+# JSON-RPC error codes
+PARSE_ERROR = -32700
+INVALID_REQUEST = -32600
+METHOD_NOT_FOUND = -32601
+INVALID_PARAMS = -32602
+INTERNAL_ERROR = -32603
+SERVER_NOT_INITIALIZED = -32002
+
+# Upper bound on workspace/symbol results
+MAX_WORKSPACE_SYMBOLS = 500
+
+class SymbolKind(IntEnum):
+    """LSP SymbolKind values."""
+    FILE = 1
+    MODULE = 2
+    NAMESPACE = 3
+    PACKAGE = 4
+    CLASS = 5
+    METHOD = 6
+    PROPERTY = 7
+    FIELD = 8
+    CONSTRUCTOR = 9
+    ENUM = 10
+    INTERFACE = 11
+    FUNCTION = 12
+    VARIABLE = 13
+    CONSTANT = 14
+    KEY = 20
+    ENUM_MEMBER = 22
+    STRUCT = 23
+
+class MessageType(IntEnum):
+    """LSP MessageType values for window/logMessage."""
+    ERROR = 1
+    WARNING = 2
+    INFO = 3
+    LOG = 4
+
+# Extension to language id; tree-sitter names except for the custom languages
+LANGUAGE_EXTENSIONS: Dict[str, str] = {
+    ".py": "python",
+    ".pyi": "python",
+    ".js": "javascript",
+    ".jsx": "javascript",
+    ".mjs": "javascript",
+    ".cjs": "javascript",
+    ".ts": "typescript",
+    ".tsx": "tsx",
+    ".go": "go",
+    ".rs": "rust",
+    ".java": "java",
+    ".kt": "kotlin",
+    ".scala": "scala",
+    ".swift": "swift",
+    ".c": "c",
+    ".h": "c",
+    ".cc": "cpp",
+    ".cpp": "cpp",
+    ".hpp": "cpp",
+    ".cs": "csharp",
+    ".rb": "ruby",
+    ".php": "php",
+    ".lua": "lua",
+    ".sh": "bash",
+    ".co": "cobalt",
+    ".ini": "ini",
+    ".cfg": "ini",
+    ".adoc": "asciidoc",
+    ".asciidoc": "asciidoc",
+    ".asc": "asciidoc",
+    ".editorconfig": "editorconfig"
+}
+
+# Client languageId values that differ from ours
+_LANGUAGE_ALIASES = {
+    "typescriptreact": "tsx",
+    "javascriptreact": "javascript",
+    "shellscript": "bash"
+}
+
+# Languages read with parsers/query_patterns regexes instead of tree-sitter
+CUSTOM_LANGUAGES = ("asciidoc", "cobalt", "editorconfig", "ini")
+
+# Tree-sitter node types that introduce a name through their `name` field
+_DEFINITION_SUFFIXES = ("_definition", "_declaration", "_declarator", "_item", "_spec", "_specifier")
+_NOT_DEFINITIONS = ("parameter", "import", "export")
+
+# Substring of a node or pattern type -> symbol kind, first match wins
+_KIND_KEYWORDS: Tuple[Tuple[str, SymbolKind], ...] = (
+    ("method", SymbolKind.METHOD),
+    ("constructor", SymbolKind.CONSTRUCTOR),
+    ("function", SymbolKind.FUNCTION),
+    ("class", SymbolKind.CLASS),
+    ("interface", SymbolKind.INTERFACE),
+    ("trait", SymbolKind.INTERFACE),
+    ("struct", SymbolKind.STRUCT),
+    ("enum_variant", SymbolKind.ENUM_MEMBER),
+    ("enum", SymbolKind.ENUM),
+    ("module", SymbolKind.MODULE),
+    ("mod_", SymbolKind.MODULE),
+    ("namespace", SymbolKind.NAMESPACE),
+    ("package", SymbolKind.PACKAGE),
+    ("const", SymbolKind.CONSTANT),
+    ("field", SymbolKind.FIELD),
+    ("property", SymbolKind.PROPERTY),
+    ("attribute", SymbolKind.PROPERTY),
+    ("type", SymbolKind.CLASS),
+    ("header", SymbolKind.FILE),
+    ("section", SymbolKind.MODULE),
+    ("anchor", SymbolKind.KEY)
+)
+
+# Query pattern result types that name a definition
+_PATTERN_DEFINITIONS = {
+    "function", "class", "namespace", "type_definition", "enum",
+    "section", "header", "property", "attribute", "anchor"
+}
+
+# Words in plain text; dotted/dashed keys (db.host, toc-title) stay whole
+_WORD = re.compile(r"[\w$]+(?:[.\-][\w$]+)*")
+
+class LspError(Exception):
+    """Request error reported to the client as a JSON-RPC error."""
+
+    def __init__(self, code: int, message: str):
+        super().__init__(message)
+        self.code = code
+        self.message = message
+
+Handler = Callable[[Dict[str, Any]], Awaitable[Any]]
+
+# Positions --------------------------------------------------------------
+
+def _utf16_len(text: str) -> int:
+    """Length of text in UTF-16 code units, the unit of LSP character offsets."""
+    return len(text.encode("utf-16-le")) // 2
+
+def _offset_at(text: str, position: Dict[str, int]) -> int:
+    """String index of an LSP position, clamped to the text."""
+    start = 0
+    for _ in range(position["line"]):
+        newline = text.find("\n", start)
+        if newline < 0:
+            return len(text)
+        start = newline + 1
+    end = text.find("\n", start)
+    end = len(text) if end < 0 else end
+    units, index = 0, start
+    while index < end and units < position["character"]:
+        units += 2 if ord(text[index]) > 0xFFFF else 1
+        index += 1
+    return index
+
+def _span_range(text: str, start: int, end: int) -> Dict[str, Any]:
+    """LSP range of text[start:end]."""
+    def position(offset: int) -> Dict[str, int]:
+        line_start = text.rfind("\n", 0, offset) + 1
+        return {"line": text.count("\n", 0, offset), "character": _utf16_len(text[line_start:offset])}
+    return {"start": position(start), "end": position(end)}
+
+def _contains(rng: Dict[str, Any], position: Dict[str, int]) -> bool:
+    point = (position["line"], position["character"])
+    return (
+        (rng["start"]["line"], rng["start"]["character"]) <= point
+        <= (rng["end"]["line"], rng["end"]["character"])
+    )
+
+def _range_key(rng: Dict[str, Any]) -> Tuple[int, int, int, int]:
+    return (rng["start"]["line"], rng["start"]["character"], rng["end"]["line"], rng["end"]["character"])
+
+def _apply_change(text: str, change: Dict[str, Any]) -> str:
+    """Apply one didChange content change (full text or ranged edit)."""
+    if change.get("range") is None:
+        return change["text"]
+    start = _offset_at(text, change["range"]["start"])
+    end = _offset_at(text, change["range"]["end"])
+    return text[:start] + change["text"] + text[end:]
+
+def _word_at(text: str, position: Dict[str, int]) -> Optional[str]:
+    """The word touching position, if any."""
+    lines = text.split("\n")
+    if position["line"] >= len(lines):
+        return None
+    line = lines[position["line"]]
+    index = _offset_at(line, {"line": 0, "character": position["character"]})
+    for match in _WORD.finditer(line):
+        if match.start() <= index <= match.end():
+            return match.group(0)
+    return None
+
+# Paths and languages ----------------------------------------------------
+
+def uri_to_path(uri: Optional[str]) -> Optional[str]:
+    """Local path of a file:// URI."""
+    if not uri:
+        return None
+    parts = urlsplit(uri)
+    if parts.scheme != "file":
+        return None
+    return os.path.abspath(unquote(parts.path))
+
+def path_to_uri(path: str) -> str:
+    return Path(os.path.abspath(path)).as_uri()
+
+def language_for_path(path: str, language_id: Optional[str] = None) -> Optional[str]:
+    """Language of a file by extension, falling back to the client's languageId."""
+    name = os.path.basename(path).lower()
+    language = LANGUAGE_EXTENSIONS.get(name) or LANGUAGE_EXTENSIONS.get(os.path.splitext(name)[1])
+    if language or not language_id:
+        return language
+    language_id = language_id.lower()
+    return _LANGUAGE_ALIASES.get(language_id, language_id)
+
+def _read_text(path: str) -> Optional[str]:
+    """Read a workspace file, skipping missing and oversized ones."""
+    try:
+        if os.path.getsize(path) > file_config.max_file_size:
+            return None
+        with open(path, encoding="utf-8", errors="replace") as f:
+            return f.read()
+    except OSError:
+        return None
+
+# Symbols ----------------------------------------------------------------
+
+def _symbol_kind(type_name: str) -> SymbolKind:
+    for keyword, kind in _KIND_KEYWORDS:
+        if keyword in type_name:
+            return kind
+    return SymbolKind.VARIABLE
+
+def _first_line(text: str, limit: int = 120) -> str:
+    line = text.strip().split("\n", 1)[0].strip()
+    return line if len(line) <= limit else line[:limit - 3] + "..."
+
+def _is_identifier(node_type: str) -> bool:
+    return "identifier" in node_type or node_type in ("constant", "name")
+
+def _tree_symbols(tree: Any, text: str) -> Tuple[List[Dict[str, Any]], Dict[str, List[Dict[str, Any]]]]:
+    """Definitions and identifier occurrences of a tree-sitter tree."""
+    lines = text.encode("utf-8").split(b"\n")
+
+    def position(point: Tuple[int, int]) -> Dict[str, int]:
+        row, column = point
+        line = lines[row] if row < len(lines) else b""
+        return {"line": row, "character": _utf16_len(line[:column].decode("utf-8", "replace"))}
+
+    def node_range(node: Any) -> Dict[str, Any]:
+        return {"start": position(node.start_point), "end": position(node.end_point)}
+
+    symbols: List[Dict[str, Any]] = []
+    occurrences: Dict[str, List[Dict[str, Any]]] = {}
+    stack: List[Tuple[Any, Optional[str]]] = [(tree.root_node, None)]
+    while stack:
+        node, container = stack.pop()
+        if node.child_count == 0:
+            if node.is_named and _is_identifier(node.type):
+                name = node.text.decode("utf-8", "replace")
+                occurrences.setdefault(name, []).append(node_range(node))
+            continue
+
+        child_container = container
+        if (node.is_named
+                and node.type.endswith(_DEFINITION_SUFFIXES)
+                and not any(word in node.type for word in _NOT_DEFINITIONS)):
+            name_node = node.child_by_field_name("name")
+            if name_node is not None and name_node.child_count == 0:
+                name = name_node.text.decode("utf-8", "replace")
+                symbols.append({
+                    "name": name,
+                    "kind": _symbol_kind(node.type),
+                    "range": node_range(node),
+                    "selectionRange": node_range(name_node),
+                    "detail": _first_line(node.text.decode("utf-8", "replace")),
+                    "containerName": container
+                })
+                child_container = name
+        stack.extend((child, child_container) for child in reversed(node.children))
+    return symbols, occurrences
+
+_compiled_patterns: Dict[str, List[Tuple[re.Pattern, Optional[Callable]]]] = {}
+
+def _custom_patterns(language: str) -> List[Tuple[re.Pattern, Optional[Callable]]]:
+    """Compiled regexes and extractors from parsers/query_patterns/<language>.py."""
+    if language not in _compiled_patterns:
+        compiled = []
+        try:
+            module = importlib.import_module(f"parsers.query_patterns.{language}")
+            for patterns in getattr(module, f"{language.upper()}_PATTERNS", {}).values():
+                for pattern in patterns.values():
+                    try:
+                        compiled.append((
+                            re.compile(pattern.regex_pattern or pattern.pattern, re.MULTILINE),
+                            pattern.extract
+                        ))
+                    except re.error:
+                        continue
+        except ImportError:
+            pass
+        _compiled_patterns[language] = compiled
+    return _compiled_patterns[language]
+
+def _pattern_symbols(language: str, text: str) -> List[Dict[str, Any]]:
+    """Definitions found by a custom language's query patterns."""
+    symbols: List[Dict[str, Any]] = []
+    seen: Set[Tuple[str, int]] = set()
+    for regex, extract in _custom_patterns(language):
+        pos = 0
+        while pos <= len(text):
+            match = regex.search(text, pos)
+            if match is None:
+                break
+            pos = max(match.end(), match.start() + 1)
+            try:
+                info = extract(match) if extract else {}
+            except (IndexError, AttributeError, TypeError, ValueError):
+                continue
+            if info.get("type") not in _PATTERN_DEFINITIONS:
+                continue
+            name = info.get("name") or info.get("title") or info.get("key") or info.get("id")
+            if not isinstance(name, str) or not name.strip():
+                continue
+            name = name.strip()
+            if "\n" in name:
+                # Line-oriented patterns such as ^([^=]+)= can swallow the
+                # line above; retry from the next line instead
+                pos = text.index("\n", match.start()) + 1
+                continue
+            start = text.find(name, match.start(), match.end())
+            start = match.start() if start < 0 else start
+            if (name, start) in seen:
+                continue
+            seen.add((name, start))
+            symbols.append({
+                "name": name,
+                "kind": _symbol_kind(info["type"]),
+                "range": _span_range(text, match.start(), match.end()),
+                "selectionRange": _span_range(text, start, start + len(name)),
+                "detail": _first_line(match.group(0)),
+                "containerName": None
+            })
+
+    # Properties and attributes belong to the section above them
+    symbols.sort(key=lambda symbol: _range_key(symbol["range"]))
+    section = None
+    for symbol in symbols:
+        if symbol["kind"] in (SymbolKind.FILE, SymbolKind.MODULE):
+            section = symbol["name"]
+        else:
+            symbol["containerName"] = section
+    return symbols
+
+@dataclass
+class DocumentState:
+    """A workspace file or open editor buffer and the symbols found in it."""
+    uri: str
+    path: str
+    language: Optional[str]
+    text: str
+    version: Optional[int] = None
+    is_open: bool = False
+    tree: Any = None
+    symbols: List[Dict[str, Any]] = field(default_factory=list)
+    # Identifier name -> ranges for tree-sitter documents; None means the
+    # text is searched instead
+    occurrences: Optional[Dict[str, List[Dict[str, Any]]]] = None
+
+class LspServer:
+    """[8.2.1] Language server answering navigation requests from the index."""
+
+    def __init__(self, reader: Optional[asyncio.StreamReader], writer: Optional[asyncio.StreamWriter]):
+        """Private constructor - use create() instead."""
+        self._initialized = False
+        self._pending_tasks: Set[asyncio.Task] = set()
+        self._lock = asyncio.Lock()
+        self._reader = reader
+        self._writer = writer
+        self._upsert_coordinator = UpsertCoordinator()
+        self._root: Optional[str] = None
+        self._repo: Optional[Dict[str, Any]] = None
+        self._client_ready = False
+        self._shutdown_requested = False
+        self._exit_requested = False
+        self._documents: Dict[str, DocumentState] = {}
+        self._definitions: Dict[str, List[Tuple[str, Dict[str, Any]]]] = {}
+        self._stored_paths: Dict[str, str] = {}
+        self._parsers: Dict[str, Any] = {}
+        self._requests: Dict[str, Handler] = {
+            "initialize": self._initialize,
+            "shutdown": self._shutdown,
+            "textDocument/definition": self._definition,
+            "textDocument/references": self._references,
+            "textDocument/hover": self._hover,
+            "workspace/symbol": self._workspace_symbol
+        }
+        self._notifications: Dict[str, Handler] = {
+            "initialized": self._on_initialized,
+            "exit": self._on_exit,
+            "textDocument/didOpen": self._did_open,
+            "textDocument/didChange": self._did_change,
+            "textDocument/didClose": self._did_close
+        }
+
+    async def ensure_initialized(self):
+        """Ensure the instance is properly initialized before use."""
+        if not self._initialized:
+            raise ProcessingError("LspServer not initialized. Use create() to initialize.")
+        return True
+
+    @classmethod
+    async def create(
+        cls,
+        reader: Optional[asyncio.StreamReader] = None,
+        writer: Optional[asyncio.StreamWriter] = None
+    ) -> 'LspServer':
+        """Async factory method; speaks on stdin/stdout unless streams are given."""
+        instance = cls(reader, writer)
+        try:
+            async with AsyncErrorBoundary(
+                operation_name="LSP server initialization",
+                error_types=(ProcessingError, OSError),
+                severity=ErrorSeverity.CRITICAL
+            ):
+                if instance._reader is None or instance._writer is None:
+                    instance._reader, instance._writer = await _stdio_streams()
+
+                # Register shutdown handler
+                register_shutdown_handler(instance.cleanup)
+
+                # Initialize health monitoring
+                global_health_monitor.register_component("lsp_server")
+
+                instance._initialized = True
+                await log("LSP server ready on stdio", level="info")
+                return instance
+        except Exception as e:
+            await log(f"Error initializing LSP server: {e}", level="error")
+            await instance.cleanup()
+            raise ProcessingError(f"Failed to initialize LSP server: {e}")
+
+    @property
+    def documents(self) -> int:
+        """Number of files and buffers currently indexed."""
+        return len(self._documents)
+
+    async def serve_forever(self) -> None:
+        """Handle messages until the client sends exit or closes stdin."""
+        await self.ensure_initialized()
+        while not self._exit_requested:
+            try:
+                message = await self._read_message()
+            except LspError as e:
+                await self._send({"jsonrpc": "2.0", "id": None, "error": {"code": e.code, "message": e.message}})
+                continue
+            if message is None:
+                break
+            await self._handle_message(message)
+
+    # JSON-RPC ----------------------------------------------------------
+
+    async def _read_message(self) -> Optional[Dict[str, Any]]:
+        """Read one framed message; None at end of input."""
+        headers: Dict[str, str] = {}
+        while True:
+            line = await self._reader.readline()
+            if not line:
+                return None
+            line = line.strip()
+            if not line:
+                if headers:
+                    break
+                continue
+            name, _, value = line.decode("ascii", "replace").partition(":")
+            headers[name.strip().lower()] = value.strip()
+
+        try:
+            length = int(headers["content-length"])
+        except (KeyError, ValueError):
+            raise LspError(PARSE_ERROR, "Missing or invalid Content-Length header")
+        try:
+            body = await self._reader.readexactly(length)
+        except asyncio.IncompleteReadError:
+            return None
+        try:
+            message = json.loads(body)
+        except ValueError:
+            raise LspError(PARSE_ERROR, "Message body must be valid JSON")
+        if not isinstance(message, dict):
+            raise LspError(INVALID_REQUEST, "Message must be a JSON object")
+        return message
+
+    async def _handle_message(self, message: Dict[str, Any]) -> None:
+        """Dispatch a request or notification."""
+        method = message.get("method")
+        params = message.get("params") or {}
+        if not isinstance(method, str):
+            # Responses to server requests; this server never sends any
+            return
+
+        if "id" not in message:
+            handler = self._notifications.get(method)
+            if handler is None or (not self._client_ready and method != "exit"):
+                return
+            try:
+                await handler(params)
+            except Exception as e:
+                await log(f"LSP notification {method} failed: {e}", level="error")
+            return
+
+        request_id = message["id"]
+        try:
+            if not self._client_ready and method != "initialize":
+                raise LspError(SERVER_NOT_INITIALIZED, "Server not initialized")
+            if self._shutdown_requested:
+                raise LspError(INVALID_REQUEST, "Server is shutting down")
+            handler = self._requests.get(method)
+            if handler is None:
+                raise LspError(METHOD_NOT_FOUND, f"Unknown method: {method}")
+            result = await handler(params)
+        except LspError as e:
+            await self._send({"jsonrpc": "2.0", "id": request_id, "error": {"code": e.code, "message": e.message}})
+            return
+        except (KeyError, TypeError) as e:
+            await self._send({"jsonrpc": "2.0", "id": request_id, "error": {"code": INVALID_PARAMS, "message": f"Invalid params: {e}"}})
+            return
+        except Exception as e:
+            await log(f"LSP request {method} failed: {e}", level="error")
+            await global_health_monitor.update_component_status(
+                "lsp_server",
+                ComponentStatus.DEGRADED,
+                error=True,
+                details={"method": method, "error": str(e)}
+            )
+            await self._send({"jsonrpc": "2.0", "id": request_id, "error": {"code": INTERNAL_ERROR, "message": str(e)}})
+            return
+        await self._send({"jsonrpc": "2.0", "id": request_id, "result": result})
+
+    async def _send(self, payload: Dict[str, Any]) -> None:
+        body = json.dumps(payload, default=json_default).encode("utf-8")
+        async with self._lock:
+            self._writer.write(f"Content-Length: {len(body)}\r\n\r\n".encode("ascii") + body)
+            try:
+                await self._writer.drain()
+            except ConnectionError:
+                self._exit_requested = True
+
+    async def _log_message(self, message: str, message_type: MessageType = MessageType.INFO) -> None:
+        """Show a message in the client's output panel."""
+        await self._send({
+            "jsonrpc": "2.0",
+            "method": "window/logMessage",
+            "params": {"type": message_type, "message": message}
+        })
+
+    # Lifecycle ---------------------------------------------------------
+
+    async def _initialize(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        root = uri_to_path(params.get("rootUri")) or params.get("rootPath")
+        folders = params.get("workspaceFolders") or []
+        if not root and folders:
+            root = uri_to_path(folders[0].get("uri"))
+        self._root = os.path.abspath(root) if root else None
+        self._client_ready = True
+        return {
+            "capabilities": {
+                "textDocumentSync": {"openClose": True, "change": 2},
+                "definitionProvider": True,
+                "referencesProvider": True,
+                "hoverProvider": True,
+                "workspaceSymbolProvider": True
+            },
+            "serverInfo": {"name": "repoanalyzer"}
+        }
+
+    async def _on_initialized(self, params: Dict[str, Any]) -> None:
+        # Load in the background so the client is not blocked; requests
+        # answer from whatever has been loaded so far
+        task = asyncio.create_task(self._load_workspace())
+        self._pending_tasks.add(task)
+        task.add_done_callback(self._pending_tasks.discard)
+
+    async def _shutdown(self, params: Dict[str, Any]) -> None:
+        self._shutdown_requested = True
+        return None
+
+    async def _on_exit(self, params: Dict[str, Any]) -> None:
+        self._exit_requested = True
+
+    async def _load_workspace(self) -> None:
+        """Parse every indexed file of the repository rooted at the client's folder."""
+        if not self._root:
+            return
+        try:
+            repo = await self._upsert_coordinator.get_repository(os.path.basename(self._root))
+            if not repo:
+                await self._log_message(
+                    f"{self._root} is not indexed; run 'index' first. Only open files are searchable.",
+                    MessageType.WARNING
+                )
+                return
+            self._repo = repo
+
+            backend = await get_storage_backend()
+            rows = await backend.fetch(
+                "SELECT file_path FROM code_snippets WHERE repo_id = $1", repo["id"]
+            )
+            rows += await backend.fetch(
+                """
+                SELECT d.file_path FROM repo_docs d
+                JOIN repo_doc_relations r ON r.doc_id = d.id
+                WHERE r.repo_id = $1
+                """,
+                repo["id"]
+            )
+            for row in rows:
+                stored = row["file_path"]
+                path = stored if os.path.isabs(stored) else os.path.join(self._root, stored)
+                self._stored_paths[os.path.abspath(path)] = stored
+
+            loop = asyncio.get_running_loop()
+            loaded = 0
+            for path in sorted(self._stored_paths):
+                uri = path_to_uri(path)
+                if uri in self._documents:
+                    continue
+                text = await loop.run_in_executor(None, _read_text, path)
+                # The client may have opened the file while it was read
+                if text is None or uri in self._documents:
+                    continue
+                await self._update_document(DocumentState(uri, path, language_for_path(path), text))
+                loaded += 1
+            await self._log_message(f"Loaded {loaded} files from repository {repo['repo_name']}")
+        except Exception as e:
+            await log(f"Error loading LSP workspace {self._root}: {e}", level="error")
+            await self._log_message(f"Could not load the index: {e}", MessageType.ERROR)
+
+    # Documents ---------------------------------------------------------
+
+    async def _get_parser(self, language: str) -> Any:
+        """Tree-sitter parser for a language, or None if there is none."""
+        if language not in self._parsers:
+            parser = None
+            try:
+                from parsers.tree_sitter_parser import TreeSitterParser
+                candidate = TreeSitterParser(language)
+                if await candidate.ensure_initialized():
+                    parser = candidate
+            except Exception as e:
+                await log(f"No tree-sitter parser for {language}: {e}", level="debug")
+            self._parsers[language] = parser
+        return self._parsers[language]
+
+    async def _update_document(
+        self,
+        doc: DocumentState,
+        old_tree: Any = None,
+        old_source: Optional[str] = None
+    ) -> None:
+        """(Re-)parse a document and refresh the definition index."""
+        self._forget_document(doc.uri)
+        doc.tree, doc.symbols, doc.occurrences = None, [], None
+
+        if doc.language in CUSTOM_LANGUAGES:
+            doc.symbols = _pattern_symbols(doc.language, doc.text)
+        elif doc.language:
+            parser = await self._get_parser(doc.language)
+            if parser is not None:
+                result = await parser.parse_incremental(
+                    doc.text, old_tree, doc.path, old_source=old_source, extract_features=False
+                )
+                if result.success and result.tree is not None:
+                    doc.symbols, doc.occurrences = _tree_symbols(result.tree, doc.text)
+                    # Only open buffers are edited, so only they keep a tree
+                    if doc.is_open:
+                        doc.tree = result.tree
+
+        self._documents[doc.uri] = doc
+        for symbol in doc.symbols:
+            self._definitions.setdefault(symbol["name"], []).append((doc.uri, symbol))
+
+    def _forget_document(self, uri: str) -> None:
+        doc = self._documents.pop(uri, None)
+        if doc is None:
+            return
+        for name in {symbol["name"] for symbol in doc.symbols}:
+            entries = [entry for entry in self._definitions.get(name, []) if entry[0] != uri]
+            if entries:
+                self._definitions[name] = entries
+            else:
+                self._definitions.pop(name, None)
+
+    async def _did_open(self, params: Dict[str, Any]) -> None:
+        item = params["textDocument"]
+        path = uri_to_path(item["uri"]) or item["uri"]
+        await self._update_document(DocumentState(
+            uri=item["uri"],
+            path=path,
+            language=language_for_path(path, item.get("languageId")),
+            text=item["text"],
+            version=item.get("version"),
+            is_open=True
+        ))
+
+    async def _did_change(self, params: Dict[str, Any]) -> None:
+        item = params["textDocument"]
+        doc = self._documents.get(item["uri"])
+        if doc is None or not doc.is_open:
+            return
+        old_text, old_tree = doc.text, doc.tree
+        for change in params.get("contentChanges", []):
+            doc.text = _apply_change(doc.text, change)
+        doc.version = item.get("version")
+        await self._update_document(doc, old_tree=old_tree, old_source=old_text)
+
+    async def _did_close(self, params: Dict[str, Any]) -> None:
+        uri = params["textDocument"]["uri"]
+        doc = self._documents.get(uri)
+        if doc is None:
+            return
+        # Indexed files fall back to their saved contents
+        text = _read_text(doc.path) if doc.path in self._stored_paths else None
+        if text is None:
+            self._forget_document(uri)
+            return
+        await self._update_document(DocumentState(uri, doc.path, doc.language, text))
+
+    async def _document(self, uri: str) -> DocumentState:
+        """An open or indexed document, loading it from disk if needed."""
+        doc = self._documents.get(uri)
+        if doc is not None:
+            return doc
+        path = uri_to_path(uri)
+        text = _read_text(path) if path else None
+        if text is None:
+            raise LspError(INVALID_PARAMS, f"Unknown document: {uri}")
+        doc = DocumentState(uri, path, language_for_path(path), text)
+        await self._update_document(doc)
+        return doc
+
+    async def _target(self, params: Dict[str, Any]) -> Tuple[DocumentState, Optional[str]]:
+        """The document and name at a TextDocumentPositionParams position."""
+        doc = await self._document(params["textDocument"]["uri"])
+        position = params["position"]
+        for symbol in doc.symbols:
+            if _contains(symbol["selectionRange"], position):
+                return doc, symbol["name"]
+        for name, ranges in (doc.occurrences or {}).items():
+            if any(_contains(rng, position) for rng in ranges):
+                return doc, name
+        # Strings and comments in code, and all custom-language text
+        return doc, _word_at(doc.text, position)
+
+    def _occurrences(self, doc: DocumentState, name: str) -> List[Dict[str, Any]]:
+        if doc.occurrences is not None:
+            return doc.occurrences.get(name, [])
+        pattern = re.compile(r"(?<![\w$])" + re.escape(name) + r"(?![\w$])")
+        return [_span_range(doc.text, m.start(), m.end()) for m in pattern.finditer(doc.text)]
+
+    def _location(self, uri: str, rng: Dict[str, Any]) -> Dict[str, Any]:
+        return {"uri": uri, "range": rng}
+
+    def _display_path(self, path: str) -> str:
+        if self._root and path.startswith(self._root + os.sep):
+            return os.path.relpath(path, self._root)
+        return path
+
+    # Graph -------------------------------------------------------------
+
+    async def _graph_neighbours(
+        self,
+        path: str,
+        rel_types: Sequence[str],
+        direction: str = "out",
+        max_depth: int = 1
+    ) -> Set[str]:
+        """Absolute paths of Code nodes linked to a file."""
+        stored = self._stored_paths.get(path)
+        if not self._repo or stored is None:
+            return set()
+        try:
+            backend = await get_storage_backend()
+            edges = await backend.traverse(
+                "Code",
+                {"repo_id": self._repo["id"], "file_path": stored},
+                list(rel_types),
+                direction=direction,
+                max_depth=max_depth
+            )
+        except Exception as e:
+            await log(f"Graph lookup for {path} failed: {e}", level="debug")
+            return set()
+        end = "target" if direction == "out" else "source"
+        neighbours = set()
+        for edge in edges:
+            file_path = (edge.get(end) or {}).get("file_path")
+            if file_path:
+                absolute = file_path if os.path.isabs(file_path) else os.path.join(self._root, file_path)
+                neighbours.add(os.path.abspath(absolute))
+        neighbours.discard(path)
+        return neighbours
+
+    async def _ranked_definitions(self, doc: DocumentState, name: str) -> List[Tuple[int, str, Dict[str, Any]]]:
+        """Definitions of name, best first: same file, linked files, same language, rest."""
+        entries = self._definitions.get(name, [])
+        if not entries:
+            return []
+        linked: Set[str] = set()
+        if any(uri != doc.uri for uri, _ in entries):
+            linked = await self._graph_neighbours(doc.path, ["DEPENDS_ON", "RELATED_TO"], max_depth=2)
+
+        def rank(uri: str) -> int:
+            target = self._documents[uri]
+            if uri == doc.uri:
+                return 0
+            if target.path in linked:
+                return 1
+            if target.language == doc.language:
+                return 2
+            return 3
+
+        return sorted(
+            ((rank(uri), uri, symbol) for uri, symbol in entries),
+            key=lambda entry: (entry[0], entry[1], _range_key(entry[2]["selectionRange"]))
+        )
+
+    # Requests ----------------------------------------------------------
+
+    async def _definition(self, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        doc, name = await self._target(params)
+        if not name:
+            return []
+        ranked = await self._ranked_definitions(doc, name)
+        if not ranked:
+            return []
+        best = ranked[0][0]
+        return [
+            self._location(uri, symbol["selectionRange"])
+            for rank, uri, symbol in ranked
+            if rank == best
+        ]
+
+    async def _references(self, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        _doc, name = await self._target(params)
+        if not name:
+            return []
+        include_declaration = (params.get("context") or {}).get("includeDeclaration", True)
+        declarations = {
+            (uri, _range_key(symbol["selectionRange"]))
+            for uri, symbol in self._definitions.get(name, [])
+        }
+
+        locations = []
+        seen = set()
+        for uri in sorted(self._documents):
+            for rng in self._occurrences(self._documents[uri], name):
+                key = (uri, _range_key(rng))
+                if key in seen or (not include_declaration and key in declarations):
+                    continue
+                seen.add(key)
+                locations.append(self._location(uri, rng))
+        if include_declaration:
+            for uri, symbol in self._definitions.get(name, []):
+                key = (uri, _range_key(symbol["selectionRange"]))
+                if key not in seen:
+                    seen.add(key)
+                    locations.append(self._location(uri, symbol["selectionRange"]))
+        return locations
+
+    async def _workspace_symbol(self, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        query = (params.get("query") or "").lower()
+        names = [name for name in self._definitions if query in name.lower()]
+        # Prefix matches first, then shorter names
+        names.sort(key=lambda name: (not name.lower().startswith(query), len(name), name))
+
+        results = []
+        for name in names:
+            for uri, symbol in self._definitions[name]:
+                results.append({
+                    "name": name,
+                    "kind": symbol["kind"],
+                    "location": self._location(uri, symbol["selectionRange"]),
+                    "containerName": symbol["containerName"] or self._display_path(self._documents[uri].path)
+                })
+                if len(results) >= MAX_WORKSPACE_SYMBOLS:
+                    return results
+        return results
+
+    async def _hover(self, params: Dict[str, Any]) -> Optional[Dict[str, Any]]:
+        doc, name = await self._target(params)
+        if not name:
+            return None
+        ranked = await self._ranked_definitions(doc, name)
+        if not ranked:
+            return None
+        _rank, uri, symbol = ranked[0]
+        target = self._documents[uri]
+        kind = SymbolKind(symbol["kind"]).name.replace("_", " ").lower()
+        sections = [
+            f"```{target.language or ''}\n{symbol['detail']}\n```",
+            f"{kind} `{name}` in `{self._display_path(target.path)}`, "
+            f"line {symbol['selectionRange']['start']['line'] + 1}"
+        ]
+        if len(ranked) > 1:
+            sections.append(f"{len(ranked) - 1} other definition(s)")
+
+        dependencies = await self._graph_neighbours(target.path, ["DEPENDS_ON"])
+        dependents = await self._graph_neighbours(target.path, ["DEPENDS_ON"], direction="in")
+        if dependencies or dependents:
+            sections.append(f"Depends on {len(dependencies)} file(s); {len(dependents)} file(s) depend on it")
+        return {"contents": {"kind": "markdown", "value": "\n\n".join(sections)}}
+
+    async def cleanup(self):
+        """Cancel background loading and close the output stream."""
+        try:
+            if self._pending_tasks:
+                for task in self._pending_tasks:
+                    if not task.done():
+                        task.cancel()
+                await asyncio.gather(*self._pending_tasks, return_exceptions=True)
+                self._pending_tasks.clear()
+
+            if self._writer is not None:
+                self._writer.close()
+                self._writer = None
+
+            self._documents.clear()
+            self._definitions.clear()
+            if self._initialized:
+                global_health_monitor.unregister_component("lsp_server")
+            self._initialized = False
+            await log("LSP server cleaned up", level="info")
+        except Exception as e:
+            await log(f"Error cleaning up LSP server: {e}", level="error")
+
+async def _stdio_streams() -> Tuple[asyncio.StreamReader, asyncio.StreamWriter]:
+    """Asyncio streams over the process's stdin and stdout."""
+    loop = asyncio.get_running_loop()
+    reader = asyncio.StreamReader()
+    await loop.connect_read_pipe(lambda: asyncio.StreamReaderProtocol(reader), sys.stdin)
+    transport, protocol = await loop.connect_write_pipe(asyncio.streams.FlowControlMixin, sys.stdout)
+    writer = asyncio.StreamWriter(transport, protocol, reader, loop)
+    return reader, writer
+
+__all__ = [
+    "LspError",
+    "LspServer",
+    "SymbolKind",
+    "language_for_path",
+    "path_to_uri",
+    "uri_to_path"
+]
@@ -0,0 +1,555 @@
+"""[8.1] Local HTTP/JSON API over the index.
+
+Flow:
+1. Endpoints (GET with query parameters, or POST with a JSON object body):
+   - /health                        Server and storage backend status
+   - /repos?type=                   Indexed repositories
+   - /search/code?q=&repo=&language=&path=&kind=  Hybrid search over code
+   - /search/docs?q=&repo=&path=&kind=  Hybrid search over documentation
+   - /dependencies?repo=&path=&depth=  Files a file depends on
+   - /references?repo=&path=        Files a file references
+   - /symbols?repo=&name=           Definitions of a name
+   - /usages?repo=&name=&path=      Where a symbol is used, across files
+   - /calls?repo=&entry=&depth=     Call tree from a file or function
+   - /crates?repo=                  Rust crates and their dependencies
+   - /implementations?repo=&trait=  Types implementing a Rust trait
+   - /traits?repo=&type=&path=      Traits a Rust type implements
+   - /tests?repo=&name=&path=&depth=  Rust tests that exercise a function
+   - /findings?repo=&analysis=&kind=&path=  Stored analysis findings
+   - /audit/rust?repo=              Rust unsafe/panic/FFI counts per crate and module
+   - /features/rust?repo=           Code per Cargo feature, dead and undeclared features
+   - /packages?repo=&ecosystem=     Declared packages, resolved versions and importing files
+   - /patterns?repo=&type=          Stored code/doc/arch patterns
+
+2. Search Filters:
+   - q may hold filters (lang:rust path:src/** kind:function repo:foo
+     -path:tests); language, path and kind, and exclude_language,
+     exclude_path and exclude_kind, take the same values as parameters
+     (comma-separated or a JSON list)
+
+3. Pagination:
+   - page (1-based) and page_size (capped by api_config.max_page_size)
+   - Responses are {"data", "page", "page_size", "has_more"} plus "total"
+     when it is known without scanning further
+
+4. Errors:
+   - ApiError carries the HTTP status; responses are {"error": message}
+   - 400 invalid parameters, 404 unknown route or repository,
+     405 wrong method, 413 body too large, 500 unexpected failures
+"""
+
+import json
+import asyncio
+from http import HTTPStatus
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Set, Tuple
+from urllib.parse import parse_qs, urlsplit
+
+from config.config import api_config, storage_config
+from utils.logger import log
+from utils.cli_output import json_default
+from utils.error_handling import AsyncErrorBoundary, ProcessingError, ErrorSeverity
+from utils.shutdown import register_shutdown_handler
+from utils.health_monitor import global_health_monitor, ComponentStatus
+from db.upsert_ops import UpsertCoordinator
+from semantic.filters import SearchFilters
+
+class ApiError(Exception):
+    """Request error reported to the client with an HTTP status."""
+
+    def __init__(self, status: int, message: str):
+        super().__init__(message)
+        self.status = status
+        self.message = message
+
+Handler = Callable[[Dict[str, Any]], Awaitable[Any]]
+
+def _str_param(
+    params: Dict[str, Any],
+    name: str,
+    required: bool = False,
+    choices: Optional[Tuple[str, ...]] = None
+) -> Optional[str]:
+    """Read a string parameter."""
+    value = params.get(name)
+    if value is None or value == "":
+        if required:
+            raise ApiError(400, f"Missing required parameter '{name}'")
+        return None
+    if not isinstance(value, (str, int)):
+        raise ApiError(400, f"Parameter '{name}' must be a string")
+    value = str(value)
+    if choices and value not in choices:
+        raise ApiError(400, f"Parameter '{name}' must be one of: {', '.join(choices)}")
+    return value
+
+def _int_param(
+    params: Dict[str, Any],
+    name: str,
+    default: int,
+    minimum: Optional[int] = None,
+    maximum: Optional[int] = None
+) -> int:
+    """Read an integer parameter within bounds."""
+    value = params.get(name)
+    if value is None or value == "":
+        return default
+    if isinstance(value, bool):
+        raise ApiError(400, f"Parameter '{name}' must be an integer")
+    try:
+        value = int(value)
+    except (TypeError, ValueError):
+        raise ApiError(400, f"Parameter '{name}' must be an integer")
+    if minimum is not None and value < minimum:
+        raise ApiError(400, f"Parameter '{name}' must be >= {minimum}")
+    if maximum is not None and value > maximum:
+        raise ApiError(400, f"Parameter '{name}' must be <= {maximum}")
+    return value
+
+def _filter_params(params: Dict[str, Any], names: Tuple[str, ...]) -> SearchFilters:
+    """Read search filters: each name and exclude_<name>, a list or a comma-separated string."""
+    filters = SearchFilters()
+    for name in names:
+        for key, negate in ((name, False), (f"exclude_{name}", True)):
+            value = params.get(key)
+            if value is None or value == "":
+                continue
+            if isinstance(value, bool) or not (
+                isinstance(value, (str, int))
+                or (isinstance(value, list) and all(isinstance(item, (str, int)) for item in value))
+            ):
+                raise ApiError(400, f"Parameter '{key}' must be a string or a list of strings")
+            filters.add(name, value if isinstance(value, list) else str(value), negate=negate)
+    return filters
+
+def _page_params(params: Dict[str, Any]) -> Tuple[int, int]:
+    """Read page and page_size."""
+    page = _int_param(params, "page", 1, minimum=1)
+    page_size = _int_param(
+        params, "page_size", api_config.default_page_size,
+        minimum=1, maximum=api_config.max_page_size
+    )
+    return page, page_size
+
+def _paginate(items: List[Any], page: int, page_size: int) -> Dict[str, Any]:
+    """Slice a fully materialized result list."""
+    start = (page - 1) * page_size
+    return {
+        "data": items[start:start + page_size],
+        "page": page,
+        "page_size": page_size,
+        "total": len(items),
+        "has_more": start + page_size < len(items)
+    }
+
+def _page_window(items: List[Any], page: int, page_size: int) -> Dict[str, Any]:
+    """Wrap a result list fetched with limit=page_size + 1 at the page offset."""
+    return {
+        "data": items[:page_size],
+        "page": page,
+        "page_size": page_size,
+        "has_more": len(items) > page_size
+    }
+
+class ApiServer:
+    """[8.1.1] Minimal asyncio HTTP server answering JSON queries."""
+
+    def __init__(self, host: str, port: int):
+        """Private constructor - use create() instead."""
+        self._initialized = False
+        self._pending_tasks: Set[asyncio.Task] = set()
+        self._lock = asyncio.Lock()
+        self._host = host
+        self._port = port
+        self._server: Optional[asyncio.AbstractServer] = None
+        self._upsert_coordinator = UpsertCoordinator()
+        self._routes: Dict[str, Handler] = {
+            "/health": self._health,
+            "/repos": self._repos,
+            "/search/code": self._search_code,
+            "/search/docs": self._search_docs,
+            "/dependencies": self._dependencies,
+            "/references": self._references,
+            "/symbols": self._symbols,
+            "/usages": self._usages,
+            "/calls": self._calls,
+            "/crates": self._crates,
+            "/implementations": self._implementations,
+            "/traits": self._traits,
+            "/tests": self._tests,
+            "/findings": self._findings,
+            "/audit/rust": self._rust_audit,
+            "/features/rust": self._rust_features,
+            "/packages": self._packages,
+            "/patterns": self._patterns
+        }
+
+    async def ensure_initialized(self):
+        """Ensure the instance is properly initialized before use."""
+        if not self._initialized:
+            raise ProcessingError("ApiServer not initialized. Use create() to initialize.")
+        return True
+
+    @classmethod
+    async def create(cls, host: Optional[str] = None, port: Optional[int] = None) -> 'ApiServer':
+        """Async factory method to create a server bound to host:port."""
+        instance = cls(host or api_config.host, api_config.port if port is None else port)
+        try:
+            async with AsyncErrorBoundary(
+                operation_name="API server initialization",
+                error_types=(ProcessingError, OSError),
+                severity=ErrorSeverity.CRITICAL
+            ):
+                instance._server = await asyncio.start_server(
+                    instance._handle_connection, instance._host, instance._port
+                )
+                # Port 0 asks the OS for a free port; report the real one
+                instance._host, instance._port = instance._server.sockets[0].getsockname()[:2]
+
+                # Register shutdown handler
+                register_shutdown_handler(instance.cleanup)
+
+                # Initialize health monitoring
+                global_health_monitor.register_component("api_server")
+
+                instance._initialized = True
+                await log(f"API server listening on http://{instance._host}:{instance._port}", level="info")
+                return instance
+        except Exception as e:
+            await log(f"Error initializing API server: {e}", level="error")
+            await instance.cleanup()
+            raise ProcessingError(f"Failed to initialize API server: {e}")
+
+    @property
+    def address(self) -> Tuple[str, int]:
+        """The (host, port) the server is bound to."""
+        return self._host, self._port
+
+    async def serve_forever(self) -> None:
+        """Serve requests until cancelled."""
+        await self.ensure_initialized()
+        async with self._server:
+            await self._server.serve_forever()
+
+    # HTTP --------------------------------------------------------------
+
+    async def _handle_connection(self, reader: asyncio.StreamReader, writer: asyncio.StreamWriter) -> None:
+        """Answer a single request and close the connection."""
+        task = asyncio.current_task()
+        self._pending_tasks.add(task)
+        try:
+            try:
+                method, path, params = await asyncio.wait_for(
+                    self._read_request(reader), timeout=api_config.request_timeout
+                )
+                status, payload = await self._dispatch(method, path, params)
+            except ApiError as e:
+                status, payload = e.status, {"error": e.message}
+            except asyncio.TimeoutError:
+                status, payload = 408, {"error": "Request timed out"}
+            except (asyncio.IncompleteReadError, ConnectionError):
+                return
+            await self._write_response(writer, status, payload)
+        finally:
+            self._pending_tasks.discard(task)
+            writer.close()
+            try:
+                await writer.wait_closed()
+            except ConnectionError:
+                pass
+
+    async def _read_request(self, reader: asyncio.StreamReader) -> Tuple[str, str, Dict[str, Any]]:
+        """Parse the request line, headers, query string and JSON body."""
+        request_line = await reader.readline()
+        try:
+            method, target, _version = request_line.decode("latin-1").split()
+        except ValueError:
+            raise ApiError(400, "Malformed request line")
+
+        headers: Dict[str, str] = {}
+        while True:
+            line = await reader.readline()
+            if line in (b"\r\n", b"\n", b""):
+                break
+            name, _, value = line.decode("latin-1").partition(":")
+            headers[name.strip().lower()] = value.strip()
+
+        url = urlsplit(target)
+        params: Dict[str, Any] = {
+            key: values[-1] for key, values in parse_qs(url.query).items()
+        }
+
+        try:
+            length = int(headers.get("content-length") or 0)
+        except ValueError:
+            raise ApiError(400, "Invalid Content-Length")
+        if length < 0:
+            raise ApiError(400, "Invalid Content-Length")
+        if length > api_config.max_body_size:
+            raise ApiError(413, "Request body too large")
+        if length:
+            body = await reader.readexactly(length)
+            try:
+                payload = json.loads(body)
+            except ValueError:
+                raise ApiError(400, "Request body must be valid JSON")
+            if not isinstance(payload, dict):
+                raise ApiError(400, "Request body must be a JSON object")
+            params.update(payload)
+
+        return method.upper(), url.path.rstrip("/") or "/", params
+
+    async def _dispatch(self, method: str, path: str, params: Dict[str, Any]) -> Tuple[int, Any]:
+        """Route a request to its handler."""
+        handler = self._routes.get(path)
+        if handler is None:
+            raise ApiError(404, f"Unknown endpoint: {path}")
+        if method not in ("GET", "POST"):
+            raise ApiError(405, f"Method {method} not allowed")
+        try:
+            return 200, await handler(params)
+        except ApiError:
+            raise
+        except Exception as e:
+            await log(f"API request {path} failed: {e}", level="error")
+            await global_health_monitor.update_component_status(
+                "api_server",
+                ComponentStatus.DEGRADED,
+                error=True,
+                details={"path": path, "error": str(e)}
+            )
+            raise ApiError(500, "Internal server error")
+
+    async def _write_response(self, writer: asyncio.StreamWriter, status: int, payload: Any) -> None:
+        body = json.dumps(payload, default=json_default).encode("utf-8")
+        head = (
+            f"HTTP/1.1 {status} {HTTPStatus(status).phrase}\r\n"
+            "Content-Type: application/json; charset=utf-8\r\n"
+            f"Content-Length: {len(body)}\r\n"
+            "Connection: close\r\n"
+            "\r\n"
+        ).encode("latin-1")
+        writer.write(head + body)
+        try:
+            await writer.drain()
+        except ConnectionError:
+            pass
+
+    # Handlers ----------------------------------------------------------
+
+    async def _resolve_repo(self, params: Dict[str, Any], required: bool = True) -> Optional[Dict[str, Any]]:
+        """Look up the repository named by the `repo` parameter (id or name)."""
+        repo_ref = _str_param(params, "repo", required=required)
+        if repo_ref is None:
+            return None
+        repo = await self._upsert_coordinator.get_repository(repo_ref)
+        if not repo:
+            raise ApiError(404, f"Repository not found: {repo_ref}")
+        return repo
+
+    async def _health(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        return {"status": "ok", "storage_backend": storage_config.backend}
+
+    async def _repos(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo_type = _str_param(params, "type", choices=("active", "reference"))
+        page, page_size = _page_params(params)
+        repos = await self._upsert_coordinator.list_repositories(repo_type)
+        return _paginate(repos or [], page, page_size)
+
+    async def _search_code(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        query = _str_param(params, "q", required=True)
+        filters = _filter_params(params, ("language", "path", "kind"))
+        repo = await self._resolve_repo(params, required=False)
+        page, page_size = _page_params(params)
+
+        from semantic.search import get_search_engine
+        engine = await get_search_engine()
+        results = await engine.search_code(
+            query,
+            repo_id=repo["id"] if repo else None,
+            limit=page_size + 1,
+            offset=(page - 1) * page_size,
+            filters=filters
+        )
+        return _page_window(results or [], page, page_size)
+
+    async def _search_docs(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        query = _str_param(params, "q", required=True)
+        filters = _filter_params(params, ("path", "kind"))
+        repo = await self._resolve_repo(params, required=False)
+        page, page_size = _page_params(params)
+
+        from semantic.search import get_search_engine
+        engine = await get_search_engine()
+        results = await engine.search_docs(
+            query,
+            repo_id=repo["id"] if repo else None,
+            limit=page_size + 1,
+            offset=(page - 1) * page_size,
+            filters=filters
+        )
+        return _page_window(results or [], page, page_size)
+
+    async def _dependencies(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        file_path = _str_param(params, "path", required=True)
+        depth = _int_param(params, "depth", 3, minimum=1, maximum=10)
+        page, page_size = _page_params(params)
+
+        from ai_tools.graph_capabilities import get_graph_analysis
+        analysis = await get_graph_analysis()
+        dependencies = await analysis.get_dependencies(repo["id"], file_path, depth=depth)
+        return _paginate(dependencies or [], page, page_size)
+
+    async def _references(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        file_path = _str_param(params, "path", required=True)
+        page, page_size = _page_params(params)
+
+        from ai_tools.graph_capabilities import get_graph_analysis
+        analysis = await get_graph_analysis()
+        references = await analysis.get_references(repo["id"], file_path)
+        return _paginate(references or [], page, page_size)
+
+    async def _symbols(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        name = _str_param(params, "name", required=True)
+        page, page_size = _page_params(params)
+
+        from indexer.symbol_table import find_symbols
+        symbols = await find_symbols(repo["id"], name)
+        return _paginate(symbols or [], page, page_size)
+
+    async def _usages(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        name = _str_param(params, "name", required=True)
+        file_path = _str_param(params, "path")
+        page, page_size = _page_params(params)
+
+        from indexer.symbol_table import find_usages
+        usages = await find_usages(repo["id"], name, file_path)
+        return _paginate(usages or [], page, page_size)
+
+    async def _calls(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        entry_point = _str_param(params, "entry", required=True)
+        depth = _int_param(params, "depth", 5, minimum=1, maximum=20)
+        page, page_size = _page_params(params)
+
+        from ai_tools.graph_capabilities import get_graph_analysis
+        analysis = await get_graph_analysis()
+        trees = await analysis.trace_code_flow(entry_point, repo["id"], max_depth=depth)
+        return _paginate(trees or [], page, page_size)
+
+    async def _crates(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        page, page_size = _page_params(params)
+
+        from indexer.rust_crates import list_crates
+        crates = await list_crates(repo["id"])
+        return _paginate(crates or [], page, page_size)
+
+    async def _implementations(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        trait = _str_param(params, "trait", required=True)
+        page, page_size = _page_params(params)
+
+        from indexer.symbol_table import find_implementations
+        implementations = await find_implementations(repo["id"], trait)
+        return _paginate(implementations or [], page, page_size)
+
+    async def _traits(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        type_name = _str_param(params, "type", required=True)
+        file_path = _str_param(params, "path")
+        page, page_size = _page_params(params)
+
+        from indexer.symbol_table import find_traits
+        traits = await find_traits(repo["id"], type_name, file_path)
+        return _paginate(traits or [], page, page_size)
+
+    async def _tests(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        name = _str_param(params, "name", required=True)
+        file_path = _str_param(params, "path")
+        depth = _int_param(params, "depth", 3, minimum=0, maximum=20)
+        page, page_size = _page_params(params)
+
+        from indexer.symbol_table import find_tests
+        tests = await find_tests(repo["id"], name, file_path, depth)
+        return _paginate(tests or [], page, page_size)
+
+    async def _findings(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        analysis = _str_param(params, "analysis")
+        kind = _str_param(params, "kind")
+        file_path = _str_param(params, "path")
+        page, page_size = _page_params(params)
+
+        from indexer.rust_audit import list_findings
+        findings = await list_findings(repo["id"], analysis, kind, file_path)
+        return _paginate(findings or [], page, page_size)
+
+    async def _rust_audit(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+
+        from indexer.rust_audit import rust_audit_report
+        return await rust_audit_report(repo["id"])
+
+    async def _rust_features(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+
+        from indexer.rust_features import rust_feature_report
+        return await rust_feature_report(repo["id"])
+
+    async def _packages(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        ecosystem = _str_param(params, "ecosystem", choices=("pypi", "cargo", "npm", "go"))
+        page, page_size = _page_params(params)
+
+        from indexer.package_deps import list_packages
+        packages = await list_packages(repo["id"], ecosystem)
+        return _paginate(packages or [], page, page_size)
+
+    async def _patterns(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        repo = await self._resolve_repo(params)
+        pattern_type = _str_param(params, "type", choices=("code", "doc", "arch"))
+        page, page_size = _page_params(params)
+
+        from db.pattern_storage import get_pattern_storage
+        storage = await get_pattern_storage()
+        patterns = await storage.get_patterns(repo["id"], pattern_type) or {}
+        rows = [
+            {"category": category, **row}
+            for category, items in patterns.items()
+            for row in items
+        ]
+        return _paginate(rows, page, page_size)
+
+    async def cleanup(self):
+        """Stop listening and cancel in-flight requests."""
+        try:
+            if self._server is not None:
+                self._server.close()
+
+            if self._pending_tasks:
+                for task in self._pending_tasks:
+                    if not task.done():
+                        task.cancel()
+                await asyncio.gather(*self._pending_tasks, return_exceptions=True)
+                self._pending_tasks.clear()
+
+            if self._server is not None:
+                await self._server.wait_closed()
+                self._server = None
+
+            if self._initialized:
+                global_health_monitor.unregister_component("api_server")
+            self._initialized = False
+            await log("API server cleaned up", level="info")
+        except Exception as e:
+            await log(f"Error cleaning up API server: {e}", level="error")
+
+__all__ = [
+    "ApiError",
+    "ApiServer"
+]
@@ -9,7 +9,11 @@ from .config import (
     Config,
     DatabaseConfig,
     GraphConfig,
-    RetryConfig
+    RetryConfig,
+    StorageConfig,
+    ApiConfig,
+    IndexingConfig,
+    AnalysisConfig
 )
 
 __all__ = [
@@ -21,5 +25,9 @@ __all__ = [
     'Config',
     'DatabaseConfig',
     'GraphConfig',
-    'RetryConfig'
+    'RetryConfig',
+    'StorageConfig',
+    'ApiConfig',
+    'IndexingConfig',
+    'AnalysisConfig'
 ] 
\ No newline at end of file
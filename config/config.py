@@ -120,6 +120,70 @@ class RetryConfig:
     error_threshold: int = int(os.getenv('RETRY_ERROR_THRESHOLD', '5'))
     cooldown_period: int = int(os.getenv('RETRY_COOLDOWN', '300'))
 
+@dataclass
+class StorageConfig:
+    """Storage backend selection.
+
+    "server" uses PostgreSQL and Neo4j; "embedded" keeps everything in a
+    single local SQLite file and needs no running services.
+    """
+    backend: str = os.getenv('STORAGE_BACKEND', 'server')
+    embedded_path: str = os.getenv('EMBEDDED_DB_PATH', '.repoanalyzer/index.db')
+
+@dataclass
+class ApiConfig:
+    """Local HTTP API server configuration."""
+    host: str = os.getenv('API_HOST', '127.0.0.1')
+    port: int = int(os.getenv('API_PORT', '8765'))
+    default_page_size: int = int(os.getenv('API_DEFAULT_PAGE_SIZE', '20'))
+    max_page_size: int = int(os.getenv('API_MAX_PAGE_SIZE', '100'))
+    max_body_size: int = int(os.getenv('API_MAX_BODY_SIZE', '1048576'))  # 1MB
+    request_timeout: float = float(os.getenv('API_REQUEST_TIMEOUT', '30.0'))
+
+@dataclass
+class IndexingConfig:
+    """Indexing pipeline configuration.
+
+    parse_workers processes parse and extract features; 0 parses on the
+    event loop. The queues bound how many files wait for a parser and how
+    many parsed files wait to be stored.
+
+    In watch mode, file events are batched once no event arrived for
+    watch_debounce seconds, at the latest watch_max_delay seconds after the
+    first one, or as soon as watch_batch_size paths are pending.
+
+    Code is embedded per function, class and method; a block longer than
+    chunk_max_lines lines or chunk_max_chars characters is split into
+    windows that overlap by chunk_overlap_lines lines.
+    """
+    parse_workers: int = int(os.getenv('INDEX_PARSE_WORKERS', str(os.cpu_count() or 1)))
+    write_workers: int = int(os.getenv('INDEX_WRITE_WORKERS', '4'))
+    parse_queue_size: int = int(os.getenv('INDEX_PARSE_QUEUE_SIZE', '256'))
+    write_queue_size: int = int(os.getenv('INDEX_WRITE_QUEUE_SIZE', '128'))
+    watch_debounce: float = float(os.getenv('INDEX_WATCH_DEBOUNCE', '0.5'))
+    watch_max_delay: float = float(os.getenv('INDEX_WATCH_MAX_DELAY', '5'))
+    watch_batch_size: int = int(os.getenv('INDEX_WATCH_BATCH_SIZE', '500'))
+    chunk_max_lines: int = int(os.getenv('INDEX_CHUNK_MAX_LINES', '60'))
+    chunk_max_chars: int = int(os.getenv('INDEX_CHUNK_MAX_CHARS', '2000'))
+    chunk_overlap_lines: int = int(os.getenv('INDEX_CHUNK_OVERLAP_LINES', '10'))
+
+@dataclass
+class AnalysisConfig:
+    """Static analysis configuration.
+
+    blocking_calls adds call patterns (time.sleep, requests.*, std::fs::*)
+    to the built-in denylist of calls that block inside async functions;
+    blocking_allow exempts patterns from it. blocking_depth caps how many
+    synchronous calls deep a blocking call is followed.
+    """
+    blocking_calls: List[str] = field(default_factory=lambda: [
+        pattern.strip() for pattern in os.getenv('ANALYSIS_BLOCKING_CALLS', '').split(',') if pattern.strip()
+    ])
+    blocking_allow: List[str] = field(default_factory=lambda: [
+        pattern.strip() for pattern in os.getenv('ANALYSIS_BLOCKING_ALLOW', '').split(',') if pattern.strip()
+    ])
+    blocking_depth: int = int(os.getenv('ANALYSIS_BLOCKING_DEPTH', '4'))
+
 class Config:
     """Configuration management for the application."""
     
@@ -351,31 +415,37 @@ file_config = FileConfig.create()
 database_config = DatabaseConfig()
 graph_config = GraphConfig()
 retry_config = RetryConfig()
+storage_config = StorageConfig()
+api_config = ApiConfig()
+indexing_config = IndexingConfig()
+analysis_config = AnalysisConfig()
 
 @handle_errors(error_types=(Exception,))
 async def validate_configs() -> bool:
     """Validate all configuration settings."""
     async with AsyncErrorBoundary("configuration validation", severity=ErrorSeverity.CRITICAL):
-        # Validate PostgreSQL config
-        if not all([
-            postgres_config.host,
-            postgres_config.port,
-            postgres_config.database,
-            postgres_config.user,
-            postgres_config.password
-        ]):
-            log("Invalid PostgreSQL configuration", level="error")
-            return False
+        # The embedded backend needs no database services
+        if storage_config.backend != 'embedded':
+            # Validate PostgreSQL config
+            if not all([
+                postgres_config.host,
+                postgres_config.port,
+                postgres_config.database,
+                postgres_config.user,
+                postgres_config.password
+            ]):
+                log("Invalid PostgreSQL configuration", level="error")
+                return False
 
-        # Validate Neo4j config
-        if not all([
-            neo4j_config.uri,
-            neo4j_config.user,
-            neo4j_config.password,
-            neo4j_config.database
-        ]):
-            log("Invalid Neo4j configuration", level="error")
-            return False
+            # Validate Neo4j config
+            if not all([
+                neo4j_config.uri,
+                neo4j_config.user,
+                neo4j_config.password,
+                neo4j_config.database
+            ]):
+                log("Invalid Neo4j configuration", level="error")
+                return False
 
         # Validate parser config
         if not os.path.exists(parser_config.language_data_path):
@@ -379,6 +379,24 @@ class ConnectionManager:
         except Exception as e:
             log(f"Error releasing connection: {e}", level="error")
     
+    async def initialize_postgres(self) -> None:
+        """Initialize the PostgreSQL pool (shared with Neo4j initialization)."""
+        await self.initialize()
+
+    async def get_postgres_connection(self) -> Connection:
+        """Get a PostgreSQL connection from the pool."""
+        return await self.get_connection()
+
+    async def release_postgres_connection(self, conn: Connection) -> None:
+        """Release a PostgreSQL connection back to the pool."""
+        await self.release_connection(conn)
+
+    async def get_session(self):
+        """Get a Neo4j session; callers must close it."""
+        if not self._initialized:
+            await self.initialize()
+        return self._neo4j_driver.session(database=Neo4jConfig.database)
+
     async def cleanup(self):
         """Clean up all resources."""
         try:
@@ -0,0 +1,545 @@
+"""[6.10] Embedded single-file storage backend.
+
+Flow:
+1. Storage:
+   - One SQLite file (storage_config.embedded_path) holds every table
+   - Graph nodes and relationships live in graph_nodes/graph_edges in the same file
+   - An in-process adjacency index mirrors the graph tables for traversal
+   - vector_search computes cosine similarity and text_search BM25 over the
+     search_text tokens in Python, over the rows the filter leaves
+
+2. SQL Dialect:
+   - Callers keep writing PostgreSQL-flavoured SQL with $N placeholders
+   - SqliteDialect rewrites types, casts, placeholders, ANY($N) and the ~
+     regex operator
+   - pgvector/GIN indexes and extensions are skipped
+
+3. Concurrency:
+   - All SQLite and graph work runs on one worker thread, so it is safe to
+     call from any event loop (including submit_async_task's background loop)
+   - Statements autocommit; execute_batch groups statements atomically
+"""
+
+import os
+import re
+import json
+import math
+import sqlite3
+import asyncio
+from collections import Counter, deque
+from concurrent.futures import ThreadPoolExecutor
+from datetime import date, datetime
+from functools import lru_cache
+from typing import Any, Callable, Dict, List, Optional, Sequence, Tuple
+
+import numpy as np
+
+from config.config import storage_config
+from db.storage import (
+    EMBEDDED_BACKEND,
+    StorageBackend,
+    check_identifier,
+    cosine_similarity,
+    to_vector
+)
+from utils.error_handling import handle_async_errors, DatabaseError
+
+# BM25 term-frequency saturation and length normalization for text_search
+_BM25_K1 = 1.2
+_BM25_B = 0.75
+
+class SqliteDialect:
+    """Rewrites the PostgreSQL subset used by this codebase for SQLite."""
+
+    _SKIP = re.compile(
+        r"\bUSING\s+(ivfflat|hnsw|gin|gist)\b|^\s*CREATE\s+EXTENSION\b|^\s*SET\s+",
+        re.IGNORECASE
+    )
+    _REWRITES: List[Tuple[re.Pattern, str]] = [
+        (re.compile(r"\b(BIG)?SERIAL\s+PRIMARY\s+KEY\b", re.IGNORECASE), "INTEGER PRIMARY KEY AUTOINCREMENT"),
+        (re.compile(r"\bTIMESTAMP\s+WITH(OUT)?\s+TIME\s+ZONE\b", re.IGNORECASE), "TIMESTAMP"),
+        (re.compile(r"\bJSONB\b", re.IGNORECASE), "TEXT"),
+        (re.compile(r"\bVECTOR\s*\(\s*\d+\s*\)", re.IGNORECASE), "TEXT"),
+        (re.compile(r"\b(TEXT|INTEGER|VARCHAR)\s*\[\]", re.IGNORECASE), "TEXT"),
+        (re.compile(r"\bTSVECTOR\b", re.IGNORECASE), "TEXT"),
+        (re.compile(r"::\s*[A-Za-z_]+(\s*\(\s*\d+\s*\))?(\[\])?"), ""),
+        (re.compile(r"=\s*ANY\s*\(\s*\$(\d+)\s*\)", re.IGNORECASE), r"IN (SELECT value FROM json_each(?\1))"),
+        (re.compile(r"\$(\d+)"), r"?\1"),
+        (re.compile(r"\bNOW\s*\(\s*\)", re.IGNORECASE), "CURRENT_TIMESTAMP"),
+        (re.compile(r"\bILIKE\b", re.IGNORECASE), "LIKE"),
+        (re.compile(r"(?<=\s)~(?=\s)"), "REGEXP"),
+        (re.compile(r"\bGREATEST\s*\(", re.IGNORECASE), "MAX("),
+        (re.compile(r"\bLEAST\s*\(", re.IGNORECASE), "MIN("),
+        (re.compile(r"\s+CASCADE\s*(;?)\s*$", re.IGNORECASE), r"\1"),
+    ]
+
+    @staticmethod
+    @lru_cache(maxsize=512)
+    def translate(sql: str) -> Optional[str]:
+        """Translate one statement; None if SQLite has no equivalent and it can be skipped."""
+        if SqliteDialect._SKIP.search(sql):
+            return None
+        for pattern, replacement in SqliteDialect._REWRITES:
+            sql = pattern.sub(replacement, sql)
+        return sql
+
+    # SQLite has no ADD COLUMN IF NOT EXISTS; the backend checks the table first
+    ADD_COLUMN = re.compile(
+        r"^\s*ALTER\s+TABLE\s+(\w+)\s+ADD\s+COLUMN\s+IF\s+NOT\s+EXISTS\s+(\w+)",
+        re.IGNORECASE
+    )
+
+    @staticmethod
+    def split(script: str) -> List[str]:
+        """Split a DDL script into statements (no semicolons inside literals)."""
+        script = re.sub(r"--[^\n]*", "", script)
+        return [stmt.strip() for stmt in script.split(";") if stmt.strip()]
+
+    @staticmethod
+    def param(value: Any) -> Any:
+        """Convert a Python value to something sqlite3 can bind."""
+        if isinstance(value, bool):
+            return int(value)
+        if value is None or isinstance(value, (str, int, float, bytes)):
+            return value
+        if isinstance(value, (datetime, date)):
+            return value.isoformat()
+        if isinstance(value, np.ndarray) or hasattr(value, "detach"):
+            return json.dumps(to_vector(value))
+        if isinstance(value, (dict, list, tuple, set)):
+            return json.dumps(list(value) if isinstance(value, (tuple, set)) else value, default=str)
+        return str(value)
+
+def _regexp(pattern: str, value: Any) -> bool:
+    """SQLite's REGEXP operator, which PostgreSQL's ~ is rewritten to."""
+    return value is not None and re.search(pattern, str(value)) is not None
+
+def _rank(rows: List[Dict[str, Any]], score: str, tiebreak: Optional[str]) -> None:
+    """Sort rows by score, highest first, then by the tiebreak column (NULLs last)."""
+    if tiebreak:
+        # The flag keeps None from being compared with the other values
+        rows.sort(key=lambda row: (row.get(tiebreak) is None, row.get(tiebreak)))
+    rows.sort(key=lambda row: row[score], reverse=True)
+
+def _node_key(key: Dict[str, Any]) -> str:
+    return json.dumps(key, sort_keys=True, default=str)
+
+def _matches(properties: Dict[str, Any], match: Dict[str, Any]) -> bool:
+    return all(properties.get(k) == v for k, v in match.items())
+
+class EmbeddedGraph:
+    """In-process property graph persisted to the graph_* tables.
+
+    Only touched from the backend's worker thread.
+    """
+
+    SCHEMA = """
+    CREATE TABLE IF NOT EXISTS graph_nodes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        label TEXT NOT NULL,
+        node_key TEXT NOT NULL,
+        properties TEXT NOT NULL,
+        UNIQUE(label, node_key)
+    );
+    CREATE TABLE IF NOT EXISTS graph_edges (
+        start_id INTEGER NOT NULL REFERENCES graph_nodes(id) ON DELETE CASCADE,
+        rel_type TEXT NOT NULL,
+        end_id INTEGER NOT NULL REFERENCES graph_nodes(id) ON DELETE CASCADE,
+        properties TEXT NOT NULL,
+        PRIMARY KEY (start_id, rel_type, end_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_graph_nodes_label ON graph_nodes(label);
+    CREATE INDEX IF NOT EXISTS idx_graph_edges_end ON graph_edges(end_id, rel_type);
+    """
+
+    def __init__(self, conn: sqlite3.Connection):
+        self._conn = conn
+        self._nodes: Dict[int, Dict[str, Any]] = {}
+        self._index: Dict[Tuple[str, str], int] = {}
+        self._out: Dict[int, Dict[Tuple[str, int], Dict[str, Any]]] = {}
+        self._in: Dict[int, Dict[Tuple[str, int], Dict[str, Any]]] = {}
+
+    def load(self) -> None:
+        """Create the graph tables and build the adjacency index."""
+        self._conn.executescript(self.SCHEMA)
+        for node_id, label, node_key, properties in self._conn.execute(
+            "SELECT id, label, node_key, properties FROM graph_nodes"
+        ):
+            self._nodes[node_id] = {"label": label, "key": node_key, "properties": json.loads(properties)}
+            self._index[(label, node_key)] = node_id
+        for start_id, rel_type, end_id, properties in self._conn.execute(
+            "SELECT start_id, rel_type, end_id, properties FROM graph_edges"
+        ):
+            props = json.loads(properties)
+            self._out.setdefault(start_id, {})[(rel_type, end_id)] = props
+            self._in.setdefault(end_id, {})[(rel_type, start_id)] = props
+
+    def clear(self) -> None:
+        """Drop every node and relationship."""
+        self._conn.execute("DELETE FROM graph_edges")
+        self._conn.execute("DELETE FROM graph_nodes")
+        self._nodes.clear()
+        self._index.clear()
+        self._out.clear()
+        self._in.clear()
+
+    def merge_node(self, label: str, key: Dict[str, Any], properties: Dict[str, Any]) -> int:
+        node_key = _node_key(key)
+        node_id = self._index.get((label, node_key))
+        merged = dict(self._nodes[node_id]["properties"]) if node_id is not None else {}
+        merged.update(properties)
+        merged.update(key)
+        payload = json.dumps(merged, default=str)
+        if node_id is None:
+            cursor = self._conn.execute(
+                "INSERT INTO graph_nodes (label, node_key, properties) VALUES (?, ?, ?)",
+                (label, node_key, payload)
+            )
+            node_id = cursor.lastrowid
+            self._index[(label, node_key)] = node_id
+        else:
+            self._conn.execute("UPDATE graph_nodes SET properties = ? WHERE id = ?", (payload, node_id))
+        self._nodes[node_id] = {"label": label, "key": node_key, "properties": json.loads(payload)}
+        return node_id
+
+    def merge_relationship(self, start_id: int, rel_type: str, end_id: int, properties: Dict[str, Any]) -> None:
+        merged = dict(self._out.get(start_id, {}).get((rel_type, end_id), {}))
+        merged.update(properties)
+        self._conn.execute(
+            """
+            INSERT INTO graph_edges (start_id, rel_type, end_id, properties) VALUES (?, ?, ?, ?)
+            ON CONFLICT (start_id, rel_type, end_id) DO UPDATE SET properties = excluded.properties
+            """,
+            (start_id, rel_type, end_id, json.dumps(merged, default=str))
+        )
+        self._out.setdefault(start_id, {})[(rel_type, end_id)] = merged
+        self._in.setdefault(end_id, {})[(rel_type, start_id)] = merged
+
+    def find(self, label: str, match: Dict[str, Any]) -> List[int]:
+        node_key = _node_key(match)
+        exact = self._index.get((label, node_key))
+        if exact is not None:
+            return [exact]
+        return [
+            node_id for node_id, node in self._nodes.items()
+            if node["label"] == label and _matches(node["properties"], match)
+        ]
+
+    def properties(self, node_id: int) -> Dict[str, Any]:
+        return self._nodes[node_id]["properties"]
+
+    def label(self, node_id: int) -> str:
+        return self._nodes[node_id]["label"]
+
+    def delete_nodes(self, node_ids: List[int]) -> int:
+        for node_id in node_ids:
+            for (rel_type, end_id) in list(self._out.pop(node_id, {})):
+                self._in.get(end_id, {}).pop((rel_type, node_id), None)
+            for (rel_type, start_id) in list(self._in.pop(node_id, {})):
+                self._out.get(start_id, {}).pop((rel_type, node_id), None)
+            node = self._nodes.pop(node_id)
+            self._index.pop((node["label"], node["key"]), None)
+            self._conn.execute("DELETE FROM graph_edges WHERE start_id = ? OR end_id = ?", (node_id, node_id))
+            self._conn.execute("DELETE FROM graph_nodes WHERE id = ?", (node_id,))
+        return len(node_ids)
+
+    def delete_relationships(self, rel_type: str, match: Dict[str, Any]) -> int:
+        doomed = [
+            (start_id, end_id)
+            for start_id, edges in self._out.items()
+            for (edge_type, end_id), props in edges.items()
+            if edge_type == rel_type and _matches(props, match)
+        ]
+        for start_id, end_id in doomed:
+            self._out[start_id].pop((rel_type, end_id), None)
+            self._in.get(end_id, {}).pop((rel_type, start_id), None)
+            self._conn.execute(
+                "DELETE FROM graph_edges WHERE start_id = ? AND rel_type = ? AND end_id = ?",
+                (start_id, rel_type, end_id)
+            )
+        return len(doomed)
+
+    def traverse(self, start_ids: List[int], rel_types: Sequence[str], direction: str, max_depth: int) -> List[Dict[str, Any]]:
+        wanted = set(rel_types)
+        visited = set(start_ids)
+        seen_edges = set()
+        frontier = deque((node_id, 0) for node_id in start_ids)
+        edges = []
+        while frontier:
+            node_id, depth = frontier.popleft()
+            if depth >= max_depth:
+                continue
+            steps = []
+            if direction in ("out", "both"):
+                steps.extend((rel, nbr, props, node_id, nbr) for (rel, nbr), props in self._out.get(node_id, {}).items())
+            if direction in ("in", "both"):
+                steps.extend((rel, nbr, props, nbr, node_id) for (rel, nbr), props in self._in.get(node_id, {}).items())
+            for rel_type, neighbor, props, source, target in steps:
+                if rel_type not in wanted or (source, rel_type, target) in seen_edges:
+                    continue
+                seen_edges.add((source, rel_type, target))
+                edges.append({
+                    "source": self.properties(source),
+                    "source_label": self.label(source),
+                    "target": self.properties(target),
+                    "target_label": self.label(target),
+                    "type": rel_type,
+                    "properties": props,
+                    "depth": depth + 1
+                })
+                if neighbor not in visited:
+                    visited.add(neighbor)
+                    frontier.append((neighbor, depth + 1))
+        return edges
+
+class EmbeddedBackend(StorageBackend):
+    """SQLite plus an in-process graph in a single file."""
+
+    name = EMBEDDED_BACKEND
+
+    def __init__(self):
+        super().__init__()
+        self._path = storage_config.embedded_path
+        self._conn: Optional[sqlite3.Connection] = None
+        self._graph: Optional[EmbeddedGraph] = None
+        self._executor: Optional[ThreadPoolExecutor] = None
+
+    @property
+    def path(self) -> str:
+        return self._path
+
+    async def _call(self, fn: Callable, *args: Any) -> Any:
+        loop = asyncio.get_running_loop()
+        return await loop.run_in_executor(self._executor, fn, *args)
+
+    async def _open(self) -> None:
+        directory = os.path.dirname(os.path.abspath(self._path))
+        os.makedirs(directory, exist_ok=True)
+        self._executor = ThreadPoolExecutor(max_workers=1, thread_name_prefix="embedded-store")
+
+        def _connect():
+            conn = sqlite3.connect(self._path, check_same_thread=False, isolation_level=None)
+            conn.row_factory = sqlite3.Row
+            conn.execute("PRAGMA journal_mode=WAL")
+            conn.execute("PRAGMA foreign_keys=ON")
+            conn.create_function("regexp", 2, _regexp, deterministic=True)
+            graph = EmbeddedGraph(conn)
+            graph.load()
+            return conn, graph
+
+        self._conn, self._graph = await self._call(_connect)
+
+    async def _close(self) -> None:
+        if self._conn is not None:
+            conn = self._conn
+            self._conn = None
+            await self._call(conn.close)
+        if self._executor is not None:
+            self._executor.shutdown(wait=True)
+            self._executor = None
+
+    # Relational --------------------------------------------------------
+
+    def _execute_sync(self, sql: str, params: Sequence[Any]) -> sqlite3.Cursor:
+        translated = SqliteDialect.translate(sql)
+        if translated is None:
+            return None
+        add_column = SqliteDialect.ADD_COLUMN.match(translated)
+        if add_column:
+            table, column = (check_identifier(name) for name in add_column.groups())
+            if any(row[1] == column for row in self._conn.execute(f"PRAGMA table_info({table})")):
+                return None
+            translated = f"ALTER TABLE {table} ADD COLUMN {column}" + translated[add_column.end():]
+        return self._conn.execute(translated, [SqliteDialect.param(p) for p in params])
+
+    @handle_async_errors(error_types=DatabaseError)
+    async def execute(self, sql: str, *params: Any) -> None:
+        try:
+            await self._call(self._execute_sync, sql, params)
+        except sqlite3.Error as e:
+            raise DatabaseError(f"Embedded statement failed: {e}")
+
+    @handle_async_errors(error_types=DatabaseError)
+    async def execute_script(self, sql: str) -> None:
+        def _run():
+            for statement in SqliteDialect.split(sql):
+                self._execute_sync(statement, ())
+        try:
+            await self._call(_run)
+        except sqlite3.Error as e:
+            raise DatabaseError(f"Embedded script failed: {e}")
+
+    @handle_async_errors(error_types=DatabaseError)
+    async def execute_batch(self, statements: List[Tuple[str, Sequence[Any]]]) -> None:
+        def _run():
+            self._conn.execute("BEGIN")
+            try:
+                for sql, params in statements:
+                    self._execute_sync(sql, params)
+                self._conn.execute("COMMIT")
+            except Exception:
+                self._conn.execute("ROLLBACK")
+                raise
+        try:
+            await self._call(_run)
+        except sqlite3.Error as e:
+            raise DatabaseError(f"Embedded batch failed: {e}")
+
+    async def fetch(self, sql: str, *params: Any) -> List[Dict[str, Any]]:
+        def _run():
+            cursor = self._execute_sync(sql, params)
+            return [dict(row) for row in cursor.fetchall()] if cursor is not None else []
+        try:
+            return await self._call(_run)
+        except sqlite3.Error as e:
+            raise DatabaseError(f"Embedded query failed: {e}")
+
+    async def vector_search(
+        self,
+        table: str,
+        embedding: Any,
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        min_similarity: float = 0.0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        check_identifier(table)
+        query_vector = to_vector(embedding)
+        sql = f"""
+        SELECT {", ".join(columns)}, embedding AS _embedding
+        FROM {table}
+        WHERE embedding IS NOT NULL{f" AND ({where})" if where else ""}
+        """
+        rows = await self.fetch(sql, *params)
+        scored = []
+        for row in rows:
+            stored = row.pop("_embedding")
+            try:
+                vector = json.loads(stored) if isinstance(stored, str) else stored
+            except ValueError:
+                continue
+            similarity = cosine_similarity(query_vector, vector)
+            if similarity >= min_similarity:
+                row["similarity"] = similarity
+                scored.append(row)
+        _rank(scored, "similarity", tiebreak)
+        return scored[offset:offset + limit]
+
+    async def text_search(
+        self,
+        table: str,
+        terms: Sequence[str],
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        check_identifier(table)
+        terms = list(dict.fromkeys(term for term in terms if term))
+        if not terms:
+            return []
+        sql = f"""
+        SELECT {", ".join(columns)}, search_text AS _search_text
+        FROM {table}
+        WHERE search_text IS NOT NULL{f" AND ({where})" if where else ""}
+        """
+        rows = await self.fetch(sql, *params)
+        # BM25 over the rows the filter leaves
+        documents = []
+        frequency = Counter()
+        for row in rows:
+            tokens = (row.pop("_search_text") or "").split()
+            counts = Counter(tokens)
+            documents.append((row, counts, len(tokens)))
+            frequency.update(term for term in terms if term in counts)
+        if not frequency:
+            return []
+        average = sum(length for _, _, length in documents) / len(documents) or 1.0
+        scored = []
+        for row, counts, length in documents:
+            score = 0.0
+            for term in terms:
+                tf = counts.get(term, 0)
+                if tf:
+                    idf = math.log(1 + (len(documents) - frequency[term] + 0.5) / (frequency[term] + 0.5))
+                    score += idf * tf * (_BM25_K1 + 1) / (tf + _BM25_K1 * (1 - _BM25_B + _BM25_B * length / average))
+            if score > 0:
+                row["text_score"] = score
+                scored.append(row)
+        _rank(scored, "text_score", tiebreak)
+        return scored[offset:offset + limit]
+
+    # Graph -------------------------------------------------------------
+
+    async def merge_node(self, label: str, key: Dict[str, Any], properties: Optional[Dict[str, Any]] = None) -> None:
+        check_identifier(label)
+        await self._call(self._graph.merge_node, label, key, properties or {})
+
+    async def merge_relationship(
+        self,
+        start_label: str,
+        start_key: Dict[str, Any],
+        rel_type: str,
+        end_label: str,
+        end_key: Dict[str, Any],
+        properties: Optional[Dict[str, Any]] = None
+    ) -> None:
+        check_identifier(start_label)
+        check_identifier(end_label)
+        check_identifier(rel_type)
+
+        def _run():
+            start_id = self._graph.merge_node(start_label, start_key, {})
+            end_id = self._graph.merge_node(end_label, end_key, {})
+            self._graph.merge_relationship(start_id, rel_type, end_id, properties or {})
+        await self._call(_run)
+
+    async def delete_nodes(self, label: str, match: Dict[str, Any]) -> int:
+        check_identifier(label)
+        return await self._call(lambda: self._graph.delete_nodes(self._graph.find(label, match)))
+
+    async def delete_relationships(self, rel_type: str, match: Dict[str, Any]) -> int:
+        check_identifier(rel_type)
+        return await self._call(self._graph.delete_relationships, rel_type, match)
+
+    async def find_nodes(self, label: str, match: Dict[str, Any], limit: Optional[int] = None) -> List[Dict[str, Any]]:
+        check_identifier(label)
+
+        def _run():
+            ids = self._graph.find(label, match)
+            return [dict(self._graph.properties(node_id)) for node_id in ids[:limit]]
+        return await self._call(_run)
+
+    async def traverse(
+        self,
+        label: str,
+        match: Dict[str, Any],
+        rel_types: Sequence[str],
+        direction: str = "out",
+        max_depth: int = 1
+    ) -> List[Dict[str, Any]]:
+        check_identifier(label)
+        for rel_type in rel_types:
+            check_identifier(rel_type)
+        if direction not in ("out", "in", "both"):
+            raise DatabaseError(f"Invalid traversal direction: {direction}")
+
+        def _run():
+            start_ids = self._graph.find(label, match)
+            return self._graph.traverse(start_ids, rel_types, direction, max_depth)
+        return await self._call(_run)
+
+    async def clear_graph(self) -> None:
+        """Remove all graph data (used by schema resets)."""
+        await self._call(self._graph.clear)
+
+__all__ = [
+    "SqliteDialect",
+    "EmbeddedGraph",
+    "EmbeddedBackend"
+]
@@ -66,6 +66,7 @@ import asyncio
 from typing import Optional, Set, Dict, Any, List
 from utils.logger import log
 from db.connection import connection_manager
+from db.storage import get_storage_backend, is_embedded
 from utils.cache import UnifiedCache, cache_coordinator
 from utils.error_handling import (
     DatabaseError, 
@@ -198,6 +199,10 @@ class GraphSyncCoordinator:
             if not self._initialized:
                 await self.ensure_initialized()
             
+            # Without GDS there is nothing to project; traversals read the graph directly
+            if is_embedded():
+                return True
+            
             projection_name = f"code-repo-{repo_id}"
             
             async with self._lock:
@@ -235,6 +240,9 @@ class GraphSyncCoordinator:
             error_types=ProcessingError,
             severity=ErrorSeverity.ERROR
         ):
+            if is_embedded():
+                return True
+            
             graph_name = f"pattern-repo-{repo_id}"
             
             async with self._lock:
@@ -627,6 +635,9 @@ class GraphSyncCoordinator:
         Returns:
             bool: True if synchronization was successful
         """
+        if is_embedded():
+            return await self._sync_embedded_graph(nodes, relationships)
+        
         async with transaction_scope(distributed=True) as txn:
             try:
                 # Create nodes
@@ -668,8 +679,43 @@ class GraphSyncCoordinator:
                 await log(f"Error in graph synchronization: {str(e)}", level="error")
                 return False
 
+    async def _sync_embedded_graph(self, nodes: List[Dict[str, Any]], relationships: List[Dict[str, Any]]) -> bool:
+        """sync_graph for the embedded backend, keyed on the node `id` property."""
+        backend = await get_storage_backend()
+        labels = {}
+        try:
+            for node in nodes:
+                label = (node.get("labels") or ["Node"])[0]
+                labels[node.get("id")] = label
+                await backend.merge_node(label, {"id": node.get("id")}, node.get("properties", {}))
+            
+            for rel in relationships:
+                await backend.merge_relationship(
+                    labels.get(rel.get("start_node"), "Node"), {"id": rel.get("start_node")},
+                    rel.get("type"),
+                    labels.get(rel.get("end_node"), "Node"), {"id": rel.get("end_node")},
+                    {**rel.get("properties", {}), "id": rel.get("id")}
+                )
+            return True
+        except Exception as e:
+            await log(f"Error in graph synchronization: {str(e)}", level="error")
+            return False
+
     async def store_pattern_node(self, pattern_data: dict) -> None:
         """Store pattern node with tree-sitter enhancements."""
+        if is_embedded():
+            backend = await get_storage_backend()
+            await backend.merge_node("Pattern", {"id": pattern_data["pattern_id"]}, {
+                "type": pattern_data["pattern_type"],
+                "language": pattern_data.get("language"),
+                "confidence": pattern_data.get("confidence", 0.7),
+                "ai_confidence": pattern_data.get("ai_confidence"),
+                "complexity": pattern_data.get("complexity"),
+                "tree_sitter_type": pattern_data.get("tree_sitter_type"),
+                "tree_sitter_language": pattern_data.get("tree_sitter_language")
+            })
+            return
+        
         session = await connection_manager.get_session()
         try:
             async with AsyncErrorBoundary(
@@ -754,8 +800,10 @@ async def get_graph_sync() -> GraphSyncCoordinator:
     Returns:
         GraphSyncCoordinator: The singleton graph sync coordinator instance
     """
+    global _graph_sync, graph_sync
     if not _graph_sync._initialized:
-        await _graph_sync.ensure_initialized()
+        _graph_sync = await GraphSyncCoordinator.create()
+        graph_sync = _graph_sync
     return _graph_sync
 
 # For backward compatibility and direct access
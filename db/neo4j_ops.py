@@ -10,6 +10,7 @@ import asyncio
 import time
 import functools
 from db.connection import connection_manager
+from db.storage import is_embedded
 from db.transaction import transaction_scope
 from db.retry_utils import (
     with_retry, 
@@ -171,8 +172,9 @@ class Neo4jTools:
                 error_types=DatabaseError,
                 severity=ErrorSeverity.CRITICAL
             ):
-                # Initialize connection
-                await connection_manager.initialize()
+                # Initialize connection (the embedded backend has no Neo4j)
+                if not is_embedded():
+                    await connection_manager.initialize()
                 
                 # Register shutdown handler
                 register_shutdown_handler(instance.cleanup)
@@ -298,7 +300,18 @@ neo4j_tools = _neo4j_tools
 # Update the run_query function to properly classify errors
 @with_retry()
 async def run_query(query: str, params: Optional[Dict[str, Any]] = None) -> List[Dict[str, Any]]:
-    """Run a Neo4j query and return results."""
+    """Run a Neo4j query and return results.
+
+    Raw Cypher needs Neo4j: on the embedded backend this raises, so callers
+    not yet ported to the StorageBackend graph API [6.9] fail visibly
+    rather than finding nothing.
+    """
+    if is_embedded():
+        await log("Cypher query not supported on the embedded storage backend", level="warning")
+        raise NonRetryableNeo4jError(
+            "Cypher queries are not supported on the embedded storage backend; "
+            "use the server backend (Neo4j) for this operation"
+        )
     session = await connection_manager.get_session()
     try:
         async with AsyncErrorBoundary("neo4j_query_execution"):
@@ -359,6 +372,13 @@ async def create_schema_indexes_and_constraints():
                 await run_query("CREATE INDEX IF NOT EXISTS FOR (d:Documentation) ON (d.repo_id, d.path)")
                 await run_query("CREATE INDEX IF NOT EXISTS FOR (r:Repository) ON (r.id)")
                 await run_query("CREATE INDEX IF NOT EXISTS FOR (l:Language) ON (l.name)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (s:Symbol) ON (s.repo_id, s.id)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (s:Symbol) ON (s.repo_id, s.name)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (s:Symbol) ON (s.repo_id, s.qualified_name)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (s:Symbol) ON (s.repo_id, s.file_path)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (c:Crate) ON (c.repo_id, c.name)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (m:Module) ON (m.repo_id, m.crate, m.path)")
+                await run_query("CREATE INDEX IF NOT EXISTS FOR (p:Package) ON (p.repo_id, p.ecosystem, p.name)")
                 
                 # Enhanced pattern indexes
                 await run_query("CREATE INDEX IF NOT EXISTS FOR (p:Pattern) ON (p.id, p.type)")
@@ -374,6 +394,7 @@ async def create_schema_indexes_and_constraints():
                 # Create constraints for uniqueness
                 await run_query("CREATE CONSTRAINT IF NOT EXISTS FOR (r:Repository) REQUIRE r.id IS UNIQUE")
                 await run_query("CREATE CONSTRAINT IF NOT EXISTS FOR (c:Code) REQUIRE (c.repo_id, c.file_path) IS UNIQUE")
+                await run_query("CREATE CONSTRAINT IF NOT EXISTS FOR (s:Symbol) REQUIRE (s.repo_id, s.id) IS UNIQUE")
                 await run_query("CREATE CONSTRAINT IF NOT EXISTS FOR (p:Pattern) REQUIRE p.id IS UNIQUE")
                 await run_query("CREATE CONSTRAINT IF NOT EXISTS FOR (ai:AIInsight) REQUIRE ai.id IS UNIQUE")
                 
@@ -420,10 +420,10 @@ class PatternStorageCoordinator:
     ) -> List[Dict[str, Any]]:
         """Get code patterns from database."""
         query = """
-            SELECT p.*, c.file_path, c.language
+            SELECT p.*
             FROM code_patterns p
-            JOIN code_snippets c ON c.id = p.code_id
-            WHERE c.repo_id = $1
+            WHERE p.repo_id = $1
+            ORDER BY p.pattern_id
         """
         return await txn.fetch(query, repo_id)
     
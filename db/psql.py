@@ -1,7 +1,8 @@
 """[6.1] PostgreSQL database operations with AI support.
 
 This module provides high-level database operations using the centralized connection manager,
-with special handling for AI-enhanced operations.
+with special handling for AI-enhanced operations. With the embedded storage backend [6.10]
+the same calls are served from the local SQLite file.
 """
 
 import asyncio
@@ -17,6 +18,7 @@ from utils.error_handling import (
 from db.retry_utils import RetryManager, RetryConfig
 from utils.async_runner import submit_async_task, get_loop
 from db.connection import connection_manager
+from db.storage import get_storage_backend, is_embedded
 from utils.shutdown import register_shutdown_handler
 from utils.cache import UnifiedCache, cache_coordinator
 from utils.health_monitor import global_health_monitor, ComponentStatus, monitor_database
@@ -95,6 +97,9 @@ async def query(
     _metrics["cache_misses"] += 1
     
     async def _execute_query():
+        if is_embedded():
+            backend = await get_storage_backend()
+            return await backend.fetch(sql, *(params or ()))
         conn = await connection_manager.get_connection()
         try:
             async with conn.transaction():
@@ -156,6 +161,9 @@ async def execute(
     _metrics["total_queries"] += 1
     
     async def _execute_command():
+        if is_embedded():
+            backend = await get_storage_backend()
+            return await backend.execute(sql, *(params or ()))
         conn = await connection_manager.get_connection()
         try:
             async with conn.transaction():
@@ -207,6 +215,9 @@ async def execute_many(
 ) -> None:
     """Execute a SQL command with multiple parameter sets."""
     async def _execute_many_command():
+        if is_embedded():
+            backend = await get_storage_backend()
+            return await backend.execute_batch([(sql, params) for params in params_list])
         conn = await connection_manager.get_connection()
         try:
             async with conn.transaction():
@@ -229,6 +240,11 @@ async def execute_batch(
 ) -> None:
     """Execute a batch of SQL commands efficiently."""
     async def _execute_batch():
+        if is_embedded():
+            backend = await get_storage_backend()
+            for i in range(0, len(params_list), batch_size):
+                await backend.execute_batch([(sql, params) for params in params_list[i:i + batch_size]])
+            return
         conn = await connection_manager.get_connection()
         try:
             async with conn.transaction():
@@ -251,6 +267,9 @@ async def execute_parallel_queries(
 ) -> List[List[Dict[str, Any]]]:
     """Execute multiple queries in parallel."""
     async def _execute_single_query(sql: str, params: Optional[Tuple] = None) -> List[Dict[str, Any]]:
+        if is_embedded():
+            backend = await get_storage_backend()
+            return await backend.fetch(sql, *(params or ()))
         conn = await connection_manager.get_connection()
         try:
             async with conn.transaction():
@@ -291,6 +310,10 @@ async def execute_vector_similarity_search(
     min_similarity: float = 0.7
 ) -> List[Dict[str, Any]]:
     """Execute vector similarity search optimized for AI operations."""
+    if is_embedded():
+        backend = await get_storage_backend()
+        return await backend.vector_search(table, embedding, limit=limit, min_similarity=min_similarity)
+    
     sql = f"""
     SELECT *,
            1 - (embedding <=> $1::vector) as similarity
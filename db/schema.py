@@ -22,6 +22,7 @@ from utils.error_handling import (
 from db.retry_utils import RetryManager, RetryConfig
 from utils.async_runner import submit_async_task, get_loop
 from db.connection import connection_manager
+from db.storage import get_storage_backend, is_embedded
 from utils.shutdown import register_shutdown_handler
 from db.transaction import transaction_scope
 from utils.health_monitor import global_health_monitor, ComponentStatus, monitor_database
@@ -128,25 +129,11 @@ class SchemaManager:
         if not self._initialized:
             await self.ensure_initialized()
             
-        task = asyncio.create_task(connection_manager.get_postgres_connection())
+        backend = await get_storage_backend()
+        task = asyncio.create_task(backend.execute_script(sql))
         self._pending_tasks.add(task)
         try:
-            conn = await task
-            try:
-                async with conn.transaction():
-                    task = asyncio.create_task(conn.execute(sql))
-                    self._pending_tasks.add(task)
-                    try:
-                        await task
-                    finally:
-                        self._pending_tasks.remove(task)
-            finally:
-                task = asyncio.create_task(connection_manager.release_postgres_connection(conn))
-                self._pending_tasks.add(task)
-                try:
-                    await task
-                finally:
-                    self._pending_tasks.remove(task)
+            await task
         finally:
             self._pending_tasks.remove(task)
     
@@ -165,12 +152,18 @@ class SchemaManager:
                 tables = [
                     "code_patterns", "doc_patterns", "arch_patterns",
                     "repo_doc_relations", "doc_versions", "doc_clusters",
-                    "repo_docs", "code_snippets", "repositories"
+                    "repo_docs", "indexing_job_files", "indexing_jobs", "findings", "file_symbols", "file_manifest",
+                    "code_chunks", "code_snippets", "repositories"
                 ]
                 
                 for table in tables:
                     await self._execute_query(f"DROP TABLE IF EXISTS {table} CASCADE;")
                 
+                # The embedded graph shares the file with the tables
+                if is_embedded():
+                    backend = await get_storage_backend()
+                    await backend.clear_graph()
+                
                 # Record success and timing
                 self._metrics["successful_operations"] += 1
                 operation_time = time.time() - start_time
@@ -200,11 +193,19 @@ class SchemaManager:
             repo_type TEXT DEFAULT 'active',  -- 'active' or 'reference'
             active_repo_id INTEGER,           -- If this is a reference repo, stores the ID of the active repo it is associated with.
             last_updated TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            indexed_commit TEXT,              -- Commit the index reflects
+            indexed_ref TEXT,                 -- Branch, tag or sha it was indexed from; NULL for the working tree
+            indexed_dirty BOOLEAN,            -- Working tree had uncommitted changes
+            root_path TEXT,                   -- Directory indexed; path filters match file paths relative to it
             CONSTRAINT fk_active_repo
                 FOREIGN KEY(active_repo_id)
                     REFERENCES repositories(id)
                     ON DELETE SET NULL
         );
+        ALTER TABLE repositories ADD COLUMN IF NOT EXISTS indexed_commit TEXT;
+        ALTER TABLE repositories ADD COLUMN IF NOT EXISTS indexed_ref TEXT;
+        ALTER TABLE repositories ADD COLUMN IF NOT EXISTS indexed_dirty BOOLEAN;
+        ALTER TABLE repositories ADD COLUMN IF NOT EXISTS root_path TEXT;
         """
         await self._execute_query(sql)
     
@@ -226,6 +227,117 @@ class SchemaManager:
         """
         await self._execute_query(sql)
     
+    async def create_code_chunks_table(self, txn) -> None:
+        """[6.6.13] Functions, classes and methods of each file, embedded one by one."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS code_chunks (
+            id SERIAL PRIMARY KEY,
+            repo_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+            file_path TEXT NOT NULL,
+            language TEXT,
+            kind TEXT NOT NULL,              -- 'function', 'class', 'method' or 'module'
+            symbol TEXT,                     -- qualified name, e.g. 'Parser.parse'
+            parent_symbol TEXT,              -- enclosing class or function
+            start_line INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            part INTEGER NOT NULL DEFAULT 0, -- window of a block split for size
+            content TEXT NOT NULL,
+            embedding VECTOR(768),
+            search_text TEXT,                -- identifier tokens for full-text search
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        ALTER TABLE code_chunks ADD COLUMN IF NOT EXISTS search_text TEXT;
+        CREATE INDEX IF NOT EXISTS idx_code_chunks_file ON code_chunks(repo_id, file_path);
+        CREATE INDEX IF NOT EXISTS idx_code_chunks_embedding
+        ON code_chunks USING ivfflat (embedding vector_cosine_ops);
+        CREATE INDEX IF NOT EXISTS idx_code_chunks_search
+        ON code_chunks USING gin (to_tsvector('simple', coalesce(search_text, '')));
+        """
+        await self._execute_query(sql)
+    
+    async def create_file_manifest_table(self, txn) -> None:
+        """[6.6.14] Track what each indexed file looked like when it was indexed."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS file_manifest (
+            repo_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+            file_path TEXT NOT NULL,
+            content_hash TEXT NOT NULL,      -- sha256 of the file bytes
+            parser_version TEXT NOT NULL,
+            embedding_model TEXT NOT NULL,
+            indexed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (repo_id, file_path)
+        );
+        """
+        await self._execute_query(sql)
+    
+    async def create_indexing_jobs_table(self, txn) -> None:
+        """[6.6.15] Persist indexing runs so an interrupted one can be resumed."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS indexing_jobs (
+            id SERIAL PRIMARY KEY,
+            repo_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+            repo_path TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'running',  -- 'running', 'interrupted', 'failed' or 'completed'
+            options JSONB,                           -- full, ref and commit the run was started with
+            total_files INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_indexing_jobs_repo ON indexing_jobs(repo_id, id);
+        """
+        await self._execute_query(sql)
+    
+    async def create_indexing_job_files_table(self, txn) -> None:
+        """[6.6.10] Per-file progress of an indexing job."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS indexing_job_files (
+            job_id INTEGER NOT NULL REFERENCES indexing_jobs(id) ON DELETE CASCADE,
+            file_path TEXT NOT NULL,
+            content_hash TEXT,                     -- sha256 when the job was planned
+            status TEXT NOT NULL DEFAULT 'pending',  -- 'pending', 'stored' or 'failed'
+            last_error TEXT,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (job_id, file_path)
+        );
+        """
+        await self._execute_query(sql)
+    
+    async def create_file_symbols_table(self, txn) -> None:
+        """[6.6.11] Definitions, references and imports extracted from each file."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS file_symbols (
+            repo_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+            file_path TEXT NOT NULL,
+            language TEXT NOT NULL,
+            symbols JSONB NOT NULL,          -- {"definitions", "references", "imports", "scopes", ...}
+            graph_hash TEXT,                 -- digest of the Symbol nodes and edges last written
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (repo_id, file_path)
+        );
+        """
+        await self._execute_query(sql)
+    
+    async def create_findings_table(self, txn) -> None:
+        """[6.6.12] Results of repository analyses, replaced on every run of an analysis."""
+        sql = """
+        CREATE TABLE IF NOT EXISTS findings (
+            id SERIAL PRIMARY KEY,
+            repo_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+            analysis TEXT NOT NULL,          -- e.g. 'rust_audit'
+            kind TEXT NOT NULL,              -- what was found, e.g. 'unsafe_block'
+            severity TEXT NOT NULL,          -- 'info', 'warning' or 'error'
+            file_path TEXT NOT NULL,
+            line INTEGER,
+            symbol TEXT,                     -- enclosing function or item
+            message TEXT NOT NULL,
+            details JSONB,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX IF NOT EXISTS idx_findings_repo ON findings(repo_id, analysis);
+        """
+        await self._execute_query(sql)
+    
     async def create_repo_docs_table(self, txn) -> None:
         """[6.6.2] Create documentation storage with versioning."""
         sql_table = """
@@ -240,16 +352,20 @@ class SchemaManager:
             embedding VECTOR(768) NULL,
             metadata JSONB,
             quality_metrics JSONB,
+            search_text TEXT,        -- identifier tokens for full-text search
             created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
             updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
         );
+        ALTER TABLE repo_docs ADD COLUMN IF NOT EXISTS search_text TEXT;
         """
         await self._execute_query(sql_table)
         
-        # Vector similarity index
+        # Vector similarity and full-text indexes
         sql_index = """
         CREATE INDEX IF NOT EXISTS idx_repo_docs_embedding 
         ON repo_docs USING ivfflat (embedding vector_cosine_ops);
+        CREATE INDEX IF NOT EXISTS idx_repo_docs_search
+        ON repo_docs USING gin (to_tsvector('simple', coalesce(search_text, '')));
         """
         await self._execute_query(sql_index)
     
@@ -354,14 +470,22 @@ class SchemaManager:
             try:
                 async with AsyncErrorBoundary("schema creation", error_types=(SchemaError, PostgresError, Neo4jError)):
                     # Initialize connections
-                    await connection_manager.initialize_postgres()
-                    await connection_manager.initialize()
+                    if not is_embedded():
+                        await connection_manager.initialize_postgres()
+                        await connection_manager.initialize()
+                    await get_storage_backend()
                     
                     async with transaction_scope(distributed=True) as txn:
                         # Create PostgreSQL tables in order of dependencies
                         tables = [
                             self.create_repositories_table,
                             self.create_code_snippets_table,
+                            self.create_code_chunks_table,
+                            self.create_file_manifest_table,
+                            self.create_indexing_jobs_table,
+                            self.create_indexing_job_files_table,
+                            self.create_file_symbols_table,
+                            self.create_findings_table,
                             self.create_repo_docs_table,
                             self.create_repo_doc_relations_table,
                             self.create_doc_versions_table,
@@ -379,13 +503,14 @@ class SchemaManager:
                             finally:
                                 self._pending_tasks.remove(task)
                         
-                        # Create Neo4j schema
-                        await self.create_neo4j_schema(txn)
+                        # Create Neo4j schema (the embedded graph needs none)
+                        if not is_embedded():
+                            await self.create_neo4j_schema(txn)
                         
                         # Record schema creation in transaction metrics
                         await txn.record_operation("create_all_tables", {
                             "postgres_tables": len(tables),
-                            "neo4j_schema": True,
+                            "neo4j_schema": not is_embedded(),
                             "timestamp": time.time()
                         })
                     
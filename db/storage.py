@@ -0,0 +1,533 @@
+"""[6.9] Pluggable storage backends.
+
+Flow:
+1. Backend Selection:
+   - storage_config.backend chooses the implementation ("server" or "embedded")
+   - get_storage_backend() returns the process-wide singleton
+
+2. Backend Interface:
+   - Relational: execute, execute_script, execute_batch, fetch, fetchrow
+   - Vector: vector_search with cosine similarity over an embedding column
+   - Full text: text_search over the identifier tokens in a search_text column
+   - Graph: merge_node, merge_relationship, delete_nodes, find_nodes, traverse
+
+3. Implementations:
+   - ServerBackend: PostgreSQL/pgvector via connection_manager, Neo4j via run_query
+   - EmbeddedBackend [6.10]: single SQLite file plus an in-process graph
+
+SQL handed to a backend is written in the PostgreSQL dialect with $N
+placeholders; the embedded backend translates it. Graph labels and
+relationship types are validated identifiers, never user input.
+"""
+
+import re
+import json
+import asyncio
+from abc import ABC, abstractmethod
+from typing import Any, Dict, List, Optional, Sequence, Set, Tuple
+
+import numpy as np
+
+from config.config import storage_config
+from utils.logger import log
+from utils.error_handling import (
+    handle_async_errors,
+    AsyncErrorBoundary,
+    DatabaseError,
+    PostgresError,
+    Neo4jError,
+    ErrorSeverity
+)
+from utils.shutdown import register_shutdown_handler
+from utils.health_monitor import global_health_monitor, ComponentStatus
+
+SERVER_BACKEND = "server"
+EMBEDDED_BACKEND = "embedded"
+
+_IDENTIFIER = re.compile(r"^[A-Za-z_][A-Za-z0-9_]*$")
+_TERM = re.compile(r"^[a-z0-9]+$")
+
+def check_identifier(name: str) -> str:
+    """Validate a label, relationship type or table name before interpolation."""
+    if not name or not _IDENTIFIER.match(name):
+        raise DatabaseError(f"Invalid identifier: {name!r}")
+    return name
+
+def to_vector(embedding: Any) -> Optional[List[float]]:
+    """Normalize an embedding (ndarray, tensor or sequence) to a list of floats."""
+    if embedding is None:
+        return None
+    if hasattr(embedding, "detach"):
+        embedding = embedding.detach().cpu().numpy()
+    return [float(x) for x in np.asarray(embedding, dtype=np.float32).ravel()]
+
+def cosine_similarity(a: Sequence[float], b: Sequence[float]) -> float:
+    """Cosine similarity of two vectors; 0.0 when either is empty or zero."""
+    va = np.asarray(a, dtype=np.float32)
+    vb = np.asarray(b, dtype=np.float32)
+    if va.size == 0 or va.shape != vb.shape:
+        return 0.0
+    denom = float(np.linalg.norm(va) * np.linalg.norm(vb))
+    return float(np.dot(va, vb) / denom) if denom else 0.0
+
+class StorageBackend(ABC):
+    """Interface shared by the server and embedded storage backends."""
+
+    name: str = ""
+
+    def __init__(self):
+        """Private constructor - use create() instead."""
+        self._initialized = False
+        self._pending_tasks: Set[asyncio.Task] = set()
+        self._lock = asyncio.Lock()
+
+    async def ensure_initialized(self):
+        """Ensure the instance is properly initialized before use."""
+        if not self._initialized:
+            raise DatabaseError(f"{self.__class__.__name__} not initialized. Use create() to initialize.")
+        return True
+
+    @classmethod
+    async def create(cls) -> 'StorageBackend':
+        """Async factory method to create and initialize a backend instance."""
+        instance = cls()
+        try:
+            async with AsyncErrorBoundary(
+                operation_name=f"{cls.name} storage backend initialization",
+                error_types=DatabaseError,
+                severity=ErrorSeverity.CRITICAL
+            ):
+                await instance._open()
+
+                # Register shutdown handler
+                register_shutdown_handler(instance.cleanup)
+
+                # Initialize health monitoring
+                global_health_monitor.register_component(f"storage_{cls.name}")
+
+                instance._initialized = True
+                await log(f"{cls.name} storage backend initialized", level="info")
+                return instance
+        except Exception as e:
+            await log(f"Error initializing {cls.name} storage backend: {e}", level="error")
+            await instance.cleanup()
+            raise DatabaseError(f"Failed to initialize {cls.name} storage backend: {e}")
+
+    @property
+    def supports_projections(self) -> bool:
+        """Whether the backend has GDS-style named graph projections."""
+        return False
+
+    # Lifecycle ---------------------------------------------------------
+
+    @abstractmethod
+    async def _open(self) -> None:
+        """Open connections or files."""
+
+    @abstractmethod
+    async def _close(self) -> None:
+        """Close connections or files."""
+
+    async def cleanup(self):
+        """Clean up backend resources."""
+        try:
+            if self._pending_tasks:
+                for task in self._pending_tasks:
+                    if not task.done():
+                        task.cancel()
+                await asyncio.gather(*self._pending_tasks, return_exceptions=True)
+                self._pending_tasks.clear()
+
+            await self._close()
+
+            if self._initialized:
+                global_health_monitor.unregister_component(f"storage_{self.name}")
+            self._initialized = False
+            await log(f"{self.name} storage backend cleaned up", level="info")
+        except Exception as e:
+            await log(f"Error cleaning up {self.name} storage backend: {e}", level="error")
+
+    # Relational --------------------------------------------------------
+
+    @abstractmethod
+    async def execute(self, sql: str, *params: Any) -> None:
+        """Execute a single statement."""
+
+    @abstractmethod
+    async def execute_script(self, sql: str) -> None:
+        """Execute one or more parameterless statements (DDL)."""
+
+    @abstractmethod
+    async def execute_batch(self, statements: List[Tuple[str, Sequence[Any]]]) -> None:
+        """Execute several statements atomically."""
+
+    @abstractmethod
+    async def fetch(self, sql: str, *params: Any) -> List[Dict[str, Any]]:
+        """Run a query and return all rows as dicts."""
+
+    async def fetchrow(self, sql: str, *params: Any) -> Optional[Dict[str, Any]]:
+        """Run a query and return the first row, if any."""
+        rows = await self.fetch(sql, *params)
+        return rows[0] if rows else None
+
+    @abstractmethod
+    async def vector_search(
+        self,
+        table: str,
+        embedding: Any,
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        min_similarity: float = 0.0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        """Rank rows of `table` by cosine similarity of their `embedding` column.
+
+        `where` may reference `params` as $1..$N. Each row carries a
+        `similarity` key in [-1, 1], highest first; rows with equal
+        similarity are ordered by the `tiebreak` column, so pages are stable.
+        Rows below `min_similarity` are filtered out before paging.
+        """
+
+    @abstractmethod
+    async def text_search(
+        self,
+        table: str,
+        terms: Sequence[str],
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        """Rank rows of `table` whose `search_text` column holds any of `terms`.
+
+        Terms are tokens from semantic/lexical.py [5.2]. `where` may
+        reference `params` as $1..$N. Each row carries a positive
+        `text_score`, highest first, ties ordered by the `tiebreak` column.
+        """
+
+    # Graph -------------------------------------------------------------
+
+    @abstractmethod
+    async def merge_node(self, label: str, key: Dict[str, Any], properties: Optional[Dict[str, Any]] = None) -> None:
+        """Create or update the node identified by (label, key)."""
+
+    @abstractmethod
+    async def merge_relationship(
+        self,
+        start_label: str,
+        start_key: Dict[str, Any],
+        rel_type: str,
+        end_label: str,
+        end_key: Dict[str, Any],
+        properties: Optional[Dict[str, Any]] = None
+    ) -> None:
+        """Create or update a relationship, creating missing endpoints."""
+
+    @abstractmethod
+    async def delete_nodes(self, label: str, match: Dict[str, Any]) -> int:
+        """Delete nodes whose properties contain `match`, with their relationships."""
+
+    @abstractmethod
+    async def delete_relationships(self, rel_type: str, match: Dict[str, Any]) -> int:
+        """Delete relationships of a type whose properties contain `match`."""
+
+    @abstractmethod
+    async def find_nodes(self, label: str, match: Dict[str, Any], limit: Optional[int] = None) -> List[Dict[str, Any]]:
+        """Return properties of nodes whose properties contain `match`."""
+
+    @abstractmethod
+    async def traverse(
+        self,
+        label: str,
+        match: Dict[str, Any],
+        rel_types: Sequence[str],
+        direction: str = "out",
+        max_depth: int = 1
+    ) -> List[Dict[str, Any]]:
+        """Breadth-first walk from the matching nodes.
+
+        Returns one dict per relationship visited:
+        {"source", "source_label", "target", "target_label", "type",
+         "properties", "depth"}; source/target follow the edge direction.
+        """
+
+    @abstractmethod
+    async def clear_graph(self) -> None:
+        """Remove every node and relationship."""
+
+class ServerBackend(StorageBackend):
+    """PostgreSQL with pgvector plus Neo4j."""
+
+    name = SERVER_BACKEND
+
+    @property
+    def supports_projections(self) -> bool:
+        return True
+
+    async def _open(self) -> None:
+        from db.connection import connection_manager
+        await connection_manager.initialize()
+
+    async def _close(self) -> None:
+        # The connection manager owns and closes its own pools
+        return None
+
+    @staticmethod
+    def _prepare(params: Sequence[Any]) -> List[Any]:
+        prepared = []
+        for value in params:
+            if isinstance(value, np.ndarray) or hasattr(value, "detach"):
+                value = "[" + ",".join(str(x) for x in to_vector(value)) + "]"
+            elif isinstance(value, dict):
+                value = json.dumps(value)
+            prepared.append(value)
+        return prepared
+
+    async def _run(self, method: str, sql: str, params: Sequence[Any]):
+        from db.connection import connection_manager
+        conn = await connection_manager.get_postgres_connection()
+        try:
+            task = asyncio.create_task(getattr(conn, method)(sql, *self._prepare(params)))
+            self._pending_tasks.add(task)
+            try:
+                return await task
+            finally:
+                self._pending_tasks.remove(task)
+        finally:
+            await connection_manager.release_postgres_connection(conn)
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def execute(self, sql: str, *params: Any) -> None:
+        await self._run("execute", sql, params)
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def execute_script(self, sql: str) -> None:
+        await self._run("execute", sql, ())
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def execute_batch(self, statements: List[Tuple[str, Sequence[Any]]]) -> None:
+        from db.connection import connection_manager
+        conn = await connection_manager.get_postgres_connection()
+        try:
+            async with conn.transaction():
+                for sql, params in statements:
+                    await conn.execute(sql, *self._prepare(params))
+        finally:
+            await connection_manager.release_postgres_connection(conn)
+
+    async def fetch(self, sql: str, *params: Any) -> List[Dict[str, Any]]:
+        rows = await self._run("fetch", sql, params)
+        return [dict(row) for row in rows or []]
+
+    async def vector_search(
+        self,
+        table: str,
+        embedding: Any,
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        min_similarity: float = 0.0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        check_identifier(table)
+        vector_param = f"${len(params) + 1}"
+        similarity_param = f"${len(params) + 2}"
+        # The threshold is part of the filter, so LIMIT/OFFSET page over rows that pass it
+        sql = f"""
+        SELECT {", ".join(columns)}, 1 - (embedding <=> {vector_param}::vector) AS similarity
+        FROM {table}
+        WHERE embedding IS NOT NULL
+          AND 1 - (embedding <=> {vector_param}::vector) >= {similarity_param}{f" AND ({where})" if where else ""}
+        ORDER BY embedding <=> {vector_param}::vector{f", {check_identifier(tiebreak)}" if tiebreak else ""}
+        LIMIT {int(limit)} OFFSET {int(offset)};
+        """
+        return await self.fetch(sql, *params, np.asarray(to_vector(embedding)), float(min_similarity))
+
+    async def text_search(
+        self,
+        table: str,
+        terms: Sequence[str],
+        columns: Sequence[str] = ("*",),
+        where: Optional[str] = None,
+        params: Sequence[Any] = (),
+        limit: int = 10,
+        offset: int = 0,
+        tiebreak: Optional[str] = None
+    ) -> List[Dict[str, Any]]:
+        check_identifier(table)
+        # Tokens are lowercase alphanumerics, so they are safe in a tsquery
+        terms = [term for term in dict.fromkeys(terms) if _TERM.match(term)]
+        if not terms:
+            return []
+        query_param = f"${len(params) + 1}"
+        # Same expression as the GIN index in schema.py, so the index is used
+        document = "to_tsvector('simple', coalesce(search_text, ''))"
+        sql = f"""
+        SELECT {", ".join(columns)}, ts_rank_cd({document}, to_tsquery('simple', {query_param}), 1) AS text_score
+        FROM {table}
+        WHERE {document} @@ to_tsquery('simple', {query_param}){f" AND ({where})" if where else ""}
+        ORDER BY text_score DESC{f", {check_identifier(tiebreak)}" if tiebreak else ""}
+        LIMIT {int(limit)} OFFSET {int(offset)};
+        """
+        return await self.fetch(sql, *params, " | ".join(terms))
+
+    @staticmethod
+    def _match_clause(alias: str, match: Dict[str, Any], prefix: str) -> Tuple[str, Dict[str, Any]]:
+        conditions = []
+        params = {}
+        for i, (prop, value) in enumerate(match.items()):
+            check_identifier(prop)
+            conditions.append(f"{alias}.{prop} = ${prefix}{i}")
+            params[f"{prefix}{i}"] = value
+        return " AND ".join(conditions) or "true", params
+
+    async def _cypher(self, query: str, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        from db.neo4j_ops import run_query
+        return await run_query(query, params) or []
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError))
+    async def merge_node(self, label: str, key: Dict[str, Any], properties: Optional[Dict[str, Any]] = None) -> None:
+        check_identifier(label)
+        key_props = ", ".join(f"{check_identifier(k)}: $key.{k}" for k in key)
+        await self._cypher(
+            f"MERGE (n:{label} {{{key_props}}}) SET n += $properties, n.updated_at = timestamp()",
+            {"key": key, "properties": {**(properties or {}), **key}}
+        )
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError))
+    async def merge_relationship(
+        self,
+        start_label: str,
+        start_key: Dict[str, Any],
+        rel_type: str,
+        end_label: str,
+        end_key: Dict[str, Any],
+        properties: Optional[Dict[str, Any]] = None
+    ) -> None:
+        check_identifier(start_label)
+        check_identifier(end_label)
+        check_identifier(rel_type)
+        start_props = ", ".join(f"{check_identifier(k)}: $start.{k}" for k in start_key)
+        end_props = ", ".join(f"{check_identifier(k)}: $end.{k}" for k in end_key)
+        await self._cypher(
+            f"""
+            MERGE (a:{start_label} {{{start_props}}})
+            MERGE (b:{end_label} {{{end_props}}})
+            MERGE (a)-[r:{rel_type}]->(b)
+            SET r += $properties
+            """,
+            {"start": start_key, "end": end_key, "properties": properties or {}}
+        )
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=0)
+    async def delete_nodes(self, label: str, match: Dict[str, Any]) -> int:
+        check_identifier(label)
+        condition, params = self._match_clause("n", match, "m")
+        rows = await self._cypher(
+            f"MATCH (n:{label}) WHERE {condition} DETACH DELETE n RETURN count(*) AS deleted",
+            params
+        )
+        return rows[0]["deleted"] if rows else 0
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=0)
+    async def delete_relationships(self, rel_type: str, match: Dict[str, Any]) -> int:
+        check_identifier(rel_type)
+        condition, params = self._match_clause("r", match, "m")
+        rows = await self._cypher(
+            f"MATCH ()-[r:{rel_type}]->() WHERE {condition} DELETE r RETURN count(*) AS deleted",
+            params
+        )
+        return rows[0]["deleted"] if rows else 0
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+    async def find_nodes(self, label: str, match: Dict[str, Any], limit: Optional[int] = None) -> List[Dict[str, Any]]:
+        check_identifier(label)
+        condition, params = self._match_clause("n", match, "m")
+        limit_clause = f" LIMIT {int(limit)}" if limit else ""
+        rows = await self._cypher(
+            f"MATCH (n:{label}) WHERE {condition} RETURN properties(n) AS node{limit_clause}",
+            params
+        )
+        return [row["node"] for row in rows]
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+    async def traverse(
+        self,
+        label: str,
+        match: Dict[str, Any],
+        rel_types: Sequence[str],
+        direction: str = "out",
+        max_depth: int = 1
+    ) -> List[Dict[str, Any]]:
+        check_identifier(label)
+        types = "|".join(check_identifier(t) for t in rel_types)
+        condition, params = self._match_clause("s", match, "m")
+        pattern = {
+            "out": f"-[rels:{types}*1..{int(max_depth)}]->",
+            "in": f"<-[rels:{types}*1..{int(max_depth)}]-",
+            "both": f"-[rels:{types}*1..{int(max_depth)}]-"
+        }[direction]
+        rows = await self._cypher(
+            f"""
+            MATCH p = (s:{label}){pattern}(t)
+            WHERE {condition}
+            WITH p, last(relationships(p)) AS r, length(p) AS depth
+            RETURN DISTINCT properties(startNode(r)) AS source, labels(startNode(r))[0] AS source_label,
+                   properties(endNode(r)) AS target, labels(endNode(r))[0] AS target_label,
+                   type(r) AS type, properties(r) AS properties, depth
+            ORDER BY depth
+            """,
+            params
+        )
+        seen = set()
+        edges = []
+        for row in rows:
+            key = (json.dumps(row["source"], sort_keys=True, default=str), row["type"],
+                   json.dumps(row["target"], sort_keys=True, default=str))
+            if key not in seen:
+                seen.add(key)
+                edges.append(row)
+        return edges
+
+    @handle_async_errors(error_types=(Neo4jError, DatabaseError))
+    async def clear_graph(self) -> None:
+        await self._cypher("MATCH (n) DETACH DELETE n", {})
+
+# Global instance
+_storage_backend: Optional[StorageBackend] = None
+
+def is_embedded() -> bool:
+    """Whether the configured backend is the embedded one."""
+    return storage_config.backend == EMBEDDED_BACKEND
+
+async def get_storage_backend() -> StorageBackend:
+    """Get the configured storage backend singleton."""
+    global _storage_backend
+    if _storage_backend is None:
+        if is_embedded():
+            from db.embedded_store import EmbeddedBackend
+            _storage_backend = await EmbeddedBackend.create()
+        elif storage_config.backend == SERVER_BACKEND:
+            _storage_backend = await ServerBackend.create()
+        else:
+            raise DatabaseError(f"Unknown storage backend: {storage_config.backend}")
+    return _storage_backend
+
+__all__ = [
+    "SERVER_BACKEND",
+    "EMBEDDED_BACKEND",
+    "StorageBackend",
+    "ServerBackend",
+    "check_identifier",
+    "to_vector",
+    "cosine_similarity",
+    "is_embedded",
+    "get_storage_backend"
+]
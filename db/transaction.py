@@ -11,10 +11,12 @@ This module provides centralized transaction management across multiple database
 """
 
 import asyncio
+import contextvars
 from contextlib import asynccontextmanager
-from typing import Optional, Set, Dict, Any, List, Tuple
+from typing import Optional, Set, Dict, Any, List, Tuple, Sequence
 from utils.logger import log
 from db.connection import connection_manager
+from db.storage import get_storage_backend, is_embedded
 from utils.cache import cache_coordinator
 from utils.error_handling import (
     handle_async_errors, 
@@ -34,6 +36,10 @@ from utils.health_monitor import global_health_monitor, ComponentStatus
 import time
 import uuid
 
+# Id of the embedded transaction scope the current task runs in; a context
+# variable because the coordinator is shared by concurrent writers
+_current_txn_id: contextvars.ContextVar[Optional[str]] = contextvars.ContextVar("current_txn_id", default=None)
+
 class TransactionState:
     """Transaction state for distributed transactions."""
     PREPARING = "preparing"
@@ -70,6 +76,58 @@ class TransactionCoordinator:
         self._transaction_timeouts: Dict[str, float] = {}
         self._transaction_metrics: Dict[str, Dict[str, Any]] = {}
         self._transaction_participants: Dict[str, Set[str]] = {}
+        self._changed_repos: Set[int] = set()
+    
+    async def track_repo_change(self, repo_id: int) -> None:
+        """Track repository changes for cache invalidation."""
+        self._changed_repos.add(repo_id)
+    
+    async def record_operation(self, operation: str, details: Dict[str, Any]) -> None:
+        """Record an operation performed inside the current transaction."""
+        txn_id = _current_txn_id.get()
+        if txn_id:
+            await transaction_monitor.record_transaction_operation(txn_id, {
+                "operation": operation,
+                "details": details,
+                "timestamp": time.time()
+            })
+    
+    async def fetch(self, sql: str, *params: Any) -> List[Dict[str, Any]]:
+        """Run a query on the transaction's connection (or the storage backend)."""
+        if self.pg_conn:
+            return [dict(row) for row in await self.pg_conn.fetch(sql, *params)]
+        backend = await get_storage_backend()
+        return await backend.fetch(sql, *params)
+    
+    async def execute(self, sql: str, *params: Any) -> None:
+        """Execute a statement on the transaction's connection (or the storage backend)."""
+        if self.pg_conn:
+            await self.pg_conn.execute(sql, *params)
+            return
+        backend = await get_storage_backend()
+        await backend.execute(sql, *params)
+    
+    async def execute_batch(self, statements: List[Tuple[str, Sequence[Any]]]) -> None:
+        """Execute several statements on the transaction's connection (or as one backend batch)."""
+        if self.pg_conn:
+            # Runs of the same statement go to the server together
+            i = 0
+            while i < len(statements):
+                sql = statements[i][0]
+                j = i
+                while j < len(statements) and statements[j][0] == sql:
+                    j += 1
+                await self.pg_conn.executemany(sql, [params for _, params in statements[i:j]])
+                i = j
+            return
+        backend = await get_storage_backend()
+        await backend.execute_batch(statements)
+    
+    async def _invalidate_repo_caches(self) -> None:
+        """Invalidate cached entries for repositories changed in this transaction."""
+        changed, self._changed_repos = self._changed_repos, set()
+        for repo_id in changed:
+            await cache_coordinator.invalidate_pattern(f"repo:{repo_id}")
     
     async def track_ai_operation(self, operation_type: str) -> None:
         """Track AI operation statistics."""
@@ -384,6 +442,34 @@ async def get_transaction_coordinator() -> TransactionCoordinator:
         raise DatabaseError("Transaction coordinator not initialized. Must call initialize_transaction_coordinator first.")
     return _transaction_coordinator
 
+@asynccontextmanager
+async def _embedded_transaction_scope(invalidate_cache: bool):
+    """Transaction scope for the embedded backend.
+
+    Embedded statements commit individually (grouped work goes through
+    execute_batch), so this only tracks changes and invalidates caches.
+    """
+    if _transaction_coordinator is None or not _transaction_coordinator._initialized:
+        await initialize_transaction_coordinator()
+    
+    txn_id = f"txn_{int(time.time() * 1000)}_{id(asyncio.current_task())}"
+    txn_token = _current_txn_id.set(txn_id)
+    await transaction_monitor.record_transaction_start(txn_id, {
+        "embedded": True,
+        "invalidate_cache": invalidate_cache,
+        "start_time": time.time()
+    })
+    try:
+        yield _transaction_coordinator
+        if invalidate_cache:
+            await _transaction_coordinator._invalidate_repo_caches()
+        await transaction_monitor.record_transaction_end(txn_id, "committed")
+    except Exception as e:
+        await transaction_monitor.record_transaction_end(txn_id, "failed", error=e)
+        raise
+    finally:
+        _current_txn_id.reset(txn_token)
+
 @asynccontextmanager
 @handle_async_errors(error_types=(PostgresError, Neo4jError, TransactionError, CacheError, ConnectionError, Exception))
 async def transaction_scope(invalidate_cache: bool = True, distributed: bool = False):
@@ -393,6 +479,11 @@ async def transaction_scope(invalidate_cache: bool = True, distributed: bool = F
         invalidate_cache: Whether to invalidate caches after transaction
         distributed: Whether to use distributed transaction protocol
     """
+    if is_embedded():
+        async with _embedded_transaction_scope(invalidate_cache) as txn:
+            yield txn
+        return
+    
     if not _transaction_coordinator._initialized:
         await _transaction_coordinator.ensure_initialized()
     
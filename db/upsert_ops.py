@@ -1,12 +1,13 @@
 """[6.5] Unified database upsert operations.
 
 This module provides centralized upsert operations across multiple databases:
-1. Code storage (PostgreSQL + Neo4j)
-2. Documentation storage (PostgreSQL)
+1. Code storage (relational + graph)
+2. Documentation storage (relational + graph)
 3. Repository metadata
 4. Document sharing
 
-All database operations use the centralized connection manager and transaction coordinator.
+All database operations go through the configured storage backend [6.9]
+and the transaction coordinator.
 """
 
 import json
@@ -28,9 +29,11 @@ from utils.error_handling import (
 from db.retry_utils import RetryManager, RetryConfig
 from utils.async_runner import submit_async_task, get_loop
 from db.connection import connection_manager
+from db.storage import get_storage_backend
 from db.transaction import transaction_scope
 from parsers.types import ParserResult, ExtractedFeatures
 from embedding.embedding_models import doc_embedder
+from semantic.lexical import search_text
 from utils.shutdown import register_shutdown_handler
 from db.graph_sync import get_graph_sync
 from db.neo4j_ops import get_neo4j_tools
@@ -61,154 +64,146 @@ class UpsertCoordinator:
                 log(f"Error initializing upsert coordinator: {e}", level="error")
                 raise
     
-    @handle_async_errors(error_types=[PostgresError, DatabaseError])
-    async def store_code_in_postgres(self, code_data: Dict, txn: Transaction) -> None:
-        """Store code data in PostgreSQL."""
-        conn = await connection_manager.get_postgres_connection()
+    async def _run_tracked(self, coro):
+        """Await a storage call while tracking it for cleanup."""
+        task = asyncio.create_task(coro)
+        self._pending_tasks.add(task)
         try:
-            async with conn.transaction():
-                sql = """
-                INSERT INTO code_snippets (repo_id, file_path, ast, embedding, enriched_features)
-                VALUES ($1, $2, $3, $4, $5)
-                ON CONFLICT (repo_id, file_path) 
-                DO UPDATE SET 
-                    ast = EXCLUDED.ast,
-                    embedding = EXCLUDED.embedding,
-                    enriched_features = EXCLUDED.enriched_features;
-                """
-                
-                task = asyncio.create_task(conn.execute(sql, (
-                    code_data['repo_id'],
-                    code_data['file_path'],
-                    json.dumps(code_data['ast']) if code_data.get('ast') else None,
-                    code_data.get('embedding'),
-                    json.dumps(code_data['enriched_features']) if code_data.get('enriched_features') else None
-                )))
-                self._pending_tasks.add(task)
-                try:
-                    await task
-                finally:
-                    self._pending_tasks.remove(task)
+            return await task
         finally:
-            await connection_manager.release_postgres_connection(conn)
+            self._pending_tasks.remove(task)
+
+    @handle_async_errors(error_types=[PostgresError, DatabaseError])
+    async def store_code_in_postgres(self, code_data: Dict, txn: Transaction) -> None:
+        """Store code data in the relational store."""
+        backend = await get_storage_backend()
+        sql = """
+        INSERT INTO code_snippets (repo_id, file_path, ast, embedding, enriched_features)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (repo_id, file_path) 
+        DO UPDATE SET 
+            ast = EXCLUDED.ast,
+            embedding = EXCLUDED.embedding,
+            enriched_features = EXCLUDED.enriched_features;
+        """
+        await self._run_tracked(backend.execute(
+            sql,
+            code_data['repo_id'],
+            code_data['file_path'],
+            json.dumps(code_data['ast']) if code_data.get('ast') else None,
+            code_data.get('embedding'),
+            json.dumps(code_data['enriched_features']) if code_data.get('enriched_features') else None
+        ))
     
     @handle_async_errors(error_types=[Neo4jError, DatabaseError])
     async def store_code_in_neo4j(self, code_data: Dict, txn: Transaction) -> None:
-        """Store code data in Neo4j."""
-        session = await connection_manager.get_session()
-        try:
-            cypher = """
-            MERGE (c:Code {repo_id: $repo_id, file_path: $file_path})
-            SET c += $properties,
-                c.updated_at = timestamp()
-            """
-            properties = {
-                'repo_id': code_data['repo_id'],
-                'file_path': code_data['file_path'],
-                'ast': code_data.get('ast'),
-                'embedding': code_data.get('embedding'),
-                'enriched_features': code_data.get('enriched_features')
-            }
-            
-            task = asyncio.create_task(session.run(cypher, {
-                'repo_id': code_data['repo_id'], 
-                'file_path': code_data['file_path'], 
-                'properties': properties
-            }))
-            self._pending_tasks.add(task)
-            try:
-                await task
-            finally:
-                self._pending_tasks.remove(task)
-        finally:
-            await session.close()
+        """Store code data as a Code node in the graph store."""
+        backend = await get_storage_backend()
+        properties = {
+            'ast': json.dumps(code_data['ast']) if code_data.get('ast') else None,
+            'enriched_features': json.dumps(code_data['enriched_features']) if code_data.get('enriched_features') else None
+        }
+        await self._run_tracked(backend.merge_node(
+            "Code",
+            {'repo_id': code_data['repo_id'], 'file_path': code_data['file_path']},
+            properties
+        ))
     
+    async def store_symbols_in_postgres(self, code_data: Dict, txn: Transaction) -> None:
+        """[6.5.9] Store a file's extracted symbols for the symbol table [1.6].
+
+        graph_hash is kept so the symbol graph is only rewritten when the
+        resolved result changes. A file without symbols (extraction failed or
+        the language has no extractor) loses its row and Symbol nodes.
+        """
+        backend = await get_storage_backend()
+        symbols = code_data.get('symbols')
+        if symbols is None:
+            await self._run_tracked(backend.execute(
+                "DELETE FROM file_symbols WHERE repo_id = $1 AND file_path = $2;",
+                code_data['repo_id'],
+                code_data['file_path']
+            ))
+            await self._run_tracked(backend.delete_nodes(
+                "Symbol",
+                {'repo_id': code_data['repo_id'], 'file_path': code_data['file_path']}
+            ))
+            return
+        sql = """
+        INSERT INTO file_symbols (repo_id, file_path, language, symbols, updated_at)
+        VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+        ON CONFLICT (repo_id, file_path)
+        DO UPDATE SET
+            language = EXCLUDED.language,
+            symbols = EXCLUDED.symbols,
+            updated_at = EXCLUDED.updated_at;
+        """
+        await self._run_tracked(backend.execute(
+            sql,
+            code_data['repo_id'],
+            code_data['file_path'],
+            symbols.get('language'),
+            json.dumps(symbols)
+        ))
+
     @handle_async_errors(error_types=[PostgresError, DatabaseError])
     async def store_doc_in_postgres(self, doc_data: Dict) -> int:
-        """Store document data in PostgreSQL and return doc_id."""
-        conn = await connection_manager.get_postgres_connection()
-        try:
-            async with conn.transaction():
-                # Store document
-                sql = """
-                INSERT INTO repo_docs (file_path, content, doc_type, version, cluster_id, 
-                                    related_code_path, embedding, metadata, quality_metrics)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                RETURNING id;
-                """
-                
-                task = asyncio.create_task(conn.fetchrow(sql, (
-                    doc_data['file_path'],
-                    doc_data['content'],
-                    doc_data.get('doc_type', 'markdown'),
-                    doc_data.get('version', 1),
-                    doc_data.get('cluster_id'),
-                    doc_data.get('related_code_path'),
-                    doc_data.get('embedding'),
-                    json.dumps(doc_data.get('metadata', {})),
-                    json.dumps(doc_data.get('quality_metrics', {}))
-                )))
-                self._pending_tasks.add(task)
-                try:
-                    result = await task
-                    doc_id = result['id']
-                finally:
-                    self._pending_tasks.remove(task)
-                
-                # Create relation
-                relation_sql = """
-                INSERT INTO repo_doc_relations (repo_id, doc_id, is_primary)
-                VALUES ($1, $2, $3)
-                ON CONFLICT (repo_id, doc_id) DO UPDATE
-                SET is_primary = EXCLUDED.is_primary;
-                """
-                
-                task = asyncio.create_task(conn.execute(relation_sql, (
-                    doc_data['repo_id'],
-                    doc_id,
-                    doc_data.get('is_primary', False)
-                )))
-                self._pending_tasks.add(task)
-                try:
-                    await task
-                finally:
-                    self._pending_tasks.remove(task)
-                
-                return doc_id
-        finally:
-            await connection_manager.release_postgres_connection(conn)
+        """Store document data in the relational store and return doc_id."""
+        backend = await get_storage_backend()
+
+        # Store document
+        sql = """
+        INSERT INTO repo_docs (file_path, content, doc_type, version, cluster_id, 
+                            related_code_path, embedding, metadata, quality_metrics, search_text)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id;
+        """
+        result = await self._run_tracked(backend.fetchrow(
+            sql,
+            doc_data['file_path'],
+            doc_data['content'],
+            doc_data.get('doc_type', 'markdown'),
+            doc_data.get('version', 1),
+            doc_data.get('cluster_id'),
+            doc_data.get('related_code_path'),
+            doc_data.get('embedding'),
+            json.dumps(doc_data.get('metadata', {})),
+            json.dumps(doc_data.get('quality_metrics', {})),
+            search_text(doc_data['file_path'], doc_data['content'])
+        ))
+        doc_id = result['id']
+
+        # Create relation
+        relation_sql = """
+        INSERT INTO repo_doc_relations (repo_id, doc_id, is_primary)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (repo_id, doc_id) DO UPDATE
+        SET is_primary = EXCLUDED.is_primary;
+        """
+        await self._run_tracked(backend.execute(
+            relation_sql,
+            doc_data['repo_id'],
+            doc_id,
+            doc_data.get('is_primary', False)
+        ))
+        return doc_id
     
     @handle_async_errors(error_types=[Neo4jError, DatabaseError])
     async def store_doc_in_neo4j(self, doc_data: Dict) -> None:
-        """Store document data in Neo4j."""
-        session = await connection_manager.get_session()
-        try:
-            cypher = """
-            MERGE (d:Documentation {repo_id: $repo_id, path: $path})
-            SET d += $properties
-            """
-            properties = {
-                'repo_id': doc_data['repo_id'],
-                'path': doc_data['file_path'],
-                'content': doc_data['content'],
-                'type': doc_data.get('doc_type', 'markdown'),
-                'version': doc_data.get('version', 1),
-                'cluster_id': doc_data.get('cluster_id'),
-                'metadata': doc_data.get('metadata', {})
-            }
-            
-            task = asyncio.create_task(session.run(cypher, {
-                'repo_id': doc_data['repo_id'], 
-                'path': doc_data['file_path'],
-                'properties': properties
-            }))
-            self._pending_tasks.add(task)
-            try:
-                await task
-            finally:
-                self._pending_tasks.remove(task)
-        finally:
-            await session.close()
+        """Store document data as a Documentation node in the graph store."""
+        backend = await get_storage_backend()
+        properties = {
+            'content': doc_data['content'],
+            'type': doc_data.get('doc_type', 'markdown'),
+            'version': doc_data.get('version', 1),
+            'cluster_id': doc_data.get('cluster_id'),
+            'metadata': json.dumps(doc_data.get('metadata', {}))
+        }
+        await self._run_tracked(backend.merge_node(
+            "Documentation",
+            {'repo_id': doc_data['repo_id'], 'path': doc_data['file_path']},
+            properties
+        ))
     
     @handle_async_errors(error_types=(PostgresError, Neo4jError, TransactionError))
     async def upsert_code_snippet(self, code_data: Dict) -> None:
@@ -220,6 +215,8 @@ class UpsertCoordinator:
             async with transaction_scope() as txn:
                 await txn.track_repo_change(code_data['repo_id'])
                 await self.store_code_in_postgres(code_data, txn)
+                if 'symbols' in code_data:
+                    await self.store_symbols_in_postgres(code_data, txn)
                 
                 if code_data.get('ast'):
                     await self.store_code_in_neo4j(code_data, txn)
@@ -261,6 +258,79 @@ class UpsertCoordinator:
                 await self.store_doc_in_neo4j(doc_data)
                 return doc_id
     
+    @handle_async_errors(error_types=(PostgresError, Neo4jError, TransactionError), default_return=0)
+    async def replace_docstrings(self, repo_id: int, file_path: str, docs: List[Dict[str, Any]]) -> int:
+        """[6.5.10] Replace the docstrings stored for one source file.
+
+        Each doc has content and metadata. Rows are stored with doc_type
+        'docstring', file_path and related_code_path set to the source file,
+        so deleting the file removes them.
+        """
+        if not self._initialized:
+            await self.initialize()
+
+        async with transaction_scope() as txn:
+            await txn.track_repo_change(repo_id)
+            backend = await get_storage_backend()
+            await self._run_tracked(backend.execute_batch([
+                ("""
+                DELETE FROM repo_doc_relations
+                WHERE repo_id = $1
+                  AND doc_id IN (SELECT id FROM repo_docs WHERE related_code_path = $2 AND doc_type = 'docstring');
+                """, (repo_id, file_path)),
+                ("""
+                DELETE FROM repo_docs
+                WHERE related_code_path = $1 AND doc_type = 'docstring'
+                  AND id NOT IN (SELECT doc_id FROM repo_doc_relations);
+                """, (file_path,))
+            ]))
+            for doc in docs:
+                await self.store_doc_in_postgres({
+                    'repo_id': repo_id,
+                    'file_path': file_path,
+                    'content': doc['content'],
+                    'doc_type': 'docstring',
+                    'related_code_path': file_path,
+                    'metadata': doc.get('metadata', {}),
+                    'is_primary': False,
+                    'embedding': await doc_embedder.embed_text(doc['content'])
+                })
+            return len(docs)
+
+    @handle_async_errors(error_types=(PostgresError, TransactionError), default_return=0)
+    async def replace_code_chunks(
+        self,
+        repo_id: int,
+        file_path: str,
+        language: Optional[str],
+        chunks: List[Dict[str, Any]]
+    ) -> int:
+        """[6.5.11] Replace the function-level chunks stored for one source file.
+
+        Each chunk has kind, symbol, parent_symbol, start_line, end_line,
+        part, content and embedding (see indexer/code_chunks.py [1.14]).
+        The path, symbol and content are tokenized for full-text search.
+        """
+        if not self._initialized:
+            await self.initialize()
+
+        async with transaction_scope() as txn:
+            await txn.track_repo_change(repo_id)
+            backend = await get_storage_backend()
+            batch = [("DELETE FROM code_chunks WHERE repo_id = $1 AND file_path = $2;", (repo_id, file_path))]
+            for chunk in chunks:
+                batch.append(("""
+                INSERT INTO code_chunks (repo_id, file_path, language, kind, symbol, parent_symbol,
+                                         start_line, end_line, part, content, embedding, search_text)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12);
+                """, (
+                    repo_id, file_path, language, chunk['kind'], chunk.get('symbol'), chunk.get('parent_symbol'),
+                    chunk['start_line'], chunk['end_line'], chunk.get('part', 0), chunk['content'], chunk.get('embedding'),
+                    search_text(file_path, chunk.get('symbol'), chunk['content'])
+                )))
+            await self._run_tracked(backend.execute_batch(batch))
+            return len(chunks)
+
     @handle_async_errors(error_types=(PostgresError, TransactionError))
     async def upsert_repository(self, repo_data: Dict) -> int:
         """[6.5.4] Store repository with transaction coordination."""
@@ -268,39 +338,101 @@ class UpsertCoordinator:
             await self.initialize()
             
         async with transaction_scope() as txn:
-            conn = await connection_manager.get_postgres_connection()
-            try:
-                async with conn.transaction():
-                    sql = """
-                    INSERT INTO repositories (repo_name, source_url, repo_type, active_repo_id)
-                    VALUES ($1, $2, $3, $4)
-                    ON CONFLICT (repo_name) 
-                    DO UPDATE SET
-                        source_url = EXCLUDED.source_url,
-                        repo_type = EXCLUDED.repo_type,
-                        active_repo_id = EXCLUDED.active_repo_id,
-                        last_updated = CURRENT_TIMESTAMP
-                    RETURNING id;
-                    """
-                    
-                    future = submit_async_task(conn.fetchrow(sql, (
-                        repo_data['repo_name'],
-                        repo_data.get('source_url'),
-                        repo_data.get('repo_type', 'active'),
-                        repo_data.get('active_repo_id')
-                    )))
-                    self._pending_tasks.add(future)
-                    try:
-                        result = await asyncio.wrap_future(future)
-                        repo_id = result['id']
-                        await txn.track_repo_change(repo_id)
-                        log(f"Upserted repository {repo_data['repo_name']}", level="info")
-                        return repo_id
-                    finally:
-                        self._pending_tasks.remove(future)
-            finally:
-                await connection_manager.release_postgres_connection(conn)
-    
+            backend = await get_storage_backend()
+            sql = """
+            INSERT INTO repositories (repo_name, source_url, repo_type, active_repo_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (repo_name) 
+            DO UPDATE SET
+                source_url = EXCLUDED.source_url,
+                repo_type = EXCLUDED.repo_type,
+                active_repo_id = EXCLUDED.active_repo_id,
+                last_updated = CURRENT_TIMESTAMP
+            RETURNING id;
+            """
+            result = await self._run_tracked(backend.fetchrow(
+                sql,
+                repo_data['repo_name'],
+                repo_data.get('source_url'),
+                repo_data.get('repo_type', 'active'),
+                repo_data.get('active_repo_id')
+            ))
+            repo_id = result['id']
+            await txn.track_repo_change(repo_id)
+            log(f"Upserted repository {repo_data['repo_name']}", level="info")
+            return repo_id
+
+    @handle_async_errors(error_types=(PostgresError, TransactionError))
+    async def record_indexed_commit(
+        self,
+        repo_id: int,
+        commit: str,
+        ref: Optional[str] = None,
+        dirty: Optional[bool] = None
+    ) -> None:
+        """[6.5.8] Record which commit a repository's index reflects."""
+        if not self._initialized:
+            await self.initialize()
+
+        async with transaction_scope() as txn:
+            backend = await get_storage_backend()
+            sql = """
+            UPDATE repositories
+            SET indexed_commit = $2, indexed_ref = $3, indexed_dirty = $4, last_updated = CURRENT_TIMESTAMP
+            WHERE id = $1;
+            """
+            await self._run_tracked(backend.execute(sql, repo_id, commit, ref, dirty))
+            await txn.track_repo_change(repo_id)
+
+    @handle_async_errors(error_types=(PostgresError, TransactionError))
+    async def record_repository_root(self, repo_id: int, root_path: str) -> None:
+        """[6.5.12] Record the directory a repository is indexed from.
+
+        Search path filters [5.3] match file paths relative to it.
+        """
+        if not self._initialized:
+            await self.initialize()
+
+        async with transaction_scope() as txn:
+            backend = await get_storage_backend()
+            sql = "UPDATE repositories SET root_path = $2 WHERE id = $1;"
+            await self._run_tracked(backend.execute(sql, repo_id, root_path))
+            await txn.track_repo_change(repo_id)
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError), default_return=[])
+    async def list_repositories(self, repo_type: Optional[str] = None) -> List[Dict[str, Any]]:
+        """List stored repositories, optionally filtered by type."""
+        if not self._initialized:
+            await self.initialize()
+
+        sql = """
+        SELECT r.id, r.repo_name, r.repo_type, r.source_url, r.active_repo_id, r.last_updated,
+               r.indexed_commit, r.indexed_ref, r.indexed_dirty,
+               (SELECT COUNT(*) FROM code_snippets cs WHERE cs.repo_id = r.id) AS code_files,
+               (SELECT COUNT(*) FROM repo_doc_relations rdr WHERE rdr.repo_id = r.id) AS docs
+        FROM repositories r
+        WHERE ($1::text IS NULL OR r.repo_type = $1)
+        ORDER BY r.id;
+        """
+        backend = await get_storage_backend()
+        return await self._run_tracked(backend.fetch(sql, repo_type))
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def get_repository(self, repo_ref: Any) -> Optional[Dict[str, Any]]:
+        """Look up a repository by id or by name."""
+        if not self._initialized:
+            await self.initialize()
+
+        if isinstance(repo_ref, int) or str(repo_ref).isdigit():
+            sql = "SELECT * FROM repositories WHERE id = $1;"
+            param = int(repo_ref)
+        else:
+            sql = "SELECT * FROM repositories WHERE repo_name = $1;"
+            param = str(repo_ref)
+
+        backend = await get_storage_backend()
+        return await self._run_tracked(backend.fetchrow(sql, param))
+
     @handle_async_errors(error_types=[PostgresError, DatabaseError])
     async def share_docs_with_repo(self, doc_ids: List[int], target_repo_id: int) -> Dict:
         """[6.5.5] Share documents with another repository."""
@@ -308,33 +440,22 @@ class UpsertCoordinator:
             await self.initialize()
             
         async with transaction_scope() as txn:
-            conn = await connection_manager.get_postgres_connection()
-            try:
-                async with conn.transaction():
-                    sql = """
-                    INSERT INTO repo_doc_relations (repo_id, doc_id, is_primary)
-                    SELECT $1, doc_id, false
-                    FROM repo_doc_relations
-                    WHERE doc_id = ANY($2)
-                    ON CONFLICT (repo_id, doc_id) DO NOTHING
-                    RETURNING doc_id;
-                    """
-                    
-                    future = submit_async_task(conn.fetch(sql, (target_repo_id, doc_ids)))
-                    self._pending_tasks.add(future)
-                    try:
-                        result = await asyncio.wrap_future(future)
-                        shared_count = len(result)
-                        await txn.track_repo_change(target_repo_id)
-                        return {
-                            'shared_count': shared_count,
-                            'target_repo_id': target_repo_id,
-                            'doc_ids': [r['doc_id'] for r in result]
-                        }
-                    finally:
-                        self._pending_tasks.remove(future)
-            finally:
-                await connection_manager.release_postgres_connection(conn)
+            backend = await get_storage_backend()
+            sql = """
+            INSERT INTO repo_doc_relations (repo_id, doc_id, is_primary)
+            SELECT DISTINCT $1::integer, doc_id, false
+            FROM repo_doc_relations
+            WHERE doc_id = ANY($2::integer[])
+            ON CONFLICT (repo_id, doc_id) DO NOTHING
+            RETURNING doc_id;
+            """
+            result = await self._run_tracked(backend.fetch(sql, target_repo_id, list(doc_ids)))
+            await txn.track_repo_change(target_repo_id)
+            return {
+                'shared_count': len(result),
+                'target_repo_id': target_repo_id,
+                'doc_ids': [r['doc_id'] for r in result]
+            }
     
     @handle_async_errors(error_types=(PostgresError, Neo4jError, TransactionError))
     async def store_parsed_content(
@@ -377,7 +498,48 @@ class UpsertCoordinator:
                 "file_path": file_path,
                 "features_count": len(features.to_dict())
             })
-    
+
+    @handle_async_errors(error_types=(PostgresError, Neo4jError, TransactionError), default_return=False)
+    async def delete_code_files(self, repo_id: int, file_paths: List[str]) -> bool:
+        """[6.5.7] Remove everything stored for files that no longer exist.
+
+        Drops code_snippets, code_chunks, code_patterns and file_symbols
+        rows, this repository's links to docs read from those files (and the
+        docs once nothing links to them), and the matching Code, Symbol and
+        Documentation nodes. Returns False on failure.
+        """
+        if not self._initialized:
+            await self.initialize()
+        if not file_paths:
+            return True
+
+        async with transaction_scope() as txn:
+            await txn.track_repo_change(repo_id)
+            backend = await get_storage_backend()
+            paths = list(file_paths)
+            await self._run_tracked(backend.execute_batch([
+                ("DELETE FROM code_snippets WHERE repo_id = $1 AND file_path = ANY($2::text[]);", (repo_id, paths)),
+                ("DELETE FROM code_chunks WHERE repo_id = $1 AND file_path = ANY($2::text[]);", (repo_id, paths)),
+                ("DELETE FROM code_patterns WHERE repo_id = $1 AND file_path = ANY($2::text[]);", (repo_id, paths)),
+                ("DELETE FROM file_symbols WHERE repo_id = $1 AND file_path = ANY($2::text[]);", (repo_id, paths)),
+                ("""
+                DELETE FROM repo_doc_relations
+                WHERE repo_id = $1
+                  AND doc_id IN (SELECT id FROM repo_docs WHERE file_path = ANY($2::text[]));
+                """, (repo_id, paths)),
+                ("""
+                DELETE FROM repo_docs
+                WHERE file_path = ANY($1::text[])
+                  AND id NOT IN (SELECT doc_id FROM repo_doc_relations);
+                """, (paths,))
+            ]))
+            for file_path in paths:
+                await self._run_tracked(backend.delete_nodes("Code", {'repo_id': repo_id, 'file_path': file_path}))
+                await self._run_tracked(backend.delete_nodes("Symbol", {'repo_id': repo_id, 'file_path': file_path}))
+                await self._run_tracked(backend.delete_nodes("Documentation", {'repo_id': repo_id, 'path': file_path}))
+            await log(f"Removed {len(paths)} deleted file(s) from repository {repo_id}", level="info")
+            return True
+
     @handle_async_errors(error_types=DatabaseError)
     async def upsert_pattern(
         self,
@@ -27,6 +27,13 @@ from db.retry_utils import RetryManager, RetryConfig
 from semantic.vector_store import VectorStore
 import time
 
+# Pretrained models; the index manifest records these to detect stale embeddings
+CODE_EMBEDDING_MODEL = 'microsoft/graphcodebert-base'
+DOC_EMBEDDING_MODEL = 'sentence-transformers/all-mpnet-base-v2'
+
+# Texts embedded per model call by embed_batch_with_context
+EMBED_BATCH_SIZE = 16
+
 # Initialize cache for embeddings
 embedding_cache = UnifiedCache("embeddings", eviction_policy="lru", max_size=1000)
 
@@ -256,6 +263,53 @@ class PatternAwareEmbedder:
         
         return embedding
     
+    async def embed_batch_with_context(
+        self,
+        texts: List[str],
+        context: Optional[Dict[str, Any]] = None,
+        pattern_type: Optional[str] = None
+    ) -> List[List[float]]:
+        """Generate embeddings for texts sharing one context, EMBED_BATCH_SIZE per model call."""
+        if not self._initialized:
+            await self.initialize()
+        
+        prepared = []
+        for text in texts:
+            if context:
+                text = self._enrich_with_context(text, context)
+            if pattern_type:
+                text = self._apply_pattern_processing(text, pattern_type)
+            prepared.append(text)
+        
+        embeddings = []
+        for start in range(0, len(prepared), EMBED_BATCH_SIZE):
+            embeddings.extend(await self._generate_base_embeddings(prepared[start:start + EMBED_BATCH_SIZE]))
+        
+        if pattern_type:
+            embeddings = [await self._enhance_with_pattern(embedding, pattern_type) for embedding in embeddings]
+        return embeddings
+    
+    async def _generate_base_embedding(self, text: str) -> List[float]:
+        """Generate the model embedding of one text."""
+        return (await self._generate_base_embeddings([text]))[0]
+    
+    async def _generate_base_embeddings(self, texts: List[str]) -> List[List[float]]:
+        """Mean-pooled last hidden states of texts, from one forward pass off the event loop."""
+        if not texts:
+            return []
+        
+        def _run():
+            inputs = self.tokenizer(
+                texts, padding=True, truncation=True, max_length=512, return_tensors="pt"
+            ).to(self.model.device)
+            with torch.no_grad():
+                hidden = self.model(**inputs).last_hidden_state
+            mask = inputs["attention_mask"].unsqueeze(-1).to(hidden.dtype)
+            pooled = (hidden * mask).sum(dim=1) / mask.sum(dim=1).clamp(min=1)
+            return pooled.cpu().tolist()
+        
+        return await asyncio.get_running_loop().run_in_executor(None, _run)
+    
     def _enrich_with_context(self, text: str, context: Dict[str, Any]) -> str:
         """Enrich text with contextual information."""
         enriched = text
@@ -312,8 +366,8 @@ class CodeEmbedder(PatternAwareEmbedder):
                 severity=ErrorSeverity.CRITICAL
             ):
                 # Initialize model and tokenizer
-                instance.tokenizer = AutoTokenizer.from_pretrained('microsoft/graphcodebert-base')
-                instance.model = AutoModel.from_pretrained('microsoft/graphcodebert-base').to(torch.device('cuda' if torch.cuda.is_available() else 'cpu'))
+                instance.tokenizer = AutoTokenizer.from_pretrained(CODE_EMBEDDING_MODEL)
+                instance.model = AutoModel.from_pretrained(CODE_EMBEDDING_MODEL).to(torch.device('cuda' if torch.cuda.is_available() else 'cpu'))
                 instance.model.eval()
                 
                 # Initialize cache
@@ -355,6 +409,19 @@ class CodeEmbedder(PatternAwareEmbedder):
         
         return await self.embed_with_context(code, context, pattern_type)
     
+    async def embed_code_batch(
+        self,
+        codes: List[str],
+        language: str,
+        context: Optional[Dict[str, Any]] = None,
+        pattern_type: Optional[str] = None
+    ) -> List[List[float]]:
+        """Generate code embeddings for several snippets of one language in batched model calls."""
+        context = dict(context or {})
+        context["language"] = language
+        
+        return await self.embed_batch_with_context(codes, context, pattern_type)
+    
     async def embed_pattern(
         self,
         pattern: Dict[str, Any]
@@ -429,8 +496,8 @@ class DocEmbedder(PatternAwareEmbedder):
                 severity=ErrorSeverity.CRITICAL
             ):
                 # Initialize model and tokenizer
-                instance.tokenizer = AutoTokenizer.from_pretrained('sentence-transformers/all-mpnet-base-v2')
-                instance.model = AutoModel.from_pretrained('sentence-transformers/all-mpnet-base-v2').to(torch.device('cuda' if torch.cuda.is_available() else 'cpu'))
+                instance.tokenizer = AutoTokenizer.from_pretrained(DOC_EMBEDDING_MODEL)
+                instance.model = AutoModel.from_pretrained(DOC_EMBEDDING_MODEL).to(torch.device('cuda' if torch.cuda.is_available() else 'cpu'))
                 instance.model.eval()
                 
                 # Initialize cache
@@ -3,12 +3,22 @@
 Improved Repository Indexing Entry Point with Automated Graph Projection Re‑invocation
 
 This module coordinates repository indexing and analysis using a modern,
-asyncio‑driven structure. It supports:
-  - Active repository indexing (defaulting to the current working directory)
-  - Cloning and indexing a reference repository via a GitHub URL (--clone-ref)
-  - Documentation operations (e.g. sharing, searching)
-  - Watch mode: files are monitored and changes are reindexed continuously
-  - Reference repository learning: learn patterns from reference repositories
+asyncio‑driven structure. It exposes one subcommand per workflow:
+  - index:        Index a repository (defaulting to the current working directory)
+  - watch:        Index, then monitor files and reindex changes continuously
+  - search code:  Vector search over indexed code
+  - search docs:  Vector search over indexed documentation
+  - learn:        Index reference repositories and learn patterns from them
+  - repos:        List indexed repositories
+  - report:       Summarize what the index holds for a repository
+  - serve:        Expose the index over a local HTTP JSON API
+  - lsp:          Run a Language Server Protocol server on stdin/stdout
+
+Every subcommand prints its result to stdout as JSON (default) or as a table
+(--format table) and returns a meaningful exit code:
+  0 success, 1 error, 2 usage error, 3 no results.
+Log output goes to stderr. lsp owns stdout for the protocol, so its result is
+written to stderr as well.
 """
 
 import argparse
@@ -16,27 +26,16 @@ import asyncio
 import os
 import signal
 import sys
+from typing import Any, Callable, Dict, List, Optional
 from utils.logger import log, log_sync # Use our central logger
-from parsers.types import (
-    ParserResult,
-    FileType,
-    ParserType,
-    Documentation,
-    ComplexityMetrics,
-    ExtractedFeatures
-)
-from parsers.models import (
-    FileMetadata,
-    FileClassification,
-    LanguageFeatures,
-    PatternMatch,
-    PatternDefinition,
-    QueryPattern,
-    QueryResult
-)
-from parsers.feature_extractor import (
-    TreeSitterFeatureExtractor,
-    CustomFeatureExtractor
+from utils.cli_output import (
+    OUTPUT_FORMATS,
+    EXIT_OK,
+    EXIT_ERROR,
+    EXIT_USAGE,
+    EXIT_NO_RESULTS,
+    emit,
+    emit_error
 )
 
 # Use the new consolidated module for schema initialization.
@@ -47,26 +46,38 @@ from db.graph_sync import get_graph_sync
 from db.psql import query  # Database query operations
 from db.schema import SchemaManager  # Use SchemaManager for schema operations
 from indexer.unified_indexer import process_repository_indexing
+from indexer.git_source import GitTreeSource
+from indexer.index_jobs import IndexJob, list_jobs
+from indexer.rust_audit import rust_audit_report
+from indexer.rust_features import rust_feature_report
+from indexer.package_deps import list_packages
+from indexer.ignore_rules import explain_ignore, find_root
 from db.upsert_ops import UpsertCoordinator  # Use UpsertCoordinator for database operations
-from embedding.embedding_models import code_embedder, doc_embedder  # Use global instances
 from semantic.search import (  # Updated import path
     search_code,
-    search_docs,
-    search_engine
+    search_docs
 )
 # TODO: Implement AI tools before enabling these imports
 # from ai_tools.graph_capabilities import graph_analysis
 # from ai_tools.ai_interface import AIAssistant
-from watcher.file_watcher import DirectoryWatcher
-from utils.error_handling import handle_async_errors, AsyncErrorBoundary, handle_errors
+from watcher.file_watcher import DirectoryWatcher, handle_file_batch
+from utils.error_handling import handle_async_errors, AsyncErrorBoundary, handle_errors, ProcessingError, DatabaseError
 from utils.app_init import _initialize_components
 from utils.shutdown import register_shutdown_handler
 from utils.async_runner import submit_async_task, cleanup_tasks
 from db.connection import connection_manager
+from config.config import api_config, indexing_config
 
 # TODO: Implement AI Assistant before enabling
 # ai_assistant = AIAssistant()
 
+class CommandError(Exception):
+    """A subcommand failed in a way that should be reported to the caller."""
+
+    def __init__(self, message: str, exit_code: int = EXIT_ERROR):
+        super().__init__(message)
+        self.exit_code = exit_code
+
 # ------------------------------------------------------------------
 # Asynchronous tasks delegating major responsibilities.
 # ------------------------------------------------------------------
@@ -83,227 +94,520 @@ async def process_share_docs(share_docs_arg: str):
         upsert_coordinator = UpsertCoordinator()
         await upsert_coordinator.initialize()
         result = await upsert_coordinator.share_docs_with_repo(doc_ids, int(target_repo))
-        log(f"Sharing docs result: {result}", level="info")
+        await log(f"Sharing docs result: {result}", level="info")
+        return result
 
-@handle_async_errors()
-async def handle_file_change(file_path: str, repo_id: int):
-    log(f"File changed: {file_path}", level="info")
-    await process_repository_indexing(file_path, repo_id, single_file=True)
-    graph_sync = await get_graph_sync()
-    await graph_sync.invalidate_projection(repo_id)
-    await graph_sync.ensure_projection(repo_id)
-    # TODO: Implement graph analysis before enabling
-    # await graph_analysis.analyze_code_structure(repo_id)
+async def resolve_repository(upsert_coordinator: UpsertCoordinator, repo_ref: Optional[str]) -> Dict[str, Any]:
+    """Resolve a --repo argument (id, name or path) to a repository row.
 
-# TODO: Implement AI Assistant before enabling
-# @handle_async_errors()
-# async def learn_from_reference_repo(reference_repo_id: int, active_repo_id: int = None):
-#     """
-#     Learn patterns from a reference repository and optionally apply them to an active repo.
-#     """
-#     async with AsyncErrorBoundary("learning from reference repository"):
-#         # Add support for deep learning from multiple repositories
-#         if isinstance(reference_repo_id, list):
-#             learn_result = await ai_assistant.deep_learn_from_multiple_repositories(reference_repo_id)
-#         else:
-#             # Learn from single reference repository
-#             learn_result = await ai_assistant.learn_from_reference_repo(reference_repo_id)
-#         
-#         log(f"Learned patterns from reference repo: {learn_result}", level="info")
-#         
-#         # Apply patterns to active repository if specified
-#         if active_repo_id:
-#             apply_result = await ai_assistant.apply_reference_patterns(reference_repo_id, active_repo_id)
-#             log(f"Applied patterns to active repo: {apply_result}", level="info")
-#             return apply_result
-#         return learn_result
+    Defaults to the repository named after the current working directory.
+    """
+    if repo_ref is None:
+        repo_ref = os.path.basename(os.getcwd())
+    elif not str(repo_ref).isdigit() and os.path.isdir(repo_ref):
+        repo_ref = os.path.basename(os.path.abspath(repo_ref))
+
+    repo = await upsert_coordinator.get_repository(repo_ref)
+    if not repo:
+        raise CommandError(f"Unknown repository: {repo_ref}", EXIT_NO_RESULTS)
+    return repo
 
 # ------------------------------------------------------------------
-# Main async routine assembling tasks (indexing, sharing, searching).
+# Main async routine assembling tasks (indexing, sharing, watching).
 # ------------------------------------------------------------------
 
-@handle_async_errors()
-async def main_async(args):
-    """Main async coordinator for indexing, documentation operations, and watch mode."""
+@handle_async_errors(error_types=(ProcessingError, DatabaseError))
+async def main_async(args) -> Optional[Dict[str, Any]]:
+    """Main async coordinator for indexing, documentation operations, and watch mode.
+
+    Returns a summary of what was indexed, or None if indexing failed.
+    """
     try:
-        # Initialize application components including database pools
-        await _initialize_components()
-        
-        if args.clean:
-            log("Cleaning databases and reinitializing schema...", level="info")
+        if getattr(args, "clean", False):
+            await log("Cleaning databases and reinitializing schema...", level="info")
             schema_manager = SchemaManager()
             await schema_manager.drop_all_tables()
             await schema_manager.create_all_tables()
             await create_schema_indexes_and_constraints()
-            
-        repo_path = args.index if args.index else os.getcwd()
-        repo_name = os.path.basename(os.path.abspath(repo_path))
-        
+
+        workers = getattr(args, "workers", None)
+        if workers is not None:
+            if workers < 0:
+                raise CommandError("--workers must be 0 or more", EXIT_USAGE)
+            indexing_config.parse_workers = workers
+
+        repo_path = os.path.abspath(getattr(args, "path", None) or os.getcwd())
+        repo_name = os.path.basename(repo_path)
+
         # [0.2] Repository Setup
         upsert_coordinator = UpsertCoordinator()
         await upsert_coordinator.initialize()
         repo_id = await upsert_coordinator.upsert_repository({
             'repo_name': repo_name,
             'repo_type': 'active',
-            'source_url': args.clone_ref
+            'source_url': getattr(args, "clone_ref", None)
         })
-        
-        # Store reference repository if provided
-        reference_repo_id = None
-        reference_repo_ids = []
-        
-        # Handle single reference repository
-        if args.learn_ref:
-            reference_repo = os.path.basename(os.path.abspath(args.learn_ref))
-            reference_repo_id = await upsert_coordinator.upsert_repository({
-                'repo_name': reference_repo,
+        if repo_id is None:
+            raise CommandError(f"Could not register repository {repo_name}")
+
+        # Store reference repositories if provided (paths, URLs or repository IDs)
+        reference_repos: List[Dict[str, Any]] = []
+        for ref in getattr(args, "refs", None) or []:
+            ref = ref.strip()
+            if ref.isdigit():
+                reference_repos.append({'id': int(ref), 'path': None})
+                continue
+            ref_name = os.path.basename(os.path.abspath(ref))
+            ref_id = await upsert_coordinator.upsert_repository({
+                'repo_name': ref_name,
                 'repo_type': 'reference',
                 'active_repo_id': repo_id,
-                'source_url': args.learn_ref if '://' in args.learn_ref else None
+                'source_url': ref if '://' in ref else None
             })
-            reference_repo_ids.append(reference_repo_id)
-        
-        # Handle multiple reference repositories
-        if args.multi_ref:
-            multi_refs = args.multi_ref.split(',')
-            for ref in multi_refs:
-                ref = ref.strip()
-                # Check if it's a repository ID
-                if ref.isdigit():
-                    reference_repo_ids.append(int(ref))
-                else:
-                    # It's a path or URL
-                    ref_name = os.path.basename(os.path.abspath(ref))
-                    ref_id = await upsert_coordinator.upsert_repository({
-                        'repo_name': ref_name,
-                        'repo_type': 'reference',
-                        'active_repo_id': repo_id,
-                        'source_url': ref if '://' in ref else None
-                    })
-                    reference_repo_ids.append(ref_id)
-        
+            reference_repos.append({'id': ref_id, 'path': ref})
+
+        # Resolve --ref/--since up front so a bad revision is a usage error
+        source = None
+        ref, since = getattr(args, "ref", None), getattr(args, "since", None)
+        if ref or since:
+            try:
+                source = await GitTreeSource.open(repo_path, ref or "HEAD", since=since)
+            except ProcessingError as e:
+                raise CommandError(str(e), EXIT_USAGE)
+
+        # --resume continues the last unfinished job [1.5] from the commit it indexed
+        job = None
+        if getattr(args, "resume", False):
+            job = await IndexJob.load_resumable(repo_id)
+            if job is None:
+                raise CommandError(f"No unfinished indexing job for {repo_name}", EXIT_NO_RESULTS)
+            if job.options.get("commit"):
+                try:
+                    source = await GitTreeSource.open(repo_path, job.options["commit"])
+                except ProcessingError as e:
+                    raise CommandError(str(e), EXIT_USAGE)
+
         # [0.3] Processing Tasks
         futures = []
         # Core indexing using UnifiedIndexer [1.0]
-        future = submit_async_task(process_repository_indexing(repo_path, repo_id))
-        futures.append(asyncio.wrap_future(future))
-        
-        # Index reference repository if provided
-        if args.learn_ref:
-            future = submit_async_task(process_repository_indexing(args.learn_ref, reference_repo_id))
+        full = getattr(args, "full", False)
+        if not getattr(args, "skip_index", False):
+            future = submit_async_task(process_repository_indexing(repo_path, repo_id, full=full, source=source, job=job))
             futures.append(asyncio.wrap_future(future))
-        
-        # Index multiple reference repositories
-        if args.multi_ref:
-            multi_refs = args.multi_ref.split(',')
-            for i, ref in enumerate(multi_refs):
-                ref = ref.strip()
-                if not ref.isdigit():  # Only process paths, not repository IDs
-                    future = submit_async_task(process_repository_indexing(ref, reference_repo_ids[i]))
-                    futures.append(asyncio.wrap_future(future))
-        
+
+        # Index reference repositories given as paths
+        for ref in reference_repos:
+            if ref['path']:
+                future = submit_async_task(process_repository_indexing(ref['path'], ref['id'], repo_type="reference", full=full))
+                futures.append(asyncio.wrap_future(future))
+
         # Documentation operations
-        if args.share_docs:
+        if getattr(args, "share_docs", None):
             future = submit_async_task(process_share_docs(args.share_docs))
-            futures.append(future)
-        if args.search_docs:
-            # Uses SearchEngine [5.0]
-            results = await search_docs(args.search_docs, repo_id=repo_id)
-            log(f"Doc search results: {results}", level="info")
+            futures.append(asyncio.wrap_future(future))
 
         # Wait for all futures to complete
+        errors = []
+        results = []
         if futures:
-            await asyncio.gather(*futures, return_exceptions=True)
-        
-        # TODO: Implement AI Assistant before enabling reference repository learning
-        # Reference repository learning
-        # if args.deep_learning and len(reference_repo_ids) >= 2:
-        #     # Deep learning from multiple reference repositories
-        #     log(f"Starting deep learning from {len(reference_repo_ids)} reference repositories", level="info")
-        #     deep_learning_result = await ai_assistant.deep_learn_from_multiple_repositories(reference_repo_ids)
-        #     
-        #     if args.apply_ref_patterns:
-        #         # Apply patterns from all reference repositories to the active repo
-        #         await ai_assistant.apply_cross_repository_patterns(repo_id, reference_repo_ids)
-        #         
-        #     log(f"Deep learning complete: {deep_learning_result}", level="info")
-        # elif reference_repo_id or reference_repo_ids:
-        #     # Regular learning from a single reference repository
-        #     repo_to_learn = reference_repo_id or reference_repo_ids[0]
-        #     await learn_from_reference_repo(repo_to_learn, repo_id if args.apply_ref_patterns else None)
-        
+            results = await asyncio.gather(*futures, return_exceptions=True)
+            errors = [str(result) for result in results if isinstance(result, Exception)]
+        # The first future is this repository's indexing run, if there was one
+        files = results[0] if results and not getattr(args, "skip_index", False) else None
+
+        summary = {
+            'repo_id': repo_id,
+            'repo_name': repo_name,
+            'repo_path': repo_path,
+            'reference_repo_ids': [ref['id'] for ref in reference_repos],
+            'files': files if isinstance(files, dict) else None,
+            'errors': errors
+        }
+
         # [0.4] Watch Mode
-        if args.watch:
-            log("Watch mode enabled: Starting file watcher...", level="info")
-            
+        if getattr(args, "watch", False):
+            await log("Watch mode enabled: Starting file watcher...", level="info")
+
             # Instantiate DirectoryWatcher and start watching
             directory_watcher = await DirectoryWatcher.create()
-            await directory_watcher.watch_directory(repo_path, repo_id, on_change=handle_file_change)
+            async def on_batch(changed, deleted):
+                return await handle_file_batch(repo_path, repo_id, changed, deleted)
+            await directory_watcher.watch_directory(repo_path, repo_id, on_batch=on_batch)
         else:
             # One-time graph analysis
-            log("Invoking graph projection once after indexing.", level="info")
+            await log("Invoking graph projection once after indexing.", level="info")
             graph_sync = await get_graph_sync()
             await graph_sync.invalidate_projection(repo_id)
             await graph_sync.ensure_projection(repo_id)
             # TODO: Implement graph analysis before enabling
             # await graph_analysis.analyze_code_structure(repo_id)
+
+        return summary
     except asyncio.CancelledError:
-        log("Indexing was cancelled.", level="info")
+        await log("Indexing was cancelled.", level="info")
+        raise
+    except CommandError:
         raise
     except Exception as e:
-        log(f"Unexpected error: {e}", level="error")
+        await log(f"Unexpected error: {e}", level="error")
         raise
 
-@handle_async_errors(error_types=(Exception,))
-async def main():
-    """CLI entry point with argument parsing."""
-    parser = argparse.ArgumentParser(description="Repository indexing and analysis tool.")
+# ------------------------------------------------------------------
+# Subcommands. Each returns (result, exit_code); the result is
+# rendered by utils.cli_output in the requested format.
+# ------------------------------------------------------------------
+
+async def cmd_index(args):
+    """[0.5] index: index a repository once and refresh its projection.
+
+    With --explain-ignore, only report the ignore rule deciding a path.
+    With --resume, continue the repository's last unfinished indexing job.
+    """
+    if args.resume and (args.ref or args.since or args.full):
+        raise CommandError("--resume cannot be combined with --ref, --since or --full", EXIT_USAGE)
+    if args.explain_ignore:
+        repo_path = os.path.abspath(args.path or os.getcwd())
+        if not os.path.exists(args.explain_ignore):
+            raise CommandError(f"No such file or directory: {args.explain_ignore}", EXIT_USAGE)
+        return explain_ignore(args.explain_ignore, root=find_root(repo_path)), EXIT_OK
+    summary = await main_async(args)
+    if summary is None:
+        raise CommandError("Indexing failed; see log output for details")
+    return summary, EXIT_ERROR if summary['errors'] else EXIT_OK
+
+async def cmd_watch(args):
+    """[0.6] watch: index a repository, then keep reindexing changed files."""
+    args.watch = True
+    summary = await main_async(args)
+    if summary is None:
+        raise CommandError("Watch mode failed; see log output for details")
+    return summary, EXIT_OK
+
+async def cmd_search_code(args):
+    """[0.7] search code: hybrid vector and full-text search over indexed functions, classes and methods."""
+    if args.page < 1 or args.limit < 1:
+        raise CommandError("--page and --limit must be 1 or more", EXIT_USAGE)
+    upsert_coordinator = UpsertCoordinator()
+    repo = await resolve_repository(upsert_coordinator, args.repo) if args.repo else None
+    results = await search_code(
+        args.query,
+        language=args.language,
+        repo_id=repo['id'] if repo else None,
+        limit=args.limit,
+        offset=(args.page - 1) * args.limit
+    )
+    results = results or []
+    return results, EXIT_OK if results else EXIT_NO_RESULTS
+
+async def cmd_search_docs(args):
+    """[0.8] search docs: hybrid vector and full-text search over indexed documentation."""
+    if args.page < 1 or args.limit < 1:
+        raise CommandError("--page and --limit must be 1 or more", EXIT_USAGE)
+    upsert_coordinator = UpsertCoordinator()
+    repo = await resolve_repository(upsert_coordinator, args.repo) if args.repo else None
+    results = await search_docs(
+        args.query,
+        repo_id=repo['id'] if repo else None,
+        limit=args.limit,
+        offset=(args.page - 1) * args.limit
+    )
+    results = results or []
+    return results, EXIT_OK if results else EXIT_NO_RESULTS
+
+async def cmd_learn(args):
+    """[0.9] learn: index reference repositories and learn patterns from them."""
+    from ai_tools.reference_repository_learning import ReferenceRepositoryLearning
+
+    if args.deep and len(args.refs) < 2:
+        raise CommandError("--deep requires at least two reference repositories", EXIT_USAGE)
+
+    summary = await main_async(args)
+    if summary is None:
+        raise CommandError("Indexing reference repositories failed; see log output for details")
+
+    learner = await ReferenceRepositoryLearning.create()
+    reference_repo_ids = summary['reference_repo_ids']
+    learned = []
+    if args.deep:
+        learned.append(await learner.deep_learn_from_multiple_repositories(reference_repo_ids))
+    else:
+        for reference_repo_id in reference_repo_ids:
+            learned.append(await learner.learn_from_repository(reference_repo_id))
+
+    applied = []
+    if args.apply:
+        for reference_repo_id in reference_repo_ids:
+            applied.append(await learner.apply_patterns_to_project(reference_repo_id, summary['repo_id']))
+
+    summary['learned'] = learned
+    summary['applied'] = applied
+    failed = any(result is None for result in learned + applied)
+    return summary, EXIT_ERROR if failed or summary['errors'] else EXIT_OK
+
+async def cmd_repos(args):
+    """[0.10] repos: list indexed repositories."""
+    upsert_coordinator = UpsertCoordinator()
+    repos = await upsert_coordinator.list_repositories(args.type)
+    return repos, EXIT_OK if repos else EXIT_NO_RESULTS
+
+async def _report_summary(repo: Dict[str, Any], args) -> Dict[str, Any]:
+    """Counts of indexed files, docs and patterns for a repository."""
+    repo_id = repo['id']
+    code = await query(
+        """
+        SELECT COUNT(*) AS files,
+               COUNT(embedding) AS embedded,
+               MAX(updated_at) AS last_indexed
+        FROM code_snippets WHERE repo_id = $1;
+        """,
+        (repo_id,)
+    )
+    docs = await query(
+        """
+        SELECT rd.doc_type, COUNT(*) AS count
+        FROM repo_docs rd JOIN repo_doc_relations rdr ON rd.id = rdr.doc_id
+        WHERE rdr.repo_id = $1
+        GROUP BY rd.doc_type ORDER BY rd.doc_type;
+        """,
+        (repo_id,)
+    )
+    patterns = {}
+    for table in ("code_patterns", "doc_patterns", "arch_patterns"):
+        rows = await query(f"SELECT COUNT(*) AS count FROM {table} WHERE repo_id = $1;", (repo_id,))
+        patterns[table] = rows[0]['count'] if rows else 0
+
+    code_row = code[0] if code else {}
+    return {
+        'repo_id': repo_id,
+        'repo_name': repo['repo_name'],
+        'repo_type': repo.get('repo_type'),
+        'code_files': code_row.get('files', 0),
+        'embedded_files': code_row.get('embedded', 0),
+        'last_indexed': code_row.get('last_indexed'),
+        'indexed_commit': repo.get('indexed_commit'),
+        'indexed_ref': repo.get('indexed_ref'),
+        'indexed_dirty': repo.get('indexed_dirty'),
+        'docs': {row['doc_type']: row['count'] for row in docs},
+        'patterns': patterns
+    }
+
+async def _report_jobs(repo: Dict[str, Any], args) -> List[Dict[str, Any]]:
+    """Recent indexing jobs [1.5] of a repository and their progress."""
+    return await list_jobs(repo['id'])
+
+async def _report_rust_audit(repo: Dict[str, Any], args) -> Dict[str, Any]:
+    """unsafe, panic and FFI sites [1.8] per crate and module."""
+    return await rust_audit_report(repo['id'])
+
+async def _report_rust_features(repo: Dict[str, Any], args) -> Dict[str, Any]:
+    """Code behind each Cargo feature [1.10], with dead and undeclared features."""
+    return await rust_feature_report(repo['id'])
+
+async def _report_packages(repo: Dict[str, Any], args) -> List[Dict[str, Any]]:
+    """Declared packages [1.12] with constraints, resolved versions and importing files."""
+    return await list_packages(repo['id'])
+
+# Report kinds available to the `report` subcommand
+REPORTS: Dict[str, Callable] = {
+    "summary": _report_summary,
+    "jobs": _report_jobs,
+    "rust-audit": _report_rust_audit,
+    "rust-features": _report_rust_features,
+    "packages": _report_packages,
+}
+
+async def cmd_report(args):
+    """[0.11] report: summarize what the index holds for a repository."""
+    upsert_coordinator = UpsertCoordinator()
+    repo = await resolve_repository(upsert_coordinator, args.repo)
+    report = await REPORTS[args.kind](repo, args)
+    return report, EXIT_OK
+
+async def cmd_serve(args):
+    """[0.12] serve: answer JSON queries over HTTP until interrupted."""
+    from api.server import ApiServer
+
+    server = await ApiServer.create(args.host, args.port)
+    host, port = server.address
+    try:
+        await server.serve_forever()
+    except asyncio.CancelledError:
+        pass
+    finally:
+        await server.cleanup()
+    return {'status': 'stopped', 'host': host, 'port': port}, EXIT_OK
+
+async def cmd_lsp(args):
+    """[0.13] lsp: answer editor navigation requests over stdio until exit."""
+    from api.lsp import LspServer
+
+    server = await LspServer.create()
+    try:
+        await server.serve_forever()
+    except asyncio.CancelledError:
+        pass
+    finally:
+        documents = server.documents
+        await server.cleanup()
+    return {'status': 'stopped', 'documents': documents}, EXIT_OK
+
+# ------------------------------------------------------------------
+# Argument parsing and dispatch.
+# ------------------------------------------------------------------
+
+def _add_index_arguments(parser: argparse.ArgumentParser) -> None:
+    """Arguments shared by subcommands that index a repository."""
+    parser.add_argument("path", nargs="?", default=os.getcwd(),
+                        help="Local repository path to index. Defaults to current directory.")
     parser.add_argument("--clean", action="store_true",
                         help="Clean and reinitialize databases before starting")
-    parser.add_argument("--index", nargs="?", type=str,
-                        help="Local repository path to index. Defaults to current directory.",
-                        default=os.getcwd(), const=os.getcwd())
-    parser.add_argument("--clone-ref", type=str,
-                        help="Clone and index a reference repository from GitHub (provide Git URL)")
-    parser.add_argument("--share-docs", type=str,
-                        help="Share docs in format 'doc_id1,doc_id2:target_repo_id'")
-    parser.add_argument("--search-docs", type=str,
-                        help="Search for documentation by term")
-    parser.add_argument("--watch", action="store_true",
-                        help="Watch for file changes and continuously update graph projection")
-    parser.add_argument("--learn-ref", type=str,
-                        help="Learn patterns from a reference repository (path or URL)")
-    parser.add_argument("--multi-ref", type=str, 
-                        help="Learn from multiple reference repositories (comma-separated paths or repository IDs)")
-    parser.add_argument("--apply-ref-patterns", action="store_true",
-                        help="Apply learned patterns from reference repo to the active repo")
-    parser.add_argument("--deep-learning", action="store_true",
-                        help="Use deep learning across multiple repositories (requires --multi-ref)")
-    
-    args = parser.parse_args()
-    
+    parser.add_argument("--full", action="store_true",
+                        help="Reindex every file, even those unchanged since the last run")
+    parser.add_argument("--workers", type=int, metavar="N",
+                        help=f"Parser processes (default: {indexing_config.parse_workers}; 0 parses in-process)")
+
+def build_parser() -> argparse.ArgumentParser:
+    """Build the subcommand argument parser."""
+    # --format is accepted before or after the subcommand; SUPPRESS keeps a
+    # subcommand from overwriting a value given at the top level.
+    common = argparse.ArgumentParser(add_help=False)
+    common.add_argument("--format", choices=OUTPUT_FORMATS, default=argparse.SUPPRESS,
+                        help="Output format (default: json)")
+
+    parser = argparse.ArgumentParser(description="Repository indexing and analysis tool.")
+    parser.add_argument("--format", choices=OUTPUT_FORMATS, default="json",
+                        help="Output format (default: json)")
+    subparsers = parser.add_subparsers(dest="command", metavar="COMMAND")
+    subparsers.required = True
+
+    # index
+    index_parser = subparsers.add_parser("index", parents=[common],
+                                         help="Index a repository")
+    _add_index_arguments(index_parser)
+    index_parser.add_argument("--clone-ref", type=str,
+                              help="Source Git URL recorded for the repository")
+    index_parser.add_argument("--share-docs", type=str,
+                              help="Share docs in format 'doc_id1,doc_id2:target_repo_id'")
+    index_parser.add_argument("--ref", type=str,
+                              help="Index this branch, tag or commit from git without checking it out")
+    index_parser.add_argument("--since", type=str, metavar="REV",
+                              help="Only reindex files changed between REV and --ref (default HEAD)")
+    index_parser.add_argument("--resume", action="store_true",
+                              help="Continue the last interrupted or failed indexing job")
+    index_parser.add_argument("--explain-ignore", type=str, metavar="PATH",
+                              help="Report which ignore rule matches PATH instead of indexing")
+    index_parser.set_defaults(handler=cmd_index)
+
+    # watch
+    watch_parser = subparsers.add_parser("watch", parents=[common],
+                                         help="Index, then reindex files as they change")
+    _add_index_arguments(watch_parser)
+    watch_parser.set_defaults(handler=cmd_watch)
+
+    # search code / search docs
+    search_parser = subparsers.add_parser("search", help="Search the index")
+    search_subparsers = search_parser.add_subparsers(dest="search_target", metavar="TARGET")
+    search_subparsers.required = True
+
+    code_parser = search_subparsers.add_parser("code", parents=[common], help="Search code")
+    code_parser.add_argument("query", help="Search query; may hold filters such as lang:rust path:src/** kind:function repo:foo -path:tests")
+    code_parser.add_argument("--repo", help="Repository id, name or path")
+    code_parser.add_argument("--language", help="Restrict results to a language")
+    code_parser.add_argument("--limit", type=int, default=10, help="Maximum number of results")
+    code_parser.add_argument("--page", type=int, default=1, help="Page of --limit results to show (1-based)")
+    code_parser.set_defaults(handler=cmd_search_code, columns=["file_path", "start_line", "end_line", "symbol", "language", "score", "snippet"])
+
+    docs_parser = search_subparsers.add_parser("docs", parents=[common], help="Search documentation")
+    docs_parser.add_argument("query", help="Search query; may hold filters such as path:docs/** kind:markdown")
+    docs_parser.add_argument("--repo", help="Repository id, name or path")
+    docs_parser.add_argument("--limit", type=int, default=10, help="Maximum number of results")
+    docs_parser.add_argument("--page", type=int, default=1, help="Page of --limit results to show (1-based)")
+    docs_parser.set_defaults(handler=cmd_search_docs, columns=["id", "file_path", "doc_type", "score"])
+
+    # learn
+    learn_parser = subparsers.add_parser("learn", parents=[common],
+                                         help="Learn patterns from reference repositories")
+    learn_parser.add_argument("refs", nargs="+",
+                              help="Reference repositories (paths, URLs or repository IDs)")
+    learn_parser.add_argument("--path", default=os.getcwd(),
+                              help="Active repository the references belong to. Defaults to current directory.")
+    learn_parser.add_argument("--apply", action="store_true",
+                              help="Apply learned patterns to the active repository")
+    learn_parser.add_argument("--deep", action="store_true",
+                              help="Learn across multiple reference repositories (requires two or more)")
+    learn_parser.set_defaults(handler=cmd_learn, skip_index=True)
+
+    # repos
+    repos_parser = subparsers.add_parser("repos", parents=[common], help="List indexed repositories")
+    repos_parser.add_argument("--type", choices=["active", "reference"],
+                              help="Only list repositories of this type")
+    repos_parser.set_defaults(handler=cmd_repos,
+                              columns=["id", "repo_name", "repo_type", "code_files", "docs", "indexed_commit", "last_updated"])
+
+    # report
+    report_parser = subparsers.add_parser("report", parents=[common], help="Report on an indexed repository")
+    report_parser.add_argument("--repo", help="Repository id, name or path. Defaults to current directory.")
+    report_parser.add_argument("--kind", choices=sorted(REPORTS), default="summary",
+                               help="Report to produce (default: summary)")
+    report_parser.set_defaults(handler=cmd_report)
+
+    # serve
+    serve_parser = subparsers.add_parser("serve", parents=[common],
+                                         help="Serve the index over a local HTTP JSON API")
+    serve_parser.add_argument("--host", default=api_config.host,
+                              help=f"Interface to bind (default: {api_config.host})")
+    serve_parser.add_argument("--port", type=int, default=api_config.port,
+                              help=f"Port to listen on (default: {api_config.port})")
+    serve_parser.set_defaults(handler=cmd_serve)
+
+    # lsp
+    lsp_parser = subparsers.add_parser("lsp", parents=[common],
+                                       help="Run a Language Server Protocol server on stdin/stdout")
+    lsp_parser.set_defaults(handler=cmd_lsp, result_stream=sys.stderr)
+
+    return parser
+
+async def run_command(args) -> int:
+    """Initialize components, run the selected subcommand and print its result."""
+    stream = getattr(args, "result_stream", None)
     try:
-        await main_async(args)
-    except KeyboardInterrupt:
-        log("KeyboardInterrupt caught – shutting down.", level="warning")
-        sys.exit(0)
+        # Initialize application components including database pools
+        await _initialize_components()
+        result, exit_code = await args.handler(args)
+    except CommandError as e:
+        emit_error(str(e), args.format, e.exit_code, stream=stream)
+        return e.exit_code
     except Exception as e:
-        log(f"Fatal error in main process: {e}", level="error")
-        sys.exit(1)
+        await log(f"Fatal error in {args.command}: {e}", level="error")
+        emit_error(str(e), args.format, stream=stream)
+        return EXIT_ERROR
+
+    emit(result, args.format, columns=getattr(args, "columns", None), stream=stream)
+    return exit_code
+
+async def main(argv: Optional[List[str]] = None) -> int:
+    """CLI entry point with argument parsing."""
+    args = build_parser().parse_args(argv)
+
+    try:
+        return await run_command(args)
+    except KeyboardInterrupt:
+        await log("KeyboardInterrupt caught – shutting down.", level="warning")
+        return EXIT_OK
 
 if __name__ == "__main__":
     # Register cleanup handlers before starting
     from utils.async_runner import cleanup_tasks
-    
+
     # Register handlers in correct order (database cleanup before async tasks)
     register_shutdown_handler(connection_manager.cleanup)  # Database cleanup first
     register_shutdown_handler(cleanup_tasks)  # Async tasks cleanup last
-    
+
     try:
-        asyncio.run(main())
+        sys.exit(asyncio.run(main()))
     except KeyboardInterrupt:
         log_sync("KeyboardInterrupt caught – shutting down.", level="warning")
-        sys.exit(0)
+        sys.exit(EXIT_OK)
     except Exception as e:
         log_sync(f"Fatal error in main process: {e}", level="error")
-        sys.exit(1) 
\ No newline at end of file
+        sys.exit(EXIT_ERROR)
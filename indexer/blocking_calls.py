@@ -0,0 +1,200 @@
+"""[1.11] Blocking calls in async code.
+
+Flow:
+1. Calls:
+   - The symbol table [1.6] resolves each call of a Python or Rust function;
+     calls it can't resolve are named by their full import path
+     (time.sleep, std::fs::read_to_string, requests.get)
+   - Calls made inside a closure handed to spawn_blocking, run_in_executor
+     and the like run off the runtime and are skipped
+
+2. Denylist:
+   - Built-in patterns per language (BLOCKING_CALLS) plus
+     analysis_config.blocking_calls, minus analysis_config.blocking_allow;
+     patterns are fnmatch globs over the full path
+
+3. Chains:
+   - From every async def / async fn, direct calls into synchronous
+     functions of the repository are followed up to
+     analysis_config.blocking_depth deep; async callees report their own
+   - Each blocking call reached is a finding on the async function, with the
+     call chain that leads to it
+
+4. Findings:
+   - Stored in findings with analysis 'blocking_async', replacing the
+     previous run's rows
+"""
+
+import fnmatch
+import json
+from collections import defaultdict
+from typing import Any, Dict, List, Optional, Tuple
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from config.config import analysis_config
+from indexer.symbol_table import SymbolTable, external_path, symbol_id
+
+ANALYSIS = "blocking_async"
+
+# Calls that block the thread they run on, per language family
+BLOCKING_CALLS = {
+    "python": [
+        "time.sleep",
+        "requests.*",
+        "urllib.request.urlopen",
+        "subprocess.run",
+        "subprocess.call",
+        "subprocess.check_call",
+        "subprocess.check_output",
+        "os.system",
+        "socket.create_connection"
+    ],
+    "rust": [
+        "std::fs::*",
+        "std::thread::sleep",
+        "std::net::TcpStream::connect",
+        "std::net::TcpListener::bind",
+        "std::net::UdpSocket::bind",
+        "std::io::stdin",
+        "reqwest::blocking::*"
+    ]
+}
+
+_FUNCTION_KINDS = {"function", "method"}
+
+def _separator(family: str) -> str:
+    return "::" if family == "rust" else "."
+
+def blocking_pattern(path: str, family: str) -> Optional[str]:
+    """The denylist pattern a call path matches, if any."""
+    configured = [pattern for pattern in analysis_config.blocking_calls if ("::" in pattern) == (family == "rust")]
+    if any(fnmatch.fnmatchcase(path, pattern) for pattern in analysis_config.blocking_allow):
+        return None
+    return next(
+        (pattern for pattern in BLOCKING_CALLS.get(family, []) + configured if fnmatch.fnmatchcase(path, pattern)),
+        None
+    )
+
+def _caller(info, scope: str) -> Optional[str]:
+    """The innermost function or method a scope is in."""
+    while scope:
+        if (info.defs.get(scope) or {}).get("kind") in _FUNCTION_KINDS:
+            return scope
+        scope = scope.rpartition(".")[0]
+    return None
+
+def _call_graph(table: SymbolTable) -> Tuple[Dict[str, Dict[str, int]], Dict[str, List[Dict[str, Any]]]]:
+    """Calls into repository functions, and blocking calls, per calling function id."""
+    calls: Dict[str, Dict[str, int]] = defaultdict(dict)
+    blocking: Dict[str, List[Dict[str, Any]]] = defaultdict(list)
+    for info in table.files.values():
+        if info.family not in BLOCKING_CALLS:
+            continue
+        for reference in info.symbols.get("references", []):
+            if reference.get("kind") not in ("call", "macro") or reference.get("offloaded"):
+                continue
+            qualified = _caller(info, reference.get("scope") or "")
+            if qualified is None:
+                continue
+            caller = symbol_id(info.path, qualified)
+            line = min(reference.get("lines") or [reference.get("line")])
+            resolved = table.resolve(info, reference)
+            head = (reference.get("qualifier") or reference["name"]).split(_separator(info.family))[0]
+            # A unique repository definition of an imported name is a guess, not the import
+            if resolved is not None and not (resolved[1] == "name" and head in info.bindings):
+                target, _ = resolved
+                if (table.files[target.file].defs.get(target.qualified) or {}).get("kind") in _FUNCTION_KINDS:
+                    callee = symbol_id(target.file, target.qualified)
+                    calls[caller][callee] = min(line, calls[caller].get(callee, line))
+                continue
+            path = external_path(info, reference["name"], reference.get("qualifier"))
+            pattern = blocking_pattern(path, info.family)
+            if pattern is not None:
+                blocking[caller].append({"call": path, "pattern": pattern, "file_path": info.path, "line": line})
+    return calls, blocking
+
+def _definition(table: SymbolTable, node_id: str) -> Tuple[str, Dict[str, Any]]:
+    file_path, _, qualified = node_id.partition("#")
+    return qualified, table.files[file_path].defs.get(qualified) or {}
+
+def blocking_findings(table: SymbolTable, depth: Optional[int] = None) -> List[Dict[str, Any]]:
+    """[1.11.1] Blocking calls reachable from each async function, with their call chains."""
+    depth = analysis_config.blocking_depth if depth is None else depth
+    calls, blocking = _call_graph(table)
+    findings = []
+    for info in sorted(table.files.values(), key=lambda info: info.path):
+        if info.family not in BLOCKING_CALLS:
+            continue
+        for qualified, definition in sorted(info.defs.items(), key=lambda item: item[1]["line"]):
+            if not definition.get("async") or definition.get("kind") not in _FUNCTION_KINDS:
+                continue
+            start = symbol_id(info.path, qualified)
+            # Breadth first, so each blocking call is reported with its shortest chain
+            queue: List[Tuple[str, List[Dict[str, Any]]]] = [(start, [])]
+            visited, seen = {start}, set()
+            while queue:
+                current, chain = queue.pop(0)
+                current_qualified, _ = _definition(table, current)
+                for site in blocking.get(current, []):
+                    key = (site["file_path"], site["line"], site["call"])
+                    if key in seen:
+                        continue
+                    seen.add(key)
+                    findings.append({
+                        "file_path": info.path,
+                        "line": chain[0]["line"] if chain else site["line"],
+                        "symbol": qualified.replace(".", _separator(info.family)),
+                        "language": info.symbols.get("language"),
+                        "call": site["call"],
+                        "pattern": site["pattern"],
+                        "chain": chain + [{"symbol": current_qualified, "file_path": site["file_path"], "line": site["line"], "call": site["call"]}]
+                    })
+                if len(chain) >= depth:
+                    continue
+                for callee, line in sorted(calls.get(current, {}).items()):
+                    if callee in visited or _definition(table, callee)[1].get("async"):
+                        continue
+                    visited.add(callee)
+                    hop = {"symbol": current_qualified, "file_path": current.partition("#")[0], "line": line, "call": _definition(table, callee)[0]}
+                    queue.append((callee, chain + [hop]))
+    return findings
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return={})
+async def run_blocking_call_check(repo_id: int, repo_path: str) -> Dict[str, int]:
+    """[1.11.2] Store blocking calls in the repository's async functions as findings."""
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, language, symbols FROM file_symbols WHERE repo_id = $1 AND language IN ('python', 'rust');",
+        repo_id
+    )
+    findings = blocking_findings(SymbolTable(repo_path, rows))
+
+    batch = [("DELETE FROM findings WHERE repo_id = $1 AND analysis = $2;", (repo_id, ANALYSIS))]
+    for finding in findings:
+        via = [hop["call"] for hop in finding["chain"][:-1]]
+        message = f"{finding['call']} blocks the async function {finding['symbol']}"
+        batch.append((
+            """
+            INSERT INTO findings (repo_id, analysis, kind, severity, file_path, line, symbol, message, details)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+            """,
+            (
+                repo_id, ANALYSIS, "blocking_call", "warning", finding["file_path"], finding["line"], finding["symbol"],
+                f"{message} via {' -> '.join(via)}" if via else message,
+                json.dumps({key: finding[key] for key in ("language", "call", "pattern", "chain")})
+            )
+        ))
+    await backend.execute_batch(batch)
+    if findings:
+        await log(f"Blocking calls in async code of repository {repo_id}: {len(findings)} finding(s)", level="info")
+    return {"findings": len(findings)}
+
+__all__ = [
+    "ANALYSIS",
+    "BLOCKING_CALLS",
+    "blocking_pattern",
+    "blocking_findings",
+    "run_blocking_call_check"
+]
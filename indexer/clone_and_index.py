@@ -227,5 +227,4 @@ async def clone_and_index_repo(
                 await context['transaction'].track_repo_change(repo_id)
                 
                 # Index repository using unified indexer
-                files = await get_files(temp_dir)
-                await process_repository_indexing(temp_dir, repo_id, files=files)
\ No newline at end of file
+                await process_repository_indexing(temp_dir, repo_id, repo_type="reference")
\ No newline at end of file
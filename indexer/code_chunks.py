@@ -0,0 +1,227 @@
+"""[1.14] Function-level code chunks.
+
+Flow:
+1. Blocks:
+   - BlockExtractor.extract_blocks gives the function, class and method
+     blocks of a parsed file as character spans; the symbol extractor's
+     definitions name them and give the enclosing symbol
+   - Languages the block extractor has no patterns for use the definitions'
+     own line spans as blocks
+
+2. Chunks:
+   - Each function and method is a chunk with its line span, qualified
+     symbol and parent symbol
+   - A class keeps its own lines (signature, docstring, fields) and leaves
+     its methods to their own chunks; lines outside every block (imports,
+     constants, script code) are module chunks
+   - Blocks over indexing_config.chunk_max_lines or chunk_max_chars are cut
+     into windows overlapping by chunk_overlap_lines, numbered by part
+
+3. Storage:
+   - Each chunk is embedded on its own, in batches per file, and replaces
+     the file's previous chunks in code_chunks [6.5.11]; search ranks
+     chunks, not files
+   - The file-level embedding in code_snippets is the mean of its chunks',
+     so the whole file is not embedded again
+"""
+
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional, Tuple
+
+from utils.logger import log
+from config.config import indexing_config
+
+# Block and definition kinds that become chunks, by chunk kind
+CHUNK_KINDS = {
+    "function": "function",
+    "method": "method",
+    "class": "class",
+    "struct": "class",
+    "enum": "class",
+    "union": "class",
+    "trait": "class",
+    "interface": "class"
+}
+
+@dataclass
+class _Block:
+    kind: str
+    start_line: int
+    end_line: int
+    symbol: Optional[str] = None
+    parent_symbol: Optional[str] = None
+
+def _line_of(content: str, offset: int) -> int:
+    return content.count("\n", 0, max(offset, 0)) + 1
+
+def _qualified(definition: Dict[str, Any]) -> str:
+    scope = definition.get("scope") or ""
+    return f"{scope}.{definition['name']}" if scope else definition["name"]
+
+def _definitions(symbols: Optional[Dict[str, Any]]) -> List[Dict[str, Any]]:
+    return [
+        definition for definition in (symbols or {}).get("definitions", [])
+        if definition.get("kind") in CHUNK_KINDS and definition.get("end_line")
+    ]
+
+def _named(block: _Block, definitions: List[Dict[str, Any]]) -> _Block:
+    """Name a block after the definition it spans; decorators and comments may start it early."""
+    candidates = [
+        definition for definition in definitions
+        if block.start_line <= definition["line"] <= block.end_line
+        and definition["end_line"] <= block.end_line
+    ]
+    if not candidates:
+        return block
+    definition = min(
+        candidates,
+        key=lambda definition: (definition["line"] - block.start_line) + (block.end_line - definition["end_line"])
+    )
+    block.symbol = _qualified(definition)
+    block.parent_symbol = definition.get("scope") or None
+    block.kind = CHUNK_KINDS[definition["kind"]]
+    return block
+
+def blocks_from_extractor(
+    content: str,
+    extracted: List[Dict[str, Any]],
+    symbols: Optional[Dict[str, Any]] = None
+) -> List[_Block]:
+    """Function, class and method blocks of extract_blocks output, as line spans."""
+    definitions = _definitions(symbols)
+    blocks = {}
+    for item in extracted:
+        kind = CHUNK_KINDS.get(item.get("type"))
+        start, end = item.get("start") or 0, item.get("end") or 0
+        if kind is None or end <= start:
+            continue
+        span = (_line_of(content, start), _line_of(content, end - 1))
+        # Several patterns can match the same block
+        blocks.setdefault(span, _named(_Block(kind, *span), definitions))
+    return list(blocks.values())
+
+def blocks_from_symbols(symbols: Optional[Dict[str, Any]]) -> List[_Block]:
+    """Function, class and method blocks from the symbol extractor's definitions."""
+    return [
+        _Block(
+            CHUNK_KINDS[definition["kind"]],
+            definition["line"],
+            definition["end_line"],
+            _qualified(definition),
+            definition.get("scope") or None
+        )
+        for definition in _definitions(symbols)
+    ]
+
+def _windows(lines: List[Tuple[int, str]], max_lines: int, max_chars: int, overlap: int) -> List[List[Tuple[int, str]]]:
+    """Consecutive runs of lines within the size limits, each repeating the last overlap lines of the previous one."""
+    windows = []
+    start = 0
+    while start < len(lines):
+        end, size = start, 0
+        while end < len(lines) and end - start < max_lines and (end == start or size + len(lines[end][1]) + 1 <= max_chars):
+            size += len(lines[end][1]) + 1
+            end += 1
+        windows.append(lines[start:end])
+        if end >= len(lines):
+            break
+        start = max(end - overlap, start + 1)
+    return windows
+
+def _runs(numbers: List[int]) -> List[List[int]]:
+    runs: List[List[int]] = []
+    for number in numbers:
+        if runs and runs[-1][-1] == number - 1:
+            runs[-1].append(number)
+        else:
+            runs.append([number])
+    return runs
+
+def build_chunks(
+    content: str,
+    blocks: List[_Block],
+    max_lines: Optional[int] = None,
+    max_chars: Optional[int] = None,
+    overlap: Optional[int] = None
+) -> List[Dict[str, Any]]:
+    """[1.14.1] Chunks of a file: each block's own lines, then the module's, split for size."""
+    max_lines = max(1, max_lines or indexing_config.chunk_max_lines)
+    max_chars = max(1, max_chars or indexing_config.chunk_max_chars)
+    overlap = indexing_config.chunk_overlap_lines if overlap is None else overlap
+    overlap = max(0, min(overlap, max_lines - 1))
+    source = content.splitlines()
+
+    # Outer blocks first; a block nested in another is carved out of it
+    blocks = sorted(
+        (block for block in blocks if 1 <= block.start_line <= block.end_line <= len(source)),
+        key=lambda block: (block.start_line, -block.end_line)
+    )
+    owner: List[Optional[int]] = [None] * (len(source) + 1)
+    for index, block in enumerate(blocks):
+        owner[block.start_line:block.end_line + 1] = [index] * (block.end_line - block.start_line + 1)
+    own: Dict[Optional[int], List[int]] = {}
+    for line in range(1, len(source) + 1):
+        own.setdefault(owner[line], []).append(line)
+
+    chunks = []
+    for index in list(range(len(blocks))) + [None]:
+        block = blocks[index] if index is not None else None
+        part = 0
+        for run in _runs(own.get(index, [])):
+            lines = [(line, source[line - 1]) for line in run]
+            # Blank lines at either end add nothing to a run
+            while lines and not lines[0][1].strip():
+                lines.pop(0)
+            while lines and not lines[-1][1].strip():
+                lines.pop()
+            if not lines:
+                continue
+            for window in _windows(lines, max_lines, max_chars, overlap):
+                chunks.append({
+                    "kind": block.kind if block else "module",
+                    "symbol": block.symbol if block else None,
+                    "parent_symbol": block.parent_symbol if block else None,
+                    "start_line": window[0][0],
+                    "end_line": window[-1][0],
+                    "part": part,
+                    "content": "\n".join(text for _, text in window)
+                })
+                part += 1
+    chunks.sort(key=lambda chunk: (chunk["start_line"], chunk["end_line"]))
+    return chunks
+
+async def file_chunks(
+    language: Optional[str],
+    content: str,
+    ast: Optional[Dict[str, Any]] = None,
+    symbols: Optional[Dict[str, Any]] = None
+) -> List[Dict[str, Any]]:
+    """[1.14.2] Chunks of one parsed file."""
+    blocks: List[_Block] = []
+    if language and ast:
+        try:
+            from parsers.block_extractor import get_block_extractor
+            extractor = await get_block_extractor(language)
+            if extractor is not None:
+                blocks = blocks_from_extractor(content, await extractor.extract_blocks(ast, content) or [], symbols)
+        except Exception as e:
+            await log(f"Block extraction unavailable for {language}: {e}", level="debug")
+    if not blocks:
+        blocks = blocks_from_symbols(symbols)
+    return build_chunks(content, blocks)
+
+def file_embedding(chunks: List[Dict[str, Any]]) -> Optional[List[float]]:
+    """[1.14.3] The mean of a file's chunk embeddings, or None without any."""
+    vectors = [chunk["embedding"] for chunk in chunks if chunk.get("embedding") is not None]
+    if not vectors:
+        return None
+    return [float(sum(values)) / len(vectors) for values in zip(*vectors)]
+
+__all__ = [
+    "CHUNK_KINDS",
+    "blocks_from_extractor",
+    "blocks_from_symbols",
+    "build_chunks",
+    "file_chunks",
+    "file_embedding"
+]
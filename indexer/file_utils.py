@@ -7,7 +7,6 @@ This module provides utilities for file operations, including:
 """
 
 import os
-from pathlib import Path
 from typing import List, Set, Optional, Dict, Any
 from utils.logger import log
 from parsers.types import FileType
@@ -24,6 +23,7 @@ from utils.request_cache import cached_in_request
 from utils.health_monitor import global_health_monitor, ComponentStatus, monitor_operation
 import asyncio
 from config import FileConfig
+from indexer.ignore_rules import IgnoreRules, find_root, get_ignore_rules
 
 # Initialize file config
 file_config = FileConfig()
@@ -65,20 +65,26 @@ async def _check_health() -> Dict[str, Any]:
         }
     }
 
-def should_ignore(file_path: str) -> bool:
+def should_ignore(file_path: str, rules: Optional[IgnoreRules] = None, is_dir: bool = False) -> bool:
     """Check if file should be ignored based on patterns.
     
+    Applies FileConfig.ignore_patterns, .git/info/exclude and every
+    .gitignore and .repoanalyzerignore above the file with gitignore
+    semantics (see indexer.ignore_rules).
+    
     Args:
         file_path: The path to check
+        rules: Rules of the tree being walked; defaults to the git working
+            tree containing the file
+        is_dir: Whether file_path is a directory
         
     Returns:
         True if the file should be ignored, False otherwise
     """
-    # Use patterns from config
-    ignore_patterns = set(file_config.ignore_patterns)
-    
-    path_parts = Path(file_path).parts
-    return any(pattern in path_parts for pattern in ignore_patterns)
+    if rules is None:
+        directory = file_path if is_dir else os.path.dirname(os.path.abspath(file_path))
+        rules = get_ignore_rules(find_root(directory))
+    return rules.is_ignored(file_path, is_dir)
 
 @handle_async_errors()
 @cached_in_request(lambda file_path: f"classify:{file_path}")
@@ -144,12 +150,20 @@ async def get_files(base_path: str, file_types: Set = None) -> List[str]:
         files = []
         try:
             with monitor_operation("get_files", "file_utils"):
-                for root, _, filenames in os.walk(base_path):
+                # Re-read ignore files so edits since the last walk apply
+                base_path = os.path.abspath(base_path)
+                rules = get_ignore_rules(find_root(base_path), refresh=True)
+                for root, dirnames, filenames in os.walk(base_path):
+                    # Don't descend into ignored directories
+                    dirnames[:] = [
+                        dirname for dirname in dirnames
+                        if not should_ignore(os.path.join(root, dirname), rules, is_dir=True)
+                    ]
                     for filename in filenames:
                         file_path = os.path.join(root, filename)
                         
                         # Skip ignored files
-                        if should_ignore(file_path):
+                        if should_ignore(file_path, rules):
                             continue
                         
                         # Check file type
@@ -0,0 +1,238 @@
+"""[4.5] Git object database access for indexing refs and commit ranges.
+
+Flow:
+1. Revision Resolution:
+   - GitTreeSource.open() resolves a branch, tag or commit (and an optional
+     --since base) without touching the working tree or HEAD
+   - head_state() reports the checked-out commit for working-tree indexing
+
+2. File Listing:
+   - list_files() walks the commit's tree, or only the paths changed since
+     the base revision (renames count as delete + add)
+   - Ignored, binary and oversized blobs are skipped like files on disk, with
+     the .gitignore files of the commit [1.4]
+
+3. Reading:
+   - read() decodes blob contents straight from the object database
+   - hash_files(), exists() and is_ignored() let the manifest [1.3] plan
+     against the commit
+
+Paths are reported as absolute working-tree paths, so an index built from a
+ref and one built from the checkout key files the same way.
+"""
+
+import os
+import asyncio
+import hashlib
+from typing import Dict, List, Optional, Set, Tuple
+
+import git
+from git.exc import BadName, GitCommandError, InvalidGitRepositoryError, NoSuchPathError
+
+from config import FileConfig
+from parsers.types import FileType
+from indexer.file_utils import should_ignore, classify_file
+from indexer.ignore_rules import IgnoreRules
+from utils.logger import log
+from utils.error_handling import ProcessingError
+
+file_config = FileConfig()
+
+# Git treats a NUL byte in the first 8000 bytes as binary
+_BINARY_SNIFF_SIZE = 8000
+_SYMLINK_MODE = 0o120000
+
+def _open_repo(repo_path: str) -> git.Repo:
+    try:
+        return git.Repo(repo_path, search_parent_directories=True)
+    except (InvalidGitRepositoryError, NoSuchPathError):
+        raise ProcessingError(f"Not a git repository: {repo_path}")
+
+def _resolve(repo: git.Repo, rev: str) -> git.Commit:
+    try:
+        return repo.commit(rev)
+    except (BadName, ValueError, GitCommandError):
+        raise ProcessingError(f"Unknown revision: {rev}")
+
+def _decode(data: bytes) -> Optional[str]:
+    for encoding in file_config.supported_encodings:
+        try:
+            return data.decode(encoding)
+        except UnicodeDecodeError:
+            continue
+    return None
+
+class GitTreeSource:
+    """[4.5.1] The files of one commit, read from the object database."""
+
+    def __init__(self, repo: git.Repo, commit: git.Commit, root: str, rev: str, base: Optional[git.Commit] = None):
+        """Private constructor - use open() instead."""
+        self._repo = repo
+        self._commit = commit
+        self._base = base
+        self._root = root
+        self._rev = rev
+        self._git_root = os.path.abspath(repo.working_tree_dir or root)
+        self._blobs: Optional[Dict[str, git.Blob]] = None
+        self._paths: Set[str] = set()
+        self._rules: Optional[IgnoreRules] = None
+        self._lock = asyncio.Lock()
+
+    @classmethod
+    async def open(cls, repo_path: str, rev: str = "HEAD", since: Optional[str] = None) -> 'GitTreeSource':
+        """Resolve rev (and since) in the repository containing repo_path.
+
+        Raises:
+            ProcessingError: If the path is not in a git repository or a revision is unknown
+        """
+        root = os.path.abspath(repo_path)
+
+        def _open():
+            repo = _open_repo(root)
+            commit = _resolve(repo, rev)
+            base = _resolve(repo, since) if since else None
+            return repo, commit, base
+
+        loop = asyncio.get_running_loop()
+        repo, commit, base = await loop.run_in_executor(None, _open)
+        return cls(repo, commit, root, rev, base)
+
+    @property
+    def rev(self) -> str:
+        return self._rev
+
+    @property
+    def commit_sha(self) -> str:
+        return self._commit.hexsha
+
+    @property
+    def base_sha(self) -> Optional[str]:
+        return self._base.hexsha if self._base is not None else None
+
+    async def _run(self, fn, *args):
+        # gitpython objects are not thread-safe; one call at a time
+        async with self._lock:
+            loop = asyncio.get_running_loop()
+            return await loop.run_in_executor(None, fn, *args)
+
+    def _absolute(self, path: str) -> str:
+        return os.path.join(self._git_root, *path.split("/"))
+
+    def _under_root(self, path: str) -> bool:
+        return path == self._root or path.startswith(self._root.rstrip(os.sep) + os.sep)
+
+    def _walk_tree(self) -> Dict[str, git.Blob]:
+        items = {}
+        for item in self._commit.tree.traverse():
+            if item.type != "blob":
+                continue
+            path = self._absolute(item.path)
+            self._paths.add(path)
+            if item.mode != _SYMLINK_MODE:
+                items[path] = item
+
+        def _read_ignore_file(path: str) -> Optional[str]:
+            item = items.get(path)
+            return _decode(item.data_stream.read()) if item is not None else None
+
+        rules = self._rules = IgnoreRules(self._git_root, read_file=_read_ignore_file)
+        blobs = {}
+        for path, item in items.items():
+            if not self._under_root(path) or should_ignore(path, rules):
+                continue
+            if item.size > file_config.max_file_size:
+                continue
+            blobs[path] = item
+        return blobs
+
+    async def _tree(self) -> Dict[str, git.Blob]:
+        if self._blobs is None:
+            self._blobs = await self._run(self._walk_tree)
+        return self._blobs
+
+    def _changed_paths(self) -> Tuple[List[str], List[str]]:
+        """Paths added or modified since the base, and paths deleted or renamed away."""
+        changed, removed = [], []
+        for diff in self._base.diff(self._commit, M=True):
+            if diff.deleted_file or diff.renamed_file:
+                removed.append(self._absolute(diff.a_path))
+            if not diff.deleted_file:
+                changed.append(self._absolute(diff.b_path))
+        return changed, removed
+
+    async def list_files(self) -> List[str]:
+        """Processable files of the commit, or those changed since the base revision."""
+        blobs = await self._tree()
+        if self._base is None:
+            candidates = sorted(blobs)
+        else:
+            changed, removed = await self._run(self._changed_paths)
+            candidates = sorted(path for path in set(changed) if path in blobs)
+            await log(
+                f"{self._base.hexsha[:12]}..{self._commit.hexsha[:12]}: "
+                f"{len(candidates)} changed, {len(removed)} deleted or renamed",
+                level="info"
+            )
+
+        files = []
+        for path in candidates:
+            # Same CODE/DOC filter get_files() applies on disk
+            classification = await classify_file(path)
+            if classification and classification.file_type in {FileType.CODE, FileType.DOC}:
+                files.append(path)
+        return files
+
+    def _raw(self, path: str) -> Optional[bytes]:
+        blob = self._blobs.get(path) if self._blobs is not None else None
+        return blob.data_stream.read() if blob is not None else None
+
+    def _text(self, path: str) -> Optional[bytes]:
+        data = self._raw(path)
+        if data is None or b"\0" in data[:_BINARY_SNIFF_SIZE]:
+            return None
+        return data
+
+    async def read(self, path: str) -> Optional[str]:
+        """Decoded contents of a file at the commit, or None if absent or binary."""
+        await self._tree()
+        data = await self._run(self._text, path)
+        return _decode(data) if data is not None else None
+
+    async def hash_files(self, paths: List[str]) -> Dict[str, Optional[str]]:
+        """sha256 of each blob, matching what the manifest records for files on disk."""
+        await self._tree()
+
+        def _hash_all():
+            hashes = {}
+            for path in paths:
+                data = self._raw(path)
+                hashes[path] = hashlib.sha256(data).hexdigest() if data is not None else None
+            return hashes
+
+        return await self._run(_hash_all)
+
+    def exists(self, path: str) -> bool:
+        """Whether the commit has a file at path (after list_files())."""
+        return path in self._paths
+
+    def is_ignored(self, path: str) -> bool:
+        """Whether the commit's ignore files exclude path (after list_files())."""
+        return self._rules is not None and should_ignore(path, self._rules)
+
+async def head_state(repo_path: str) -> Tuple[Optional[str], Optional[bool]]:
+    """(HEAD commit, has uncommitted changes) of the checkout, or (None, None) outside git."""
+    def _state():
+        try:
+            repo = git.Repo(os.path.abspath(repo_path), search_parent_directories=True)
+            return repo.head.commit.hexsha, repo.is_dirty(untracked_files=True)
+        except (InvalidGitRepositoryError, NoSuchPathError, ValueError, GitCommandError):
+            # Not a repository, or one without commits yet
+            return None, None
+
+    loop = asyncio.get_running_loop()
+    return await loop.run_in_executor(None, _state)
+
+__all__ = [
+    "GitTreeSource",
+    "head_state"
+]
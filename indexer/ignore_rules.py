@@ -0,0 +1,343 @@
+"""[1.4] Gitignore-compatible ignore rules.
+
+Flow:
+1. Rule Sources (lowest to highest precedence):
+   - FileConfig.ignore_patterns, treated as gitignore patterns
+   - .git/info/exclude of the repository
+   - .gitignore, then .repoanalyzerignore, in every directory from the root
+     down to the file; deeper files override shallower ones
+
+2. Matching:
+   - Globs (*, ?, [...], **), anchored and directory-only patterns and
+     negation follow gitignore(5)
+   - The last matching rule wins; a file inside an ignored directory stays
+     ignored even if a later rule re-includes it, as in git
+
+3. Integration Points:
+   - should_ignore() in file_utils, and so get_files() and the watcher
+   - GitTreeSource [4.5.1] reads ignore files from the commit via read_file
+   - explain() backs `index --explain-ignore`
+"""
+
+import os
+import re
+from dataclasses import dataclass
+from functools import lru_cache
+from typing import Callable, Dict, List, Optional
+
+from config import FileConfig
+
+file_config = FileConfig()
+
+# Per-directory ignore files, in increasing precedence
+IGNORE_FILES = (".gitignore", ".repoanalyzerignore")
+
+CONFIG_SOURCE = "FileConfig.ignore_patterns"
+
+@dataclass
+class IgnoreRule:
+    """One pattern line of an ignore file."""
+    pattern: str
+    source: str
+    line: Optional[int]
+    base: str
+    negated: bool
+    dir_only: bool
+    regex: "re.Pattern"
+
+    def matches(self, rel_path: str, is_dir: bool) -> bool:
+        if self.dir_only and not is_dir:
+            return False
+        if self.base:
+            if not rel_path.startswith(self.base + "/"):
+                return False
+            rel_path = rel_path[len(self.base) + 1:]
+        return self.regex.match(rel_path) is not None
+
+    def to_dict(self) -> Dict:
+        return {
+            "pattern": self.pattern,
+            "source": self.source,
+            "line": self.line,
+            "negated": self.negated
+        }
+
+@dataclass
+class IgnoreMatch:
+    """The rule deciding a path, and the path (or parent directory) it matched."""
+    rule: IgnoreRule
+    matched: str
+
+    @property
+    def ignored(self) -> bool:
+        return not self.rule.negated
+
+def _translate_segment(segment: str) -> str:
+    """Regex for one path segment of a gitignore glob."""
+    out, i, n = [], 0, len(segment)
+    while i < n:
+        c = segment[i]
+        if c == "\\" and i + 1 < n:
+            out.append(re.escape(segment[i + 1]))
+            i += 2
+            continue
+        if c == "*":
+            # Asterisks that are not a whole "**" segment act like one
+            while i + 1 < n and segment[i + 1] == "*":
+                i += 1
+            out.append("[^/]*")
+        elif c == "?":
+            out.append("[^/]")
+        elif c == "[":
+            j = i + 1
+            if j < n and segment[j] in "!^":
+                j += 1
+            if j < n and segment[j] == "]":
+                j += 1
+            while j < n and segment[j] != "]":
+                j += 1
+            if j >= n:
+                out.append(re.escape(c))
+            else:
+                body = segment[i + 1:j]
+                negate = body[:1] in ("!", "^")
+                if negate:
+                    body = body[1:]
+                body = body.replace("\\", "\\\\")
+                out.append(f"[{'^' if negate else ''}{body}]")
+                i = j
+        else:
+            out.append(re.escape(c))
+        i += 1
+    return "".join(out)
+
+def _translate(pattern: str, anchored: bool) -> "re.Pattern":
+    parts = pattern.split("/")
+    out = []
+    for i, segment in enumerate(parts):
+        last = i == len(parts) - 1
+        if segment == "**":
+            out.append(".*" if last else "(?:.*/)?")
+        else:
+            out.append(_translate_segment(segment) + ("" if last else "/"))
+    prefix = "" if anchored else "(?:.*/)?"
+    return re.compile(f"^{prefix}{''.join(out)}$", re.DOTALL)
+
+def parse_rule(line: str, source: str, line_no: Optional[int], base: str = "") -> Optional[IgnoreRule]:
+    """Parse one ignore file line; None for blanks and comments."""
+    text = line.rstrip("\r\n")
+    if not text or text.startswith("#"):
+        return None
+    # Trailing spaces are dropped unless escaped
+    while text.endswith(" ") and not text.endswith("\\ "):
+        text = text[:-1]
+    if not text:
+        return None
+
+    pattern = text
+    negated = text.startswith("!")
+    if negated:
+        text = text[1:]
+    dir_only = text.endswith("/")
+    if dir_only:
+        text = text.rstrip("/")
+    if not text:
+        return None
+    # A slash anywhere but at the end anchors the pattern to its directory
+    anchored = "/" in text
+    text = text.lstrip("/")
+    if not text:
+        return None
+
+    return IgnoreRule(
+        pattern=pattern,
+        source=source,
+        line=line_no,
+        base=base,
+        negated=negated,
+        dir_only=dir_only,
+        regex=_translate(text, anchored)
+    )
+
+def _read_disk(path: str) -> Optional[str]:
+    try:
+        with open(path, "r", encoding="utf-8", errors="replace") as f:
+            return f.read()
+    except OSError:
+        return None
+
+def _git_dir(root: str) -> Optional[str]:
+    """The repository's git directory, following a worktree's `gitdir:` file."""
+    dot_git = os.path.join(root, ".git")
+    if os.path.isdir(dot_git):
+        return dot_git
+    content = _read_disk(dot_git) if os.path.isfile(dot_git) else None
+    if content and content.startswith("gitdir:"):
+        return os.path.join(root, content[len("gitdir:"):].strip())
+    return None
+
+@lru_cache(maxsize=1024)
+def find_root(path: str) -> str:
+    """The git working tree containing path, or path itself outside git."""
+    start = os.path.abspath(path)
+    current = start
+    while True:
+        if os.path.exists(os.path.join(current, ".git")):
+            return current
+        parent = os.path.dirname(current)
+        if parent == current:
+            return start
+        current = parent
+
+class IgnoreRules:
+    """Ignore rules of one tree, loaded lazily per directory."""
+
+    def __init__(self, root: str, read_file: Optional[Callable[[str], Optional[str]]] = None):
+        self.root = os.path.abspath(root)
+        self._read_file = read_file or _read_disk
+        self._dir_rules: Dict[str, List[IgnoreRule]] = {}
+        self._base_rules = self._load_base_rules()
+
+    def _load_base_rules(self) -> List[IgnoreRule]:
+        rules = []
+        for pattern in file_config.ignore_patterns:
+            rule = parse_rule(pattern, CONFIG_SOURCE, None)
+            if rule:
+                rules.append(rule)
+        # info/exclude is never part of a commit, so it always comes from disk
+        git_dir = _git_dir(self.root)
+        if git_dir:
+            exclude = os.path.join(git_dir, "info", "exclude")
+            rules.extend(self._parse_file(exclude, _read_disk(exclude), ""))
+        return rules
+
+    @staticmethod
+    def _parse_file(path: str, content: Optional[str], base: str) -> List[IgnoreRule]:
+        if not content:
+            return []
+        rules = []
+        for line_no, line in enumerate(content.splitlines(), start=1):
+            rule = parse_rule(line, path, line_no, base)
+            if rule:
+                rules.append(rule)
+        return rules
+
+    def _rules_in(self, rel_dir: str) -> List[IgnoreRule]:
+        """Rules from the ignore files of one directory ('' for the root)."""
+        if rel_dir not in self._dir_rules:
+            directory = os.path.join(self.root, *rel_dir.split("/")) if rel_dir else self.root
+            rules = []
+            for name in IGNORE_FILES:
+                path = os.path.join(directory, name)
+                rules.extend(self._parse_file(path, self._read_file(path), rel_dir))
+            self._dir_rules[rel_dir] = rules
+        return self._dir_rules[rel_dir]
+
+    def invalidate(self, directory: Optional[str] = None) -> None:
+        """Forget cached ignore files (of one directory, or all of them)."""
+        if directory is None:
+            self._dir_rules.clear()
+            self._base_rules = self._load_base_rules()
+            return
+        rel = self._relative(directory)
+        if rel is not None:
+            self._dir_rules.pop(rel, None)
+
+    def _relative(self, path: str) -> Optional[str]:
+        rel = os.path.relpath(os.path.abspath(path), self.root)
+        if rel == ".":
+            return ""
+        if rel == ".." or rel.startswith(".." + os.sep):
+            return None
+        return rel.replace(os.sep, "/")
+
+    def match(self, path: str, is_dir: bool = False) -> Optional[IgnoreMatch]:
+        """The rule that decides path, or None if no rule matches it."""
+        rel = self._relative(path)
+        if rel is None:
+            # Outside the tree only the configured patterns apply
+            rel, dir_rules = os.path.abspath(path).lstrip(os.sep).replace(os.sep, "/"), False
+        else:
+            dir_rules = True
+        if not rel:
+            return None
+
+        parts = rel.split("/")
+        rules = list(self._base_rules)
+        if dir_rules:
+            rules.extend(self._rules_in(""))
+        # A re-included parent explains a path no rule matches directly
+        included: Optional[IgnoreMatch] = None
+        for i in range(1, len(parts) + 1):
+            candidate = "/".join(parts[:i])
+            candidate_is_dir = is_dir or i < len(parts)
+            decided = None
+            for rule in reversed(rules):
+                if rule.matches(candidate, candidate_is_dir):
+                    decided = IgnoreMatch(rule, candidate)
+                    break
+            if i == len(parts):
+                return decided or included
+            # Nothing inside an excluded directory can be re-included
+            if decided is not None:
+                if decided.ignored:
+                    return decided
+                included = decided
+            if dir_rules:
+                rules.extend(self._rules_in(candidate))
+        return None
+
+    def is_ignored(self, path: str, is_dir: bool = False) -> bool:
+        decided = self.match(path, is_dir)
+        return decided is not None and decided.ignored
+
+    def explain(self, path: str) -> Dict:
+        """Which rule, if any, ignores or re-includes path."""
+        path = os.path.abspath(path)
+        decided = self.match(path, os.path.isdir(path))
+        return {
+            "path": path,
+            "root": self.root,
+            "ignored": decided is not None and decided.ignored,
+            "matched": decided.matched if decided else None,
+            "rule": decided.rule.to_dict() if decided else None
+        }
+
+# Rules of working trees on disk, by root
+_rules_cache: Dict[str, IgnoreRules] = {}
+
+def get_ignore_rules(root: str, refresh: bool = False) -> IgnoreRules:
+    """The cached rules for a tree on disk; refresh re-reads its ignore files."""
+    root = os.path.abspath(root)
+    rules = _rules_cache.get(root)
+    if rules is None:
+        rules = _rules_cache[root] = IgnoreRules(root)
+    elif refresh:
+        rules.invalidate()
+    return rules
+
+def invalidate_ignore_file(path: str) -> bool:
+    """Drop cached rules after an ignore file changed; True if path is one."""
+    if os.path.basename(path) not in IGNORE_FILES and not path.endswith(os.path.join("info", "exclude")):
+        return False
+    for rules in _rules_cache.values():
+        rules.invalidate()
+    return True
+
+def explain_ignore(path: str, root: Optional[str] = None) -> Dict:
+    """[1.4.1] Report the rule deciding whether path is ignored."""
+    path = os.path.abspath(path)
+    root = root or find_root(os.path.dirname(path) if not os.path.isdir(path) else path)
+    return get_ignore_rules(root, refresh=True).explain(path)
+
+__all__ = [
+    "IGNORE_FILES",
+    "IgnoreRule",
+    "IgnoreMatch",
+    "IgnoreRules",
+    "parse_rule",
+    "find_root",
+    "get_ignore_rules",
+    "invalidate_ignore_file",
+    "explain_ignore"
+]
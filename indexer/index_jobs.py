@@ -0,0 +1,229 @@
+"""[1.5] Resumable indexing jobs.
+
+Flow:
+1. Start:
+   - IndexJob.start() persists the run (its options and every file to index,
+     with the content hash it was planned with) before any file is processed
+
+2. Checkpoints:
+   - file_stored() buffers files whose rows and nodes were written;
+     checkpoint() marks them stored and records them in the manifest [1.3]
+     as one statement batch of a repository_transaction [4.1], so the
+     embedded backend, which commits statements singly, applies it atomically
+     too
+   - A file stored after the last checkpoint is stored again on resume; the
+     upserts are idempotent
+
+3. Finish and Resume:
+   - finish() records completed, failed (some files failed) or interrupted,
+     with the last error; a run finishes its job only after the derived
+     graphs are rebuilt and the indexed commit is recorded
+   - IndexJob.load_resumable() returns the latest job of a repository unless
+     it completed; its remaining files are the pending and failed ones, and
+     a resumed job rebuilds the derived graphs even when none remain
+"""
+
+import json
+import asyncio
+from typing import Any, Dict, List, Optional
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from indexer.manifest import manifest_statements
+
+# Job states
+JOB_RUNNING = "running"
+JOB_INTERRUPTED = "interrupted"
+JOB_FAILED = "failed"
+JOB_COMPLETED = "completed"
+
+# File states
+FILE_PENDING = "pending"
+FILE_STORED = "stored"
+FILE_FAILED = "failed"
+
+# Stored files written per checkpoint transaction
+CHECKPOINT_SIZE = 50
+
+class IndexJob:
+    """[1.5.1] One persisted indexing run and its per-file progress."""
+
+    def __init__(self, job_id: int, repo_id: int, repo_path: str, options: Dict[str, Any], files: Dict[str, Dict[str, Any]]):
+        """Private constructor - use start() or load_resumable() instead."""
+        self.id = job_id
+        self.repo_id = repo_id
+        self.repo_path = repo_path
+        self.options = options
+        self._files = files
+        self._stored: List[str] = []
+        self._lock = asyncio.Lock()
+
+    @classmethod
+    async def start(
+        cls,
+        repo_id: int,
+        repo_path: str,
+        hashes: Dict[str, Optional[str]],
+        options: Optional[Dict[str, Any]] = None
+    ) -> 'IndexJob':
+        """Persist a new job for the files to index, keyed to their planned hashes."""
+        # Imported here to avoid a circular import through the indexer
+        from indexer.clone_and_index import repository_transaction
+
+        options = options or {}
+        async with repository_transaction() as context:
+            txn = context['transaction']
+            await txn.track_repo_change(repo_id)
+            rows = await txn.fetch(
+                """
+                INSERT INTO indexing_jobs (repo_id, repo_path, status, options, total_files)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id;
+                """,
+                repo_id, repo_path, JOB_RUNNING, json.dumps(options), len(hashes)
+            )
+            job_id = rows[0]["id"]
+            await txn.execute_batch([
+                (
+                    "INSERT INTO indexing_job_files (job_id, file_path, content_hash) VALUES ($1, $2, $3);",
+                    (job_id, path, digest)
+                )
+                for path, digest in hashes.items()
+            ])
+
+        await log(f"Started indexing job {job_id} for {len(hashes)} file(s) of repository {repo_id}", level="info")
+        files = {path: {"content_hash": digest, "status": FILE_PENDING} for path, digest in hashes.items()}
+        return cls(job_id, repo_id, repo_path, options, files)
+
+    @classmethod
+    async def load_resumable(cls, repo_id: int) -> Optional['IndexJob']:
+        """The repository's latest job, or None if there is none or it completed."""
+        backend = await get_storage_backend()
+        job = await backend.fetchrow(
+            """
+            SELECT id, repo_path, status, options FROM indexing_jobs
+            WHERE repo_id = $1 ORDER BY id DESC LIMIT 1;
+            """,
+            repo_id
+        )
+        if not job or job["status"] == JOB_COMPLETED:
+            return None
+
+        rows = await backend.fetch(
+            "SELECT file_path, content_hash, status FROM indexing_job_files WHERE job_id = $1;",
+            job["id"]
+        )
+        options = job["options"]
+        if isinstance(options, str):
+            options = json.loads(options)
+        files = {row["file_path"]: {"content_hash": row["content_hash"], "status": row["status"]} for row in rows}
+        return cls(job["id"], repo_id, job["repo_path"], options or {}, files)
+
+    @property
+    def remaining(self) -> List[str]:
+        """Files not stored yet: pending, or failed last time."""
+        return sorted(path for path, entry in self._files.items() if entry["status"] != FILE_STORED)
+
+    @property
+    def hashes(self) -> Dict[str, Optional[str]]:
+        return {path: entry["content_hash"] for path, entry in self._files.items()}
+
+    async def file_stored(self, file_path: str) -> None:
+        """Note a stored file; every CHECKPOINT_SIZE files are checkpointed together."""
+        self._stored.append(file_path)
+        if len(self._stored) >= CHECKPOINT_SIZE:
+            await self.checkpoint()
+
+    async def checkpoint(self) -> None:
+        """[1.5.2] Mark buffered files stored and record them in the manifest, atomically."""
+        from indexer.clone_and_index import repository_transaction
+
+        async with self._lock:
+            if not self._stored:
+                return
+            paths, self._stored = self._stored, []
+            hashes = {path: self._files[path]["content_hash"] for path in paths if self._files[path]["content_hash"]}
+            try:
+                async with repository_transaction() as context:
+                    txn = context['transaction']
+                    await txn.track_repo_change(self.repo_id)
+                    await txn.execute_batch(
+                        [(
+                            """
+                            UPDATE indexing_job_files SET status = $2, last_error = NULL, updated_at = CURRENT_TIMESTAMP
+                            WHERE job_id = $1 AND file_path = ANY($3::text[]);
+                            """,
+                            (self.id, FILE_STORED, paths)
+                        )]
+                        + manifest_statements(self.repo_id, hashes)
+                        + [("UPDATE indexing_jobs SET updated_at = CURRENT_TIMESTAMP WHERE id = $1;", (self.id,))]
+                    )
+            except Exception:
+                # Keep them for the next checkpoint
+                self._stored = paths + self._stored
+                raise
+            for path in paths:
+                self._files[path]["status"] = FILE_STORED
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def files_failed(self, errors: Dict[str, str]) -> None:
+        """Record files that could not be read, parsed or stored."""
+        if not errors:
+            return
+        backend = await get_storage_backend()
+        await backend.execute_batch([
+            (
+                """
+                UPDATE indexing_job_files SET status = $3, last_error = $4, updated_at = CURRENT_TIMESTAMP
+                WHERE job_id = $1 AND file_path = $2;
+                """,
+                (self.id, path, FILE_FAILED, error)
+            )
+            for path, error in errors.items()
+        ])
+        for path in errors:
+            if path in self._files:
+                self._files[path]["status"] = FILE_FAILED
+
+    @handle_async_errors(error_types=(PostgresError, DatabaseError))
+    async def finish(self, status: str, error: Optional[str] = None) -> None:
+        """Record how the run ended, after a last checkpoint."""
+        if self._stored:
+            await self.checkpoint()
+        backend = await get_storage_backend()
+        await backend.execute(
+            """
+            UPDATE indexing_jobs SET status = $2, last_error = $3, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1;
+            """,
+            self.id, status, error
+        )
+        await log(f"Indexing job {self.id} {status}" + (f": {error}" if error else ""), level="info")
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return=[])
+async def list_jobs(repo_id: int, limit: int = 10) -> List[Dict[str, Any]]:
+    """[1.5.3] Recent jobs of a repository with per-status file counts."""
+    backend = await get_storage_backend()
+    return await backend.fetch(
+        """
+        SELECT j.id, j.status, j.total_files,
+               SUM(CASE WHEN f.status = 'stored' THEN 1 ELSE 0 END) AS stored,
+               SUM(CASE WHEN f.status = 'failed' THEN 1 ELSE 0 END) AS failed,
+               j.last_error, j.created_at, j.updated_at
+        FROM indexing_jobs j LEFT JOIN indexing_job_files f ON f.job_id = j.id
+        WHERE j.repo_id = $1
+        GROUP BY j.id, j.status, j.total_files, j.last_error, j.created_at, j.updated_at
+        ORDER BY j.id DESC LIMIT $2;
+        """,
+        repo_id, limit
+    )
+
+__all__ = [
+    "JOB_RUNNING",
+    "JOB_INTERRUPTED",
+    "JOB_FAILED",
+    "JOB_COMPLETED",
+    "IndexJob",
+    "list_jobs"
+]
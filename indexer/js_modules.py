@@ -0,0 +1,342 @@
+"""[1.13] JavaScript/TypeScript module resolution.
+
+Flow:
+1. Configs:
+   - tsconfig.json / jsconfig.json and package.json in the directories of the
+     repository's JavaScript and TypeScript files are read, from the working
+     tree or the indexed commit
+   - tsconfig files may have comments and trailing commas; relative
+     "extends" chains are followed, and baseUrl and paths are taken from the
+     file that sets them
+   - Every package.json with a name is a workspace package, so imports of it
+     link across package boundaries in a monorepo
+
+2. Specifiers:
+   - Relative (./x, ../y) and absolute paths resolve from the importing file
+   - Bare specifiers try the nearest tsconfig's paths (exact patterns, then
+     the longest * prefix), then its baseUrl, then workspace packages by
+     name: their "exports" map (subpaths, * patterns, conditions in the
+     order they are written), else "module"/"main"/"types" for the package
+     itself or the subpath as a file
+   - Anything else is external: a Node built-in or a dependency
+
+3. Probing:
+   - The path itself, then with .ts/.tsx/.js/.jsx/.mjs/.cjs/.d.ts, then
+     index.* in it as a directory; './x.js' also finds x.ts
+   - Targets in build output (dist/, lib/, build/, out/) that aren't in the
+     repository fall back to the same path under src/
+"""
+
+import os
+import re
+import json
+from dataclasses import dataclass, field
+from typing import Any, Awaitable, Callable, Container, Dict, Iterable, List, Optional, Set, Tuple
+
+from utils.logger import log
+from indexer.async_utils import async_read_file
+
+JS_EXTENSIONS = (".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs", ".d.ts")
+CONFIG_FILES = ("tsconfig.json", "jsconfig.json")
+PACKAGE_FILE = "package.json"
+
+# Node modules available without installing anything
+NODE_BUILTINS = {
+    "assert", "async_hooks", "buffer", "child_process", "cluster", "console", "constants", "crypto",
+    "dgram", "diagnostics_channel", "dns", "domain", "events", "fs", "http", "http2", "https",
+    "inspector", "module", "net", "os", "path", "perf_hooks", "process", "punycode", "querystring",
+    "readline", "repl", "stream", "string_decoder", "sys", "timers", "tls", "trace_events", "tty",
+    "url", "util", "v8", "vm", "wasi", "worker_threads", "zlib"
+}
+
+# Directories build tools write compiled output to, in place of src/
+_BUILD_DIRECTORIES = ("dist", "lib", "build", "out")
+
+# "exports" conditions, other than subpaths, that can select a file
+_CONDITIONS = {"source", "import", "module", "default", "require", "node", "browser", "types", "development", "production"}
+
+_JSONC_TOKEN = re.compile(r'"(?:\\.|[^"\\])*"|//[^\n]*|/\*.*?\*/', re.DOTALL)
+_TRAILING_COMMA = re.compile(r",(\s*[}\]])")
+
+def parse_jsonc(content: str) -> Any:
+    """JSON with // and /* */ comments and trailing commas, as tsconfig allows."""
+    stripped = _JSONC_TOKEN.sub(lambda match: match.group(0) if match.group(0).startswith('"') else "", content)
+    # Commas before a closing bracket, outside strings
+    parts = re.split(r'("(?:\\.|[^"\\])*")', stripped)
+    return json.loads("".join(part if index % 2 else _TRAILING_COMMA.sub(r"\1", part) for index, part in enumerate(parts)))
+
+@dataclass
+class TsConfig:
+    directory: str
+    base_url: Optional[str] = None
+    # pattern -> substitutions, absolute
+    paths: Dict[str, List[str]] = field(default_factory=dict)
+
+@dataclass
+class Package:
+    directory: str
+    name: str
+    manifest: Dict[str, Any]
+
+def _package_name(spec: str) -> Tuple[str, str]:
+    """A bare specifier as (package name, subpath): @scope/pkg/a/b -> (@scope/pkg, ./a/b)."""
+    parts = spec.split("/")
+    count = 2 if spec.startswith("@") else 1
+    rest = "/".join(parts[count:])
+    return "/".join(parts[:count]), f"./{rest}" if rest else "."
+
+def _match_pattern(pattern: str, spec: str) -> Optional[str]:
+    """What a tsconfig paths or exports * pattern's star stands for in spec, if it matches."""
+    if "*" not in pattern:
+        return "" if pattern == spec else None
+    prefix, _, suffix = pattern.partition("*")
+    if spec.startswith(prefix) and spec.endswith(suffix) and len(spec) >= len(prefix) + len(suffix):
+        return spec[len(prefix):len(spec) - len(suffix)]
+    return None
+
+class JsResolver:
+    """Node/TypeScript module resolution over the files of one repository."""
+
+    def __init__(self, repo_path: str, configs: Dict[str, TsConfig], packages: List[Package]):
+        self.repo_path = os.path.abspath(repo_path)
+        self.configs = configs
+        self.packages = {package.name: package for package in sorted(packages, key=lambda package: package.directory)}
+        self.package_dirs = {package.directory: package for package in packages}
+
+    # Probing -------------------------------------------------------------
+
+    def probe(self, base: str, files: Container[str]) -> Optional[str]:
+        """The file a path stands for: itself, with an extension, or a directory's index."""
+        base = os.path.normpath(base)
+        candidates = [base] + [base + ext for ext in JS_EXTENSIONS]
+        # import './x.js' from TypeScript refers to x.ts
+        stem, ext = os.path.splitext(base)
+        if ext in (".js", ".jsx", ".mjs", ".cjs"):
+            candidates += [stem + ".ts", stem + ".tsx", stem + ".mts", stem + ".cts"]
+        for candidate in candidates:
+            if candidate in files:
+                return candidate
+        # A directory with a package.json loads its main file, not its exports
+        package = self.package_dirs.get(base)
+        if package is not None:
+            found = self._main(package, files)
+            if found is not None:
+                return found
+        for ext in JS_EXTENSIONS:
+            candidate = os.path.join(base, "index" + ext)
+            if candidate in files:
+                return candidate
+        return None
+
+    def _probe_built(self, path: str, files: Container[str]) -> Optional[str]:
+        """A target, or its source when the target is build output that isn't in the repository."""
+        found = self.probe(path, files)
+        if found is not None:
+            return found
+        for package_dir in sorted(self.package_dirs, key=len, reverse=True):
+            if not path.startswith(package_dir + os.sep):
+                continue
+            parts = os.path.relpath(path, package_dir).split(os.sep)
+            if parts[0] in _BUILD_DIRECTORIES:
+                stem = os.path.join(package_dir, "src", *parts[1:])
+                for suffix in (".d.ts", ".mjs", ".cjs", ".js", ".jsx"):
+                    if stem.endswith(suffix):
+                        stem = stem[:-len(suffix)]
+                        break
+                return self.probe(stem, files)
+            break
+        return None
+
+    # Packages ------------------------------------------------------------
+
+    def _exports_target(self, value: Any, star: Optional[str]) -> Iterable[str]:
+        """Paths an exports value selects, in the order Node would try them."""
+        if isinstance(value, str):
+            yield value.replace("*", star) if star is not None else value
+        elif isinstance(value, list):
+            for item in value:
+                yield from self._exports_target(item, star)
+        elif isinstance(value, dict):
+            for condition, inner in value.items():
+                if condition in _CONDITIONS:
+                    yield from self._exports_target(inner, star)
+
+    def _entry(self, package: Package, subpath: str, files: Container[str]) -> Optional[str]:
+        """The file a package subpath ("." or "./x") resolves to."""
+        exports = package.manifest.get("exports")
+        if exports is not None:
+            if not isinstance(exports, dict) or not any(key.startswith(".") for key in exports):
+                # Sugar for { ".": exports }
+                exports = {".": exports}
+            value, star = exports.get(subpath), None
+            if value is None:
+                best = None
+                for pattern in exports:
+                    matched = _match_pattern(pattern, subpath)
+                    if matched is not None and "*" in pattern and (best is None or len(pattern) > len(best[0])):
+                        best = (pattern, matched)
+                if best is not None:
+                    value, star = exports[best[0]], best[1]
+            for target in self._exports_target(value, star):
+                found = self._probe_built(os.path.join(package.directory, target), files)
+                if found is not None:
+                    return found
+            return None
+        if subpath != ".":
+            return self._probe_built(os.path.join(package.directory, subpath), files)
+        return self._main(package, files) or self.probe(os.path.join(package.directory, "src", "index"), files)
+
+    def _main(self, package: Package, files: Container[str]) -> Optional[str]:
+        """The file package.json's entry fields point at, else the package's index."""
+        for key in ("source", "module", "main", "types", "typings"):
+            target = package.manifest.get(key)
+            if isinstance(target, str) and os.path.normpath(os.path.join(package.directory, target)) != package.directory:
+                found = self._probe_built(os.path.join(package.directory, target), files)
+                if found is not None:
+                    return found
+        for ext in JS_EXTENSIONS:
+            candidate = os.path.join(package.directory, "index" + ext)
+            if candidate in files:
+                return candidate
+        return None
+
+    # Resolution ----------------------------------------------------------
+
+    def config_for(self, file_path: str) -> Optional[TsConfig]:
+        """The nearest tsconfig.json or jsconfig.json above a file."""
+        directory = os.path.dirname(file_path)
+        while directory.startswith(self.repo_path):
+            if directory in self.configs:
+                return self.configs[directory]
+            if directory == self.repo_path:
+                break
+            directory = os.path.dirname(directory)
+        return None
+
+    def resolve(self, file_path: str, spec: str, files: Container[str]) -> Optional[str]:
+        """[1.13.1] The repository file an import specifier in file_path loads, if any."""
+        if not spec or spec.startswith("node:"):
+            return None
+        if spec.startswith((".", "/")):
+            base = spec if spec.startswith("/") else os.path.join(os.path.dirname(file_path), spec)
+            return self.probe(base, files)
+
+        config = self.config_for(file_path)
+        if config is not None and config.paths:
+            exact = config.paths.get(spec)
+            candidates = [(spec, "")] if exact is not None else []
+            if not candidates:
+                patterns = [
+                    (pattern, matched) for pattern in config.paths
+                    for matched in [_match_pattern(pattern, spec)] if matched is not None and "*" in pattern
+                ]
+                candidates = sorted(patterns, key=lambda item: len(item[0].partition("*")[0]), reverse=True)[:1]
+            for pattern, matched in candidates:
+                for substitution in config.paths[pattern]:
+                    found = self.probe(substitution.replace("*", matched), files)
+                    if found is not None:
+                        return found
+        if config is not None and config.base_url is not None:
+            found = self.probe(os.path.join(config.base_url, spec), files)
+            if found is not None:
+                return found
+
+        name, subpath = _package_name(spec)
+        package = self.packages.get(name)
+        if package is not None:
+            return self._entry(package, subpath, files)
+        return None
+
+def is_builtin(spec: str) -> bool:
+    """Whether a specifier names a Node built-in module."""
+    return spec.startswith("node:") or spec.split("/")[0] in NODE_BUILTINS
+
+async def _read_config(
+    path: str,
+    reader: Callable[[str], Awaitable[Optional[str]]],
+    seen: Set[str]
+) -> Optional[Dict[str, Any]]:
+    """compilerOptions of a tsconfig with its relative extends chain applied; paths made absolute."""
+    if path in seen or (reader is async_read_file and not os.path.isfile(path)):
+        return None
+    seen.add(path)
+    content = await reader(path)
+    if content is None:
+        return None
+    try:
+        data = parse_jsonc(content)
+    except json.JSONDecodeError as e:
+        await log(f"Skipping unreadable {path}: {e}", level="warning")
+        return None
+    directory = os.path.dirname(path)
+    options: Dict[str, Any] = {}
+    extends = data.get("extends")
+    for parent in ([extends] if isinstance(extends, str) else extends or []):
+        # Configs from packages (@tsconfig/node18) live in node_modules, outside the index
+        if isinstance(parent, str) and parent.startswith("."):
+            parent_path = os.path.normpath(os.path.join(directory, parent))
+            if not parent_path.endswith(".json"):
+                parent_path += ".json"
+            inherited = await _read_config(parent_path, reader, seen)
+            if inherited:
+                options.update(inherited)
+    compiler = data.get("compilerOptions") or {}
+    if isinstance(compiler.get("baseUrl"), str):
+        options["baseUrl"] = os.path.normpath(os.path.join(directory, compiler["baseUrl"]))
+    if isinstance(compiler.get("paths"), dict):
+        # Substitutions are relative to baseUrl, or to the config that declares them
+        root = options.get("baseUrl") if isinstance(compiler.get("baseUrl"), str) else directory
+        options["paths"] = {
+            pattern: [os.path.normpath(os.path.join(root, target)) for target in targets if isinstance(target, str)]
+            for pattern, targets in compiler["paths"].items() if isinstance(targets, list)
+        }
+    return options
+
+async def load_js_resolver(
+    repo_path: str,
+    files: List[str],
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> JsResolver:
+    """[1.13.2] tsconfig/jsconfig files and workspace packages from the directories of files up to the repository root."""
+    repo_path = os.path.abspath(repo_path)
+    reader = reader or async_read_file
+    directories: Set[str] = set()
+    for file_path in files:
+        directory = os.path.dirname(file_path)
+        while directory.startswith(repo_path) and directory not in directories:
+            directories.add(directory)
+            if directory == repo_path:
+                break
+            directory = os.path.dirname(directory)
+
+    configs: Dict[str, TsConfig] = {}
+    packages: List[Package] = []
+    for directory in sorted(directories):
+        for file_name in CONFIG_FILES:
+            options = await _read_config(os.path.join(directory, file_name), reader, set())
+            if options is not None:
+                configs[directory] = TsConfig(directory, options.get("baseUrl"), options.get("paths") or {})
+                break
+        path = os.path.join(directory, PACKAGE_FILE)
+        if reader is async_read_file and not os.path.isfile(path):
+            continue
+        content = await reader(path)
+        if content is None:
+            continue
+        try:
+            manifest = json.loads(content)
+        except json.JSONDecodeError as e:
+            await log(f"Skipping unreadable {path}: {e}", level="warning")
+            continue
+        if isinstance(manifest, dict) and isinstance(manifest.get("name"), str):
+            packages.append(Package(directory, manifest["name"], manifest))
+    return JsResolver(repo_path, configs, packages)
+
+__all__ = [
+    "JS_EXTENSIONS",
+    "NODE_BUILTINS",
+    "parse_jsonc",
+    "is_builtin",
+    "JsResolver",
+    "load_js_resolver"
+]
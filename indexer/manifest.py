@@ -0,0 +1,210 @@
+"""[1.3] Content-hash index manifest.
+
+Flow:
+1. Fingerprints:
+   - Each indexed file is recorded with the sha256 of its bytes
+   - parser_version() and embedding_version() identify how it was indexed
+
+2. Planning:
+   - plan_indexing() compares the files found on disk with the manifest
+   - New and edited files, and files indexed by an older parser or embedding
+     model, are (re)indexed; everything else is skipped
+   - Manifest entries under the indexed path whose file is gone, or is now
+     ignored [1.4], are removed, from disk or from the indexed commit
+
+3. Storage:
+   - file_manifest sits next to code_snippets, keyed by (repo_id, file_path)
+   - record_indexed() runs once the files are stored; indexing jobs [1.5]
+     checkpoint the same rows as they go
+   - remove_deleted() drops rows and Code nodes for removed files via the
+     UpsertCoordinator [6.5]
+"""
+
+import os
+import asyncio
+import hashlib
+from dataclasses import dataclass, field
+from importlib import metadata
+from typing import Any, Dict, List, Optional, Tuple
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from db.upsert_ops import UpsertCoordinator
+from indexer.ignore_rules import find_root, get_ignore_rules
+from embedding.embedding_models import CODE_EMBEDDING_MODEL, DOC_EMBEDDING_MODEL
+
+# Bump when parsing or the stored features change in a way that needs a reindex
+PARSER_VERSION = "11"
+
+_HASH_CHUNK_SIZE = 1 << 20
+
+def parser_version() -> str:
+    """PARSER_VERSION plus the installed grammar bundle, whose ASTs change between releases."""
+    try:
+        grammars = metadata.version("tree-sitter-language-pack")
+    except metadata.PackageNotFoundError:
+        grammars = "unknown"
+    return f"{PARSER_VERSION}/tree-sitter-language-pack-{grammars}"
+
+def embedding_version() -> str:
+    """The code and doc embedding models, in that order."""
+    return f"{CODE_EMBEDDING_MODEL},{DOC_EMBEDDING_MODEL}"
+
+def file_hash(file_path: str) -> Optional[str]:
+    """sha256 of a file's bytes, or None if it cannot be read."""
+    digest = hashlib.sha256()
+    try:
+        with open(file_path, "rb") as f:
+            for chunk in iter(lambda: f.read(_HASH_CHUNK_SIZE), b""):
+                digest.update(chunk)
+    except OSError:
+        return None
+    return digest.hexdigest()
+
+@dataclass
+class IndexPlan:
+    """What an indexing run has to do, relative to the manifest."""
+    changed: List[str] = field(default_factory=list)
+    unchanged: List[str] = field(default_factory=list)
+    removed: List[str] = field(default_factory=list)
+    hashes: Dict[str, str] = field(default_factory=dict)
+
+    def summary(self) -> Dict[str, int]:
+        return {
+            "indexed": len(self.changed),
+            "unchanged": len(self.unchanged),
+            "removed": len(self.removed)
+        }
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return={})
+async def load_manifest(repo_id: int) -> Dict[str, Dict]:
+    """Manifest rows of a repository, by file path."""
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        """
+        SELECT file_path, content_hash, parser_version, embedding_model
+        FROM file_manifest WHERE repo_id = $1;
+        """,
+        repo_id
+    )
+    return {row["file_path"]: row for row in rows}
+
+def _under(path: str, root: str) -> bool:
+    return path == root or path.startswith(root.rstrip(os.sep) + os.sep)
+
+async def plan_indexing(
+    repo_id: int,
+    root: str,
+    files: List[str],
+    full: bool = False,
+    source: Optional[Any] = None,
+    removed_under: Optional[List[str]] = None
+) -> IndexPlan:
+    """[1.3.1] Split the files under root into changed and unchanged ones.
+
+    Flow:
+    1. Hash the files off the event loop
+    2. Compare hash, parser version and embedding model with the manifest
+    3. Report manifest entries under root whose file no longer exists or
+       is now ignored
+
+    With full=True every file counts as changed; removals are still reported.
+    removed_under limits removals to entries at or below those paths (e.g.
+    the files and directories a watcher saw deleted) instead of all of root.
+    A source (GitTreeSource [4.5.1]) supplies hashes, existence and ignore
+    rules for a commit instead of the working tree.
+    """
+    if source is not None:
+        hashes = await source.hash_files(files)
+        exists, ignored = source.exists, source.is_ignored
+    else:
+        loop = asyncio.get_running_loop()
+        hashes = await loop.run_in_executor(None, lambda: {path: file_hash(path) for path in files})
+        exists, ignored = os.path.exists, get_ignore_rules(find_root(root)).is_ignored
+    manifest = await load_manifest(repo_id)
+    parser, embedding = parser_version(), embedding_version()
+
+    plan = IndexPlan()
+    for path in files:
+        digest = hashes.get(path)
+        entry = manifest.get(path)
+        if digest is not None:
+            plan.hashes[path] = digest
+        if (
+            full
+            or digest is None
+            or entry is None
+            or entry["content_hash"] != digest
+            or entry["parser_version"] != parser
+            or entry["embedding_model"] != embedding
+        ):
+            plan.changed.append(path)
+        else:
+            plan.unchanged.append(path)
+
+    # Files that are gone or newly ignored; unreadable ones stay indexed
+    seen = set(files)
+    scopes = [root] if removed_under is None else removed_under
+    plan.removed = sorted(
+        path for path in manifest
+        if path not in seen and any(_under(path, scope) for scope in scopes)
+        and (not exists(path) or ignored(path))
+    )
+    return plan
+
+_UPSERT_SQL = """
+INSERT INTO file_manifest (repo_id, file_path, content_hash, parser_version, embedding_model, indexed_at)
+VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+ON CONFLICT (repo_id, file_path)
+DO UPDATE SET
+    content_hash = EXCLUDED.content_hash,
+    parser_version = EXCLUDED.parser_version,
+    embedding_model = EXCLUDED.embedding_model,
+    indexed_at = EXCLUDED.indexed_at;
+"""
+
+def manifest_statements(repo_id: int, hashes: Dict[str, str]) -> List[Tuple[str, Tuple]]:
+    """Upserts recording files as indexed with the current parser and embedding models."""
+    parser, embedding = parser_version(), embedding_version()
+    return [
+        (_UPSERT_SQL, (repo_id, path, digest, parser, embedding))
+        for path, digest in hashes.items()
+    ]
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError))
+async def record_indexed(repo_id: int, hashes: Dict[str, str]) -> None:
+    """Record files as indexed with the current parser and embedding models."""
+    if not hashes:
+        return
+    backend = await get_storage_backend()
+    await backend.execute_batch(manifest_statements(repo_id, hashes))
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError))
+async def remove_deleted(repo_id: int, file_paths: List[str], upsert_coordinator: UpsertCoordinator) -> None:
+    """Drop stored rows, graph nodes and manifest entries of deleted files."""
+    if not file_paths:
+        return
+    # Keep the entries on failure so the next run retries the removal
+    if not await upsert_coordinator.delete_code_files(repo_id, file_paths):
+        return
+    backend = await get_storage_backend()
+    await backend.execute(
+        "DELETE FROM file_manifest WHERE repo_id = $1 AND file_path = ANY($2::text[]);",
+        repo_id,
+        list(file_paths)
+    )
+    await log(f"Dropped {len(file_paths)} deleted file(s) from the manifest of repository {repo_id}", level="debug")
+
+__all__ = [
+    "PARSER_VERSION",
+    "IndexPlan",
+    "parser_version",
+    "embedding_version",
+    "file_hash",
+    "load_manifest",
+    "manifest_statements",
+    "plan_indexing",
+    "record_indexed",
+    "remove_deleted"
+]
@@ -0,0 +1,626 @@
+"""[1.12] Declared package dependencies.
+
+Flow:
+1. Manifests:
+   - In every directory of the repository's indexed files: requirements*.txt,
+     pyproject.toml, Cargo.toml, package.json and go.mod, read from the
+     working tree or the indexed commit
+   - Each declared dependency keeps its ecosystem (pypi, cargo, npm, go),
+     version constraint, kind (runtime, dev, build, optional, peer,
+     indirect) and the manifest declaring it
+   - Path, workspace and file: dependencies are part of the repository and
+     are skipped
+
+2. Lockfiles:
+   - poetry.lock / uv.lock, Cargo.lock, package-lock.json / yarn.lock /
+     pnpm-lock.yaml next to a manifest give the resolved versions; an exact
+     pin (==1.2.3, go.mod requirements) is its own resolved version
+
+3. Imports:
+   - Import statements the symbol extractor kept are matched to the declared
+     packages of their language's ecosystem: Python module heads (with the
+     known distribution/module name mismatches), bare JS specifiers, Go
+     module path prefixes and Rust crate names
+
+4. Storage:
+   - Package {repo_id, ecosystem, name} nodes; Repository DEPENDS_ON each
+     package and Code IMPORTS_PACKAGE the packages the file imports
+   - The arch_patterns row 'package_dependencies' holds the same data as JSON
+   - The graph is rebuilt only when its digest (kept on the Repository node)
+     changes
+"""
+
+import os
+import re
+import json
+import hashlib
+from collections import defaultdict
+from dataclasses import dataclass, field
+from typing import Any, Awaitable, Callable, Dict, Iterable, List, Optional, Set, Tuple
+
+from utils.logger import log
+from utils.toml_compat import tomllib
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError, Neo4jError
+from db.storage import get_storage_backend
+from indexer.async_utils import async_read_file
+from indexer.symbol_table import LANGUAGE_FAMILIES
+
+PATTERN_TYPE = "package_dependencies"
+
+# Manifest file names, per ecosystem
+MANIFESTS = {
+    "requirements.txt": "pypi",
+    "requirements-dev.txt": "pypi",
+    "requirements-test.txt": "pypi",
+    "dev-requirements.txt": "pypi",
+    "test-requirements.txt": "pypi",
+    "pyproject.toml": "pypi",
+    "Cargo.toml": "cargo",
+    "package.json": "npm",
+    "go.mod": "go"
+}
+
+# Lockfiles next to a manifest, per ecosystem
+LOCKFILES = {
+    "pypi": ("poetry.lock", "uv.lock"),
+    "cargo": ("Cargo.lock",),
+    "npm": ("package-lock.json", "yarn.lock", "pnpm-lock.yaml"),
+    "go": ()
+}
+
+# Language family of the importing file -> ecosystem of its packages
+FAMILY_ECOSYSTEMS = {"python": "pypi", "js": "npm", "go": "go", "rust": "cargo"}
+
+# Distributions whose top-level module isn't their name
+PYTHON_MODULES = {
+    "beautifulsoup4": ["bs4"],
+    "pillow": ["PIL"],
+    "pyyaml": ["yaml"],
+    "scikit-learn": ["sklearn"],
+    "scikit-image": ["skimage"],
+    "python-dateutil": ["dateutil"],
+    "python-dotenv": ["dotenv"],
+    "opencv-python": ["cv2"],
+    "opencv-python-headless": ["cv2"],
+    "protobuf": ["google.protobuf"],
+    "attrs": ["attr", "attrs"],
+    "pyjwt": ["jwt"],
+    "pymysql": ["pymysql"],
+    "psycopg2-binary": ["psycopg2"],
+    "gitpython": ["git"],
+    "msgpack-python": ["msgpack"],
+    "pycryptodome": ["Crypto"],
+    "tree-sitter": ["tree_sitter"],
+    "faiss-cpu": ["faiss"],
+    "faiss-gpu": ["faiss"]
+}
+
+_REQUIREMENT = re.compile(r"^([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*(.*)$")
+_PINNED = re.compile(r"^===?\s*([A-Za-z0-9][A-Za-z0-9.+!_-]*)$")
+_GO_REQUIRE = re.compile(r"^(\S+)\s+(\S+)(\s*//\s*indirect)?")
+_YARN_VERSION = re.compile(r"^\s+version:?\s+\"?([^\"\s]+)\"?")
+_PNPM_PACKAGE = re.compile(r"^\s{2}'?/?((?:@[^@/\s']+/)?[^@/\s']+)[@/]([0-9][^(:'\s]*)")
+
+@dataclass
+class Declaration:
+    ecosystem: str
+    name: str
+    constraint: Optional[str]
+    kind: str
+    manifest: str
+    version: Optional[str] = None
+    # Names the package's code is imported by, when they differ from name
+    imports: List[str] = field(default_factory=list)
+
+def normalize_name(ecosystem: str, name: str) -> str:
+    """The name a package is compared by: PEP 503 for PyPI, as written elsewhere."""
+    if ecosystem == "pypi":
+        return re.sub(r"[-_.]+", "-", name).lower()
+    return name
+
+def _pinned(constraint: Optional[str]) -> Optional[str]:
+    match = _PINNED.match(constraint or "")
+    return match.group(1) if match and "*" not in match.group(1) else None
+
+# Python ----------------------------------------------------------------
+
+def parse_requirement(line: str) -> Optional[Tuple[str, Optional[str]]]:
+    """Name and constraint of a PEP 508 requirement; None for options and URLs."""
+    line = line.split(" #")[0].split(";")[0].strip()
+    if not line or line.startswith(("#", "-", ".", "/")) or "://" in line.split("@")[0]:
+        return None
+    match = _REQUIREMENT.match(line)
+    if match is None:
+        return None
+    constraint = match.group(2).strip()
+    if constraint.startswith("@"):
+        constraint = constraint[1:].strip()
+    return match.group(1), constraint or None
+
+def _requirements_txt(content: str, manifest: str) -> List[Declaration]:
+    stem = os.path.basename(manifest)
+    kind = "dev" if "dev" in stem or "test" in stem else "runtime"
+    declarations = []
+    for line in content.splitlines():
+        requirement = parse_requirement(line)
+        if requirement is not None:
+            declarations.append(Declaration("pypi", requirement[0], requirement[1], kind, manifest))
+    return declarations
+
+def _requirement_list(entries: Iterable[Any], kind: str, manifest: str) -> List[Declaration]:
+    declarations = []
+    for entry in entries:
+        # PEP 735 groups may include other groups as tables
+        requirement = parse_requirement(entry) if isinstance(entry, str) else None
+        if requirement is not None:
+            declarations.append(Declaration("pypi", requirement[0], requirement[1], kind, manifest))
+    return declarations
+
+def _poetry_table(table: Dict[str, Any], kind: str, manifest: str) -> List[Declaration]:
+    declarations = []
+    for name, value in table.items():
+        if name.lower() == "python":
+            continue
+        if isinstance(value, dict):
+            if "path" in value:
+                continue
+            constraint = value.get("version") or (f"git {value['git']}" if "git" in value else None)
+            optional = value.get("optional")
+        else:
+            constraint, optional = value if isinstance(value, str) else None, False
+        declarations.append(Declaration("pypi", name, constraint, "optional" if optional else kind, manifest))
+    return declarations
+
+def _pyproject(data: Dict[str, Any], manifest: str) -> List[Declaration]:
+    declarations = []
+    project = data.get("project") or {}
+    declarations += _requirement_list(project.get("dependencies") or [], "runtime", manifest)
+    for entries in (project.get("optional-dependencies") or {}).values():
+        declarations += _requirement_list(entries, "optional", manifest)
+    for entries in (data.get("dependency-groups") or {}).values():
+        declarations += _requirement_list(entries, "dev", manifest)
+    declarations += _requirement_list((data.get("build-system") or {}).get("requires") or [], "build", manifest)
+
+    poetry = (data.get("tool") or {}).get("poetry") or {}
+    declarations += _poetry_table(poetry.get("dependencies") or {}, "runtime", manifest)
+    declarations += _poetry_table(poetry.get("dev-dependencies") or {}, "dev", manifest)
+    for group in (poetry.get("group") or {}).values():
+        declarations += _poetry_table(group.get("dependencies") or {}, "dev", manifest)
+    return declarations
+
+def _toml_lock(content: str, cargo: bool) -> Dict[str, Set[str]]:
+    """[[package]] name/version pairs of poetry.lock, uv.lock and Cargo.lock."""
+    versions: Dict[str, Set[str]] = defaultdict(set)
+    for package in tomllib.loads(content).get("package") or []:
+        # Cargo.lock lists the workspace's own crates without a source
+        if not package.get("name") or not package.get("version") or (cargo and "source" not in package):
+            continue
+        versions[package["name"]].add(str(package["version"]))
+    return versions
+
+# Cargo -----------------------------------------------------------------
+
+_CARGO_SECTIONS = {"dependencies": "runtime", "dev-dependencies": "dev", "build-dependencies": "build"}
+
+def _cargo_table(table: Dict[str, Any], kind: str, manifest: str) -> List[Declaration]:
+    declarations = []
+    for key, value in table.items():
+        if isinstance(value, dict):
+            if "path" in value:
+                continue
+            name = value.get("package") or key
+            # The constraint is the one [workspace.dependencies] declares
+            if value.get("workspace"):
+                constraint = None
+            elif "git" in value:
+                reference = value.get("tag") or value.get("branch") or value.get("rev")
+                constraint = f"git {value['git']}" + (f"#{reference}" if reference else "")
+            else:
+                constraint = value.get("version")
+            kind_of = "optional" if value.get("optional") and kind == "runtime" else kind
+        else:
+            name, constraint, kind_of = key, value if isinstance(value, str) else None, kind
+        declarations.append(Declaration("cargo", name, constraint, kind_of, manifest, imports=[key.replace("-", "_")]))
+    return declarations
+
+def _cargo_toml(data: Dict[str, Any], manifest: str) -> List[Declaration]:
+    declarations = []
+    for section, kind in _CARGO_SECTIONS.items():
+        declarations += _cargo_table(data.get(section) or {}, kind, manifest)
+        for target in (data.get("target") or {}).values():
+            declarations += _cargo_table((target or {}).get(section) or {}, kind, manifest)
+    declarations += _cargo_table((data.get("workspace") or {}).get("dependencies") or {}, "runtime", manifest)
+    return declarations
+
+# npm -------------------------------------------------------------------
+
+_NPM_SECTIONS = {
+    "dependencies": "runtime",
+    "devDependencies": "dev",
+    "peerDependencies": "peer",
+    "optionalDependencies": "optional"
+}
+
+def _package_json(data: Dict[str, Any], manifest: str) -> List[Declaration]:
+    declarations = []
+    for section, kind in _NPM_SECTIONS.items():
+        for name, constraint in (data.get(section) or {}).items():
+            if isinstance(constraint, str) and constraint.startswith(("workspace:", "file:", "link:", "portal:")):
+                continue
+            declarations.append(Declaration("npm", name, constraint if isinstance(constraint, str) else None, kind, manifest))
+    return declarations
+
+def _package_lock(content: str) -> Dict[str, Set[str]]:
+    data = json.loads(content)
+    versions: Dict[str, Set[str]] = defaultdict(set)
+    for path, entry in (data.get("packages") or {}).items():
+        # Only top-level installs; nested node_modules hold other dependents' copies
+        if path.count("node_modules/") == 1 and path.startswith("node_modules/") and entry.get("version"):
+            versions[path[len("node_modules/"):]].add(entry["version"])
+    if not versions:
+        for name, entry in (data.get("dependencies") or {}).items():
+            if isinstance(entry, dict) and entry.get("version"):
+                versions[name].add(entry["version"])
+    return versions
+
+def _spec_name(spec: str) -> str:
+    """name of a name@range lockfile spec, for scoped (@scope/name@range) ones too."""
+    spec = spec.strip().strip("\"'")
+    at = spec.find("@", 1)
+    return spec[:at] if at > 0 else spec
+
+def _yarn_lock(content: str) -> Dict[str, Set[str]]:
+    versions: Dict[str, Set[str]] = defaultdict(set)
+    names: List[str] = []
+    for line in content.splitlines():
+        if line and not line[0].isspace() and not line.startswith("#") and line.rstrip().endswith(":"):
+            names = sorted({_spec_name(spec) for spec in line.rstrip()[:-1].split(",")})
+            continue
+        match = _YARN_VERSION.match(line)
+        if match is not None and names:
+            for name in names:
+                versions[name].add(match.group(1))
+            names = []
+    versions.pop("__metadata", None)
+    return versions
+
+def _pnpm_lock(content: str) -> Dict[str, Set[str]]:
+    versions: Dict[str, Set[str]] = defaultdict(set)
+    section = None
+    for line in content.splitlines():
+        if line and not line[0].isspace():
+            section = line.split(":")[0]
+            continue
+        if section == "packages":
+            match = _PNPM_PACKAGE.match(line)
+            if match is not None:
+                versions[match.group(1)].add(match.group(2))
+    return versions
+
+# Go --------------------------------------------------------------------
+
+def _go_mod(content: str, manifest: str) -> List[Declaration]:
+    declarations = []
+    in_block = False
+    for raw in content.splitlines():
+        line = raw.strip()
+        if in_block:
+            if line.startswith(")"):
+                in_block = False
+                continue
+            entry = line
+        elif line.startswith("require ("):
+            in_block = True
+            continue
+        elif line.startswith("require "):
+            entry = line[len("require "):].strip()
+        else:
+            continue
+        match = _GO_REQUIRE.match(entry)
+        if match is None or match.group(1).startswith("//"):
+            continue
+        version = match.group(2)
+        declarations.append(Declaration(
+            "go", match.group(1), version, "indirect" if match.group(3) else "runtime", manifest, version=version
+        ))
+    return declarations
+
+# Reading ---------------------------------------------------------------
+
+def parse_manifest(file_name: str, content: str, manifest: str) -> List[Declaration]:
+    """[1.12.1] Dependencies one manifest declares; manifest is its repository-relative path."""
+    if file_name == "pyproject.toml":
+        declarations = _pyproject(tomllib.loads(content), manifest)
+    elif file_name == "Cargo.toml":
+        declarations = _cargo_toml(tomllib.loads(content), manifest)
+    elif file_name == "package.json":
+        declarations = _package_json(json.loads(content), manifest)
+    elif file_name == "go.mod":
+        declarations = _go_mod(content, manifest)
+    else:
+        declarations = _requirements_txt(content, manifest)
+    for declaration in declarations:
+        if declaration.version is None:
+            declaration.version = _pinned(declaration.constraint)
+    return declarations
+
+def parse_lockfile(file_name: str, content: str) -> Dict[str, Set[str]]:
+    """Resolved versions per package name of one lockfile."""
+    if file_name == "package-lock.json":
+        return _package_lock(content)
+    if file_name == "yarn.lock":
+        return _yarn_lock(content)
+    if file_name == "pnpm-lock.yaml":
+        return _pnpm_lock(content)
+    return _toml_lock(content, cargo=file_name == "Cargo.lock")
+
+async def _read(reader: Callable[[str], Awaitable[Optional[str]]], path: str) -> Optional[str]:
+    if reader is async_read_file and not os.path.isfile(path):
+        return None
+    return await reader(path)
+
+async def read_declarations(
+    repo_path: str,
+    files: List[str],
+    reader: Callable[[str], Awaitable[Optional[str]]]
+) -> Tuple[List[Declaration], List[str]]:
+    """Declarations of the manifests in the directories of files, and the lockfiles used."""
+    directories = {repo_path}
+    for file_path in files:
+        directory = os.path.dirname(file_path)
+        while directory.startswith(repo_path) and directory not in directories:
+            directories.add(directory)
+            directory = os.path.dirname(directory)
+
+    declarations: List[Declaration] = []
+    lockfiles: List[str] = []
+    for directory in sorted(directories):
+        found: Dict[str, List[Declaration]] = defaultdict(list)
+        for file_name, ecosystem in MANIFESTS.items():
+            path = os.path.join(directory, file_name)
+            content = await _read(reader, path)
+            if content is None:
+                continue
+            try:
+                found[ecosystem] += parse_manifest(file_name, content, os.path.relpath(path, repo_path))
+            except (tomllib.TOMLDecodeError, json.JSONDecodeError) as e:
+                await log(f"Skipping unreadable {path}: {e}", level="warning")
+
+        for ecosystem, entries in found.items():
+            # Cargo and npm workspaces share one lockfile at their root
+            resolved: Dict[str, Set[str]] = {}
+            lock_directory = directory
+            while not resolved and lock_directory.startswith(repo_path):
+                for file_name in LOCKFILES[ecosystem]:
+                    path = os.path.join(lock_directory, file_name)
+                    content = await _read(reader, path)
+                    if content is None:
+                        continue
+                    try:
+                        resolved = parse_lockfile(file_name, content)
+                    except (tomllib.TOMLDecodeError, json.JSONDecodeError) as e:
+                        await log(f"Skipping unreadable {path}: {e}", level="warning")
+                        continue
+                    lockfiles.append(os.path.relpath(path, repo_path))
+                    break
+                if lock_directory == repo_path:
+                    break
+                lock_directory = os.path.dirname(lock_directory)
+            by_name = {normalize_name(ecosystem, name): found_versions for name, found_versions in resolved.items()}
+            for declaration in entries:
+                found_versions = by_name.get(normalize_name(ecosystem, declaration.name))
+                if found_versions:
+                    declaration.version = ", ".join(sorted(found_versions))
+            declarations += entries
+    return declarations, sorted(set(lockfiles))
+
+# Graph -----------------------------------------------------------------
+
+def _import_names(declaration: Declaration) -> List[str]:
+    """Module paths the package's code is imported by."""
+    if declaration.ecosystem == "pypi":
+        normalized = normalize_name("pypi", declaration.name)
+        names = list(PYTHON_MODULES.get(normalized) or [normalized.replace("-", "_"), normalized.replace("-", ".")])
+        if normalized.startswith("python-"):
+            names.append(normalized[len("python-"):].replace("-", "_"))
+        return names
+    if declaration.ecosystem == "cargo":
+        return declaration.imports or [declaration.name.replace("-", "_")]
+    return [declaration.name]
+
+def _imported_package(family: str, module: str) -> Optional[str]:
+    """The part of an import that names a package: what a bare specifier, module or crate starts with."""
+    if family == "js":
+        if module.startswith((".", "/", "node:")) or not module:
+            return None
+        parts = module.split("/")
+        return "/".join(parts[:2]) if module.startswith("@") else parts[0]
+    if family == "rust":
+        head = module.split("::")[0]
+        return None if head in ("crate", "self", "super", "std", "core", "alloc", "") else head
+    if family == "python" and module.startswith("."):
+        return None
+    return module or None
+
+def _match(family: str, module: str, names: Dict[str, str]) -> Optional[str]:
+    """Key of the package an import lands in, by longest module path prefix."""
+    imported = _imported_package(family, module)
+    if imported is None:
+        return None
+    if family in ("js", "rust"):
+        return names.get(imported)
+    separator = "/" if family == "go" else "."
+    parts = imported.split(separator)
+    for end in range(len(parts), 0, -1):
+        key = names.get(separator.join(parts[:end]))
+        if key is not None:
+            return key
+    return None
+
+def package_graph(repo_path: str, rows: List[Dict[str, Any]], declarations: List[Declaration]) -> Dict[str, Any]:
+    """[1.12.2] Packages with their declarations, and the files importing each."""
+    packages: Dict[Tuple[str, str], Dict[str, Any]] = {}
+    names: Dict[str, Dict[str, Tuple[str, str]]] = defaultdict(dict)
+    for declaration in sorted(declarations, key=lambda d: (d.ecosystem, d.manifest, d.name)):
+        key = (declaration.ecosystem, normalize_name(declaration.ecosystem, declaration.name))
+        package = packages.setdefault(key, {
+            "ecosystem": declaration.ecosystem,
+            "name": key[1],
+            "declarations": [],
+            "importers": {}
+        })
+        package["declarations"].append({
+            "manifest": declaration.manifest,
+            "constraint": declaration.constraint,
+            "version": declaration.version,
+            "kind": declaration.kind
+        })
+        for name in _import_names(declaration):
+            names[declaration.ecosystem].setdefault(name, key)
+
+    for row in rows:
+        family = LANGUAGE_FAMILIES.get(row["language"])
+        ecosystem = FAMILY_ECOSYSTEMS.get(family)
+        symbols = row["symbols"]
+        if isinstance(symbols, str):
+            symbols = json.loads(symbols)
+        if ecosystem is None or not symbols:
+            continue
+        for entry in symbols.get("imports", []):
+            module = entry.get("path") or entry.get("module")
+            key = _match(family, module or "", names[ecosystem]) if module else None
+            if key is None:
+                continue
+            importer = packages[key]["importers"].setdefault(row["file_path"], {"modules": set(), "line": entry["line"]})
+            importer["modules"].add(module)
+            importer["line"] = min(importer["line"], entry["line"])
+
+    result = []
+    for key in sorted(packages):
+        package = packages[key]
+        declared = package["declarations"]
+        constraints = sorted({d["constraint"] for d in declared if d["constraint"]})
+        versions = sorted({d["version"] for d in declared if d["version"]})
+        kinds = sorted({d["kind"] for d in declared})
+        result.append({
+            "ecosystem": package["ecosystem"],
+            "name": package["name"],
+            "constraint": " | ".join(constraints) or None,
+            "version": ", ".join(versions) or None,
+            "kind": "runtime" if "runtime" in kinds else kinds[0],
+            "kinds": kinds,
+            "manifests": sorted({d["manifest"] for d in declared}),
+            "declarations": declared,
+            "importers": [
+                {
+                    "file_path": file_path,
+                    "relative_path": os.path.relpath(file_path, repo_path),
+                    "modules": sorted(importer["modules"]),
+                    "line": importer["line"]
+                }
+                for file_path, importer in sorted(package["importers"].items())
+            ]
+        })
+    return {"packages": result}
+
+def _node_properties(package: Dict[str, Any]) -> Dict[str, Any]:
+    properties = {
+        key: package[key] for key in ("ecosystem", "name", "constraint", "version", "kind", "kinds", "manifests")
+        if package[key] is not None
+    }
+    properties["importers"] = len(package["importers"])
+    return properties
+
+@handle_async_errors(error_types=(PostgresError, Neo4jError, DatabaseError), default_return={})
+async def sync_package_graph(
+    repo_id: int,
+    repo_path: str,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.12.3] Rebuild the repository's Package nodes and arch_patterns row when they changed.
+
+    reader supplies manifest and lockfile contents (e.g. GitTreeSource.read
+    for a commit); by default they are read from disk.
+    """
+    repo_path = os.path.abspath(repo_path)
+    backend = await get_storage_backend()
+    rows = await backend.fetch("SELECT file_path, language, symbols FROM file_symbols WHERE repo_id = $1;", repo_id)
+    declarations, lockfiles = await read_declarations(repo_path, [row["file_path"] for row in rows], reader or async_read_file)
+    graph = package_graph(repo_path, rows, declarations)
+    graph["manifests"] = sorted({declaration.manifest for declaration in declarations})
+    graph["lockfiles"] = lockfiles
+    stats = {
+        "manifests": len(graph["manifests"]),
+        "packages": len(graph["packages"]),
+        "imports": sum(len(package["importers"]) for package in graph["packages"])
+    }
+
+    digest = hashlib.sha256(json.dumps(graph, sort_keys=True, default=str).encode("utf-8")).hexdigest()
+    repository = await backend.find_nodes("Repository", {"id": repo_id}, limit=1)
+    if repository and repository[0].get("package_graph_hash") == digest:
+        return stats
+
+    await backend.delete_nodes("Package", {"repo_id": repo_id})
+    for package in graph["packages"]:
+        key = {"repo_id": repo_id, "ecosystem": package["ecosystem"], "name": package["name"]}
+        await backend.merge_node("Package", key, _node_properties(package))
+        await backend.merge_relationship(
+            "Repository", {"id": repo_id}, "DEPENDS_ON", "Package", key,
+            {"constraint": package["constraint"], "kind": package["kind"]} if package["constraint"] else {"kind": package["kind"]}
+        )
+        for importer in package["importers"]:
+            await backend.merge_relationship(
+                "Code", {"repo_id": repo_id, "file_path": importer["file_path"]}, "IMPORTS_PACKAGE", "Package", key,
+                {"modules": importer["modules"], "line": importer["line"]}
+            )
+
+    ecosystems: Dict[str, int] = defaultdict(int)
+    for package in graph["packages"]:
+        ecosystems[package["ecosystem"]] += 1
+    await backend.execute(
+        """
+        INSERT INTO arch_patterns (repo_id, pattern_type, dependencies)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (repo_id, pattern_type)
+        DO UPDATE SET dependencies = EXCLUDED.dependencies, created_at = CURRENT_TIMESTAMP;
+        """,
+        repo_id, PATTERN_TYPE, json.dumps({**graph, "ecosystems": dict(ecosystems)}, default=str)
+    )
+    await backend.merge_node("Repository", {"id": repo_id}, {"package_graph_hash": digest})
+    await log(
+        f"Package dependencies of repository {repo_id}: {stats['packages']} package(s) "
+        f"from {stats['manifests']} manifest(s), imported by {stats['imports']} file(s)",
+        level="info"
+    )
+    return stats
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return=[])
+async def list_packages(repo_id: int, ecosystem: Optional[str] = None) -> List[Dict[str, Any]]:
+    """[1.12.4] Declared packages with their constraints, resolved versions and importing files."""
+    backend = await get_storage_backend()
+    row = await backend.fetchrow(
+        "SELECT dependencies FROM arch_patterns WHERE repo_id = $1 AND pattern_type = $2;",
+        repo_id, PATTERN_TYPE
+    )
+    if row is None or not row["dependencies"]:
+        return []
+    dependencies = json.loads(row["dependencies"]) if isinstance(row["dependencies"], str) else row["dependencies"]
+    return [
+        package for package in dependencies.get("packages", [])
+        if ecosystem is None or package["ecosystem"] == ecosystem
+    ]
+
+__all__ = [
+    "PATTERN_TYPE",
+    "MANIFESTS",
+    "PYTHON_MODULES",
+    "Declaration",
+    "normalize_name",
+    "parse_requirement",
+    "parse_manifest",
+    "parse_lockfile",
+    "read_declarations",
+    "package_graph",
+    "sync_package_graph",
+    "list_packages"
+]
@@ -0,0 +1,329 @@
+"""[2.6] Multi-process parse stage for indexing.
+
+Flow:
+1. Read Stage:
+   - File contents are read on the event loop (from disk or a GitTreeSource)
+     and queued for parsing; the bounded queue stops reading from running
+     ahead of the parsers
+
+2. Parse Stage:
+   - Worker processes classify and parse files and extract features with
+     tree-sitter, each on its own event loop
+   - Results come back as plain ParserResult/ExtractedFeatures: the raw tree
+     is dropped and anything that cannot be pickled is turned into a string
+   - Definitions, references and imports for the symbol table [1.6] are
+     extracted from the tree before it is dropped
+
+3. Write Stage:
+   - A few async writers embed and store parsed files; a full write queue
+     holds the parsers back until storage catches up
+
+Worker count and queue sizes come from IndexingConfig; parse_workers=0 parses
+on the event loop instead. If a worker process dies, the files being parsed
+at the time fail and the pool is replaced for the files after them.
+"""
+
+import asyncio
+import dataclasses
+import multiprocessing
+import signal
+import time
+from concurrent.futures import ProcessPoolExecutor
+from concurrent.futures.process import BrokenProcessPool
+from dataclasses import dataclass
+from enum import Enum
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Set
+
+from config.config import indexing_config
+from parsers.types import FileType, ParserResult
+from utils.logger import log
+from utils.error_handling import AsyncErrorBoundary, ErrorSeverity, ProcessingError
+from utils.shutdown import register_shutdown_handler
+from utils.health_monitor import global_health_monitor
+
+@dataclass
+class ParsedFile:
+    """What a parse worker sends back for one file."""
+    file_path: str
+    language: Optional[str] = None
+    file_type: Optional[FileType] = None
+    result: Optional[ParserResult] = None
+    error: Optional[str] = None
+    parse_time: float = 0.0
+    symbols: Optional[Dict[str, Any]] = None
+
+def _plain(value: Any) -> Any:
+    """Copy of value that can cross a process boundary; unknown objects become strings."""
+    if value is None or isinstance(value, (str, int, float, bool, Enum)):
+        return value
+    if isinstance(value, dict):
+        return {k if isinstance(k, (str, int, float, bool)) else str(k): _plain(v) for k, v in value.items()}
+    if isinstance(value, (list, tuple, set, frozenset)):
+        return [_plain(item) for item in value]
+    if dataclasses.is_dataclass(value) and not isinstance(value, type):
+        return {f.name: _plain(getattr(value, f.name)) for f in dataclasses.fields(value)}
+    return str(value)
+
+async def parse_content(file_path: str, content: str) -> ParsedFile:
+    """[2.6.1] Classify and parse one file's content."""
+    # Imported here so spawned workers load the parsers on first use
+    from indexer.file_utils import classify_file
+    from parsers.unified_parser import get_unified_parser
+    from parsers.symbol_extractor import extract_symbols, grammar_for
+
+    start = time.monotonic()
+    classification = await classify_file(file_path)
+    if classification is None:
+        return ParsedFile(file_path=file_path, error="Could not classify file")
+
+    parser = await get_unified_parser(
+        classification.language_id,
+        classification.file_type,
+        classification.parser_type
+    )
+    if parser is None:
+        return ParsedFile(file_path=file_path, error=f"No parser for {classification.language_id}")
+
+    result = await parser.parse_file_content(content, file_path)
+    symbols = None
+    if result.success and grammar_for(classification.language_id, file_path):
+        tree = result.tree if hasattr(result.tree, "root_node") else None
+        try:
+            symbols = extract_symbols(classification.language_id, content, tree, file_path)
+        except Exception as e:
+            # The file is still indexed; it just takes no part in name resolution
+            result.errors.append(f"Symbol extraction failed: {e}")
+    result.tree = None
+    result.ast = _plain(result.ast)
+    result.features.features = _plain(result.features.features)
+    result.metadata = _plain(result.metadata)
+    return ParsedFile(
+        file_path=file_path,
+        language=classification.language_id,
+        file_type=classification.file_type,
+        result=result,
+        error=None if result.success else "; ".join(result.errors) or "Parse failed",
+        parse_time=time.monotonic() - start,
+        symbols=symbols
+    )
+
+# Event loop of a worker process, set up once by _init_worker
+_worker_loop: Optional[asyncio.AbstractEventLoop] = None
+
+def _init_worker() -> None:
+    global _worker_loop
+    # Ctrl-C is handled by the parent, which shuts the pool down
+    signal.signal(signal.SIGINT, signal.SIG_IGN)
+    _worker_loop = asyncio.new_event_loop()
+    asyncio.set_event_loop(_worker_loop)
+
+def parse_in_worker(file_path: str, content: str) -> ParsedFile:
+    """Entry point run in a worker process."""
+    try:
+        return _worker_loop.run_until_complete(parse_content(file_path, content))
+    except Exception as e:
+        return ParsedFile(file_path=file_path, error=f"{type(e).__name__}: {e}")
+
+class ParsePool:
+    """[2.6.2] Parser processes fed and drained through bounded queues."""
+
+    def __init__(self):
+        """Private constructor - use create() instead."""
+        self._initialized = False
+        self._pending_tasks: Set[asyncio.Task] = set()
+        self._executor: Optional[ProcessPoolExecutor] = None
+        self._workers = 0
+
+    async def ensure_initialized(self):
+        """Ensure the instance is properly initialized before use."""
+        if not self._initialized:
+            raise ProcessingError("ParsePool not initialized. Use create() to initialize.")
+        return True
+
+    @classmethod
+    async def create(cls, workers: Optional[int] = None) -> 'ParsePool':
+        """Async factory method to create a ParsePool with workers processes (default from IndexingConfig)."""
+        instance = cls()
+        try:
+            async with AsyncErrorBoundary(
+                operation_name="parse pool initialization",
+                error_types=ProcessingError,
+                severity=ErrorSeverity.CRITICAL
+            ):
+                instance._workers = max(indexing_config.parse_workers if workers is None else workers, 0)
+                if instance._workers:
+                    instance._executor = instance._new_executor()
+
+                register_shutdown_handler(instance.cleanup)
+                global_health_monitor.register_component("parse_pool")
+
+                instance._initialized = True
+                await log(f"Parse pool initialized with {instance._workers} worker process(es)", level="info")
+                return instance
+        except Exception as e:
+            await log(f"Error initializing parse pool: {e}", level="error")
+            await instance.cleanup()
+            raise ProcessingError(f"Failed to initialize parse pool: {e}")
+
+    @property
+    def workers(self) -> int:
+        return self._workers
+
+    def _new_executor(self) -> ProcessPoolExecutor:
+        # spawn: forking a process with running threads and an event loop is unsafe
+        return ProcessPoolExecutor(
+            max_workers=self._workers,
+            mp_context=multiprocessing.get_context("spawn"),
+            initializer=_init_worker
+        )
+
+    async def _replace_executor(self, broken: ProcessPoolExecutor) -> None:
+        """Swap a pool whose worker died for a fresh one (once, however many files saw it break)."""
+        if self._executor is not broken:
+            return
+        self._executor = self._new_executor()
+        await log("A parse worker process died; restarted the parse pool", level="warning")
+        loop = asyncio.get_running_loop()
+        await loop.run_in_executor(None, lambda: broken.shutdown(wait=False, cancel_futures=True))
+
+    async def _parse(self, file_path: str, content: str) -> ParsedFile:
+        if self._executor is None:
+            try:
+                return await parse_content(file_path, content)
+            except Exception as e:
+                return ParsedFile(file_path=file_path, error=f"{type(e).__name__}: {e}")
+        executor = self._executor
+        loop = asyncio.get_running_loop()
+        try:
+            return await loop.run_in_executor(executor, parse_in_worker, file_path, content)
+        except BrokenProcessPool as e:
+            # The file may have caused the crash, so it is not retried
+            await self._replace_executor(executor)
+            return ParsedFile(file_path=file_path, error=f"Parser process died: {e}")
+
+    def _track(self, coro) -> asyncio.Task:
+        task = asyncio.create_task(coro)
+        self._pending_tasks.add(task)
+        task.add_done_callback(self._pending_tasks.discard)
+        return task
+
+    async def run(
+        self,
+        files: List[str],
+        read: Callable[[str], Awaitable[Optional[str]]],
+        write: Callable[[ParsedFile, str], Awaitable[None]]
+    ) -> Dict[str, Any]:
+        """[2.6.3] Read, parse and write files through the three stages.
+
+        Flow:
+        1. A reader queues (path, content) pairs for the parsers
+        2. One dispatcher per worker hands files to the pool and queues results
+        3. Writers call write() for each parsed file
+
+        Returns:
+            Counts of parsed, stored and failed files, the failed paths and
+            the error of each
+        """
+        if not self._initialized:
+            await self.ensure_initialized()
+
+        parse_queue: asyncio.Queue = asyncio.Queue(maxsize=max(indexing_config.parse_queue_size, 1))
+        write_queue: asyncio.Queue = asyncio.Queue(maxsize=max(indexing_config.write_queue_size, 1))
+        dispatchers = max(self._workers, 1)
+        writers = max(indexing_config.write_workers, 1)
+        parsed_count, stored = 0, 0
+        failed: List[str] = []
+        errors: Dict[str, str] = {}
+
+        async def read_stage():
+            for file_path in files:
+                content = await read(file_path)
+                if content is None:
+                    failed.append(file_path)
+                    errors[file_path] = "Could not read file"
+                    continue
+                await parse_queue.put((file_path, content))
+            for _ in range(dispatchers):
+                await parse_queue.put(None)
+
+        async def parse_stage():
+            nonlocal parsed_count
+            while True:
+                item = await parse_queue.get()
+                if item is None:
+                    return
+                file_path, content = item
+                parsed = await self._parse(file_path, content)
+                if parsed.error:
+                    failed.append(file_path)
+                    errors[file_path] = parsed.error
+                    await log(f"Error parsing {file_path}: {parsed.error}", level="warning")
+                    continue
+                parsed_count += 1
+                await write_queue.put((parsed, content))
+
+        async def write_stage():
+            nonlocal stored
+            while True:
+                item = await write_queue.get()
+                if item is None:
+                    return
+                parsed, content = item
+                try:
+                    await write(parsed, content)
+                    stored += 1
+                except Exception as e:
+                    failed.append(parsed.file_path)
+                    errors[parsed.file_path] = f"{type(e).__name__}: {e}"
+                    await log(f"Error storing {parsed.file_path}: {e}", level="error")
+
+        producers = [self._track(read_stage())] + [self._track(parse_stage()) for _ in range(dispatchers)]
+        consumers = [self._track(write_stage()) for _ in range(writers)]
+        try:
+            await asyncio.gather(*producers)
+            for _ in range(writers):
+                await write_queue.put(None)
+            await asyncio.gather(*consumers)
+        except BaseException:
+            for task in producers + consumers:
+                task.cancel()
+            await asyncio.gather(*producers, *consumers, return_exceptions=True)
+            raise
+
+        return {
+            "parsed": parsed_count,
+            "stored": stored,
+            "failed": len(failed),
+            "failed_files": failed,
+            "errors": errors
+        }
+
+    async def cleanup(self):
+        """Clean up parse pool resources."""
+        try:
+            if self._pending_tasks:
+                for task in self._pending_tasks:
+                    if not task.done():
+                        task.cancel()
+                await asyncio.gather(*self._pending_tasks, return_exceptions=True)
+                self._pending_tasks.clear()
+
+            if self._executor is not None:
+                executor, self._executor = self._executor, None
+                loop = asyncio.get_running_loop()
+                await loop.run_in_executor(None, lambda: executor.shutdown(wait=True, cancel_futures=True))
+
+            global_health_monitor.unregister_component("parse_pool")
+
+            self._initialized = False
+            await log("Parse pool cleaned up", level="info")
+        except Exception as e:
+            await log(f"Error cleaning up parse pool: {e}", level="error")
+            raise ProcessingError(f"Failed to cleanup parse pool: {e}")
+
+__all__ = [
+    "ParsedFile",
+    "ParsePool",
+    "parse_content",
+    "parse_in_worker"
+]
@@ -0,0 +1,189 @@
+"""[1.8] Rust safety and panic audit.
+
+Flow:
+1. Sites:
+   - The safety sites the Rust symbol extractor records at parse time:
+     unsafe blocks, functions, impls and traits, unwrap()/expect() calls,
+     panicking macros (panic!, unreachable!, todo!, unimplemented!), raw
+     pointer types, extern blocks with their FFI declarations and extern fns
+   - Sites in tests (a tests/ or benches/ directory, a tests module) are kept
+     but counted separately
+
+2. Attribution:
+   - Each site belongs to the module of the crate graph [1.7] its file and
+     scope are in, and to that module's crate
+
+3. Findings:
+   - Every site is stored in findings with analysis 'rust_audit', replacing
+     the previous run's rows
+   - rust_audit_report() rolls the counts up per crate and module
+"""
+
+import os
+import json
+from collections import Counter
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from indexer.rust_crates import load_crate_graph
+
+ANALYSIS = "rust_audit"
+
+# kind -> (severity, message)
+AUDIT_KINDS = {
+    "unsafe_block": ("warning", "unsafe block"),
+    "unsafe_fn": ("warning", "unsafe function"),
+    "unsafe_impl": ("warning", "unsafe impl"),
+    "unsafe_trait": ("warning", "unsafe trait"),
+    "unwrap": ("info", "unwrap() panics on None or Err"),
+    "expect": ("info", "expect() panics on None or Err"),
+    "panic": ("warning", "panicking macro"),
+    "raw_pointer": ("info", "raw pointer type"),
+    "extern_block": ("info", "extern block"),
+    "ffi_declaration": ("info", "foreign function or static"),
+    "extern_fn": ("info", "function exported with a foreign ABI")
+}
+
+_TEST_DIRECTORIES = {"tests", "benches"}
+
+def _in_test(file_path: str, crate_dir: str, scope: str) -> bool:
+    relative = os.path.relpath(file_path, crate_dir)
+    if any(part in _TEST_DIRECTORIES for part in relative.split(os.sep)[:-1]):
+        return True
+    return "tests" in scope.split(".")
+
+def _counts(rows: List[Dict[str, Any]]) -> Dict[str, int]:
+    counter = Counter(row["kind"] for row in rows)
+    return {kind: counter[kind] for kind in AUDIT_KINDS if counter[kind]}
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return={})
+async def run_rust_audit(
+    repo_id: int,
+    repo_path: str,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.8.1] Store the repository's Rust safety sites as findings.
+
+    reader supplies Cargo.toml contents, as for sync_crate_graph [1.7.3].
+    """
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, symbols FROM file_symbols WHERE repo_id = $1 AND language = 'rust';",
+        repo_id
+    )
+    graph = await load_crate_graph(repo_path, rows, reader)
+
+    findings = []
+    for file_path in sorted(graph.symbols):
+        for site in graph.symbols[file_path].get("safety", []):
+            if site["kind"] not in AUDIT_KINDS:
+                continue
+            severity, message = AUDIT_KINDS[site["kind"]]
+            scope = site.get("scope") or ""
+            module = graph.module_for(file_path, scope)
+            package = graph.package_of(module) if module is not None else None
+            symbol = site.get("name") or scope.replace(".", "::") or None
+            crate_dir = package.directory if package is not None else graph.repo_path
+            findings.append((
+                repo_id, ANALYSIS, site["kind"], severity, file_path, site["line"], symbol,
+                f"{message}: {site['detail']}" if site.get("detail") else message,
+                json.dumps({
+                    "crate": package.name if package is not None else None,
+                    "target": module.target if module is not None else None,
+                    "module": module.name if module is not None else None,
+                    "column": site.get("column"),
+                    "detail": site.get("detail"),
+                    "test": _in_test(file_path, crate_dir, scope)
+                })
+            ))
+
+    await backend.execute_batch(
+        [("DELETE FROM findings WHERE repo_id = $1 AND analysis = $2;", (repo_id, ANALYSIS))] + [
+            (
+                """
+                INSERT INTO findings (repo_id, analysis, kind, severity, file_path, line, symbol, message, details)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+                """,
+                finding
+            )
+            for finding in findings
+        ]
+    )
+    if findings:
+        await log(f"Rust audit of repository {repo_id}: {len(findings)} finding(s)", level="info")
+    return {"findings": len(findings)}
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return=[])
+async def list_findings(
+    repo_id: int,
+    analysis: Optional[str] = None,
+    kind: Optional[str] = None,
+    file_path: Optional[str] = None
+) -> List[Dict[str, Any]]:
+    """[1.8.2] Stored findings of a repository, optionally of one analysis, kind or file."""
+    backend = await get_storage_backend()
+    conditions, params = ["repo_id = $1"], [repo_id]
+    for column, value in (("analysis", analysis), ("kind", kind), ("file_path", os.path.abspath(file_path) if file_path else None)):
+        if value is not None:
+            params.append(value)
+            conditions.append(f"{column} = ${len(params)}")
+    rows = await backend.fetch(
+        f"""
+        SELECT analysis, kind, severity, file_path, line, symbol, message, details
+        FROM findings WHERE {' AND '.join(conditions)}
+        ORDER BY file_path, line, kind;
+        """,
+        *params
+    )
+    findings = []
+    for row in rows:
+        row = dict(row)
+        if isinstance(row.get("details"), str):
+            row["details"] = json.loads(row["details"])
+        findings.append(row)
+    return findings
+
+async def rust_audit_report(repo_id: int) -> Dict[str, Any]:
+    """[1.8.3] Rust audit counts per crate and module, with test code counted apart."""
+    findings = await list_findings(repo_id, ANALYSIS) or []
+    code = [row for row in findings if not (row["details"] or {}).get("test")]
+
+    crates: Dict[Optional[str], Dict[Optional[str], List[Dict[str, Any]]]] = {}
+    for row in code:
+        details = row["details"] or {}
+        modules = crates.setdefault(details.get("crate"), {})
+        modules.setdefault(f"{details.get('target')}:{details.get('module')}" if details.get("module") else None, []).append(row)
+
+    report = []
+    for crate, modules in sorted(crates.items(), key=lambda item: item[0] or ""):
+        rows = [row for module_rows in modules.values() for row in module_rows]
+        report.append({
+            # None for files no crate target reaches through mod declarations
+            "crate": crate,
+            "counts": _counts(rows),
+            "modules": [
+                {
+                    "target": module_rows[0]["details"].get("target"),
+                    "module": module_rows[0]["details"].get("module"),
+                    "files": sorted({row["file_path"] for row in module_rows}),
+                    "counts": _counts(module_rows)
+                }
+                for _, module_rows in sorted(modules.items(), key=lambda item: item[0] or "")
+            ]
+        })
+    return {
+        "repo_id": repo_id,
+        "totals": _counts(code),
+        "tests": _counts([row for row in findings if (row["details"] or {}).get("test")]),
+        "crates": report
+    }
+
+__all__ = [
+    "ANALYSIS",
+    "AUDIT_KINDS",
+    "run_rust_audit",
+    "list_findings",
+    "rust_audit_report"
+]
@@ -0,0 +1,571 @@
+"""[1.7] Rust crate and module graph.
+
+Flow:
+1. Manifests:
+   - Cargo.toml files in the directories above the repository's Rust files
+     are read, from the working tree or the indexed commit
+   - [workspace] members and exclude globs mark workspace crates; version
+     and edition may be inherited from [workspace.package]
+   - Each [package] is a Crate with a lib target (src/lib.rs or [lib]) and
+     bin targets (src/main.rs, src/bin/*.rs or [[bin]]), and the features
+     of its [features] table
+
+2. Module Tree:
+   - From each target's root file, mod foo; declarations are followed to
+     foo.rs or foo/mod.rs next to the declaring module, or to a #[path] file
+   - mod foo { } blocks are inline modules of the file they are in
+   - Each module keeps the cfg predicates of its mod declaration and its
+     #![cfg] attributes
+
+3. Dependencies:
+   - [dependencies], [dev-dependencies], [build-dependencies] and their
+     [target.*] variants: Crate DEPENDS_ON a workspace Crate, or an external
+     Crate node (external=true) for registry and git dependencies
+   - use declarations resolve through crate::, self::, super::, child
+     modules and workspace crate names to the module they land in: Module
+     DEPENDS_ON Module (with the items used) or DEPENDS_ON an external Crate
+
+4. Graph:
+   - Crate {repo_id, name} CONTAINS the root Module of each target; Module
+     {repo_id, crate, path} CONTAINS its child modules and its file's Code node
+   - The graph is rebuilt only when its digest (kept on the Repository node)
+     changes
+"""
+
+import os
+import json
+import fnmatch
+import hashlib
+from dataclasses import dataclass, field
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Set, Tuple
+
+from utils.logger import log
+from utils.toml_compat import tomllib
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError, Neo4jError
+from db.storage import get_storage_backend
+from indexer.async_utils import async_read_file
+
+MANIFEST = "Cargo.toml"
+
+# Dependency tables and the kind recorded on their DEPENDS_ON edges
+_DEPENDENCY_TABLES = {
+    "dependencies": "normal",
+    "dev-dependencies": "dev",
+    "build-dependencies": "build"
+}
+
+# Crates every Rust file can name without a dependency
+_BUILTIN_CRATES = {"std", "core", "alloc", "proc_macro", "test"}
+
+@dataclass
+class Dependency:
+    name: str
+    crate: str
+    kind: str
+    req: Optional[str] = None
+    path: Optional[str] = None
+    optional: bool = False
+    target: Optional[str] = None
+
+@dataclass
+class Target:
+    """A lib or bin target; its modules are keyed by id."""
+    id: str
+    kind: str
+    name: str
+    root: str
+
+@dataclass
+class Package:
+    name: str
+    manifest: str
+    version: Optional[str] = None
+    edition: Optional[str] = None
+    workspace: Optional[str] = None
+    targets: List[Target] = field(default_factory=list)
+    dependencies: List[Dependency] = field(default_factory=list)
+    # [features]: name -> features and dependencies it enables
+    features: Dict[str, List[str]] = field(default_factory=dict)
+
+    @property
+    def directory(self) -> str:
+        return os.path.dirname(self.manifest)
+
+@dataclass
+class Module:
+    target: str
+    path: Tuple[str, ...]
+    file_path: str
+    scope: str
+    inline: bool
+    line: int
+    children_dir: str
+    children: Dict[str, 'Module'] = field(default_factory=dict)
+    parent: Optional['Module'] = None
+    cfg: List[str] = field(default_factory=list)
+
+    @property
+    def name(self) -> str:
+        return "::".join(("crate",) + self.path)
+
+def _crate_name(name: str) -> str:
+    """Crate names are referred to in code with underscores."""
+    return name.replace("-", "_")
+
+def _table_value(table: Dict[str, Any], key: str, inherited: Dict[str, Any]) -> Optional[str]:
+    value = table.get(key)
+    if isinstance(value, dict) and value.get("workspace"):
+        value = inherited.get(key)
+    return str(value) if value is not None else None
+
+def _dependencies(manifest: Dict[str, Any], directory: str, workspace_deps: Dict[str, Any]) -> List[Dependency]:
+    found = []
+    tables = [(manifest, None)]
+    for cfg, target in (manifest.get("target") or {}).items():
+        if isinstance(target, dict):
+            tables.append((target, cfg))
+    for table, cfg in tables:
+        for section, kind in _DEPENDENCY_TABLES.items():
+            for name, spec in (table.get(section) or {}).items():
+                if isinstance(spec, dict) and spec.get("workspace"):
+                    inherited = workspace_deps.get(name)
+                    optional = bool(spec.get("optional"))
+                    spec = inherited if inherited is not None else spec
+                    if isinstance(spec, dict):
+                        spec = dict(spec, optional=optional or spec.get("optional", False))
+                if isinstance(spec, str):
+                    found.append(Dependency(name, name, kind, req=spec, target=cfg))
+                elif isinstance(spec, dict):
+                    path = spec.get("path")
+                    found.append(Dependency(
+                        name,
+                        spec.get("package", name),
+                        kind,
+                        req=spec.get("version") or spec.get("git"),
+                        path=os.path.normpath(os.path.join(directory, path)) if path else None,
+                        optional=bool(spec.get("optional")),
+                        target=cfg
+                    ))
+    return found
+
+class CrateGraph:
+    """Crates, targets and module trees of one repository."""
+
+    def __init__(self, repo_path: str, rows: List[Dict[str, Any]], manifests: Dict[str, Dict[str, Any]]):
+        self.repo_path = os.path.abspath(repo_path)
+        self.symbols: Dict[str, Dict[str, Any]] = {}
+        for row in rows:
+            symbols = row["symbols"]
+            self.symbols[row["file_path"]] = json.loads(symbols) if isinstance(symbols, str) else symbols
+        self.packages: Dict[str, Package] = {}
+        self.modules: Dict[Tuple[str, Tuple[str, ...]], Module] = {}
+        self.by_file: Dict[Tuple[str, str], Module] = {}
+        self._load_packages(manifests)
+        for package in self.packages.values():
+            for target in package.targets:
+                self._build_tree(target)
+
+    # Manifests -----------------------------------------------------------
+
+    def _load_packages(self, manifests: Dict[str, Dict[str, Any]]) -> None:
+        # Workspace roots first, so members can inherit from them
+        workspaces: Dict[str, Dict[str, Any]] = {}
+        for path, manifest in manifests.items():
+            workspace = manifest.get("workspace")
+            if isinstance(workspace, dict):
+                workspaces[os.path.dirname(path)] = workspace
+
+        for path, manifest in sorted(manifests.items()):
+            package = manifest.get("package")
+            if not isinstance(package, dict) or not package.get("name"):
+                continue
+            directory = os.path.dirname(path)
+            workspace_dir = self._workspace_of(directory, workspaces)
+            workspace = workspaces.get(workspace_dir, {}) if workspace_dir else {}
+            inherited = workspace.get("package") or {}
+            entry = Package(
+                name=package["name"],
+                manifest=path,
+                version=_table_value(package, "version", inherited),
+                edition=_table_value(package, "edition", inherited),
+                workspace=workspace_dir
+            )
+            entry.dependencies = _dependencies(manifest, directory, workspace.get("dependencies") or {})
+            entry.features = {
+                str(name): [str(value) for value in values]
+                for name, values in (manifest.get("features") or {}).items() if isinstance(values, list)
+            }
+            entry.targets = self._targets(entry, manifest)
+            # Two manifests with one name: the first (shallowest) wins
+            self.packages.setdefault(entry.name, entry)
+
+    def _workspace_of(self, directory: str, workspaces: Dict[str, Dict[str, Any]]) -> Optional[str]:
+        """The workspace root whose members include directory, if any."""
+        for root, workspace in workspaces.items():
+            relative = os.path.relpath(directory, root).replace(os.sep, "/")
+            if relative.startswith(".."):
+                continue
+            if relative == ".":
+                return root
+            members = workspace.get("members") or []
+            excluded = workspace.get("exclude") or []
+            if any(fnmatch.fnmatch(relative, pattern.rstrip("/")) for pattern in members) and not any(
+                fnmatch.fnmatch(relative, pattern.rstrip("/")) for pattern in excluded
+            ):
+                return root
+        return None
+
+    def _targets(self, package: Package, manifest: Dict[str, Any]) -> List[Target]:
+        directory = package.directory
+        targets = []
+        lib = manifest.get("lib") or {}
+        lib_root = os.path.normpath(os.path.join(directory, lib.get("path", "src/lib.rs")))
+        if lib_root in self.symbols:
+            targets.append(Target(package.name, "lib", _crate_name(lib.get("name", package.name)), lib_root))
+
+        bins = []
+        for entry in manifest.get("bin") or []:
+            if isinstance(entry, dict) and entry.get("name"):
+                path = entry.get("path") or (
+                    "src/main.rs" if entry["name"] == package.name else f"src/bin/{entry['name']}.rs"
+                )
+                bins.append((entry["name"], os.path.normpath(os.path.join(directory, path))))
+        if manifest.get("package", {}).get("autobins", True):
+            main = os.path.join(directory, "src", "main.rs")
+            if main in self.symbols:
+                bins.append((package.name, main))
+            bin_dir = os.path.join(directory, "src", "bin")
+            for path in sorted(self.symbols):
+                if os.path.dirname(path) == bin_dir:
+                    bins.append((os.path.splitext(os.path.basename(path))[0], path))
+                elif os.path.basename(path) == "main.rs" and os.path.dirname(os.path.dirname(path)) == bin_dir:
+                    bins.append((os.path.basename(os.path.dirname(path)), path))
+
+        seen = set()
+        for name, root in bins:
+            if root in self.symbols and root not in seen:
+                seen.add(root)
+                targets.append(Target(f"{package.name}/bin/{name}", "bin", _crate_name(name), root))
+        return targets
+
+    # Module tree ---------------------------------------------------------
+
+    def _build_tree(self, target: Target) -> None:
+        root = Module(target.id, (), target.root, "", False, 1, os.path.dirname(target.root))
+        root.cfg = list((self.symbols.get(target.root) or {}).get("module_cfg", {}).get("", []))
+        self._add(root)
+        stack, visited = [root], {target.root}
+        while stack:
+            module = stack.pop()
+            symbols = self.symbols.get(module.file_path) or {}
+            for definition in symbols.get("definitions", []):
+                if definition["kind"] != "module" or (definition.get("scope") or "") != module.scope:
+                    continue
+                name = definition["name"]
+                if definition.get("inline"):
+                    scope = f"{module.scope}.{name}" if module.scope else name
+                    child = Module(
+                        target.id, module.path + (name,), module.file_path, scope, True,
+                        definition["line"], os.path.join(module.children_dir, name)
+                    )
+                else:
+                    file_path = self._module_file(module, name, definition.get("path"))
+                    if file_path is None or file_path in visited:
+                        continue
+                    visited.add(file_path)
+                    children_dir = (
+                        os.path.dirname(file_path) if os.path.basename(file_path) == "mod.rs"
+                        else os.path.splitext(file_path)[0]
+                    )
+                    child = Module(target.id, module.path + (name,), file_path, "", False, definition["line"], children_dir)
+                # #[cfg] on the mod declaration, then the module's own #![cfg]
+                inner = (self.symbols.get(child.file_path) or {}).get("module_cfg", {}).get(child.scope, [])
+                child.cfg = list(definition.get("cfg", [])) + list(inner)
+                child.parent = module
+                module.children[name] = child
+                self._add(child)
+                stack.append(child)
+
+    def _module_file(self, module: Module, name: str, path: Optional[str]) -> Optional[str]:
+        if path:
+            # #[path] is relative to the declaring file's directory
+            base = module.children_dir if module.inline else os.path.dirname(module.file_path)
+            candidate = os.path.normpath(os.path.join(base, path))
+            return candidate if candidate in self.symbols else None
+        for candidate in (
+            os.path.join(module.children_dir, f"{name}.rs"),
+            os.path.join(module.children_dir, name, "mod.rs")
+        ):
+            if candidate in self.symbols:
+                return candidate
+        return None
+
+    def _add(self, module: Module) -> None:
+        self.modules[(module.target, module.path)] = module
+        self.by_file.setdefault((module.file_path, module.scope), module)
+
+    # Use paths -----------------------------------------------------------
+
+    def module_for(self, file_path: str, scope: str) -> Optional[Module]:
+        """The module a scope of a file belongs to (the nearest inline module)."""
+        while True:
+            module = self.by_file.get((file_path, scope))
+            if module is not None or not scope:
+                return module
+            scope = scope.rpartition(".")[0]
+
+    def package_of(self, module: Module) -> Package:
+        return self.packages[module.target.split("/")[0]]
+
+    def _lib_root(self, crate_name: str, package: Package) -> Optional[Any]:
+        """The module or external crate a crate name in package refers to."""
+        for dependency in package.dependencies:
+            if _crate_name(dependency.name) != crate_name:
+                continue
+            local = self.packages.get(dependency.crate)
+            if local is None and dependency.path:
+                local = next((p for p in self.packages.values() if p.directory == dependency.path), None)
+            if local is not None:
+                return self.modules.get((local.name, ()))
+            return dependency
+        # A bin target uses its own package's lib by name
+        if _crate_name(package.name) == crate_name:
+            return self.modules.get((package.name, ()))
+        return None
+
+    def resolve_use(self, module: Module, entry: Dict[str, Any]) -> Optional[Tuple[Any, List[str]]]:
+        """[1.7.1] The module (or external Dependency) a use lands in, and the items below it."""
+        segments = [segment for segment in (entry.get("module") or "").split("::") if segment]
+        if entry.get("name") not in (None, "*"):
+            segments.append(entry["name"])
+        if not segments:
+            return None
+        package = self.package_of(module)
+        head, rest = segments[0], segments[1:]
+        if head in ("crate", "$crate"):
+            base = self.modules.get((module.target, ()))
+        elif head in ("self", "super"):
+            base, index = module, 0
+            while index < len(segments) and segments[index] in ("self", "super"):
+                if segments[index] == "super" and base.parent is not None:
+                    base = base.parent
+                index += 1
+            rest = segments[index:]
+        elif head in module.children:
+            base = module.children[head]
+        elif head in _BUILTIN_CRATES:
+            return None
+        else:
+            base = self._lib_root(head, package)
+            if base is None:
+                # 2015 edition: paths start at the crate root
+                root = self.modules.get((module.target, ()))
+                base = root.children.get(head) if root is not None else None
+        if base is None:
+            return None
+        if isinstance(base, Dependency):
+            return base, rest
+        while rest and rest[0] in base.children:
+            base = base.children[rest[0]]
+            rest = rest[1:]
+        return base, rest
+
+    def edges(self) -> Dict[str, Any]:
+        """[1.7.2] Nodes and edges of the crate graph, in a stable order."""
+        crates, external = [], {}
+        depends = []
+        for package in sorted(self.packages.values(), key=lambda p: p.name):
+            crates.append({
+                "name": package.name,
+                "version": package.version,
+                "edition": package.edition,
+                "manifest_path": package.manifest,
+                "workspace": package.workspace,
+                "targets": [target.id for target in package.targets],
+                "features": sorted(package.features),
+                "external": False
+            })
+            for dependency in package.dependencies:
+                local = self.packages.get(dependency.crate)
+                if local is None and dependency.path:
+                    local = next((p for p in self.packages.values() if p.directory == dependency.path), None)
+                target = local.name if local is not None else dependency.crate
+                if local is None:
+                    external.setdefault(target, {"name": target, "external": True})
+                depends.append({
+                    "source": package.name,
+                    "target": target,
+                    "kind": dependency.kind,
+                    "req": dependency.req,
+                    "optional": dependency.optional,
+                    "cfg": dependency.target
+                })
+
+        modules, contains, uses = [], [], {}
+        for (target, path), module in sorted(self.modules.items()):
+            modules.append({
+                "crate": target,
+                "path": module.name,
+                "name": path[-1] if path else target.split("/")[-1],
+                "file_path": module.file_path,
+                "inline": module.inline,
+                "line": module.line,
+                "cfg": module.cfg
+            })
+            if module.parent is not None:
+                contains.append(("Module", (module.parent.target, module.parent.name), module.target, module.name))
+            else:
+                contains.append(("Crate", target.split("/")[0], module.target, module.name))
+
+            symbols = self.symbols.get(module.file_path) or {}
+            for entry in symbols.get("imports", []):
+                if self.module_for(module.file_path, entry.get("scope") or "") is not module:
+                    continue
+                resolved = self.resolve_use(module, entry)
+                if resolved is None:
+                    continue
+                landing, items = resolved
+                if isinstance(landing, Dependency):
+                    key = (module.target, module.name, "Crate", landing.crate, None)
+                    external.setdefault(landing.crate, {"name": landing.crate, "external": True})
+                elif landing is module:
+                    continue
+                else:
+                    key = (module.target, module.name, "Module", landing.target, landing.name)
+                uses.setdefault(key, set()).add("::".join(items) if items else "*" if entry.get("name") == "*" else "self")
+
+        return {
+            "crates": crates + [
+                {"name": name, "external": True} for name in sorted(external) if name not in self.packages
+            ],
+            "depends": depends,
+            "modules": modules,
+            "contains": contains,
+            "uses": [
+                {"source": (key[0], key[1]), "label": key[2], "target": key[3], "path": key[4], "items": sorted(items)}
+                for key, items in sorted(uses.items(), key=lambda item: tuple(str(part) for part in item[0]))
+            ]
+        }
+
+async def _read_manifests(repo_path: str, files: List[str], reader: Callable[[str], Awaitable[Optional[str]]]) -> Dict[str, Dict[str, Any]]:
+    """Cargo.toml of every directory from the Rust files up to the repository root."""
+    directories: Set[str] = set()
+    for file_path in files:
+        directory = os.path.dirname(file_path)
+        while directory.startswith(repo_path) and directory not in directories:
+            directories.add(directory)
+            if directory == repo_path:
+                break
+            directory = os.path.dirname(directory)
+    manifests = {}
+    for directory in sorted(directories):
+        path = os.path.join(directory, MANIFEST)
+        if reader is async_read_file and not os.path.isfile(path):
+            continue
+        content = await reader(path)
+        if content is None:
+            continue
+        try:
+            manifests[path] = tomllib.loads(content)
+        except tomllib.TOMLDecodeError as e:
+            await log(f"Skipping unreadable {path}: {e}", level="warning")
+    return manifests
+
+async def load_crate_graph(
+    repo_path: str,
+    rows: List[Dict[str, Any]],
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> CrateGraph:
+    """Crates and module trees of the Rust files in rows (file_symbols rows)."""
+    repo_path = os.path.abspath(repo_path)
+    manifests = await _read_manifests(repo_path, [row["file_path"] for row in rows], reader or async_read_file)
+    return CrateGraph(repo_path, rows, manifests)
+
+@handle_async_errors(error_types=(PostgresError, Neo4jError, DatabaseError), default_return={})
+async def sync_crate_graph(
+    repo_id: int,
+    repo_path: str,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.7.3] Rebuild the repository's Crate and Module nodes when they changed.
+
+    reader supplies Cargo.toml contents (e.g. GitTreeSource.read for a
+    commit); by default they are read from disk.
+    """
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, symbols FROM file_symbols WHERE repo_id = $1 AND language = 'rust';",
+        repo_id
+    )
+    graph = (await load_crate_graph(repo_path, rows, reader)).edges()
+    stats = {"crates": sum(1 for crate in graph["crates"] if not crate["external"]), "modules": len(graph["modules"])}
+
+    digest = hashlib.sha256(json.dumps(graph, sort_keys=True, default=str).encode("utf-8")).hexdigest()
+    repository = await backend.find_nodes("Repository", {"id": repo_id}, limit=1)
+    if repository and repository[0].get("crate_graph_hash") == digest:
+        return stats
+
+    await backend.delete_nodes("Module", {"repo_id": repo_id})
+    await backend.delete_nodes("Crate", {"repo_id": repo_id})
+    for crate in graph["crates"]:
+        await backend.merge_node(
+            "Crate", {"repo_id": repo_id, "name": crate["name"]},
+            {key: value for key, value in crate.items() if value is not None}
+        )
+    for module in graph["modules"]:
+        await backend.merge_node("Module", {"repo_id": repo_id, "crate": module["crate"], "path": module["path"]}, module)
+        if not module["inline"]:
+            await backend.merge_relationship(
+                "Module", {"repo_id": repo_id, "crate": module["crate"], "path": module["path"]},
+                "CONTAINS", "Code", {"repo_id": repo_id, "file_path": module["file_path"]}
+            )
+    for label, parent, crate, path in graph["contains"]:
+        start = (
+            {"repo_id": repo_id, "name": parent} if label == "Crate"
+            else {"repo_id": repo_id, "crate": parent[0], "path": parent[1]}
+        )
+        await backend.merge_relationship(label, start, "CONTAINS", "Module", {"repo_id": repo_id, "crate": crate, "path": path})
+    for edge in graph["depends"]:
+        await backend.merge_relationship(
+            "Crate", {"repo_id": repo_id, "name": edge["source"]},
+            "DEPENDS_ON", "Crate", {"repo_id": repo_id, "name": edge["target"]},
+            {key: value for key, value in edge.items() if key not in ("source", "target") and value is not None}
+        )
+    for edge in graph["uses"]:
+        end = (
+            {"repo_id": repo_id, "name": edge["target"]} if edge["label"] == "Crate"
+            else {"repo_id": repo_id, "crate": edge["target"], "path": edge["path"]}
+        )
+        await backend.merge_relationship(
+            "Module", {"repo_id": repo_id, "crate": edge["source"][0], "path": edge["source"][1]},
+            "DEPENDS_ON", edge["label"], end, {"items": edge["items"]}
+        )
+    await backend.merge_node("Repository", {"id": repo_id}, {"crate_graph_hash": digest})
+    await log(
+        f"Crate graph of repository {repo_id}: {stats['crates']} crate(s), {stats['modules']} module(s)",
+        level="info"
+    )
+    return stats
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def list_crates(repo_id: int) -> List[Dict[str, Any]]:
+    """[1.7.4] Workspace crates with their dependencies."""
+    backend = await get_storage_backend()
+    crates = []
+    for crate in await backend.find_nodes("Crate", {"repo_id": repo_id, "external": False}):
+        edges = await backend.traverse("Crate", {"repo_id": repo_id, "name": crate["name"]}, ["DEPENDS_ON"], direction="out")
+        crates.append({
+            **crate,
+            "dependencies": [
+                {"name": edge["target"].get("name"), "external": edge["target"].get("external", False), **edge["properties"]}
+                for edge in edges
+            ]
+        })
+    return sorted(crates, key=lambda crate: crate["name"])
+
+__all__ = [
+    "CrateGraph",
+    "load_crate_graph",
+    "sync_crate_graph",
+    "list_crates"
+]
@@ -0,0 +1,179 @@
+"""[1.9] Rust API docs.
+
+Flow:
+1. Items:
+   - Doc comments the Rust symbol extractor kept for each item (///, /** */)
+     and for each module (//!, /*! */)
+   - Each doc gets the item's path through the crate graph [1.7]:
+     my_crate::parse::Parser::new
+
+2. Intra-doc Links:
+   - [`Foo`], [`Foo::bar`], [Foo], [text](`crate::a::Foo`) and [`f()`] /
+     [`m!`] / [`struct@Foo`] are resolved by the symbol table [1.6] from the
+     item's scope, and kept with the Symbol id they point to
+
+3. Storage:
+   - One repo_docs row per documented item with doc_type 'docstring' and
+     related_code_path set to its file, so search_docs returns them
+   - A file's rows are replaced only when one of its docs changed
+"""
+
+import re
+import json
+import hashlib
+from typing import Any, Awaitable, Callable, Dict, List, Optional
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from indexer.rust_crates import CrateGraph, load_crate_graph
+from indexer.symbol_table import SymbolTable, symbol_id
+
+# [`Foo`] and [Foo]; not [text](...) or [text][ref]
+_SHORTCUT_LINK = re.compile(r"(?<!\])\[(`?)([^\[\]`]+)\1\](?![(\[])")
+# [text](Foo) and [text](`Foo`)
+_INLINE_LINK = re.compile(r"\[[^\[\]]*\]\(`?([^()`\s]+(?:\(\))?)`?\)")
+_PATH = re.compile(r"^(?:[a-z]+@)?[A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*(?:!|\(\))?$")
+
+def doc_links(doc: str) -> List[str]:
+    """Intra-doc link targets of a doc comment, in order of appearance."""
+    links = []
+    for match in _SHORTCUT_LINK.finditer(doc):
+        links.append((match.start(), match.group(2)))
+    for match in _INLINE_LINK.finditer(doc):
+        links.append((match.start(), match.group(1)))
+    seen, targets = set(), []
+    for _, target in sorted(links):
+        if _PATH.match(target) and target not in seen:
+            seen.add(target)
+            targets.append(target)
+    return targets
+
+def _link_reference(target: str, scope: str) -> Dict[str, Any]:
+    """A link target as a reference the symbol table resolves."""
+    path = target.rpartition("@")[2].rstrip("!")
+    if path.endswith("()"):
+        path = path[:-2]
+    qualifier, _, name = path.rpartition("::")
+    return {"name": name, "qualifier": qualifier or None, "scope": scope, "kind": "type"}
+
+def _item_path(graph: CrateGraph, file_path: str, scope: str, name: Optional[str]) -> str:
+    """Path of an item from its crate: my_crate::a::Type::method."""
+    module = graph.module_for(file_path, scope)
+    inner = scope
+    if module is not None and module.scope:
+        inner = scope[len(module.scope):].lstrip(".")
+    parts = [part for part in inner.split(".") if part] + ([name] if name else [])
+    if module is None:
+        return "::".join(parts)
+    crate = module.target.rpartition("/")[2].replace("-", "_")
+    return "::".join([crate, *module.path, *parts])
+
+def file_docs(graph: CrateGraph, table: SymbolTable, file_path: str) -> List[Dict[str, Any]]:
+    """[1.9.1] Docs of one file's items and modules, with resolved links."""
+    info = table.files.get(file_path)
+    if info is None:
+        return []
+    entries = []
+    for scope, doc in sorted((info.symbols.get("module_docs") or {}).items()):
+        module = graph.module_for(file_path, scope)
+        entries.append({
+            "doc": doc,
+            "kind": "module",
+            "scope": scope,
+            "item_path": _item_path(graph, file_path, scope, None),
+            "symbol": symbol_id(file_path, scope) if scope in info.defs else None,
+            "line": module.line if module is not None and module.inline else 1
+        })
+    for definition in info.symbols.get("definitions", []):
+        if not definition.get("doc"):
+            continue
+        scope = definition.get("scope") or ""
+        qualified = f"{scope}.{definition['name']}" if scope else definition["name"]
+        entries.append({
+            "doc": definition["doc"],
+            "kind": definition["kind"],
+            "scope": scope,
+            "item_path": _item_path(graph, file_path, scope, definition["name"]),
+            "symbol": symbol_id(file_path, qualified),
+            "line": definition["line"]
+        })
+
+    docs = []
+    for entry in entries:
+        # Links in an item's doc resolve from the scope the item is in;
+        # a module's inner doc resolves from inside the module
+        links = []
+        for target in doc_links(entry["doc"]):
+            resolved = table.resolve(info, _link_reference(target, entry["scope"]))
+            links.append({
+                "text": target,
+                "symbol": symbol_id(resolved[0].file, resolved[0].qualified) if resolved is not None else None
+            })
+        metadata = {
+            "language": "rust",
+            "item_path": entry["item_path"],
+            "kind": entry["kind"],
+            "symbol": entry["symbol"],
+            "line": entry["line"],
+            "links": links
+        }
+        content = f"{entry['item_path']}\n\n{entry['doc']}" if entry["item_path"] else entry["doc"]
+        metadata["digest"] = hashlib.sha256(
+            json.dumps([content, metadata], sort_keys=True).encode("utf-8")
+        ).hexdigest()
+        docs.append({"content": content, "metadata": metadata})
+    return docs
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return={})
+async def sync_rust_docs(
+    repo_id: int,
+    repo_path: str,
+    upsert_coordinator,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.9.2] Store the Rust doc comments of files whose docs changed.
+
+    reader supplies Cargo.toml contents, as for sync_crate_graph [1.7.3].
+    """
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, language, symbols FROM file_symbols WHERE repo_id = $1 AND language = 'rust';",
+        repo_id
+    )
+    if not rows:
+        return {"docs": 0, "files": 0}
+    graph = await load_crate_graph(repo_path, rows, reader)
+    table = SymbolTable(repo_path, rows)
+
+    stored: Dict[str, set] = {}
+    for row in await backend.fetch(
+        """
+        SELECT rd.related_code_path, rd.metadata
+        FROM repo_docs rd JOIN repo_doc_relations rdr ON rd.id = rdr.doc_id
+        WHERE rdr.repo_id = $1 AND rd.doc_type = 'docstring';
+        """,
+        repo_id
+    ):
+        metadata = json.loads(row["metadata"]) if isinstance(row["metadata"], str) else (row["metadata"] or {})
+        stored.setdefault(row["related_code_path"], set()).add(metadata.get("digest"))
+
+    stats = {"docs": 0, "files": 0}
+    for file_path in sorted(table.files):
+        docs = file_docs(graph, table, file_path)
+        stats["docs"] += len(docs)
+        if {doc["metadata"]["digest"] for doc in docs} != stored.get(file_path, set()):
+            await upsert_coordinator.replace_docstrings(repo_id, file_path, docs)
+            stats["files"] += 1
+    if stats["files"]:
+        await log(
+            f"Rust docs of repository {repo_id}: {stats['docs']} item doc(s), {stats['files']} file(s) rewritten",
+            level="info"
+        )
+    return stats
+
+__all__ = [
+    "doc_links",
+    "file_docs",
+    "sync_rust_docs"
+]
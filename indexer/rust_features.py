@@ -0,0 +1,328 @@
+"""[1.10] Rust cfg and Cargo feature map.
+
+Flow:
+1. Predicates:
+   - The cfg predicates the Rust symbol extractor records on items, impl
+     blocks and modules (#[cfg(...)], #![cfg(...)]) gate an item together
+     with those of its enclosing items and modules in the crate graph [1.7]
+   - feature = "x" inside all()/any() enables the item for x; under not() it
+     only excludes it
+
+2. Features:
+   - Each crate's [features] table is cross-referenced with the features its
+     code is gated on, counting the items, lines and files behind each one
+   - A feature is dead when no cfg names it and it enables no dependency and
+     no feature that is live; "default" never is
+   - A feature named in a cfg that the crate doesn't declare (and that is no
+     optional dependency) can never be enabled
+
+3. Findings:
+   - Stored in findings with analysis 'rust_features': one 'feature' row per
+     declared feature, one 'gated' row per outermost gated item, and
+     'dead_feature' and 'undeclared_feature' warnings
+   - rust_feature_report() rolls them up per crate and feature
+"""
+
+import os
+import re
+import json
+from collections import defaultdict
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Set, Tuple
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError
+from db.storage import get_storage_backend
+from indexer.rust_crates import CrateGraph, Module, Package, load_crate_graph
+from indexer.rust_audit import list_findings
+
+ANALYSIS = "rust_features"
+
+_TOKEN = re.compile(r'\s*(?:([A-Za-z_][\w:]*)|("(?:[^"\\]|\\.)*")|(.))')
+
+def _tokens(predicate: str) -> List[str]:
+    return [match.group(1) or match.group(2) or match.group(3) for match in _TOKEN.finditer(predicate) if match.group(0).strip()]
+
+def cfg_features(predicate: str) -> Tuple[List[str], List[str]]:
+    """Features a cfg predicate enables an item for, and features it excludes it for."""
+    tokens = _tokens(predicate)
+    enabled: List[str] = []
+    excluded: List[str] = []
+    position = 0
+
+    def expression(negated: bool) -> None:
+        nonlocal position
+        if position >= len(tokens):
+            return
+        name = tokens[position]
+        position += 1
+        if position < len(tokens) and tokens[position] == "=":
+            value = tokens[position + 1] if position + 1 < len(tokens) else '""'
+            position += 2
+            if name == "feature":
+                found = excluded if negated else enabled
+                if value.strip('"') not in found:
+                    found.append(value.strip('"'))
+        elif position < len(tokens) and tokens[position] == "(":
+            position += 1
+            while position < len(tokens) and tokens[position] != ")":
+                if tokens[position] == ",":
+                    position += 1
+                    continue
+                expression(negated != (name == "not"))
+            position += 1
+
+    while position < len(tokens):
+        expression(False)
+    return enabled, excluded
+
+def _package_for(graph: CrateGraph, file_path: str, module: Optional[Module]) -> Optional[Package]:
+    """The module's package, or the package whose directory holds the file (tests/, examples/)."""
+    if module is not None:
+        return graph.package_of(module)
+    candidates = [
+        package for package in graph.packages.values()
+        if file_path.startswith(package.directory + os.sep)
+    ]
+    return max(candidates, key=lambda package: len(package.directory), default=None)
+
+def _impl_name(impl: Dict[str, Any]) -> str:
+    target = impl.get("type_text") or impl.get("type") or "?"
+    return f"impl {impl['trait']} for {target}" if impl.get("trait") else f"impl {target}"
+
+def gated_items(graph: CrateGraph) -> List[Dict[str, Any]]:
+    """[1.10.1] Every Rust item whose cfg predicates name a feature, with its crate and features.
+
+    Items are listed under the features they add to those of the items
+    around them in the same file: the methods of a gated impl count towards
+    the impl, the items of a gated file module each count (the mod
+    declaration itself has no lines).
+    """
+    items = []
+    for file_path in sorted(graph.symbols):
+        symbols = graph.symbols[file_path]
+        module_cfg = symbols.get("module_cfg") or {}
+        definitions = {
+            (f"{definition['scope']}.{definition['name']}" if definition.get("scope") else definition["name"]): definition
+            for definition in symbols.get("definitions", [])
+        }
+        impls = [impl for impl in symbols.get("impls", []) if impl.get("cfg")]
+
+        def own(qualified: str) -> List[str]:
+            return list((definitions.get(qualified) or {}).get("cfg", [])) + list(module_cfg.get(qualified, []))
+
+        def enclosing(scope: str, line: int) -> List[str]:
+            """cfg of the items, inline modules and impl blocks of this file around scope."""
+            predicates: List[str] = []
+            for impl in impls:
+                impl_scope = f"{impl['scope']}.{impl['type']}" if impl.get("scope") else impl["type"]
+                if scope == impl_scope and impl["line"] <= line <= impl["end_line"]:
+                    predicates.extend(impl["cfg"])
+            while scope:
+                predicates = own(scope) + predicates
+                scope = scope.rpartition(".")[0]
+            return predicates
+
+        candidates = [
+            (qualified.replace(".", "::"), definition["kind"], definition.get("scope") or "", definition["line"],
+             definition.get("end_line", definition["line"]), own(qualified), definition)
+            for qualified, definition in definitions.items()
+        ] + [
+            (_impl_name(impl), "impl", impl.get("scope") or "", impl["line"], impl["end_line"], list(impl["cfg"]), impl)
+            for impl in impls
+        ]
+        for symbol, kind, scope, line, end_line, predicates, entry in sorted(candidates, key=lambda candidate: candidate[3]):
+            module = graph.module_for(file_path, scope)
+            # Modules declared elsewhere (mod foo; with a #[cfg]) gate the whole file
+            context: List[str] = [] if module is not None else list(module_cfg.get("", []))
+            chain = module
+            while chain is not None:
+                if not (chain.inline and chain.file_path == file_path):
+                    context = chain.cfg + context
+                chain = chain.parent
+            outer = enclosing(scope, line)
+            enabled: Set[str] = set()
+            excluded: Set[str] = set()
+            for predicate in context + outer + predicates:
+                found = cfg_features(predicate)
+                enabled.update(found[0])
+                excluded.update(found[1])
+            inherited: Set[str] = set()
+            for predicate in outer:
+                found = cfg_features(predicate)
+                inherited.update(found[0] + found[1])
+            if not (enabled | excluded) - inherited:
+                continue
+            package = _package_for(graph, file_path, module)
+            declaration = kind == "module" and not entry.get("inline")
+            items.append({
+                "crate": package.name if package is not None else None,
+                "target": module.target if module is not None else None,
+                "module": module.name if module is not None else None,
+                "file_path": file_path,
+                "line": line,
+                "lines": 0 if declaration else end_line - line + 1,
+                "symbol": symbol,
+                "kind": kind,
+                "features": sorted(enabled - inherited),
+                "all_features": sorted(enabled),
+                "excluded": sorted(excluded),
+                "cfg": list(dict.fromkeys(context + outer + predicates))
+            })
+    return items
+
+def _live_features(package: Package, used: Set[str]) -> Set[str]:
+    optional = {dependency.name for dependency in package.dependencies if dependency.optional}
+    live = {name for name in package.features if name in used}
+    changed = True
+    while changed:
+        changed = False
+        for name, enables in package.features.items():
+            if name in live:
+                continue
+            # dep:x, x/feature and x?/feature turn on a dependency or one of its features
+            if any(
+                value.startswith("dep:") or "/" in value or value in optional or value in live
+                for value in enables
+            ):
+                live.add(name)
+                changed = True
+    return live
+
+@handle_async_errors(error_types=(PostgresError, DatabaseError), default_return={})
+async def run_rust_feature_map(
+    repo_id: int,
+    repo_path: str,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.10.2] Store the repository's feature-gated items and feature findings.
+
+    reader supplies Cargo.toml contents, as for sync_crate_graph [1.7.3].
+    """
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, symbols FROM file_symbols WHERE repo_id = $1 AND language = 'rust';",
+        repo_id
+    )
+    graph = await load_crate_graph(repo_path, rows, reader)
+    items = gated_items(graph)
+
+    used: Dict[Optional[str], Dict[str, List[Dict[str, Any]]]] = defaultdict(lambda: defaultdict(list))
+    for item in items:
+        for feature in item["all_features"] + item["excluded"]:
+            used[item["crate"]][feature].append(item)
+
+    findings = []
+    for item in items:
+        findings.append((
+            repo_id, ANALYSIS, "gated", "info", item["file_path"], item["line"], item["symbol"],
+            f"{item['kind']} gated by cfg({', '.join(item['cfg'])})",
+            json.dumps({key: item[key] for key in (
+                "crate", "target", "module", "kind", "lines", "features", "all_features", "excluded", "cfg"
+            )})
+        ))
+    for package in sorted(graph.packages.values(), key=lambda package: package.name):
+        live = _live_features(package, set(used[package.name]))
+        optional = {dependency.name for dependency in package.dependencies if dependency.optional}
+        for name, enables in sorted(package.features.items()):
+            details = json.dumps({"crate": package.name, "enables": enables, "default": name in package.features.get("default", [])})
+            findings.append((repo_id, ANALYSIS, "feature", "info", package.manifest, None, name, f"feature {name}", details))
+            if name != "default" and name not in live:
+                findings.append((
+                    repo_id, ANALYSIS, "dead_feature", "warning", package.manifest, None, name,
+                    f"feature {name} gates no code and enables no dependency", details
+                ))
+        for name, sites in sorted(used[package.name].items()):
+            if name in package.features or name in optional:
+                continue
+            findings.append((
+                repo_id, ANALYSIS, "undeclared_feature", "warning", sites[0]["file_path"], sites[0]["line"], name,
+                f"cfg names feature {name}, which {package.name} doesn't declare",
+                json.dumps({"crate": package.name, "sites": len(sites)})
+            ))
+
+    await backend.execute_batch(
+        [("DELETE FROM findings WHERE repo_id = $1 AND analysis = $2;", (repo_id, ANALYSIS))] + [
+            (
+                """
+                INSERT INTO findings (repo_id, analysis, kind, severity, file_path, line, symbol, message, details)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+                """,
+                finding
+            )
+            for finding in findings
+        ]
+    )
+    stats = {
+        "gated": len(items),
+        "features": sum(len(package.features) for package in graph.packages.values()),
+        "dead": sum(1 for finding in findings if finding[2] == "dead_feature"),
+        "undeclared": sum(1 for finding in findings if finding[2] == "undeclared_feature")
+    }
+    if items or stats["features"]:
+        await log(
+            f"Rust feature map of repository {repo_id}: {stats['features']} feature(s), "
+            f"{stats['gated']} gated item(s), {stats['dead']} dead, {stats['undeclared']} undeclared",
+            level="info"
+        )
+    return stats
+
+async def rust_feature_report(repo_id: int) -> Dict[str, Any]:
+    """[1.10.3] Code size behind each Cargo feature, with dead and undeclared features, per crate."""
+    findings = await list_findings(repo_id, ANALYSIS) or []
+    crates: Dict[Optional[str], Dict[str, Dict[str, Any]]] = defaultdict(dict)
+
+    def entry(crate: Optional[str], name: str) -> Dict[str, Any]:
+        return crates[crate].setdefault(name, {
+            "feature": name,
+            "declared": False,
+            "default": False,
+            "enables": [],
+            "dead": False,
+            "undeclared": False,
+            "items": 0,
+            "lines": 0,
+            "files": set(),
+            "modules": set()
+        })
+
+    for row in findings:
+        details = row["details"] or {}
+        crate = details.get("crate")
+        if row["kind"] == "feature":
+            feature = entry(crate, row["symbol"])
+            feature.update(declared=True, default=details.get("default", False), enables=details.get("enables", []))
+        elif row["kind"] == "dead_feature":
+            entry(crate, row["symbol"])["dead"] = True
+        elif row["kind"] == "undeclared_feature":
+            entry(crate, row["symbol"])["undeclared"] = True
+        elif row["kind"] == "gated":
+            for name in details.get("features", []):
+                feature = entry(crate, name)
+                feature["items"] += 1
+                feature["lines"] += details.get("lines", 0)
+                feature["files"].add(row["file_path"])
+                if details.get("kind") == "module":
+                    feature["modules"].add(row["symbol"])
+
+    report = []
+    for crate, features in sorted(crates.items(), key=lambda item: item[0] or ""):
+        rows = [
+            {**feature, "files": len(feature["files"]), "modules": sorted(feature["modules"])}
+            for feature in sorted(features.values(), key=lambda feature: (-feature["lines"], feature["feature"]))
+        ]
+        report.append({
+            # None for gated files outside every package
+            "crate": crate,
+            "features": rows,
+            "dead": [row["feature"] for row in rows if row["dead"]],
+            "undeclared": [row["feature"] for row in rows if row["undeclared"]]
+        })
+    return {"repo_id": repo_id, "crates": report}
+
+__all__ = [
+    "ANALYSIS",
+    "cfg_features",
+    "gated_items",
+    "run_rust_feature_map",
+    "rust_feature_report"
+]
@@ -0,0 +1,1254 @@
+"""[1.6] Cross-file symbol table.
+
+Flow:
+1. Load:
+   - Definitions, references and imports extracted at parse time
+     (parsers/symbol_extractor.py) are read back from file_symbols
+   - Languages are resolved within their family: Python, JavaScript and
+     TypeScript, Go, Rust
+
+2. Resolve:
+   - Imports are bound to modules: dotted module paths (Python),
+     Node/TypeScript resolution [1.13] (JavaScript/TypeScript), package
+     directories (Go) and the crate module tree (Rust)
+   - A reference resolves through its enclosing scopes, then the file's
+     imports, its package, wildcard imports, and finally a definition that
+     is the only one of that name
+   - Member references (self.x, pkg.Fn, Type::new) resolve against the
+     class, module or type they are qualified with
+
+3. Graph:
+   - Each definition is a Symbol node keyed by (repo_id, id) with id
+     "<file_path>#<qualified name>"
+   - DEFINES edges run from the Code node (or the enclosing Symbol) to each
+     definition; REFERENCES edges run from the innermost enclosing Symbol
+     (or the Code node) to the definition used, with the lines it is used on
+   - Calls that resolve to a function, method, class or macro also get a
+     CALLS edge; the names of calls that don't resolve (builtins, external
+     packages, dynamic dispatch) are kept on the caller as unresolved_calls
+   - Rust impl blocks add IMPLEMENTS edges from the type to the trait (or
+     from the Code node for blanket impls) and HAS_METHOD edges from the
+     type to the methods of the block; traits from outside the repository
+     are external Symbol nodes with id "extern#<path>"
+   - Rust tests (#[test], #[tokio::test] and similar functions: unit tests,
+     integration tests under tests/, benches) and doctests get TESTS edges
+     to the items they call, directly or through test-only helpers of the
+     same file
+   - Python imports resolve to files (relative imports, package
+     __init__.py, src/ layouts, namespace packages), as do JavaScript and
+     TypeScript imports; Code IMPORTS Code with the imported names, and
+     imports that stay outside the repository are kept on the Code node as
+     stdlib_imports (Node built-ins for JavaScript) or third_party_imports
+   - Each file's share of the graph is hashed; only files whose nodes or
+     edges changed are rewritten
+"""
+
+import os
+import re
+import sys
+import json
+import hashlib
+from collections import defaultdict
+from dataclasses import dataclass, field
+from typing import Any, Awaitable, Callable, Dict, List, Optional, Set, Tuple
+
+from utils.logger import log
+from utils.error_handling import handle_async_errors, DatabaseError, PostgresError, Neo4jError
+from db.storage import get_storage_backend
+from parsers.symbol_extractor import CLASS_SCOPES
+from indexer.js_modules import JsResolver, is_builtin, load_js_resolver
+
+# Language families resolved together
+LANGUAGE_FAMILIES = {
+    "python": "python",
+    "javascript": "js",
+    "jsx": "js",
+    "typescript": "js",
+    "tsx": "js",
+    "go": "go",
+    "rust": "rust"
+}
+
+# Qualifiers that refer to the enclosing class or type
+_SELF_NAMES = {"self", "this", "cls", "Self"}
+
+# How many re-exports and aliases are followed
+_MAX_DEPTH = 4
+
+# Definitions a call expression can invoke (a class call constructs it)
+CALLABLE_KINDS = {"function", "method", "class", "macro"}
+
+# Unresolved call names kept per caller
+_MAX_UNRESOLVED = 50
+
+# Definitions an impl block can be for
+_IMPL_TYPE_KINDS = {"struct", "enum", "type"}
+
+EXTERNAL_PREFIX = "extern#"
+
+# Top-level modules of the standard library, for classifying Python imports
+_STDLIB_MODULES = frozenset(sys.stdlib_module_names)
+
+# Directories whose Rust files are integration tests and benchmarks
+_RUST_TEST_DIRECTORIES = {"tests": "integration", "benches": "bench"}
+
+# Doc comment code blocks that rustdoc runs; ignore and other languages are skipped
+_CODE_FENCE = re.compile(r"^[ \t]*```([^\n`]*)\n(.*?)^[ \t]*```", re.MULTILINE | re.DOTALL)
+_DOCTEST_ATTRIBUTES = {"", "rust", "should_panic", "no_run", "compile_fail", "edition2015", "edition2018", "edition2021", "edition2024"}
+_DOCTEST_USE = re.compile(r"\buse\s+([A-Za-z_][\w:]*?)(?:::\{([^}]*)\})?\s*;")
+_DOCTEST_CALL = re.compile(r"(?<![\w.:])((?:[A-Za-z_]\w*::)*[A-Za-z_]\w*)\s*\(")
+
+@dataclass
+class _Module:
+    """A module: (file, scope) pairs whose top-level definitions it holds."""
+    parts: List[Tuple[str, str]]
+    key: Tuple = ()
+
+@dataclass
+class _Target:
+    file: Optional[str] = None
+    qualified: Optional[str] = None
+    module: Optional[_Module] = None
+
+    @property
+    def is_def(self) -> bool:
+        return self.qualified is not None
+
+@dataclass
+class _FileInfo:
+    path: str
+    family: str
+    symbols: Dict[str, Any]
+    graph_hash: Optional[str] = None
+    defs: Dict[str, Dict[str, Any]] = field(default_factory=dict)
+    bindings: Dict[str, Dict[str, Any]] = field(default_factory=dict)
+    wildcards: List[Dict[str, Any]] = field(default_factory=list)
+
+def _qualified(definition: Dict[str, Any]) -> str:
+    scope = definition.get("scope") or ""
+    return f"{scope}.{definition['name']}" if scope else definition["name"]
+
+def _parent_scopes(scope: str) -> List[str]:
+    """scope and each enclosing scope, innermost first, ending with the module ("")."""
+    chain = []
+    while scope:
+        chain.append(scope)
+        scope = scope.rpartition(".")[0]
+    chain.append("")
+    return chain
+
+def symbol_id(file_path: str, qualified: str) -> str:
+    return f"{file_path}#{qualified}"
+
+class SymbolTable:
+    """Definitions of one repository, indexed for name resolution."""
+
+    def __init__(self, repo_path: str, rows: List[Dict[str, Any]], js_resolver: Optional[JsResolver] = None):
+        self.repo_path = os.path.abspath(repo_path)
+        # Without tsconfig and package.json data only relative specifiers resolve
+        self.js_resolver = js_resolver or JsResolver(self.repo_path, {}, [])
+        self.files: Dict[str, _FileInfo] = {}
+        # (file, scope) -> name -> qualified name
+        self.members: Dict[Tuple[str, str], Dict[str, str]] = defaultdict(dict)
+        # (group, type name) -> member name -> [(file, qualified)], for Go and Rust
+        # where methods of a type can live in any file of the package or crate
+        self.type_members: Dict[Tuple[str, str], Dict[str, List[Tuple[str, str]]]] = defaultdict(lambda: defaultdict(list))
+        # family -> name -> [(file, qualified)]
+        self.by_name: Dict[str, Dict[str, List[Tuple[str, str]]]] = defaultdict(lambda: defaultdict(list))
+        self.python_modules: Dict[str, List[str]] = defaultdict(list)
+        # Directories holding Python files at any depth: packages, namespace packages and roots
+        self.python_dirs: Set[str] = set()
+        self.go_packages: Dict[str, List[str]] = defaultdict(list)
+        self.rust_modules: Dict[Tuple[str, Tuple[str, ...]], str] = {}
+        self._rust_crates: Dict[str, Optional[str]] = {}
+        self._rust_libraries: Optional[Dict[str, str]] = None
+
+        for row in rows:
+            symbols = row["symbols"]
+            if isinstance(symbols, str):
+                symbols = json.loads(symbols)
+            family = LANGUAGE_FAMILIES.get(row["language"])
+            if family is None or not symbols:
+                continue
+            self.files[row["file_path"]] = _FileInfo(row["file_path"], family, symbols, row.get("graph_hash"))
+
+        for info in self.files.values():
+            self._index(info)
+
+    # Indexing ----------------------------------------------------------
+
+    def _index(self, info: _FileInfo) -> None:
+        scopes = info.symbols.get("scopes", {})
+        group = self._group(info)
+        for definition in info.symbols.get("definitions", []):
+            qualified = _qualified(definition)
+            info.defs.setdefault(qualified, definition)
+            scope = definition.get("scope") or ""
+            self.members[(info.path, scope)].setdefault(definition["name"], qualified)
+            if scope and scopes.get(scope) in CLASS_SCOPES:
+                self.type_members[(group, scope.rpartition(".")[2])][definition["name"]].append((info.path, qualified))
+            self.by_name[info.family][definition["name"]].append((info.path, qualified))
+
+        for entry in info.symbols.get("imports", []):
+            if entry.get("name") == "*":
+                info.wildcards.append(entry)
+            elif entry.get("alias"):
+                info.bindings.setdefault(entry["alias"], entry)
+
+        relative = os.path.relpath(info.path, self.repo_path)
+        if info.family == "python":
+            stem = relative[:-3] if relative.endswith(".py") else os.path.splitext(relative)[0]
+            parts = stem.split(os.sep)
+            if parts[-1] == "__init__":
+                parts = parts[:-1]
+            # Every suffix, so src/ and other layout roots still resolve
+            for start in range(len(parts)):
+                self.python_modules[".".join(parts[start:])].append(info.path)
+            directory = os.path.dirname(info.path)
+            while directory.startswith(self.repo_path) and directory not in self.python_dirs:
+                self.python_dirs.add(directory)
+                directory = os.path.dirname(directory)
+        elif info.family == "go":
+            self.go_packages[os.path.dirname(info.path)].append(info.path)
+        elif info.family == "rust":
+            crate = self._rust_crate(info.path)
+            if crate is not None:
+                self.rust_modules[(crate, self._rust_module_path(info.path, crate))] = info.path
+
+    def _group(self, info: _FileInfo) -> str:
+        """Where a type's members can be defined: the package (Go), crate (Rust) or file."""
+        if info.family == "go":
+            return os.path.dirname(info.path)
+        if info.family == "rust":
+            return self._rust_crate(info.path) or info.path
+        return info.path
+
+    def _rust_crate(self, file_path: str) -> Optional[str]:
+        """Source root of a file's crate: the nearest directory with lib.rs or main.rs."""
+        directory = os.path.dirname(file_path)
+        if directory in self._rust_crates:
+            return self._rust_crates[directory]
+        crate, current = None, directory
+        while True:
+            if any(os.path.join(current, root) in self.files for root in ("lib.rs", "main.rs")):
+                crate = current
+                break
+            parent = os.path.dirname(current)
+            if parent == current or not current.startswith(self.repo_path):
+                break
+            current = parent
+        self._rust_crates[directory] = crate
+        return crate
+
+    def _rust_library(self, name: str) -> Optional[_Module]:
+        """Root module of the library crate a path names: my_crate for crates/my-crate/src/lib.rs."""
+        if self._rust_libraries is None:
+            self._rust_libraries = {}
+            for file_path in self.files:
+                if os.path.basename(file_path) == "lib.rs":
+                    root = os.path.dirname(file_path)
+                    # The package directory's name stands in for the name in Cargo.toml
+                    package = os.path.dirname(root) if os.path.basename(root) == "src" else root
+                    self._rust_libraries.setdefault(os.path.basename(package).replace("-", "_"), root)
+        root = self._rust_libraries.get(name)
+        return self._rust_module(root, ()) if root is not None else None
+
+    @staticmethod
+    def _rust_module_path(file_path: str, crate: str) -> Tuple[str, ...]:
+        parts = os.path.splitext(os.path.relpath(file_path, crate))[0].split(os.sep)
+        if parts[-1] == "mod" or (len(parts) == 1 and parts[0] in ("lib", "main")):
+            parts = parts[:-1]
+        return tuple(parts)
+
+    # Modules -----------------------------------------------------------
+
+    def _python_under(self, root: str, parts: List[str]) -> Optional[str]:
+        """A module below root: package/__init__.py, module.py, or a namespace package's directory."""
+        target = os.path.join(root, *parts)
+        for candidate in [os.path.join(target, "__init__.py")] + ([target + ".py"] if parts else []):
+            if candidate in self.files:
+                return candidate
+        return target if parts and target in self.python_dirs else None
+
+    def _python_roots(self, info: _FileInfo) -> List[str]:
+        """Where absolute imports are looked up: the repository, src/ layouts, then the file's own project."""
+        roots = [self.repo_path] + sorted(
+            directory for directory in self.python_dirs if os.path.basename(directory) == "src"
+        )
+        # Ancestors that aren't regular packages can be sys.path entries (scripts, nested projects)
+        directory = os.path.dirname(info.path)
+        while directory.startswith(self.repo_path) and directory != self.repo_path:
+            if os.path.join(directory, "__init__.py") not in self.files and directory not in roots:
+                roots.append(directory)
+            directory = os.path.dirname(directory)
+        return roots
+
+    def python_import(self, info: _FileInfo, module: str) -> Tuple[str, Optional[str]]:
+        """Where a Python import lands.
+
+        ("local", file or namespace package directory), ("local", None) for
+        a relative import of a missing module, ("stdlib", None) or
+        ("third_party", None).
+        """
+        if module.startswith("."):
+            dots = len(module) - len(module.lstrip("."))
+            base = os.path.dirname(info.path)
+            for _ in range(dots - 1):
+                base = os.path.dirname(base)
+            rest = module[dots:]
+            target = self._python_under(base, rest.split(".") if rest else [])
+            # from . import x in a directory without __init__.py
+            if target is None and not rest:
+                target = base
+            return "local", target
+
+        parts = module.split(".")
+        roots = self._python_roots(info)
+        # The repository and src/ roots shadow the standard library; a script's directory only does for its own files
+        for index, root in enumerate(roots):
+            if index and os.path.basename(root) != "src" and parts[0] in _STDLIB_MODULES:
+                return "stdlib", None
+            target = self._python_under(root, parts)
+            if target is not None:
+                return "local", target
+        if parts[0] in _STDLIB_MODULES:
+            return "stdlib", None
+        candidates = self.python_modules.get(module, [])
+        if len(candidates) == 1:
+            return "local", candidates[0]
+        return "third_party", None
+
+    def _python_as_module(self, target: str) -> _Module:
+        # A namespace package defines nothing itself; its submodules resolve through _lookup
+        parts = [(target, "")] if target in self.files else []
+        return _Module(parts, ("python", os.path.relpath(target, self.repo_path)))
+
+    def _python_module(self, info: _FileInfo, module: str) -> Optional[_Module]:
+        _, target = self.python_import(info, module)
+        return self._python_as_module(target) if target is not None else None
+
+    def _js_module(self, info: _FileInfo, spec: str) -> Optional[_Module]:
+        target = self.js_resolver.resolve(info.path, spec, self.files)
+        return _Module([(target, "")], ("js", target)) if target is not None else None
+
+    def _go_module(self, import_path: str) -> Optional[_Module]:
+        """The repository directory an import path ends with, longest match first."""
+        best = None
+        for directory in self.go_packages:
+            relative = os.path.relpath(directory, self.repo_path).replace(os.sep, "/")
+            if relative != "." and (import_path == relative or import_path.endswith("/" + relative)):
+                if best is None or len(relative) > len(best[1]):
+                    best = (directory, relative)
+        if best is None:
+            return None
+        files = [path for path in self.go_packages[best[0]] if not path.endswith("_test.go")]
+        return _Module([(path, "") for path in files], ("go", best[0]))
+
+    def _rust_module(self, crate: str, parts: Tuple[str, ...]) -> Optional[_Module]:
+        """A crate module by path: a module file, or an inline mod in a parent file."""
+        if (crate, parts) in self.rust_modules:
+            return _Module([(self.rust_modules[(crate, parts)], "")], ("rust", crate, parts))
+        for split in range(len(parts) - 1, -1, -1):
+            parent = self.rust_modules.get((crate, parts[:split]))
+            if parent is None:
+                continue
+            scope = ".".join(parts[split:])
+            definition = self.files[parent].defs.get(scope)
+            if definition is not None and definition["kind"] == "module":
+                return _Module([(parent, scope)], ("rust", crate, parts))
+            return None
+        return None
+
+    def _module_of_def(self, file_path: str, qualified: str) -> Optional[_Module]:
+        """A module-kind definition as a namespace (Rust mod, TypeScript namespace)."""
+        info = self.files[file_path]
+        parts = [(file_path, qualified)]
+        if info.family == "rust":
+            crate = self._rust_crate(file_path)
+            if crate is not None:
+                path = self._rust_module_path(file_path, crate) + tuple(qualified.split("."))
+                module_file = self.rust_modules.get((crate, path))
+                if module_file is not None:
+                    parts.append((module_file, ""))
+                return _Module(parts, ("rust", crate, path))
+        return _Module(parts, ("def", file_path, qualified))
+
+    def _rust_current_module(self, info: _FileInfo, scope: str) -> Optional[Tuple[str, Tuple[str, ...]]]:
+        crate = self._rust_crate(info.path)
+        if crate is None:
+            return None
+        parts = list(self._rust_module_path(info.path, crate))
+        scopes = info.symbols.get("scopes", {})
+        prefix = []
+        for segment in scope.split(".") if scope else []:
+            prefix.append(segment)
+            if scopes.get(".".join(prefix)) != "module":
+                break
+            parts.append(segment)
+        return crate, tuple(parts)
+
+    def _resolve_module(self, info: _FileInfo, entry: Dict[str, Any], depth: int) -> Optional[_Module]:
+        module = entry.get("module")
+        if not module:
+            return None
+        if info.family == "python":
+            return self._python_module(info, module)
+        if info.family == "js":
+            return self._js_module(info, module)
+        if info.family == "go":
+            return self._go_module(module)
+        target = self._rust_path(info, "", module.split("::"), depth + 1)
+        return target.module if target is not None else None
+
+    # Lookup ------------------------------------------------------------
+
+    def _lookup(self, module: _Module, name: str, depth: int) -> Optional[_Target]:
+        """A name defined in, or re-exported by, a module."""
+        for file_path, scope in module.parts:
+            qualified = self.members.get((file_path, scope), {}).get(name)
+            if qualified is not None:
+                return self._as_target(file_path, qualified)
+
+        family = module.key[0] if module.key else None
+        if family == "python":
+            path = os.path.join(self.repo_path, module.key[1])
+            if path.endswith(".py"):
+                # Only a package's __init__.py has submodules
+                path = os.path.dirname(path) if os.path.basename(path) == "__init__.py" else None
+            found = self._python_under(path, [name]) if path is not None else None
+            if found is not None:
+                return _Target(module=self._python_as_module(found))
+        elif family == "rust":
+            found = self._rust_module(module.key[1], tuple(module.key[2]) + (name,))
+            if found is not None:
+                return _Target(module=found)
+
+        if depth >= _MAX_DEPTH:
+            return None
+        # Re-exports: from .x import name in __init__.py, export { name } from, pub use
+        for file_path, scope in module.parts:
+            if scope:
+                continue
+            info = self.files[file_path]
+            entry = info.bindings.get(name)
+            if entry is not None and entry.get("module") is not None:
+                target = self._resolve_binding(info, entry, depth + 1)
+                if target is not None:
+                    return target
+            for wildcard in info.wildcards:
+                inner = self._resolve_module(info, wildcard, depth + 1)
+                if inner is not None:
+                    target = self._lookup(inner, name, depth + 1)
+                    if target is not None:
+                        return target
+        return None
+
+    def _as_target(self, file_path: str, qualified: str) -> _Target:
+        definition = self.files[file_path].defs.get(qualified)
+        module = self._module_of_def(file_path, qualified) if definition and definition["kind"] == "module" else None
+        return _Target(file_path, qualified, module)
+
+    def _members_of(self, target: _Target, name: str) -> Optional[_Target]:
+        """A member of a class or type, wherever its methods are defined."""
+        if target.module is not None:
+            found = self._lookup(target.module, name, 0)
+            if found is not None or not target.is_def:
+                return found
+        if not target.is_def:
+            return None
+        qualified = self.members.get((target.file, target.qualified), {}).get(name)
+        if qualified is not None:
+            return self._as_target(target.file, qualified)
+        info = self.files[target.file]
+        if info.family in ("go", "rust"):
+            type_name = target.qualified.rpartition(".")[2]
+            found = self.type_members.get((self._group(info), type_name), {}).get(name)
+            if found:
+                return self._as_target(*found[0])
+        return None
+
+    def _resolve_binding(self, info: _FileInfo, entry: Dict[str, Any], depth: int) -> Optional[_Target]:
+        if depth > _MAX_DEPTH:
+            return None
+        module = self._resolve_module(info, entry, depth)
+        if module is None:
+            return None
+        name = entry.get("name")
+        if name is None:
+            # import a.b binds a; the path is resolved member by member
+            return _Target(module=module)
+        if name == "default" and info.family == "js":
+            for file_path, _ in module.parts:
+                for qualified, definition in self.files[file_path].defs.items():
+                    if definition.get("default"):
+                        return _Target(file_path, qualified)
+            return None
+        return self._lookup(module, name, depth)
+
+    def _rust_path(self, info: _FileInfo, scope: str, segments: List[str], depth: int) -> Optional[_Target]:
+        """Resolve a Rust path (crate::a::B, self::x, super::y, alias::z, Type::f)."""
+        current = self._rust_current_module(info, scope)
+        if not segments or depth > _MAX_DEPTH:
+            return None
+        head = segments[0]
+        if current is not None and head in ("crate", "self", "super"):
+            crate, module_parts = current
+            parts = [] if head == "crate" else list(module_parts)
+            index = 1 if head == "crate" else 0
+            while index < len(segments) and segments[index] in ("self", "super"):
+                if segments[index] == "super" and parts:
+                    parts.pop()
+                index += 1
+            module = self._rust_module(crate, tuple(parts))
+            target = _Target(module=module) if module is not None else None
+            rest = segments[index:]
+        else:
+            rest = segments[1:]
+            target = self._resolve_name(info, scope, head, depth)
+            if target is None and current is not None:
+                # 2015-style paths relative to the crate root
+                root = self._rust_module(current[0], ())
+                target = self._lookup(root, head, depth) if root is not None else None
+            if target is None:
+                # Integration tests, benches and doctests use the library by its crate name
+                library = self._rust_library(head)
+                target = _Target(module=library) if library is not None else None
+        for segment in rest:
+            if target is None or (target.module is None and not target.is_def):
+                return None
+            target = self._members_of(target, segment)
+        return target
+
+    # Resolution ----------------------------------------------------------
+
+    def _resolve_name(self, info: _FileInfo, scope: str, name: str, depth: int = 0) -> Optional[_Target]:
+        """An unqualified name: enclosing scopes, imports, the package, wildcards."""
+        scopes = info.symbols.get("scopes", {})
+        for candidate in _parent_scopes(scope):
+            # Class bodies are only visible from the class body itself
+            if candidate and candidate != scope and scopes.get(candidate) in CLASS_SCOPES:
+                continue
+            qualified = self.members.get((info.path, candidate), {}).get(name)
+            if qualified is not None:
+                return self._as_target(info.path, qualified)
+
+        entry = info.bindings.get(name)
+        if entry is not None:
+            target = self._resolve_binding(info, entry, depth + 1)
+            if target is not None:
+                return target
+
+        if info.family == "go":
+            package = info.symbols.get("package")
+            for other in self.go_packages.get(os.path.dirname(info.path), []):
+                if other != info.path and self.files[other].symbols.get("package") == package:
+                    qualified = self.members.get((other, ""), {}).get(name)
+                    if qualified is not None:
+                        return self._as_target(other, qualified)
+
+        for wildcard in info.wildcards:
+            module = self._resolve_module(info, wildcard, depth + 1)
+            if module is not None:
+                target = self._lookup(module, name, depth + 1)
+                if target is not None:
+                    return target
+        return None
+
+    def _enclosing_type(self, info: _FileInfo, scope: str) -> Optional[_Target]:
+        """The class, impl or receiver type a member reference inside scope belongs to."""
+        scopes = info.symbols.get("scopes", {})
+        for candidate in _parent_scopes(scope):
+            if candidate and scopes.get(candidate) in CLASS_SCOPES:
+                if candidate in info.defs:
+                    return self._as_target(info.path, candidate)
+                # impl Foo / func (f *Foo) in another file than Foo
+                found = self._resolve_name(info, candidate.rpartition(".")[0], candidate.rpartition(".")[2])
+                if found is not None:
+                    return found
+                return _Target(info.path, candidate)
+        return None
+
+    def resolve(self, info: _FileInfo, reference: Dict[str, Any]) -> Optional[Tuple[_Target, str]]:
+        """[1.6.1] The definition a reference uses, and how it was found."""
+        name, scope = reference["name"], reference.get("scope") or ""
+        qualifier = reference.get("qualifier")
+
+        if qualifier is None:
+            target = self._resolve_name(info, scope, name)
+            if target is not None and target.is_def:
+                return target, "scope"
+        else:
+            path = info.family == "rust" and "::" in qualifier
+            segments = qualifier.split("::" if path else ".")
+            receiver = self._receiver(info, scope)
+            target = None
+            if not path and (segments[0] in _SELF_NAMES or segments[0] == receiver):
+                target = self._enclosing_type(info, scope)
+                for segment in segments[1:]:
+                    target = self._members_of(target, segment) if target is not None else None
+            if target is None and info.family == "rust":
+                # self::f is a module path, self.f a method
+                target = self._rust_path(info, scope, segments, 0)
+            elif target is None:
+                target = self._resolve_name(info, scope, segments[0])
+                if target is None and info.family == "python" and segments[0] in info.bindings:
+                    # import a.b.c binds a; try the longest module prefix
+                    for split in range(len(segments), 1, -1):
+                        module = self._python_module(info, ".".join(segments[:split]))
+                        if module is not None:
+                            target, segments = _Target(module=module), segments[split - 1:]
+                            break
+                for segment in segments[1:]:
+                    target = self._members_of(target, segment) if target is not None else None
+            if target is not None:
+                member = self._members_of(target, name)
+                if member is not None and member.is_def:
+                    return member, "member"
+            # pkg.Fn, std::fmt::Debug: an import or path the repository doesn't define
+            if path or segments[0] in info.bindings:
+                return None
+
+        # Last resort: the only definition of that name in the language family,
+        # top-level for plain names and a member for obj.name
+        if reference.get("kind") in ("call", "type", "macro"):
+            candidates = []
+            for file_path, qualified in self.by_name[info.family].get(name, []):
+                definition = self.files[file_path].defs[qualified]
+                if qualifier is None and not definition.get("scope") and (definition.get("exported") or file_path == info.path):
+                    candidates.append((file_path, qualified))
+                elif qualifier is not None and definition.get("scope"):
+                    candidates.append((file_path, qualified))
+            if len(candidates) == 1:
+                return self._as_target(*candidates[0]), "name"
+        return None
+
+    def _receiver(self, info: _FileInfo, scope: str) -> Optional[str]:
+        """Go receiver variable of the method a scope is in."""
+        if info.family != "go":
+            return None
+        for candidate in _parent_scopes(scope):
+            definition = info.defs.get(candidate)
+            if definition is not None and definition.get("receiver"):
+                return definition["receiver"]
+        return None
+
+    # Graph ---------------------------------------------------------------
+
+    def _test_directory(self, info: _FileInfo) -> Optional[str]:
+        """integration or bench for Rust files under tests/ or benches/."""
+        if info.family != "rust":
+            return None
+        parts = os.path.relpath(info.path, self.repo_path).split(os.sep)[:-1]
+        return next((_RUST_TEST_DIRECTORIES[part] for part in reversed(parts) if part in _RUST_TEST_DIRECTORIES), None)
+
+    def _test_only(self, info: _FileInfo, qualified: str) -> bool:
+        """Whether a definition only exists for tests: in a #[cfg(test)] module or a tests/ file."""
+        if self._test_directory(info) is not None:
+            return True
+        scope = qualified
+        while scope:
+            if info.defs.get(scope, {}).get("cfg_test"):
+                return True
+            scope = scope.rpartition(".")[0]
+        return False
+
+    def _test_kind(self, info: _FileInfo, definition: Dict[str, Any]) -> Optional[str]:
+        """unit, integration or bench for a Rust test function, else None."""
+        attribute = definition.get("test")
+        if info.family != "rust" or definition.get("kind") != "function" or not attribute:
+            return None
+        if attribute.rpartition("::")[2] == "bench":
+            return "bench"
+        return self._test_directory(info) or "unit"
+
+    def _impl_target(self, info: _FileInfo, impl: Dict[str, Any], part: str, kinds: Set[str]) -> Optional[str]:
+        """Symbol id of an impl block's type or trait, if the repository defines it."""
+        if not impl.get(part):
+            return None
+        resolved = self.resolve(info, {
+            "name": impl[part],
+            "qualifier": impl.get(f"{part}_path"),
+            "scope": impl.get("scope") or "",
+            "kind": "type"
+        })
+        if resolved is None:
+            return None
+        target = resolved[0]
+        if (self.files[target.file].defs.get(target.qualified) or {}).get("kind") not in kinds:
+            return None
+        return symbol_id(target.file, target.qualified)
+
+    def _file_tests(
+        self,
+        info: _FileInfo,
+        calls: Dict[Tuple[Optional[str], str], Set[int]],
+        nodes: List[Dict[str, Any]]
+    ) -> List[Dict[str, Any]]:
+        """TESTS edges of a file's tests and doctests; doctest nodes are added to nodes."""
+        if info.family != "rust":
+            return []
+        callees: Dict[str, Dict[str, Set[int]]] = defaultdict(dict)
+        for (source, target), lines in calls.items():
+            if source is not None:
+                callees[source][target] = lines
+        prefix = f"{info.path}#"
+        helpers = {
+            symbol_id(info.path, qualified) for qualified, definition in info.defs.items()
+            if self._test_only(info, qualified) and not self._test_kind(info, definition)
+        }
+
+        edges: Dict[Tuple[str, str], Dict[str, Any]] = {}
+        for node in nodes:
+            if not node["test"]:
+                continue
+            # Follow calls through test-only helpers of this file to the code under test
+            queue, seen = [(node["id"], None)], {node["id"]}
+            while queue:
+                current, via = queue.pop(0)
+                for target, lines in sorted(callees.get(current, {}).items()):
+                    if target in helpers:
+                        if target not in seen:
+                            seen.add(target)
+                            queue.append((target, via or target[len(prefix):]))
+                        continue
+                    if target.startswith(prefix) and self._test_only(info, target[len(prefix):]):
+                        continue
+                    edge = edges.setdefault((node["id"], target), {"kind": node["test"], "via": via, "lines": set()})
+                    if via is None:
+                        edge["lines"].update(lines)
+
+        # Doctests run the code blocks of a doc comment against the crate
+        documented = [
+            (qualified, definition.get("doc"), definition.get("scope") or "", definition["line"])
+            for qualified, definition in info.defs.items()
+        ] + [
+            (scope if scope in info.defs else None, doc, scope, info.defs[scope]["line"] if scope in info.defs else 1)
+            for scope, doc in (info.symbols.get("module_docs") or {}).items()
+        ]
+        numbers: Dict[Optional[str], int] = defaultdict(int)
+        for qualified, doc, scope, line in documented:
+            for code in doctests(doc or ""):
+                # A module's outer and inner docs number their doctests together
+                numbers[qualified] += 1
+                index = numbers[qualified]
+                owner = symbol_id(info.path, qualified) if qualified else None
+                doctest = symbol_id(info.path, f"{qualified or ''}#doctest{index}")
+                nodes.append({
+                    "id": doctest,
+                    "name": f"{qualified.rpartition('.')[2] if qualified else os.path.basename(info.path)} (doctest {index})",
+                    "kind": "doctest",
+                    "qualified_name": f"{qualified or ''}#doctest{index}",
+                    "file_path": info.path,
+                    "line": line,
+                    "column": 0,
+                    "end_line": line,
+                    "language": info.symbols.get("language"),
+                    "exported": False,
+                    "trait": None,
+                    "test": "doctest",
+                    "unresolved_calls": []
+                })
+                targets = {owner} if owner else set()
+                # Paths are resolved from the documented item's scope; my_crate:: paths
+                # go through the crate's library root
+                for segments in _doctest_calls(code):
+                    resolved = self.resolve(info, {
+                        "name": segments[-1],
+                        "qualifier": "::".join(segments[:-1]) or None,
+                        "scope": scope,
+                        "kind": "call"
+                    })
+                    if resolved is not None:
+                        targets.add(symbol_id(resolved[0].file, resolved[0].qualified))
+                for target in targets:
+                    edges.setdefault((doctest, target), {"kind": "doctest", "via": None, "lines": set()})
+
+        return [
+            {"source": source, "target": target, "kind": edge["kind"], "via": edge["via"], "lines": sorted(edge["lines"])}
+            for (source, target), edge in sorted(edges.items())
+        ]
+
+    def file_imports(self, info: _FileInfo) -> Dict[str, Any]:
+        """[1.6.9] IMPORTS edges from a Python or JavaScript/TypeScript file to the files it imports, and its other imports by kind."""
+        edges: Dict[str, Dict[str, Set]] = {}
+        external: Dict[str, Set[str]] = {"stdlib": set(), "third_party": set()}
+        for entry in info.symbols.get("imports", []) if info.family in ("python", "js") else []:
+            module = entry.get("path") or entry.get("module")
+            if not module:
+                continue
+            if info.family == "js":
+                target = self.js_resolver.resolve(info.path, module, self.files)
+                if target is None and not module.startswith((".", "/")):
+                    external["stdlib" if is_builtin(module) else "third_party"].add(module)
+                    continue
+                kind = "local"
+            else:
+                kind, target = self.python_import(info, module)
+            if kind != "local":
+                external[kind].add(module)
+                continue
+            name = entry.get("name")
+            # from package import submodule lands in the submodule, unless __init__.py defines the name
+            if info.family == "python" and target is not None and name and name != "*":
+                if not target.endswith(".py"):
+                    target = self._python_under(target, [name]) or target
+                elif os.path.basename(target) == "__init__.py" and name not in self.members.get((target, ""), {}):
+                    target = self._python_under(os.path.dirname(target), [name]) or target
+            if target is None or target not in self.files or target == info.path:
+                continue
+            edge = edges.setdefault(target, {"names": set(), "lines": set()})
+            if name:
+                edge["names"].add(name)
+            edge["lines"].add(entry["line"])
+        return {
+            "imports": [
+                {"target": target, "names": sorted(edge["names"]), "lines": sorted(edge["lines"])}
+                for target, edge in sorted(edges.items())
+            ],
+            "stdlib_imports": sorted(external["stdlib"]),
+            "third_party_imports": sorted(external["third_party"])
+        }
+
+    def file_graph(self, info: _FileInfo) -> Dict[str, Any]:
+        """[1.6.2] Symbol nodes and DEFINES/REFERENCES/CALLS edges contributed by one file."""
+        language = info.symbols.get("language")
+        separator = "::" if info.family == "rust" else "."
+
+        def enclosing(scope: str) -> Optional[str]:
+            while scope and scope not in info.defs:
+                scope = scope.rpartition(".")[0]
+            return symbol_id(info.path, scope) if scope else None
+
+        references: Dict[Tuple[Optional[str], str], Dict[str, Any]] = {}
+        calls: Dict[Tuple[Optional[str], str], Set[int]] = {}
+        unresolved: Dict[Optional[str], Set[str]] = defaultdict(set)
+        for reference in info.symbols.get("references", []):
+            source = enclosing(reference.get("scope") or "")
+            lines = reference.get("lines") or [reference.get("line")]
+            resolved = self.resolve(info, reference)
+            if resolved is None:
+                if reference.get("kind") == "call":
+                    qualifier = reference.get("qualifier")
+                    unresolved[source].add(f"{qualifier}{separator}{reference['name']}" if qualifier else reference["name"])
+                continue
+            target, resolution = resolved
+            key = (source, symbol_id(target.file, target.qualified))
+            edge = references.setdefault(key, {"lines": set(), "resolution": resolution})
+            edge["lines"].update(lines)
+            definition = self.files[target.file].defs.get(target.qualified) or {}
+            if reference.get("kind") in ("call", "macro") and definition.get("kind") in CALLABLE_KINDS:
+                calls.setdefault(key, set()).update(lines)
+
+        nodes = []
+        for qualified, definition in info.defs.items():
+            nodes.append({
+                "id": symbol_id(info.path, qualified),
+                "name": definition["name"],
+                "kind": definition["kind"],
+                "qualified_name": qualified,
+                "file_path": info.path,
+                "line": definition["line"],
+                "column": definition["column"],
+                "end_line": definition["end_line"],
+                "language": language,
+                "exported": bool(definition.get("exported")),
+                "trait": definition.get("trait"),
+                "test": self._test_kind(info, definition),
+                "unresolved_calls": sorted(unresolved.get(symbol_id(info.path, qualified), ()))[:_MAX_UNRESOLVED]
+            })
+
+        defines = []
+        for qualified, definition in info.defs.items():
+            parent = definition.get("scope") or ""
+            while parent and parent not in info.defs:
+                parent = parent.rpartition(".")[0]
+            defines.append((symbol_id(info.path, parent) if parent else None, symbol_id(info.path, qualified)))
+
+        implements, has_method, external = [], set(), {}
+        for impl in info.symbols.get("impls", []):
+            owner = None if impl.get("blanket") else self._impl_target(info, impl, "type", _IMPL_TYPE_KINDS)
+            if owner is not None:
+                impl_scope = f"{impl['scope']}.{impl['type']}" if impl.get("scope") else impl["type"]
+                for method in impl.get("methods", []):
+                    has_method.add((owner, symbol_id(info.path, f"{impl_scope}.{method}"), impl.get("trait")))
+            if not impl.get("trait"):
+                continue
+            trait = self._impl_target(info, impl, "trait", {"trait"})
+            if trait is None:
+                path = external_path(info, impl["trait"], impl.get("trait_path"))
+                trait = f"{EXTERNAL_PREFIX}{path}"
+                external[trait] = {
+                    "id": trait,
+                    "name": impl["trait"],
+                    "kind": "trait",
+                    "qualified_name": path,
+                    "language": language,
+                    "external": True
+                }
+            # Blanket impls, and impls for types the repository doesn't define
+            # (Vec<Foo>, a type from another crate), start at the Code node
+            implements.append({
+                "source": owner,
+                "target": trait,
+                "type": impl.get("type_text") or impl.get("type"),
+                "line": impl["line"],
+                "methods": impl.get("methods", []),
+                "generic": bool(impl.get("generics")),
+                "blanket": bool(impl.get("blanket")),
+                "negative": bool(impl.get("negative"))
+            })
+
+        tests = self._file_tests(info, calls, nodes)
+
+        return {
+            "nodes": nodes,
+            "defines": sorted(defines, key=lambda edge: (edge[0] or "", edge[1])),
+            "references": sorted(
+                (
+                    {"source": source, "target": target, "lines": sorted(edge["lines"]), "resolution": edge["resolution"]}
+                    for (source, target), edge in references.items()
+                ),
+                key=lambda edge: (edge["source"] or "", edge["target"])
+            ),
+            "calls": sorted(
+                (
+                    {"source": source, "target": target, "lines": sorted(lines)}
+                    for (source, target), lines in calls.items()
+                ),
+                key=lambda edge: (edge["source"] or "", edge["target"])
+            ),
+            "implements": sorted(implements, key=lambda edge: (edge["line"], edge["target"])),
+            "has_method": [
+                {"source": source, "target": target, "trait": trait}
+                for source, target, trait in sorted(has_method, key=lambda edge: (edge[0], edge[1], edge[2] or ""))
+            ],
+            "external": [external[key] for key in sorted(external)],
+            "tests": tests,
+            **self.file_imports(info),
+            # Calls made at module level, kept on the Code node
+            "unresolved_calls": sorted(unresolved.get(None, ()))[:_MAX_UNRESOLVED]
+        }
+
+def doctests(doc: str) -> List[str]:
+    """Code of the doc comment's code blocks that rustdoc runs as tests."""
+    blocks = []
+    for match in _CODE_FENCE.finditer(doc):
+        attributes = {part.strip() for part in match.group(1).split(",")}
+        if attributes <= _DOCTEST_ATTRIBUTES:
+            blocks.append(match.group(2))
+    return blocks
+
+def _doctest_calls(code: str) -> List[List[str]]:
+    """Paths called in doctest code, with names from its use declarations expanded."""
+    uses: Dict[str, List[str]] = {}
+    for match in _DOCTEST_USE.finditer(code):
+        base = match.group(1).split("::")
+        names = [part.strip() for part in (match.group(2) or "").split(",") if part.strip()] if match.group(2) else None
+        if names is None:
+            uses[base[-1]] = base
+            continue
+        for name in names:
+            path, _, alias = name.partition(" as ")
+            segments = base + [segment for segment in path.strip().split("::") if segment != "self"]
+            uses[(alias or path).strip().split("::")[-1]] = segments
+    # Functions the doctest defines itself (fn main) aren't calls into the crate
+    local = set(re.findall(r"\bfn\s+([A-Za-z_]\w*)", code))
+    calls = []
+    for match in _DOCTEST_CALL.finditer(code):
+        segments = match.group(1).split("::")
+        if len(segments) == 1 and segments[0] in local:
+            continue
+        if segments[0] in uses:
+            segments = uses[segments[0]] + segments[1:]
+        if segments not in calls:
+            calls.append(segments)
+    return calls
+
+def external_path(info: _FileInfo, name: str, qualifier: Optional[str]) -> str:
+    """Full path of a name from outside the repository, through the file's imports or use declarations."""
+    separator = "::" if info.family == "rust" else "."
+    segments = (qualifier.split(separator) if qualifier else []) + [name]
+    binding = info.bindings.get(segments[0])
+    if binding is not None and binding.get("module"):
+        head = binding["module"].split(separator) + ([binding["name"]] if binding.get("name") else [])
+        segments = head + segments[1:]
+    return separator.join(segments)
+
+def _graph_hash(graph: Dict[str, Any]) -> str:
+    return hashlib.sha256(json.dumps(graph, sort_keys=True, default=str).encode("utf-8")).hexdigest()
+
+async def _write_nodes(backend, repo_id: int, file_path: str, graph: Dict[str, Any]) -> None:
+    keep = {node["id"] for node in graph["nodes"]}
+    for stale in await backend.find_nodes("Symbol", {"repo_id": repo_id, "file_path": file_path}):
+        if stale.get("id") not in keep:
+            await backend.delete_nodes("Symbol", {"repo_id": repo_id, "id": stale["id"]})
+    # Edges are owned by the file they were found in and always rewritten
+    await backend.delete_relationships("DEFINES", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("REFERENCES", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("CALLS", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("IMPLEMENTS", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("HAS_METHOD", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("TESTS", {"repo_id": repo_id, "file_path": file_path})
+    await backend.delete_relationships("IMPORTS", {"repo_id": repo_id, "file_path": file_path})
+    for node in graph["nodes"] + graph["external"]:
+        await backend.merge_node("Symbol", {"repo_id": repo_id, "id": node["id"]}, node)
+
+async def _write_edges(backend, repo_id: int, file_path: str, graph: Dict[str, Any]) -> None:
+    code_key = {"repo_id": repo_id, "file_path": file_path}
+
+    def endpoint(symbol: Optional[str]) -> Tuple[str, Dict[str, Any]]:
+        return ("Code", code_key) if symbol is None else ("Symbol", {"repo_id": repo_id, "id": symbol})
+
+    await backend.merge_node("Code", code_key, {
+        "unresolved_calls": graph["unresolved_calls"],
+        "stdlib_imports": graph["stdlib_imports"],
+        "third_party_imports": graph["third_party_imports"]
+    })
+    for parent, child in graph["defines"]:
+        start_label, start_key = endpoint(parent)
+        await backend.merge_relationship(
+            start_label, start_key, "DEFINES", "Symbol", {"repo_id": repo_id, "id": child},
+            {"repo_id": repo_id, "file_path": file_path}
+        )
+    for edge in graph["references"]:
+        start_label, start_key = endpoint(edge["source"])
+        await backend.merge_relationship(
+            start_label, start_key, "REFERENCES", "Symbol", {"repo_id": repo_id, "id": edge["target"]},
+            {
+                "repo_id": repo_id,
+                "file_path": file_path,
+                "lines": edge["lines"],
+                "count": len(edge["lines"]),
+                "resolution": edge["resolution"]
+            }
+        )
+    for edge in graph["calls"]:
+        start_label, start_key = endpoint(edge["source"])
+        await backend.merge_relationship(
+            start_label, start_key, "CALLS", "Symbol", {"repo_id": repo_id, "id": edge["target"]},
+            {"repo_id": repo_id, "file_path": file_path, "lines": edge["lines"], "count": len(edge["lines"])}
+        )
+    for edge in graph["implements"]:
+        start_label, start_key = endpoint(edge["source"])
+        await backend.merge_relationship(
+            start_label, start_key, "IMPLEMENTS", "Symbol", {"repo_id": repo_id, "id": edge["target"]},
+            {
+                "repo_id": repo_id,
+                "file_path": file_path,
+                **{key: value for key, value in edge.items() if key not in ("source", "target")}
+            }
+        )
+    for edge in graph["has_method"]:
+        await backend.merge_relationship(
+            "Symbol", {"repo_id": repo_id, "id": edge["source"]}, "HAS_METHOD", "Symbol", {"repo_id": repo_id, "id": edge["target"]},
+            {"repo_id": repo_id, "file_path": file_path, "trait": edge["trait"]}
+        )
+    for edge in graph["tests"]:
+        await backend.merge_relationship(
+            "Symbol", {"repo_id": repo_id, "id": edge["source"]}, "TESTS", "Symbol", {"repo_id": repo_id, "id": edge["target"]},
+            {"repo_id": repo_id, "file_path": file_path, "kind": edge["kind"], "via": edge["via"], "lines": edge["lines"]}
+        )
+    for edge in graph["imports"]:
+        await backend.merge_relationship(
+            "Code", code_key, "IMPORTS", "Code", {"repo_id": repo_id, "file_path": edge["target"]},
+            {"repo_id": repo_id, "file_path": file_path, "names": edge["names"], "lines": edge["lines"]}
+        )
+
+@handle_async_errors(error_types=(PostgresError, Neo4jError, DatabaseError), default_return={})
+async def sync_symbol_graph(
+    repo_id: int,
+    repo_path: str,
+    reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None
+) -> Dict[str, int]:
+    """[1.6.3] Resolve every file's symbols and rewrite the files whose graph changed.
+
+    Resolution is repeated for the whole repository because an edit in one
+    file can change what names in other files resolve to; writes are limited
+    to files whose nodes or edges differ from the stored graph_hash.
+    reader supplies tsconfig.json and package.json contents (e.g.
+    GitTreeSource.read for a commit); by default they are read from disk.
+    """
+    backend = await get_storage_backend()
+    rows = await backend.fetch(
+        "SELECT file_path, language, symbols, graph_hash FROM file_symbols WHERE repo_id = $1;",
+        repo_id
+    )
+    js_files = [row["file_path"] for row in rows if LANGUAGE_FAMILIES.get(row["language"]) == "js"]
+    js_resolver = await load_js_resolver(repo_path, js_files, reader) if js_files else None
+    table = SymbolTable(repo_path, rows, js_resolver)
+
+    dirty = {}
+    external: Set[str] = set()
+    stats = {"files": len(table.files), "symbols": 0, "references": 0, "calls": 0, "implements": 0, "tests": 0, "imports": 0, "rewritten": 0}
+    for info in table.files.values():
+        graph = table.file_graph(info)
+        stats["symbols"] += len(graph["nodes"])
+        stats["references"] += len(graph["references"])
+        stats["calls"] += len(graph["calls"])
+        stats["implements"] += len(graph["implements"])
+        stats["tests"] += len(graph["tests"])
+        stats["imports"] += len(graph["imports"])
+        external.update(node["id"] for node in graph["external"])
+        digest = _graph_hash(graph)
+        if digest != info.graph_hash:
+            dirty[info.path] = (graph, digest)
+
+    # All nodes first, so edges into other dirty files find their targets
+    for file_path, (graph, _) in dirty.items():
+        await _write_nodes(backend, repo_id, file_path, graph)
+    for file_path, (graph, _) in dirty.items():
+        await _write_edges(backend, repo_id, file_path, graph)
+    if dirty:
+        await backend.execute_batch([
+            ("UPDATE file_symbols SET graph_hash = $3 WHERE repo_id = $1 AND file_path = $2;", (repo_id, file_path, digest))
+            for file_path, (_, digest) in dirty.items()
+        ])
+        # External traits no impl block mentions any more
+        for node in await backend.find_nodes("Symbol", {"repo_id": repo_id, "external": True}):
+            if node.get("id") not in external:
+                await backend.delete_nodes("Symbol", {"repo_id": repo_id, "id": node["id"]})
+    stats["rewritten"] = len(dirty)
+    await log(
+        f"Symbol graph of repository {repo_id}: {stats['symbols']} symbol(s), "
+        f"{stats['references']} reference edge(s), {stats['calls']} call edge(s), "
+        f"{stats['implements']} impl edge(s), {stats['tests']} test edge(s), {stats['imports']} import edge(s), "
+        f"{stats['rewritten']} file(s) rewritten",
+        level="info"
+    )
+    return stats
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def find_symbols(repo_id: int, name: str, limit: int = 50) -> List[Dict[str, Any]]:
+    """[1.6.4] Definitions of a name in a repository."""
+    backend = await get_storage_backend()
+    return await backend.find_nodes("Symbol", {"repo_id": repo_id, "name": name}, limit=limit)
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def find_usages(repo_id: int, name: str, file_path: Optional[str] = None) -> List[Dict[str, Any]]:
+    """[1.6.5] Every place a symbol is used, across files.
+
+    name is a plain or qualified name (parse, Parser.parse); file_path
+    narrows it to the definition in that file.
+    """
+    backend = await get_storage_backend()
+    match = {"repo_id": repo_id, ("qualified_name" if "." in name else "name"): name}
+    if file_path:
+        match["file_path"] = os.path.abspath(file_path)
+    usages = []
+    for symbol in await backend.find_nodes("Symbol", match):
+        edges = await backend.traverse("Symbol", {"repo_id": repo_id, "id": symbol["id"]}, ["REFERENCES"], direction="in")
+        for edge in edges:
+            source = edge["source"]
+            usages.append({
+                "symbol": symbol["qualified_name"],
+                "defined_in": symbol["file_path"],
+                "file_path": edge["properties"].get("file_path"),
+                "used_by": source.get("qualified_name") or None,
+                "lines": edge["properties"].get("lines", []),
+                "resolution": edge["properties"].get("resolution")
+            })
+    usages.sort(key=lambda usage: (usage["file_path"] or "", usage["lines"][:1]))
+    return usages
+
+def _implementation(edge: Dict[str, Any], type_node: Dict[str, Any], trait_node: Dict[str, Any]) -> Dict[str, Any]:
+    properties = edge["properties"]
+    return {
+        "trait": trait_node.get("qualified_name"),
+        "trait_file": trait_node.get("file_path"),
+        "type": type_node.get("qualified_name") or properties.get("type"),
+        "type_file": type_node.get("file_path"),
+        "file_path": properties.get("file_path"),
+        "line": properties.get("line"),
+        "methods": properties.get("methods", []),
+        "generic": properties.get("generic", False),
+        "blanket": properties.get("blanket", False),
+        "negative": properties.get("negative", False)
+    }
+
+def _name_match(repo_id: int, name: str, separator: str) -> Dict[str, Any]:
+    return {"repo_id": repo_id, ("qualified_name" if separator in name else "name"): name}
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def find_implementations(repo_id: int, trait: str) -> List[Dict[str, Any]]:
+    """[1.6.6] Impl blocks of a trait: the types that implement it, and blanket impls.
+
+    trait is a name (Display) or a path (std::fmt::Display, for traits from
+    outside the repository).
+    """
+    backend = await get_storage_backend()
+    implementations = []
+    for node in await backend.find_nodes("Symbol", {**_name_match(repo_id, trait, "::"), "kind": "trait"}):
+        edges = await backend.traverse("Symbol", {"repo_id": repo_id, "id": node["id"]}, ["IMPLEMENTS"], direction="in")
+        for edge in edges:
+            # Code nodes (blanket impls, foreign types) have no qualified_name
+            source = edge["source"] if edge["source"].get("id") else {}
+            implementations.append(_implementation(edge, source, node))
+    implementations.sort(key=lambda row: (row["trait"] or "", row["file_path"] or "", row["line"] or 0))
+    return implementations
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def find_traits(repo_id: int, type_name: str, file_path: Optional[str] = None) -> List[Dict[str, Any]]:
+    """[1.6.7] Traits a type implements, from every impl block for it.
+
+    file_path narrows type_name to the definition in that file.
+    """
+    backend = await get_storage_backend()
+    match = {"repo_id": repo_id, "name": type_name}
+    if file_path:
+        match["file_path"] = os.path.abspath(file_path)
+    traits = []
+    for node in await backend.find_nodes("Symbol", match):
+        if node.get("kind") not in _IMPL_TYPE_KINDS:
+            continue
+        edges = await backend.traverse("Symbol", {"repo_id": repo_id, "id": node["id"]}, ["IMPLEMENTS"], direction="out")
+        traits.extend(_implementation(edge, node, edge["target"]) for edge in edges)
+    traits.sort(key=lambda row: (row["type"] or "", row["trait"] or ""))
+    return traits
+
+@handle_async_errors(error_types=(Neo4jError, DatabaseError), default_return=[])
+async def find_tests(repo_id: int, name: str, file_path: Optional[str] = None, depth: int = 3) -> List[Dict[str, Any]]:
+    """[1.6.8] Tests that exercise a function, for impact analysis.
+
+    A test exercises a function it has a TESTS edge to, or one of the
+    function's callers up to depth calls away; direct is False for the latter.
+    """
+    backend = await get_storage_backend()
+    match = _name_match(repo_id, name, ".")
+    if file_path:
+        match["file_path"] = os.path.abspath(file_path)
+    tests: Dict[Tuple[str, str], Dict[str, Any]] = {}
+    for symbol in await backend.find_nodes("Symbol", match):
+        edges = await backend.traverse(
+            "Symbol", {"repo_id": repo_id, "id": symbol["id"]}, ["CALLS", "TESTS"], direction="in", max_depth=depth + 1
+        )
+        for edge in edges:
+            if edge["type"] != "TESTS" or not edge["source"].get("id"):
+                continue
+            test, properties = edge["source"], edge["properties"]
+            row = {
+                "symbol": symbol["qualified_name"],
+                "defined_in": symbol["file_path"],
+                "test": test.get("qualified_name"),
+                "test_file": test.get("file_path"),
+                "kind": properties.get("kind"),
+                "line": test.get("line"),
+                "via": properties.get("via"),
+                "target": edge["target"].get("qualified_name"),
+                "direct": edge["depth"] == 1
+            }
+            # Traversal is breadth first, so the shortest path to a test comes first
+            tests.setdefault((symbol["id"], test["id"]), row)
+    return sorted(tests.values(), key=lambda row: (not row["direct"], row["test_file"] or "", row["line"] or 0))
+
+__all__ = [
+    "LANGUAGE_FAMILIES",
+    "SymbolTable",
+    "symbol_id",
+    "external_path",
+    "sync_symbol_graph",
+    "find_symbols",
+    "find_usages",
+    "find_implementations",
+    "find_traits",
+    "find_tests"
+]
@@ -4,27 +4,53 @@ Flow:
 1. Entry Points:
    - index_active_project(): For current working directory
    - process_repository_indexing(): Core indexing pipeline
+   - process_file_batch(): Changed and deleted files seen by the watcher
    - ProcessingCoordinator: Handles individual file processing
 
 2. Processing Pipeline:
    - File Discovery: get_files() finds all processable files
-   - Batch Processing: Files are processed in configurable batches
+   - Manifest [1.3]: unchanged files are skipped, deleted ones removed
+   - Indexing Jobs [1.5]: stored files are checkpointed so runs can resume
+   - Git Sources [4.5]: a ref or commit range is read from the object database
+   - Parse Pool [2.6]: Files are parsed in worker processes, then stored
+   - Code Chunks [1.14]: functions, classes and methods are embedded one by one
+   - Symbol Table [1.6]: definitions and references are resolved across files
+   - Rust Crates [1.7]: Cargo manifests and mod declarations become Crate/Module nodes
+   - Rust Audit [1.8]: unsafe, panic and FFI sites are stored as findings
+   - Rust Features [1.10]: cfg-gated items are mapped to Cargo features
+   - Blocking Calls [1.11]: blocking calls reachable from async functions are stored as findings
+   - Package Dependencies [1.12]: manifests and lockfiles become Package nodes linked to importing files
+   - Rust Docs [1.9]: doc comments are stored per item as docstrings
    - Graph Updates: Neo4j projections are updated after indexing
 
 3. Integration Points:
    - FileProcessor: Handles parsing and storage
    - Language Registry: Determines file language and parser
    - File Utils: Handles file validation and path management
+   - Index Manifest: Content hashes and parser/embedding versions per file
 """
 
 import os
 import asyncio
 import time
-from typing import Optional, Dict, List, Set, Any
+from typing import Optional, Dict, List, Set, Any, Callable, Awaitable
 from indexer.async_utils import batch_process_files
 from utils.logger import log
 from indexer.async_utils import async_read_file
-from indexer.file_utils import get_files, get_relative_path, is_processable_file
+from indexer.file_utils import get_files, get_relative_path, is_processable_file, should_ignore
+from indexer.ignore_rules import find_root, get_ignore_rules
+from indexer.manifest import plan_indexing, record_indexed, remove_deleted
+from indexer.index_jobs import IndexJob, JOB_COMPLETED, JOB_FAILED, JOB_INTERRUPTED
+from indexer.git_source import GitTreeSource, head_state
+from indexer.symbol_table import sync_symbol_graph
+from indexer.rust_crates import sync_crate_graph
+from indexer.rust_audit import run_rust_audit
+from indexer.rust_features import run_rust_feature_map
+from indexer.blocking_calls import run_blocking_call_check
+from indexer.rust_docs import sync_rust_docs
+from indexer.package_deps import LOCKFILES, MANIFESTS, sync_package_graph
+from indexer.js_modules import JS_EXTENSIONS
+from indexer.code_chunks import file_chunks, file_embedding
 from parsers.types import ParserResult, FileType, ExtractedFeatures
 from parsers.models import FileClassification
 from parsers.language_support import language_registry
@@ -33,22 +59,20 @@ from db.upsert_ops import UpsertCoordinator, upsert_coordinator
 from db.transaction import transaction_scope
 from db.graph_sync import graph_sync
 from indexer.file_processor import FileProcessor
+from indexer.parse_pool import ParsePool, ParsedFile
 from utils.error_handling import (
     handle_async_errors,
     AsyncErrorBoundary,
     ErrorSeverity,
     ProcessingError,
-    DatabaseError,
-    ErrorAudit
+    DatabaseError
 )
-from utils.request_cache import request_cache_context, cached_in_request
 from utils.shutdown import register_shutdown_handler
 from utils.async_runner import submit_async_task, cleanup_tasks
 from embedding.embedding_models import code_embedder, doc_embedder, arch_embedder
 from utils.health_monitor import global_health_monitor, ComponentStatus
 from utils.cache import UnifiedCache, cache_coordinator
 from utils.cache_analytics import get_cache_analytics
-from datetime import datetime
 
 # Initialize pattern system
 _pattern_system_initialized = False
@@ -63,6 +87,16 @@ async def ensure_pattern_system():
 # Initialize upsert coordinator
 _upsert_coordinator = UpsertCoordinator()
 
+# What a watch batch must touch for each whole-repository analysis to rerun
+_PYTHON_EXTENSIONS = (".py", ".pyi", ".pyw")
+_BLOCKING_EXTENSIONS = _PYTHON_EXTENSIONS + (".rs",)
+_RUST_EXTENSIONS, _RUST_FILES = (".rs",), ("Cargo.toml", "Cargo.lock")
+_PACKAGE_EXTENSIONS = _PYTHON_EXTENSIONS + JS_EXTENSIONS + (".mts", ".cts", ".go", ".rs")
+_PACKAGE_FILES = tuple(MANIFESTS) + tuple(name for names in LOCKFILES.values() for name in names)
+
+def _touches(paths: List[str], extensions: tuple = (), names: tuple = ()) -> bool:
+    return any(path.endswith(extensions) or os.path.basename(path) in names for path in paths)
+
 class UnifiedIndexer:
     """[1.1] Core indexing system coordinator."""
     
@@ -340,7 +374,11 @@ class UnifiedIndexer:
         }
 
 class ProcessingCoordinator:
-    """[1.2] Coordinates file processing tasks."""
+    """[1.2] Coordinates file processing tasks.
+    
+    Parsing runs in the ParsePool [2.6] worker processes; embedding and
+    storage stay on the event loop.
+    """
     
     def __init__(self):
         """Initialize the processing coordinator."""
@@ -348,9 +386,7 @@ class ProcessingCoordinator:
         self._pending_tasks: Set[asyncio.Task] = set()
         self._lock = asyncio.Lock()
         self._file_processor = None
-        self._batch_size = 50
-        self._max_concurrent_batches = 5
-        self._semaphore = asyncio.Semaphore(self._max_concurrent_batches)
+        self._parse_pool: Optional[ParsePool] = None
         self._metrics = {
             "total_files_processed": 0,
             "successful_files": 0,
@@ -378,6 +414,7 @@ class ProcessingCoordinator:
                 # Initialize required components
                 from indexer.file_processor import FileProcessor
                 instance._file_processor = await FileProcessor.create()
+                instance._parse_pool = await ParsePool.create()
                 
                 # Initialize cache
                 instance._cache = UnifiedCache("processing_coordinator")
@@ -410,114 +447,104 @@ class ProcessingCoordinator:
         """Warm up the cache with commonly used data."""
         result = {}
         for key in keys:
-            if key.startswith("batch_size:"):
-                result[key] = self._batch_size
-            elif key.startswith("max_concurrent:"):
-                result[key] = self._max_concurrent_batches
+            if key.startswith("parse_workers:") and self._parse_pool:
+                result[key] = self._parse_pool.workers
         return result
     
-    @cached_in_request(lambda files, repo_id, repo_path: f"batch:{repo_id}:{hash(tuple(files))}")
-    async def _process_batch(self, files: List[str], repo_id: int, repo_path: str) -> None:
-        """Process a batch of files with error handling."""
-        if not files:
+    async def _store_parsed(self, parsed: ParsedFile, content: str, repo_id: int) -> None:
+        """Write stage: embed and store one file parsed by the pool."""
+        if parsed.file_type == FileType.DOC:
+            await _upsert_coordinator.upsert_doc(
+                repo_id,
+                parsed.file_path,
+                content,
+                parsed.language or "markdown",
+                metadata=parsed.result.metadata
+            )
             return
-            
-        async with self._semaphore:
-            try:
-                # Create task for batch processing
-                task = asyncio.create_task(
-                    self._file_processor.process_files(files, repo_id, repo_path)
-                )
-                self._pending_tasks.add(task)
-                
-                # Wait for batch completion
-                try:
-                    await task
-                    self._metrics["successful_files"] += len(files)
-                    
-                    # Cache successful batch processing
-                    cache_key = f"processed_batch:{repo_id}:{hash(tuple(files))}"
-                    await self._cache.set(cache_key, {
-                        "status": "success",
-                        "count": len(files),
-                        "timestamp": datetime.now().isoformat()
-                    })
-                    
-                except Exception as e:
-                    self._metrics["failed_files"] += len(files)
-                    await ErrorAudit.record_error(
-                        e,
-                        "batch_processing",
-                        ProcessingError,
-                        severity=ErrorSeverity.ERROR,
-                        details={
-                            "repo_id": repo_id,
-                            "files": files
-                        }
-                    )
-                    raise
-                finally:
-                    self._metrics["total_files_processed"] += len(files)
-                    if task in self._pending_tasks:
-                        self._pending_tasks.remove(task)
-                    
-            except Exception as e:
-                await log(f"Error processing batch: {e}", level="error")
-                await global_health_monitor.update_component_status(
-                    "processing_coordinator",
-                    ComponentStatus.DEGRADED,
-                    error=True,
-                    details={
-                        "error": str(e),
-                        "batch_size": len(files)
-                    }
-                )
-                raise ProcessingError(f"Failed to process batch: {e}")
+        
+        # Only chunks are embedded; the file's embedding is their mean
+        chunks = await file_chunks(parsed.language, content, parsed.result.ast, parsed.symbols)
+        embeddings = await code_embedder.embed_code_batch(
+            [chunk['content'] for chunk in chunks],
+            parsed.language,
+            context={"file_path": parsed.file_path, "repo_id": repo_id},
+            pattern_type="code"
+        )
+        for chunk, embedding in zip(chunks, embeddings):
+            chunk['embedding'] = embedding
+        
+        await _upsert_coordinator.upsert_code_snippet({
+            'repo_id': repo_id,
+            'file_path': parsed.file_path,
+            'ast': parsed.result.ast,
+            'embedding': file_embedding(chunks),
+            'enriched_features': parsed.result.features.to_dict(),
+            'symbols': parsed.symbols
+        })
+        await _upsert_coordinator.replace_code_chunks(repo_id, parsed.file_path, parsed.language, chunks)
     
-    async def process_files(self, files: List[str], repo_id: int, repo_path: str) -> None:
-        """Process files in batches with proper error handling."""
+    async def process_files(
+        self,
+        files: List[str],
+        repo_id: int,
+        repo_path: str,
+        reader: Optional[Callable[[str], Awaitable[Optional[str]]]] = None,
+        on_stored: Optional[Callable[[str], Awaitable[None]]] = None
+    ) -> Dict[str, Any]:
+        """Parse files in the worker pool and store the results.
+        
+        reader supplies file contents (e.g. GitTreeSource.read for a commit);
+        by default files are read from disk. on_stored is awaited with the
+        path of each file once it is stored (e.g. IndexJob.file_stored [1.5.1]).
+        
+        Returns:
+            Counts of parsed, stored and failed files, the failed paths and
+            their errors
+        """
         if not self._initialized:
             await self.ensure_initialized()
         
-        async with request_cache_context() as cache:
-            try:
-                # Process files in batches
-                for i in range(0, len(files), self._batch_size):
-                    batch = files[i:i + self._batch_size]
-                    
-                    # Check request cache first
-                    cache_key = f"batch_result:{repo_id}:{hash(tuple(batch))}"
-                    if await cache.has(cache_key):
-                        continue
-                    
-                    await self._process_batch(batch, repo_id, repo_path)
-                    
-                    # Cache batch result
-                    await cache.set(cache_key, {
-                        "processed": True,
-                        "timestamp": datetime.now().isoformat()
-                    })
-                
-                # Update health status
-                await global_health_monitor.update_component_status(
-                    "processing_coordinator",
-                    ComponentStatus.HEALTHY,
-                    details={
-                        "total_processed": self._metrics["total_files_processed"],
-                        "successful": self._metrics["successful_files"],
-                        "failed": self._metrics["failed_files"]
-                    }
-                )
-                
-            except Exception as e:
-                await log(f"Error processing files: {e}", level="error")
-                await global_health_monitor.update_component_status(
-                    "processing_coordinator",
-                    ComponentStatus.UNHEALTHY,
-                    error=True,
-                    details={"error": str(e)}
-                )
-                raise ProcessingError(f"Failed to process files: {e}")
+        async def write(parsed: ParsedFile, content: str) -> None:
+            await self._store_parsed(parsed, content, repo_id)
+            if on_stored is not None:
+                await on_stored(parsed.file_path)
+        
+        start = time.monotonic()
+        try:
+            stats = await self._parse_pool.run(files, reader or async_read_file, write)
+            
+            self._metrics["total_files_processed"] += len(files)
+            self._metrics["successful_files"] += stats["stored"]
+            self._metrics["failed_files"] += stats["failed"]
+            self._metrics["processing_times"].append(time.monotonic() - start)
+            await log(
+                f"Processed {len(files)} file(s) in {repo_path} with {self._parse_pool.workers} parser(s): "
+                f"{stats['stored']} stored, {stats['failed']} failed",
+                level="info"
+            )
+            
+            # Update health status
+            await global_health_monitor.update_component_status(
+                "processing_coordinator",
+                ComponentStatus.DEGRADED if stats["failed"] else ComponentStatus.HEALTHY,
+                details={
+                    "total_processed": self._metrics["total_files_processed"],
+                    "successful": self._metrics["successful_files"],
+                    "failed": self._metrics["failed_files"]
+                }
+            )
+            return stats
+            
+        except Exception as e:
+            await log(f"Error processing files: {e}", level="error")
+            await global_health_monitor.update_component_status(
+                "processing_coordinator",
+                ComponentStatus.UNHEALTHY,
+                error=True,
+                details={"error": str(e)}
+            )
+            raise ProcessingError(f"Failed to process files: {e}")
     
     async def cleanup(self):
         """Clean up all resources."""
@@ -538,6 +565,10 @@ class ProcessingCoordinator:
                 if self._file_processor:
                     await self._file_processor.cleanup()
                 
+                # Stop the parser processes
+                if self._parse_pool:
+                    await self._parse_pool.cleanup()
+                
                 # Clean up cache
                 if self._cache:
                     try:
@@ -566,15 +597,38 @@ async def get_processing_coordinator() -> ProcessingCoordinator:
         processing_coordinator = await ProcessingCoordinator.create()
     return processing_coordinator
 
-@handle_async_errors()
-async def process_repository_indexing(repo_path: str, repo_id: int, repo_type: str = "active", single_file: bool = False) -> None:
+async def process_repository_indexing(
+    repo_path: str,
+    repo_id: int,
+    repo_type: str = "active",
+    single_file: bool = False,
+    full: bool = False,
+    source: Optional[GitTreeSource] = None,
+    job: Optional[IndexJob] = None
+) -> Optional[Dict[str, Any]]:
     """Process a repository for indexing.
     
+    Files whose content hash, parser version and embedding model match the
+    manifest [1.3] are skipped, and stored data for deleted files is removed.
+    Repository runs are persisted as an indexing job [1.5] that checkpoints
+    stored files, so an interrupted run can be resumed.
+    
     Args:
         repo_path: Path to the repository
         repo_id: ID of the repository
         repo_type: Type of repository (active/reference)
         single_file: Whether to process a single file
+        full: Reindex every file regardless of the manifest
+        source: Index a commit from the object database [4.5] instead of the working tree
+        job: Resume this unfinished job: only its remaining files are indexed
+        
+    Returns:
+        Counts of indexed, unchanged, removed and failed files, the indexed
+        commit and the job id
+        
+    Raises:
+        Whatever failed the run, so callers can report it; the job, if any,
+        is finished as failed first
     """
     async with AsyncErrorBoundary(f"indexing repository {repo_path}", severity=ErrorSeverity.ERROR):
         try:
@@ -582,23 +636,201 @@ async def process_repository_indexing(repo_path: str, repo_id: int, repo_type: s
             if not _upsert_coordinator._initialized:
                 await _upsert_coordinator.initialize()
             
-            # Get all processable files
-            files = get_files(repo_path)
-            if not files:
-                log(f"No processable files found in {repo_path}", level="warning")
-                return
+            repo_path = os.path.abspath(repo_path)
+            if not single_file:
+                await _upsert_coordinator.record_repository_root(repo_id, repo_path)
+            if job is not None:
+                # The job already holds the plan; stored files were checkpointed
+                changed, hashes = job.remaining, job.hashes
+                result = {"indexed": len(changed), "unchanged": len(hashes) - len(changed), "removed": 0}
+                await log(f"Resuming indexing job {job.id} for {repo_path}: {len(changed)} file(s) left", level="info")
+            else:
+                # Get all processable files
+                if source is not None:
+                    files = await source.list_files()
+                elif single_file:
+                    files = [repo_path] if os.path.isfile(repo_path) and await is_processable_file(repo_path) else []
+                else:
+                    files = await get_files(repo_path)
                 
-            # Process files in batches
-            await get_processing_coordinator().process_files(files, repo_id, repo_path)
+                # Skip what the manifest says is current
+                plan = await plan_indexing(repo_id, repo_path, files, full=full, source=source)
+                await log(
+                    f"Indexing {repo_path}: {len(plan.changed)} changed, "
+                    f"{len(plan.unchanged)} unchanged, {len(plan.removed)} removed",
+                    level="info"
+                )
+                
+                await remove_deleted(repo_id, plan.removed, _upsert_coordinator)
+                changed, hashes, result = plan.changed, plan.hashes, plan.summary()
+                
+                if changed and not single_file:
+                    job = await IndexJob.start(
+                        repo_id, repo_path,
+                        {path: hashes.get(path) for path in changed},
+                        {
+                            "full": full,
+                            "ref": source.rev if source is not None else None,
+                            "commit": source.commit_sha if source is not None else None
+                        }
+                    )
             
-            # Update graph projection
-            await graph_sync.invalidate_projection(repo_id)
-            await graph_sync.ensure_projection(repo_id)
+            # Parse in worker processes and store the results
+            failed: Set[str] = set()
+            try:
+                if changed:
+                    coordinator = await get_processing_coordinator()
+                    stats = await coordinator.process_files(
+                        changed, repo_id, repo_path,
+                        reader=source.read if source is not None else None,
+                        on_stored=job.file_stored if job is not None else None
+                    )
+                    
+                    # Failed files stay out of the manifest so the next run retries them
+                    failed = set(stats["failed_files"])
+                    if job is not None:
+                        await job.files_failed(stats["errors"])
+                    else:
+                        await record_indexed(repo_id, {
+                            path: hashes[path] for path in changed
+                            if path in hashes and path not in failed
+                        })
+                
+                # Re-resolve names across files, then update the graph projection.
+                # A job's files may all be stored while this is still owed, so a
+                # resumed job always runs it.
+                if changed or result.get("removed") or job is not None:
+                    if not single_file:
+                        await sync_symbol_graph(repo_id, repo_path, reader=source.read if source is not None else None)
+                        await run_blocking_call_check(repo_id, repo_path)
+                        await sync_crate_graph(repo_id, repo_path, reader=source.read if source is not None else None)
+                        await run_rust_audit(repo_id, repo_path, reader=source.read if source is not None else None)
+                        await run_rust_feature_map(repo_id, repo_path, reader=source.read if source is not None else None)
+                        await sync_rust_docs(repo_id, repo_path, _upsert_coordinator, reader=source.read if source is not None else None)
+                        await sync_package_graph(repo_id, repo_path, reader=source.read if source is not None else None)
+                    await graph_sync.invalidate_projection(repo_id)
+                    await graph_sync.ensure_projection(repo_id)
+                
+                result["failed"] = len(failed)
+                if job is not None:
+                    result["job"] = job.id
+                
+                # Record what the index now reflects; a single file says nothing about the rest
+                if not single_file:
+                    if source is not None:
+                        commit, ref, dirty = source.commit_sha, source.rev, False
+                        if job is not None and job.options.get("ref"):
+                            ref = job.options["ref"]
+                    else:
+                        commit, dirty = await head_state(repo_path)
+                        ref = None
+                    if commit is not None:
+                        await _upsert_coordinator.record_indexed_commit(repo_id, commit, ref, dirty)
+                    result["commit"] = commit
+            except asyncio.CancelledError:
+                if job is not None:
+                    await job.finish(JOB_INTERRUPTED, "Indexing was interrupted")
+                raise
+            except Exception as e:
+                if job is not None:
+                    await job.finish(JOB_FAILED, str(e))
+                raise
+            
+            # Only now is the job done: until then a resume redoes the derived data
+            if job is not None:
+                await job.finish(
+                    JOB_FAILED if failed else JOB_COMPLETED,
+                    f"{len(failed)} file(s) failed" if failed else None
+                )
+            
+            return result
             
         except Exception as e:
-            log(f"Error processing repository {repo_path}: {e}", level="error")
+            await log(f"Error processing repository {repo_path}: {e}", level="error")
             raise
 
+@handle_async_errors()
+async def process_file_batch(
+    repo_path: str,
+    repo_id: int,
+    changed: List[str],
+    deleted: List[str]
+) -> Optional[Dict[str, Any]]:
+    """Index a batch of changed files and drop deleted ones (watch mode).
+    
+    changed may name files or directories (a directory moved into the tree);
+    deleted may name directories too, in which case everything indexed below
+    them that no longer exists is removed. The manifest [1.3] still skips
+    files whose content did not change. Symbols are resolved and the graph
+    projection is refreshed once for the whole batch; the Rust, blocking-call
+    and package analyses rerun only when the batch touched their files.
+    
+    Returns:
+        Counts of indexed, unchanged, removed and failed files, and the
+        removed paths
+    """
+    async with AsyncErrorBoundary(f"indexing file batch in {repo_path}", severity=ErrorSeverity.ERROR):
+        if not _upsert_coordinator._initialized:
+            await _upsert_coordinator.initialize()
+        
+        repo_path = os.path.abspath(repo_path)
+        files: List[str] = []
+        for path in dict.fromkeys(os.path.abspath(p) for p in changed):
+            if os.path.isdir(path):
+                files.extend(await get_files(path))
+            elif os.path.isfile(path) and await is_processable_file(path):
+                files.append(path)
+        files = list(dict.fromkeys(files))
+        
+        plan = await plan_indexing(
+            repo_id, repo_path, files,
+            removed_under=[os.path.abspath(p) for p in deleted]
+        )
+        await remove_deleted(repo_id, plan.removed, _upsert_coordinator)
+        
+        failed: Set[str] = set()
+        if plan.changed:
+            coordinator = await get_processing_coordinator()
+            stats = await coordinator.process_files(plan.changed, repo_id, repo_path)
+            failed = set(stats["failed_files"])
+            await record_indexed(repo_id, {
+                path: plan.hashes[path] for path in plan.changed
+                if path in plan.hashes and path not in failed
+            })
+        
+        # One symbol resolution and projection refresh per batch. The other
+        # analyses reread the whole repository, so they only run when the
+        # batch touched a file of their language or a manifest (which the
+        # watcher reports even when it isn't indexed itself). Build output
+        # and installed packages are ignored, so they never count.
+        rules = get_ignore_rules(find_root(repo_path))
+        touched = plan.changed + plan.removed + [
+            path for path in (os.path.abspath(p) for p in list(changed) + list(deleted))
+            if not should_ignore(path, rules, os.path.isdir(path))
+        ]
+        indexed = bool(plan.changed or plan.removed)
+        rust = _touches(touched, _RUST_EXTENSIONS, _RUST_FILES)
+        packages = _touches(touched, _PACKAGE_EXTENSIONS, _PACKAGE_FILES)
+        if indexed:
+            await sync_symbol_graph(repo_id, repo_path)
+            if _touches(touched, _BLOCKING_EXTENSIONS):
+                await run_blocking_call_check(repo_id, repo_path)
+        if rust:
+            await sync_crate_graph(repo_id, repo_path)
+            await run_rust_audit(repo_id, repo_path)
+            await run_rust_feature_map(repo_id, repo_path)
+            await sync_rust_docs(repo_id, repo_path, _upsert_coordinator)
+        if packages:
+            await sync_package_graph(repo_id, repo_path)
+        if indexed or rust or packages:
+            await graph_sync.invalidate_projection(repo_id)
+            await graph_sync.ensure_projection(repo_id)
+        
+        result = plan.summary()
+        result["failed"] = len(failed)
+        result["removed_files"] = plan.removed
+        return result
+
 async def index_active_project() -> None:
     """[2.5] Index the currently active project (working directory)."""
     repo_path = os.getcwd()
@@ -4,6 +4,7 @@ This module provides Rust-specific patterns with enhanced type system and relati
 Integrates with cache analytics, error handling, and logging systems.
 """
 
+import re
 from typing import Dict, Any, List, Optional, Union, Set
 from dataclasses import dataclass, field
 from parsers.types import (
@@ -14,6 +15,7 @@ from parsers.types import (
     ExtractedFeatures, ParserType
 )
 from parsers.models import PATTERN_CATEGORIES
+from parsers.symbol_extractor import RUST_TEST_ATTRIBUTES
 from .common import (
     COMMON_PATTERNS, COMMON_CAPABILITIES, 
     process_tree_sitter_pattern, validate_tree_sitter_pattern, create_tree_sitter_context
@@ -66,13 +68,26 @@ class RustPatternContext(PatternContext):
         """Generate unique context key."""
         return f"{super().get_context_key()}:{len(self.struct_names)}:{self.has_generics}"
 
+_CFG_ATTRIBUTE = re.compile(r'^#!?\[\s*cfg\s*\((.*)\)\s*\]$', re.DOTALL)
+_CFG_FEATURE = re.compile(r'feature\s*=\s*"([^"]+)"')
+
+def _cfg_predicate(attribute: str) -> str:
+    """The predicate of a #[cfg(...)] or #![cfg(...)] attribute."""
+    match = _CFG_ATTRIBUTE.match(attribute.strip())
+    return " ".join(match.group(1).split()) if match else ""
+
+# Attribute text of a test function, with the names the symbol extractor treats as tests
+_TEST_ATTRIBUTE = "^#.([a-z_]+::)*(" + "|".join(sorted(RUST_TEST_ATTRIBUTES)) + ")([^a-z_]|$)"
+
 # Initialize pattern metrics
 PATTERN_METRICS = {
     "struct": PatternPerformanceMetrics(),
     "enum": PatternPerformanceMetrics(),
     "trait": PatternPerformanceMetrics(),
     "function": PatternPerformanceMetrics(),
-    "module": PatternPerformanceMetrics()
+    "test": PatternPerformanceMetrics(),
+    "module": PatternPerformanceMetrics(),
+    "cfg": PatternPerformanceMetrics()
 }
 
 RUST_PATTERNS = {
@@ -169,8 +184,9 @@ RUST_PATTERNS = {
                         bounds: (trait_bounds)? @syntax.trait.bounds
                         body: (declaration_list)? @syntax.trait.body) @syntax.trait.def,
                     (impl_item
-                        trait: (type_identifier) @syntax.impl.trait.name
-                        type: (type_identifier) @syntax.impl.type
+                        type_parameters: (type_parameters)? @syntax.impl.generics
+                        trait: [(type_identifier) (scoped_type_identifier) (generic_type)] @syntax.impl.trait.name
+                        type: (_) @syntax.impl.type
                         body: (declaration_list)? @syntax.impl.body) @syntax.impl.def
                 ]
                 """,
@@ -187,14 +203,19 @@ RUST_PATTERNS = {
                     "has_generics": "syntax.trait.generics" in node["captures"],
                     "has_bounds": "syntax.trait.bounds" in node["captures"],
                     "is_impl": "syntax.impl.def" in node["captures"],
+                    "implemented_for": node["captures"].get("syntax.impl.type", {}).get("text", ""),
+                    "impl_generics": "syntax.impl.generics" in node["captures"],
                     "relationships": {
                         PatternRelationType.CONTAINS: ["function", "type", "const"],
-                        PatternRelationType.DEPENDS_ON: ["trait", "module"]
+                        PatternRelationType.DEPENDS_ON: ["trait", "module"],
+                        PatternRelationType.IMPLEMENTS: (
+                            ["trait"] if "syntax.impl.def" in node["captures"] else []
+                        )
                     }
                 },
                 name="trait",
                 description="Matches Rust trait declarations and implementations",
-                examples=["trait Display { fn fmt(&self) -> String; }", "impl Debug for Point"],
+                examples=["trait Display { fn fmt(&self) -> String; }", "impl Debug for Point", "impl<T: Display> ToString for T"],
                 category=PatternCategory.SYNTAX,
                 purpose=PatternPurpose.UNDERSTANDING,
                 language_id=LANGUAGE_ID,
@@ -267,6 +288,58 @@ RUST_PATTERNS = {
                         "name_format": r'^[a-z_][a-zA-Z0-9_]*$'
                     }
                 }
+            ),
+            "test": TreeSitterAdaptivePattern(
+                pattern=f"""
+                [
+                    ((attribute_item) @syntax.test.attr
+                        .
+                        (function_item
+                            name: (identifier) @syntax.test.name
+                            body: (block)? @syntax.test.body) @syntax.test.def
+                        (#match? @syntax.test.attr "{_TEST_ATTRIBUTE}")),
+                    ((attribute_item) @syntax.test.mod.attr
+                        .
+                        (mod_item
+                            name: (identifier) @syntax.test.mod.name
+                            body: (declaration_list)? @syntax.test.mod.body) @syntax.test.mod.def
+                        (#match? @syntax.test.mod.attr "^#.cfg.test.]$"))
+                ]
+                """,
+                extract=lambda node: {
+                    "type": "test",
+                    "name": (
+                        node["captures"].get("syntax.test.name", {}).get("text", "") or
+                        node["captures"].get("syntax.test.mod.name", {}).get("text", "")
+                    ),
+                    "line_number": (
+                        node["captures"].get("syntax.test.def", {}).get("start_point", [0])[0] or
+                        node["captures"].get("syntax.test.mod.def", {}).get("start_point", [0])[0]
+                    ),
+                    "is_test_module": "syntax.test.mod.def" in node["captures"],
+                    "test_attribute": (
+                        node["captures"].get("syntax.test.attr", {}).get("text", "") or
+                        node["captures"].get("syntax.test.mod.attr", {}).get("text", "")
+                    ).strip("#[]"),
+                    "relationships": {
+                        PatternRelationType.CONTAINS: ["block", "statement"],
+                        PatternRelationType.USES: ["function", "module"]
+                    }
+                },
+                name="test",
+                description="Matches Rust test functions and #[cfg(test)] modules",
+                examples=["#[test]\nfn parses_empty() {}", "#[tokio::test]\nasync fn serves() {}", "#[cfg(test)]\nmod tests {}"],
+                category=PatternCategory.LEARNING,
+                purpose=PatternPurpose.FUNCTIONS,
+                language_id=LANGUAGE_ID,
+                confidence=0.9,
+                metadata={
+                    "metrics": PATTERN_METRICS["test"],
+                    "validation": {
+                        "required_fields": ["name"],
+                        "name_format": r'^[a-z_][a-zA-Z0-9_]*$'
+                    }
+                }
             )
         },
         PatternPurpose.MODULES: {
@@ -313,6 +386,60 @@ RUST_PATTERNS = {
                         "name_format": r'^[a-z_][a-zA-Z0-9_]*$'
                     }
                 }
+            ),
+            "cfg": TreeSitterAdaptivePattern(
+                pattern="""
+                [
+                    ((attribute_item) @syntax.cfg.attr
+                        .
+                        [
+                            (function_item name: (identifier) @syntax.cfg.name)
+                            (struct_item name: (type_identifier) @syntax.cfg.name)
+                            (enum_item name: (type_identifier) @syntax.cfg.name)
+                            (trait_item name: (type_identifier) @syntax.cfg.name)
+                            (mod_item name: (identifier) @syntax.cfg.name)
+                            (impl_item type: (_) @syntax.cfg.name)
+                        ] @syntax.cfg.def
+                        (#match? @syntax.cfg.attr "^#.cfg *[(]")),
+                    (inner_attribute_item) @syntax.cfg.inner
+                        (#match? @syntax.cfg.inner "^#!.cfg *[(]")
+                ]
+                """,
+                extract=lambda node: {
+                    "type": "cfg",
+                    "name": node["captures"].get("syntax.cfg.name", {}).get("text", ""),
+                    "line_number": (
+                        node["captures"].get("syntax.cfg.def", {}).get("start_point", [0])[0] or
+                        node["captures"].get("syntax.cfg.inner", {}).get("start_point", [0])[0]
+                    ),
+                    "predicate": _cfg_predicate(
+                        node["captures"].get("syntax.cfg.attr", {}).get("text", "") or
+                        node["captures"].get("syntax.cfg.inner", {}).get("text", "")
+                    ),
+                    "is_inner": "syntax.cfg.inner" in node["captures"],
+                    "features": _CFG_FEATURE.findall(
+                        node["captures"].get("syntax.cfg.attr", {}).get("text", "") or
+                        node["captures"].get("syntax.cfg.inner", {}).get("text", "")
+                    ),
+                    "relationships": {
+                        PatternRelationType.DEPENDS_ON: ["feature"],
+                        PatternRelationType.CONTAINS: ["function", "struct", "enum", "trait", "module"]
+                    }
+                },
+                name="cfg",
+                description="Matches items and modules gated by #[cfg(...)] predicates",
+                examples=["#[cfg(feature = \"serde\")]\nimpl Serialize for Config {}", "#![cfg(unix)]"],
+                category=PatternCategory.LEARNING,
+                purpose=PatternPurpose.MODULES,
+                language_id=LANGUAGE_ID,
+                confidence=0.9,
+                metadata={
+                    "metrics": PATTERN_METRICS["cfg"],
+                    "validation": {
+                        "required_fields": ["predicate"],
+                        "name_format": None
+                    }
+                }
             )
         }
     },
@@ -763,15 +890,24 @@ PATTERN_RELATIONSHIPS = {
     },
     "trait": {
         PatternRelationType.CONTAINS: ["function", "type", "const"],
-        PatternRelationType.DEPENDS_ON: ["trait", "module"]
+        PatternRelationType.DEPENDS_ON: ["trait", "module"],
+        PatternRelationType.IMPLEMENTS: ["trait"]
     },
     "function": {
         PatternRelationType.CONTAINS: ["block", "statement"],
         PatternRelationType.DEPENDS_ON: ["type", "module"]
     },
+    "test": {
+        PatternRelationType.CONTAINS: ["block", "statement"],
+        PatternRelationType.USES: ["function", "module"]
+    },
     "module": {
         PatternRelationType.CONTAINS: ["struct", "enum", "trait", "function"],
         PatternRelationType.DEPENDS_ON: ["module"]
+    },
+    "cfg": {
+        PatternRelationType.DEPENDS_ON: ["feature"],
+        PatternRelationType.CONTAINS: ["function", "struct", "enum", "trait", "module"]
     }
 }
 
@@ -65,7 +65,8 @@ PATTERN_METRICS = {
     "key_value": PatternPerformanceMetrics(),
     "array": PatternPerformanceMetrics(),
     "inline_table": PatternPerformanceMetrics(),
-    "string": PatternPerformanceMetrics()
+    "string": PatternPerformanceMetrics(),
+    "cargo_feature": PatternPerformanceMetrics()
 }
 
 TOML_PATTERNS = {
@@ -154,6 +155,46 @@ TOML_PATTERNS = {
                         "name_format": r'^[a-zA-Z0-9_.-]+$'
                     }
                 }
+            ),
+            "cargo_feature": TreeSitterResilientPattern(
+                pattern="""
+                (table
+                    (bare_key) @syntax.features.table
+                    (pair
+                        (_) @syntax.feature.name
+                        (array) @syntax.feature.enables) @syntax.feature.def
+                    (#eq? @syntax.features.table "features"))
+                """,
+                extract=lambda node: {
+                    "type": "cargo_feature",
+                    "name": node["captures"].get("syntax.feature.name", {}).get("text", "").strip('"'),
+                    "line_number": node["captures"].get("syntax.feature.def", {}).get("start_point", [0])[0],
+                    # "dep:x" and "x/feature" enable dependencies, bare names other features
+                    "enables": [
+                        value.strip().strip('"')
+                        for value in node["captures"].get("syntax.feature.enables", {}).get("text", "").strip("[]").split(",")
+                        if value.strip()
+                    ],
+                    "is_default": node["captures"].get("syntax.feature.name", {}).get("text", "") == "default",
+                    "relationships": {
+                        PatternRelationType.CONTAINED_BY: ["table"],
+                        PatternRelationType.DEPENDS_ON: ["cargo_feature"]
+                    }
+                },
+                name="cargo_feature",
+                description="Matches Cargo.toml [features] entries",
+                examples=['[features]\ndefault = ["std"]\nserde = ["dep:serde"]'],
+                category=PatternCategory.SYNTAX,
+                purpose=PatternPurpose.UNDERSTANDING,
+                language_id=LANGUAGE,
+                confidence=0.95,
+                metadata={
+                    "metrics": PATTERN_METRICS["cargo_feature"],
+                    "validation": {
+                        "required_fields": ["name"],
+                        "name_format": r'^[a-zA-Z0-9_.+-]+$'
+                    }
+                }
             )
         }
     },
@@ -621,6 +662,10 @@ PATTERN_RELATIONSHIPS = {
     "inline_table": {
         PatternRelationType.CONTAINS: ["key_value"],
         PatternRelationType.DEPENDS_ON: ["value"]
+    },
+    "cargo_feature": {
+        PatternRelationType.CONTAINED_BY: ["table"],
+        PatternRelationType.DEPENDS_ON: ["cargo_feature"]
     }
 }
 
@@ -0,0 +1,1456 @@
+"""Symbol extraction for cross-file name resolution.
+
+Flow:
+1. Definitions:
+   - Functions, classes and types, methods and module-level variables, each
+     with the scope it is defined in (e.g. "Parser" for Parser.parse)
+   - exported follows the language: no leading underscore (Python), export
+     (JavaScript/TypeScript), capitalized (Go), pub (Rust)
+
+2. References:
+   - Identifiers used in each scope, with the qualifier of member and path
+     expressions (self.x, pkg.Fn, crate::a::f) and whether they are called
+   - Names bound inside a function (parameters, assignments, loop and
+     pattern variables) are locals and left out
+   - References in a closure handed to a thread pool (spawn_blocking,
+     run_in_executor, ...) are marked offloaded; async functions are marked
+     async (Python, Rust)
+
+3. Imports:
+   - The local name each import binds, the module spec and the imported name
+     (None for a module, "*" for a wildcard)
+
+4. Rust Tests:
+   - Functions with #[test], #[tokio::test] and similar attributes are marked
+     test; mod items under #[cfg(test)] are marked cfg_test
+
+5. Rust cfg Predicates:
+   - The #[cfg(...)] predicates on items and impl blocks, and the #![cfg(...)]
+     of a file or inline module, for the feature map in indexer/rust_features.py
+
+6. Rust Doc Comments:
+   - /// and /** */ comments are kept as the doc of the item they precede;
+     //! and /*! */ comments as the doc of the file's or inline module's scope
+
+7. Rust Safety Sites:
+   - unsafe blocks, functions, impls and traits, unwrap()/expect() calls,
+     panicking macros, raw pointer types and extern blocks and functions,
+     with the scope they occur in, for the audit in indexer/rust_audit.py
+
+Supports Python, JavaScript/TypeScript, Go and Rust tree-sitter trees. The
+result is plain data so it can leave a parse worker and be stored as JSON;
+indexer/symbol_table.py [1.6] resolves it across files.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Any, Callable, Dict, List, Optional, Set, Tuple
+
+# Languages with an extractor, and the tree-sitter grammar used to re-parse them
+SYMBOL_LANGUAGES = {
+    "python": "python",
+    "javascript": "javascript",
+    "jsx": "javascript",
+    "typescript": "typescript",
+    "tsx": "tsx",
+    "go": "go",
+    "rust": "rust"
+}
+
+# Scope kinds whose members are not visible by bare name from nested scopes
+CLASS_SCOPES = {"class", "struct", "interface", "trait", "enum", "impl"}
+
+# Scope kinds whose bindings are locals
+FUNCTION_SCOPES = {"function", "method", "lambda"}
+
+# Calls that run the closure passed to them off the async runtime
+_OFFLOAD_CALLS = {"spawn_blocking", "block_in_place", "run_in_executor", "to_thread", "thread::spawn"}
+
+@dataclass
+class _Scope:
+    path: str
+    kind: str
+    parent: Optional['_Scope'] = None
+    locals: Set[str] = field(default_factory=set)
+    # The arguments of an offloading call (see _OFFLOAD_CALLS)
+    offload: bool = False
+
+    def child(self, name: Optional[str], kind: str) -> '_Scope':
+        """A nested scope; anonymous functions keep the enclosing path."""
+        if name is None:
+            return _Scope(self.path, kind, self)
+        return _Scope(f"{self.path}.{name}" if self.path else name, kind, self)
+
+    def is_local(self, name: str) -> bool:
+        scope = self
+        while scope is not None:
+            if scope.kind in FUNCTION_SCOPES and name in scope.locals:
+                return True
+            scope = scope.parent
+        return False
+
+    @property
+    def in_function(self) -> bool:
+        return self.kind in FUNCTION_SCOPES
+
+    @property
+    def offloaded(self) -> bool:
+        """Inside a closure passed to an offloading call."""
+        scope = self
+        while scope.kind == "lambda" and scope.parent is not None:
+            if scope.parent.offload:
+                return True
+            scope = scope.parent
+        return False
+
+    def offloading(self, function: Any) -> '_Scope':
+        """The scope for the arguments of a call to function."""
+        segments = _text(function).replace(".", "::").split("::")
+        if not any("::".join(segments[-len(call.split("::")):]) == call for call in _OFFLOAD_CALLS):
+            return self
+        return _Scope(self.path, self.kind, self.parent, self.locals, offload=True)
+
+def _text(node: Any) -> str:
+    return node.text.decode("utf-8", "replace") if node is not None else ""
+
+def _named_children(node: Any) -> List[Any]:
+    return [child for child in node.children if child.is_named]
+
+class _Extractor:
+    """Walks one tree; subclasses handle a language's node types."""
+
+    language = ""
+
+    def __init__(self):
+        self.definitions: List[Dict[str, Any]] = []
+        self.imports: List[Dict[str, Any]] = []
+        self.scopes: Dict[str, str] = {}
+        self.exports: Set[str] = set()
+        self.default_export: Optional[str] = None
+        self.package: Optional[str] = None
+        self.impls: List[Dict[str, Any]] = []
+        self._refs: List[Tuple[_Scope, str, Optional[str], str, Tuple[int, int]]] = []
+        self._stack: List[Tuple[Any, _Scope]] = []
+
+    # Recording ---------------------------------------------------------
+
+    def define(
+        self,
+        name_node: Any,
+        kind: str,
+        node: Any,
+        scope: _Scope,
+        exported: Optional[bool] = None,
+        name: Optional[str] = None
+    ) -> Optional[str]:
+        """Record a definition; returns its name."""
+        name = name or _text(name_node)
+        if not name:
+            return None
+        anchor = name_node if name_node is not None else node
+        self.definitions.append({
+            "name": name,
+            "kind": kind,
+            "scope": scope.path,
+            "line": anchor.start_point[0] + 1,
+            "column": anchor.start_point[1],
+            "end_line": node.end_point[0] + 1,
+            "exported": self.is_exported(name, node) if exported is None else exported
+        })
+        return name
+
+    def enter(self, scope: _Scope, name: Optional[str], kind: str) -> _Scope:
+        child = scope.child(name, kind)
+        if name is not None:
+            self.scopes.setdefault(child.path, kind)
+        return child
+
+    def reference(self, node: Any, scope: _Scope, name: Optional[str] = None, qualifier: Optional[str] = None, kind: str = "name") -> None:
+        name = name or _text(node)
+        if name:
+            self._refs.append((scope, name, qualifier, kind, (node.start_point[0] + 1, node.start_point[1])))
+
+    def bind(self, scope: _Scope, names: List[str]) -> None:
+        """Names bound in a function are locals; elsewhere the caller defines them."""
+        if scope.in_function:
+            scope.locals.update(names)
+
+    def add_import(self, module: Optional[str], name: Optional[str], alias: Optional[str], node: Any, **extra) -> None:
+        self.imports.append({
+            "module": module,
+            "name": name,
+            "alias": alias,
+            "line": node.start_point[0] + 1,
+            **extra
+        })
+
+    def is_exported(self, name: str, node: Any) -> bool:
+        return True
+
+    def visit(self, node: Any, scope: _Scope) -> None:
+        self._stack.append((node, scope))
+
+    def visit_children(self, node: Any, scope: _Scope, skip: Tuple[Any, ...] = ()) -> None:
+        for child in reversed(node.children):
+            if child.is_named and not any(child == other for other in skip if other is not None):
+                self._stack.append((child, scope))
+
+    # Walking -----------------------------------------------------------
+
+    def handle(self, node: Any, scope: _Scope) -> bool:
+        """Handle a node type; False to fall through to the generic walk."""
+        return False
+
+    def leaf(self, node: Any, scope: _Scope) -> None:
+        """Identifiers not consumed by a handler are references."""
+
+    def run(self, root: Any) -> Dict[str, Any]:
+        module = _Scope("", "module")
+        self._stack.append((root, module))
+        # Iterative: generated code can nest deeper than the recursion limit
+        while self._stack:
+            node, scope = self._stack.pop()
+            if self.handle(node, scope):
+                continue
+            if node.child_count == 0:
+                if node.is_named:
+                    self.leaf(node, scope)
+                continue
+            self.visit_children(node, scope)
+        return self.result()
+
+    def result(self) -> Dict[str, Any]:
+        for definition in self.definitions:
+            if definition["name"] in self.exports and not definition["scope"]:
+                definition["exported"] = True
+            if self.default_export and definition["name"] == self.default_export and not definition["scope"]:
+                definition["default"] = True
+
+        # One entry per name, qualifier, scope and kind, with every line it occurs on
+        merged: Dict[Tuple[str, Optional[str], str, str, bool], Dict[str, Any]] = {}
+        for scope, name, qualifier, kind, (line, column) in self._refs:
+            if qualifier is None and scope.is_local(name):
+                continue
+            key = (name, qualifier, scope.path, kind, scope.offloaded)
+            entry = merged.get(key)
+            if entry is None:
+                entry = merged[key] = {
+                    "name": name,
+                    "qualifier": qualifier,
+                    "scope": scope.path,
+                    "kind": kind,
+                    "line": line,
+                    "column": column,
+                    "lines": [line]
+                }
+                if scope.offloaded:
+                    entry["offloaded"] = True
+            elif line not in entry["lines"]:
+                entry["lines"].append(line)
+
+        result = {
+            "language": self.language,
+            "definitions": self.definitions,
+            "references": list(merged.values()),
+            "imports": self.imports,
+            "scopes": self.scopes
+        }
+        if self.package:
+            result["package"] = self.package
+        if self.impls:
+            result["impls"] = self.impls
+        return result
+
+# Python ------------------------------------------------------------------
+
+_PY_BINDING_CONTAINERS = {"pattern_list", "tuple_pattern", "list_pattern", "list_splat_pattern", "parenthesized_expression", "tuple", "list"}
+
+def _py_binding_names(node: Any) -> List[str]:
+    """Names bound by an assignment target or parameter list."""
+    if node is None:
+        return []
+    if node.type == "identifier":
+        return [_text(node)]
+    if node.type in _PY_BINDING_CONTAINERS or node.type in ("parameters", "lambda_parameters"):
+        names = []
+        for child in _named_children(node):
+            names.extend(_py_binding_names(child))
+        return names
+    if node.type in ("typed_parameter", "list_splat_pattern", "dictionary_splat_pattern"):
+        first = next((child for child in _named_children(node) if child.type == "identifier"), None)
+        return [_text(first)] if first is not None else []
+    if node.type in ("default_parameter", "typed_default_parameter"):
+        return _py_binding_names(node.child_by_field_name("name"))
+    if node.type == "as_pattern":
+        return _py_binding_names(node.child_by_field_name("alias"))
+    if node.type == "as_pattern_target":
+        return [name for child in _named_children(node) for name in _py_binding_names(child)]
+    return []
+
+def _dotted(node: Any) -> Optional[str]:
+    """Text of a plain name chain (a, a.b, a::b), or None for anything else."""
+    if node is None:
+        return None
+    if node.type in ("identifier", "this", "self", "super", "crate", "package_identifier", "type_identifier", "field_identifier", "property_identifier"):
+        return _text(node)
+    if node.type in ("attribute", "member_expression", "selector_expression", "scoped_identifier", "field_expression", "dotted_name", "scoped_type_identifier"):
+        text = _text(node)
+        return text if all(ch.isalnum() or ch in "_.:$" for ch in text) else None
+    return None
+
+class _PythonExtractor(_Extractor):
+    language = "python"
+
+    def is_exported(self, name: str, node: Any) -> bool:
+        return not name.startswith("_")
+
+    def handle(self, node: Any, scope: _Scope) -> bool:
+        t = node.type
+        if t == "function_definition":
+            kind = "method" if scope.kind == "class" else "function"
+            name = self.define(node.child_by_field_name("name"), kind, node, scope)
+            if name and any(child.type == "async" for child in node.children):
+                self.definitions[-1]["async"] = True
+            inner = self.enter(scope, name, kind)
+            parameters = node.child_by_field_name("parameters")
+            inner.locals.update(_py_binding_names(parameters))
+            # Defaults and annotations are evaluated where the function is defined
+            if parameters is not None:
+                for child in _named_children(parameters):
+                    for part in ("value", "type"):
+                        sub = child.child_by_field_name(part)
+                        if sub is not None:
+                            self.visit(sub, scope)
+            for part in ("return_type", "body"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope if part == "return_type" else inner)
+            return True
+        if t == "lambda":
+            inner = scope.child(None, "lambda")
+            inner.locals.update(_py_binding_names(node.child_by_field_name("parameters")))
+            self.visit(node.child_by_field_name("body"), inner)
+            return True
+        if t == "class_definition":
+            name = self.define(node.child_by_field_name("name"), "class", node, scope)
+            superclasses = node.child_by_field_name("superclasses")
+            if superclasses is not None:
+                self.visit(superclasses, scope)
+            self.visit(node.child_by_field_name("body"), self.enter(scope, name, "class"))
+            return True
+        if t == "import_statement":
+            for child in node.children_by_field_name("name"):
+                if child.type == "aliased_import":
+                    module = _text(child.child_by_field_name("name"))
+                    self.add_import(module, None, _text(child.child_by_field_name("alias")), child)
+                else:
+                    # `import a.b` binds a; a.b.f resolves through it
+                    module = _text(child)
+                    self.add_import(module.split(".")[0], None, module.split(".")[0], child, path=module)
+            return True
+        if t == "import_from_statement":
+            module = _text(node.child_by_field_name("module_name"))
+            for child in node.children_by_field_name("name"):
+                if child.type == "aliased_import":
+                    name = _text(child.child_by_field_name("name"))
+                    self.add_import(module, name, _text(child.child_by_field_name("alias")), child)
+                else:
+                    name = _text(child)
+                    self.add_import(module, name, name, child)
+            if any(child.type == "wildcard_import" for child in node.children):
+                self.add_import(module, "*", None, node)
+            return True
+        if t in ("assignment", "augmented_assignment"):
+            left = node.child_by_field_name("left")
+            names = _py_binding_names(left)
+            if t == "assignment" and names:
+                if scope.in_function:
+                    self.bind(scope, names)
+                elif left.type == "identifier":
+                    self.define(left, "variable" if scope.kind == "module" else "property", node, scope)
+                else:
+                    for identifier in self._identifiers(left):
+                        self.define(identifier, "variable" if scope.kind == "module" else "property", node, scope)
+                if left.type not in ("identifier",) and left.type not in _PY_BINDING_CONTAINERS:
+                    self.visit(left, scope)
+            else:
+                self.visit(left, scope)
+            for part in ("right", "type"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t in ("for_statement", "for_in_clause"):
+            self.bind(scope, _py_binding_names(node.child_by_field_name("left")))
+            self.visit_children(node, scope, skip=(node.child_by_field_name("left"),))
+            return True
+        if t == "named_expression":
+            self.bind(scope, _py_binding_names(node.child_by_field_name("name")))
+            self.visit(node.child_by_field_name("value"), scope)
+            return True
+        if t in ("as_pattern", "with_item", "except_clause"):
+            alias = node.child_by_field_name("alias")
+            if alias is not None:
+                self.bind(scope, _py_binding_names(alias))
+            self.visit_children(node, scope, skip=(alias,))
+            return True
+        if t in ("global_statement", "nonlocal_statement"):
+            return True
+        if t == "keyword_argument":
+            self.visit(node.child_by_field_name("value"), scope)
+            return True
+        if t == "call":
+            function = node.child_by_field_name("function")
+            self._member(function, scope, "call")
+            self.visit(node.child_by_field_name("arguments"), scope.offloading(function))
+            return True
+        if t == "attribute":
+            self._member(node, scope, "name")
+            return True
+        return False
+
+    def _identifiers(self, node: Any) -> List[Any]:
+        found, stack = [], [node]
+        while stack:
+            current = stack.pop()
+            if current.type == "identifier":
+                found.append(current)
+            elif current.type in _PY_BINDING_CONTAINERS:
+                stack.extend(_named_children(current))
+        return found
+
+    def _member(self, node: Any, scope: _Scope, kind: str) -> None:
+        if node is None:
+            return
+        if node.type == "identifier":
+            self.reference(node, scope, kind=kind)
+        elif node.type == "attribute":
+            obj = node.child_by_field_name("object")
+            attribute = node.child_by_field_name("attribute")
+            qualifier = _dotted(obj)
+            self.reference(attribute, scope, qualifier=qualifier, kind=kind)
+            if qualifier is None:
+                self.visit(obj, scope)
+            else:
+                # The head of the chain is a reference itself (a module, class or local)
+                head = obj
+                while head.type == "attribute":
+                    head = head.child_by_field_name("object")
+                self.reference(head, scope)
+        else:
+            self.visit(node, scope)
+
+    def leaf(self, node: Any, scope: _Scope) -> None:
+        if node.type == "identifier":
+            self.reference(node, scope)
+
+# JavaScript / TypeScript -------------------------------------------------
+
+_JS_FUNCTION_VALUES = {"arrow_function", "function_expression", "function", "generator_function"}
+_JS_CLASS_VALUES = {"class"}
+
+def _js_binding_names(node: Any) -> List[str]:
+    if node is None:
+        return []
+    if node.type in ("identifier", "shorthand_property_identifier_pattern"):
+        return [_text(node)]
+    if node.type in ("required_parameter", "optional_parameter"):
+        return _js_binding_names(node.child_by_field_name("pattern"))
+    if node.type == "assignment_pattern":
+        return _js_binding_names(node.child_by_field_name("left"))
+    if node.type == "pair_pattern":
+        return _js_binding_names(node.child_by_field_name("value"))
+    if node.type in ("formal_parameters", "object_pattern", "array_pattern", "rest_pattern", "object_assignment_pattern"):
+        names = []
+        for child in _named_children(node):
+            names.extend(_js_binding_names(child))
+        return names
+    return []
+
+class _JavaScriptExtractor(_Extractor):
+    language = "javascript"
+
+    def __init__(self, language: str = "javascript"):
+        super().__init__()
+        self.language = language
+
+    def is_exported(self, name: str, node: Any) -> bool:
+        # export statements mark their declarations once the walk is done
+        return False
+
+    def handle(self, node: Any, scope: _Scope) -> bool:
+        t = node.type
+        if t == "export_statement":
+            declaration = node.child_by_field_name("declaration")
+            source = node.child_by_field_name("source")
+            is_default = any(child.type == "default" for child in node.children)
+            if source is not None:
+                # Re-exports: export { a } from './x', export * from './x'
+                module = _text(source).strip("'\"`")
+                clause = next((child for child in node.children if child.type == "export_clause"), None)
+                if clause is None:
+                    self.add_import(module, "*", None, node, reexport=True)
+                else:
+                    for specifier in _named_children(clause):
+                        name = _text(specifier.child_by_field_name("name"))
+                        alias = _text(specifier.child_by_field_name("alias")) or name
+                        self.add_import(module, name, alias, specifier, reexport=True)
+                        self.exports.add(alias)
+                return True
+            if declaration is not None:
+                self.visit(declaration, scope)
+                # Definitions are recorded when the stack reaches them; mark by name afterwards
+                self._mark_export(declaration, is_default)
+                return True
+            for child in node.children:
+                if child.type == "export_clause":
+                    for specifier in _named_children(child):
+                        name = _text(specifier.child_by_field_name("name"))
+                        alias = _text(specifier.child_by_field_name("alias"))
+                        self.exports.add(name)
+                        if alias == "default":
+                            self.default_export = name
+                        self.reference(specifier.child_by_field_name("name"), scope)
+                elif child.is_named and is_default:
+                    if child.type == "identifier":
+                        self.default_export = _text(child)
+                    self.visit(child, scope)
+            return True
+        if t in ("function_declaration", "generator_function_declaration", "function_signature"):
+            name = self.define(node.child_by_field_name("name"), "function", node, scope)
+            self._function(node, scope, name, "function")
+            return True
+        if t in _JS_FUNCTION_VALUES:
+            self._function(node, scope, None, "lambda")
+            return True
+        if t in ("class_declaration", "abstract_class_declaration", "class"):
+            name_node = node.child_by_field_name("name")
+            name = self.define(name_node, "class", node, scope) if t != "class" or name_node is not None else None
+            for child in node.children:
+                if child.type in ("class_heritage", "extends_clause", "implements_clause", "type_parameters"):
+                    self.visit(child, scope)
+            self.visit(node.child_by_field_name("body"), self.enter(scope, name, "class") if name else scope.child(None, "class"))
+            return True
+        if t in ("method_definition", "method_signature", "abstract_method_signature"):
+            name = self.define(node.child_by_field_name("name"), "method", node, scope)
+            self._function(node, scope, name, "method")
+            return True
+        if t in ("public_field_definition", "field_definition"):
+            name_node = node.child_by_field_name("name") or node.child_by_field_name("property")
+            self.define(name_node, "property", node, scope)
+            value = node.child_by_field_name("value")
+            if value is not None:
+                self.visit(value, scope)
+            return True
+        if t in ("interface_declaration", "type_alias_declaration", "enum_declaration", "internal_module", "module"):
+            kind = {"interface_declaration": "interface", "type_alias_declaration": "type", "enum_declaration": "enum"}.get(t, "module")
+            name_node = node.child_by_field_name("name")
+            name = self.define(name_node, kind, node, scope)
+            inner = self.enter(scope, name, kind if kind != "type" else "module") if name else scope
+            self.visit_children(node, inner, skip=(name_node,))
+            return True
+        if t == "variable_declarator":
+            name_node = node.child_by_field_name("name")
+            value = node.child_by_field_name("value")
+            if value is not None and value.type == "call_expression" and self._require(name_node, value):
+                return True
+            if name_node is not None and name_node.type == "identifier":
+                if scope.in_function:
+                    self.bind(scope, [_text(name_node)])
+                else:
+                    kind = "function" if value is not None and value.type in _JS_FUNCTION_VALUES else (
+                        "class" if value is not None and value.type in _JS_CLASS_VALUES else "variable")
+                    self.define(name_node, kind, node, scope)
+                    if kind == "function":
+                        self._function(value, scope, _text(name_node), "function")
+                        return True
+            else:
+                names = _js_binding_names(name_node)
+                if scope.in_function:
+                    self.bind(scope, names)
+                else:
+                    for identifier in self._pattern_identifiers(name_node):
+                        self.define(identifier, "variable", node, scope)
+            for part in ("type", "value"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t == "import_statement":
+            source = node.child_by_field_name("source")
+            module = _text(source).strip("'\"`")
+            for clause in node.children:
+                if clause.type != "import_clause":
+                    continue
+                for child in _named_children(clause):
+                    if child.type == "identifier":
+                        self.add_import(module, "default", _text(child), child)
+                    elif child.type == "namespace_import":
+                        alias = next((c for c in _named_children(child) if c.type == "identifier"), None)
+                        self.add_import(module, None, _text(alias), child)
+                    elif child.type == "named_imports":
+                        for specifier in _named_children(child):
+                            if specifier.type != "import_specifier":
+                                continue
+                            name = _text(specifier.child_by_field_name("name"))
+                            alias = _text(specifier.child_by_field_name("alias")) or name
+                            self.add_import(module, name, alias, specifier)
+            if not any(child.type == "import_clause" for child in node.children):
+                # Side-effect import
+                self.add_import(module, None, None, node)
+            return True
+        if t in ("catch_clause",):
+            parameter = node.child_by_field_name("parameter")
+            self.bind(scope, _js_binding_names(parameter))
+            self.visit_children(node, scope, skip=(parameter,))
+            return True
+        if t in ("for_in_statement",):
+            left = node.child_by_field_name("left")
+            self.bind(scope, _js_binding_names(left))
+            self.visit_children(node, scope, skip=(left,))
+            return True
+        if t in ("call_expression", "new_expression"):
+            function = node.child_by_field_name("function") or node.child_by_field_name("constructor")
+            self._member(function, scope, "call")
+            for part in ("arguments", "type_arguments"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t == "member_expression":
+            self._member(node, scope, "name")
+            return True
+        if t in ("pair", "property_signature"):
+            # Object keys are not references
+            value = node.child_by_field_name("value") or node.child_by_field_name("type")
+            if value is not None:
+                self.visit(value, scope)
+            return True
+        if t in ("jsx_opening_element", "jsx_self_closing_element"):
+            name = node.child_by_field_name("name")
+            # <Component /> refers to a binding; <div /> does not
+            if name is not None and _text(name)[:1].isupper():
+                self._member(name, scope, "call")
+            for child in node.children_by_field_name("attribute"):
+                self.visit(child, scope)
+            return True
+        if t == "jsx_attribute":
+            self.visit_children(node, scope, skip=(node.children[0] if node.children else None,))
+            return True
+        return False
+
+    def _function(self, node: Any, scope: _Scope, name: Optional[str], kind: str) -> None:
+        inner = self.enter(scope, name, kind) if name else scope.child(None, kind)
+        parameters = node.child_by_field_name("parameters") or node.child_by_field_name("parameter")
+        inner.locals.update(_js_binding_names(parameters))
+        if parameters is not None:
+            # Default values and parameter types
+            for child in _named_children(parameters):
+                for part in ("value", "right", "type"):
+                    sub = child.child_by_field_name(part)
+                    if sub is not None:
+                        self.visit(sub, scope)
+        for part in ("return_type", "type_parameters", "body"):
+            sub = node.child_by_field_name(part)
+            if sub is not None:
+                self.visit(sub, inner if part == "body" else scope)
+
+    def _pattern_identifiers(self, node: Any) -> List[Any]:
+        found, stack = [], [node]
+        while stack:
+            current = stack.pop()
+            if current is None:
+                continue
+            if current.type in ("identifier", "shorthand_property_identifier_pattern"):
+                found.append(current)
+            elif current.type == "pair_pattern":
+                stack.append(current.child_by_field_name("value"))
+            elif current.type == "assignment_pattern":
+                stack.append(current.child_by_field_name("left"))
+            elif current.type in ("object_pattern", "array_pattern", "rest_pattern", "object_assignment_pattern"):
+                stack.extend(_named_children(current))
+        return found
+
+    def _require(self, name_node: Any, call: Any) -> bool:
+        """const x = require('m') and const { a, b: c } = require('m')."""
+        function = call.child_by_field_name("function")
+        arguments = call.child_by_field_name("arguments")
+        if function is None or _text(function) != "require" or arguments is None:
+            return False
+        strings = [child for child in _named_children(arguments) if child.type == "string"]
+        if len(strings) != 1 or name_node is None:
+            return False
+        module = _text(strings[0]).strip("'\"`")
+        if name_node.type == "identifier":
+            self.add_import(module, None, _text(name_node), name_node, require=True)
+        elif name_node.type == "object_pattern":
+            for child in _named_children(name_node):
+                if child.type == "shorthand_property_identifier_pattern":
+                    self.add_import(module, _text(child), _text(child), child, require=True)
+                elif child.type == "pair_pattern":
+                    key = _text(child.child_by_field_name("key"))
+                    value = child.child_by_field_name("value")
+                    if value is not None and value.type == "identifier":
+                        self.add_import(module, key, _text(value), child, require=True)
+        else:
+            return False
+        return True
+
+    def _mark_export(self, declaration: Any, is_default: bool) -> None:
+        names = []
+        name_node = declaration.child_by_field_name("name")
+        if name_node is not None:
+            names.append(_text(name_node))
+        for child in _named_children(declaration):
+            if child.type == "variable_declarator":
+                names.extend(_js_binding_names(child.child_by_field_name("name")))
+        self.exports.update(names)
+        if is_default and names:
+            self.default_export = names[0]
+
+    def _member(self, node: Any, scope: _Scope, kind: str) -> None:
+        if node is None:
+            return
+        if node.type in ("identifier", "type_identifier"):
+            self.reference(node, scope, kind=kind)
+        elif node.type in ("member_expression", "nested_identifier", "member_expression"):
+            obj = node.child_by_field_name("object")
+            prop = node.child_by_field_name("property")
+            if obj is None or prop is None:
+                named = _named_children(node)
+                if len(named) < 2:
+                    self.visit(node, scope)
+                    return
+                obj, prop = named[0], named[-1]
+            qualifier = _dotted(obj)
+            self.reference(prop, scope, qualifier=qualifier, kind=kind)
+            if qualifier is None:
+                self.visit(obj, scope)
+            else:
+                head = obj
+                while head.type in ("member_expression", "nested_identifier") and head.child_by_field_name("object") is not None:
+                    head = head.child_by_field_name("object")
+                if head.type == "identifier":
+                    self.reference(head, scope)
+        else:
+            self.visit(node, scope)
+
+    def leaf(self, node: Any, scope: _Scope) -> None:
+        if node.type in ("identifier", "type_identifier", "shorthand_property_identifier"):
+            self.reference(node, scope, kind="type" if node.type == "type_identifier" else "name")
+
+# Go ----------------------------------------------------------------------
+
+def _go_receiver_type(receiver: Any) -> Optional[str]:
+    """T for a (t *T) or (t T[K]) receiver."""
+    if receiver is None:
+        return None
+    for parameter in _named_children(receiver):
+        node = parameter.child_by_field_name("type")
+        while node is not None and node.type in ("pointer_type", "generic_type", "parenthesized_type"):
+            inner = node.child_by_field_name("type")
+            node = inner if inner is not None else next(iter(_named_children(node)), None)
+        if node is not None:
+            return _text(node)
+    return None
+
+def _go_parameter_names(parameters: Any) -> List[str]:
+    names = []
+    if parameters is None:
+        return names
+    for parameter in _named_children(parameters):
+        for name in parameter.children_by_field_name("name"):
+            names.append(_text(name))
+    return names
+
+class _GoExtractor(_Extractor):
+    language = "go"
+
+    def is_exported(self, name: str, node: Any) -> bool:
+        return name[:1].isupper()
+
+    def handle(self, node: Any, scope: _Scope) -> bool:
+        t = node.type
+        if t == "package_clause":
+            package = next((child for child in _named_children(node) if child.type == "package_identifier"), None)
+            self.package = _text(package) or None
+            return True
+        if t in ("function_declaration", "method_declaration"):
+            receiver = node.child_by_field_name("receiver")
+            owner = _go_receiver_type(receiver) if t == "method_declaration" else None
+            owner_scope = self.enter(scope, owner, "struct") if owner else scope
+            name = self.define(node.child_by_field_name("name"), "method" if owner else "function", node, owner_scope)
+            inner = self.enter(owner_scope, name, "method" if owner else "function")
+            receiver_names = _go_parameter_names(receiver)
+            inner.locals.update(receiver_names)
+            if name and owner and receiver_names:
+                # t.Method() inside a method of T resolves like self.method()
+                self.definitions[-1]["receiver"] = receiver_names[0]
+            for part in ("type_parameters", "parameters", "result"):
+                sub = node.child_by_field_name(part)
+                if sub is None:
+                    continue
+                inner.locals.update(_go_parameter_names(sub))
+                for parameter in _named_children(sub):
+                    kind_node = parameter.child_by_field_name("type")
+                    self.visit(kind_node if kind_node is not None else parameter, scope)
+            self.visit(node.child_by_field_name("body"), inner)
+            return True
+        if t == "func_literal":
+            inner = scope.child(None, "lambda")
+            parameters = node.child_by_field_name("parameters")
+            inner.locals.update(_go_parameter_names(parameters))
+            self.visit(node.child_by_field_name("body"), inner)
+            return True
+        if t in ("type_spec", "type_alias"):
+            name_node = node.child_by_field_name("name")
+            body = node.child_by_field_name("type")
+            kind = {"struct_type": "struct", "interface_type": "interface"}.get(body.type if body is not None else "", "type")
+            name = self.define(name_node, kind, node, scope)
+            if body is not None and kind == "interface":
+                inner = self.enter(scope, name, "interface")
+                for child in _named_children(body):
+                    if child.type in ("method_spec", "method_elem"):
+                        self.define(child.child_by_field_name("name"), "method", child, inner)
+                        for part in ("parameters", "result"):
+                            sub = child.child_by_field_name(part)
+                            if sub is not None:
+                                self.visit(sub, scope)
+                    else:
+                        self.visit(child, scope)
+            elif body is not None:
+                if kind == "struct":
+                    self.scopes.setdefault(scope.child(name, kind).path, kind)
+                self.visit(body, scope)
+            return True
+        if t in ("const_spec", "var_spec"):
+            names = node.children_by_field_name("name")
+            if scope.in_function:
+                self.bind(scope, [_text(name) for name in names])
+            else:
+                for name in names:
+                    self.define(name, "constant" if t == "const_spec" else "variable", node, scope)
+            for part in ("type", "value"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t == "short_var_declaration":
+            left = node.child_by_field_name("left")
+            self.bind(scope, [_text(child) for child in _named_children(left) if child.type == "identifier"])
+            self.visit(node.child_by_field_name("right"), scope)
+            return True
+        if t == "range_clause":
+            left = node.child_by_field_name("left")
+            if left is not None and any(child.type == ":=" for child in node.children):
+                self.bind(scope, [_text(child) for child in _named_children(left) if child.type == "identifier"])
+            self.visit(node.child_by_field_name("right"), scope)
+            return True
+        if t == "import_spec":
+            path = _text(node.child_by_field_name("path")).strip('"`')
+            name = node.child_by_field_name("name")
+            alias = _text(name) if name is not None else path.rstrip("/").split("/")[-1]
+            if alias == "_":
+                return True
+            if alias == ".":
+                self.add_import(path, "*", None, node)
+            else:
+                self.add_import(path, None, alias, node, implicit=name is None)
+            return True
+        if t == "call_expression":
+            self._member(node.child_by_field_name("function"), scope, "call")
+            for part in ("type_arguments", "arguments"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t == "selector_expression":
+            self._member(node, scope, "name")
+            return True
+        if t == "qualified_type":
+            package = node.child_by_field_name("package")
+            self.reference(node.child_by_field_name("name"), scope, qualifier=_text(package), kind="type")
+            self.reference(package, scope)
+            return True
+        if t == "keyed_element":
+            # Struct literal keys are field names
+            named = _named_children(node)
+            if len(named) == 2:
+                key = named[0]
+                if key.type in ("field_identifier", "literal_element") and key.child_count <= 1 and _text(key).isidentifier():
+                    self.visit(named[1], scope)
+                    return True
+            return False
+        if t == "labeled_statement":
+            self.visit_children(node, scope, skip=(node.child_by_field_name("label"),))
+            return True
+        return False
+
+    def _member(self, node: Any, scope: _Scope, kind: str) -> None:
+        if node is None:
+            return
+        if node.type == "identifier":
+            self.reference(node, scope, kind=kind)
+        elif node.type == "selector_expression":
+            operand = node.child_by_field_name("operand")
+            field_node = node.child_by_field_name("field")
+            qualifier = _dotted(operand)
+            self.reference(field_node, scope, qualifier=qualifier, kind=kind)
+            if qualifier is None:
+                self.visit(operand, scope)
+            else:
+                head = operand
+                while head.type == "selector_expression":
+                    head = head.child_by_field_name("operand")
+                self.reference(head, scope)
+        elif node.type in ("generic_type", "index_expression"):
+            named = _named_children(node)
+            if named:
+                self._member(named[0], scope, kind)
+                for child in named[1:]:
+                    self.visit(child, scope)
+        else:
+            self.visit(node, scope)
+
+    def leaf(self, node: Any, scope: _Scope) -> None:
+        if node.type in ("identifier", "type_identifier"):
+            self.reference(node, scope, kind="type" if node.type == "type_identifier" else "name")
+
+# Rust --------------------------------------------------------------------
+
+_RUST_ITEMS = {
+    "function_item": "function",
+    "function_signature_item": "function",
+    "struct_item": "struct",
+    "enum_item": "enum",
+    "union_item": "struct",
+    "trait_item": "trait",
+    "type_item": "type",
+    "const_item": "constant",
+    "static_item": "variable",
+    "mod_item": "module",
+    "macro_definition": "macro"
+}
+
+_RUST_PATTERN_CONTAINERS = {
+    "tuple_pattern", "tuple_struct_pattern", "struct_pattern", "field_pattern", "ref_pattern",
+    "reference_pattern", "mut_pattern", "slice_pattern", "or_pattern", "captured_pattern", "remaining_field_pattern"
+}
+
+def _rust_pattern_names(node: Any) -> List[str]:
+    """Bindings of a let/for/match/parameter pattern (lower-case identifiers)."""
+    names, stack = [], [node]
+    while stack:
+        current = stack.pop()
+        if current is None:
+            continue
+        if current.type in ("identifier", "shorthand_field_identifier"):
+            text = _text(current)
+            if text[:1].islower() or text[:1] == "_":
+                names.append(text)
+        elif current.type == "self":
+            names.append("self")
+        elif current.type in _RUST_PATTERN_CONTAINERS:
+            if current.type == "tuple_struct_pattern":
+                # The first child is the variant or struct path
+                stack.extend(_named_children(current)[1:])
+            elif current.type == "struct_pattern":
+                stack.extend(child for child in _named_children(current) if child.type != "type_identifier" and child.type != "scoped_type_identifier")
+            elif current.type == "field_pattern":
+                pattern = current.child_by_field_name("pattern")
+                stack.append(pattern if pattern is not None else current.child_by_field_name("name"))
+            else:
+                stack.extend(_named_children(current))
+    return names
+
+_RUST_PATH_ATTRIBUTE = re.compile(r'^path\s*=\s*"([^"]+)"$')
+
+# Attributes that make a function a test: #[test], #[tokio::test], #[rstest], ...
+RUST_TEST_ATTRIBUTES = {"test", "bench", "rstest", "test_case", "quickcheck", "proptest"}
+
+def _rust_attributes(node: Any) -> List[str]:
+    """Contents of the outer attributes on an item: ['test'], ['cfg(test)'], ['path = "x.rs"']."""
+    attributes = []
+    sibling = node.prev_named_sibling
+    while sibling is not None and sibling.type in ("attribute_item", "line_comment", "block_comment"):
+        if sibling.type == "attribute_item":
+            text = _text(sibling).strip()
+            if text.startswith("#[") and text.endswith("]"):
+                attributes.append(text[2:-1].strip())
+        sibling = sibling.prev_named_sibling
+    return list(reversed(attributes))
+
+def _rust_path_attribute(node: Any) -> Optional[str]:
+    """The file of a #[path = "..."] attribute on an item."""
+    for attribute in _rust_attributes(node):
+        match = _RUST_PATH_ATTRIBUTE.match(attribute)
+        if match:
+            return match.group(1)
+    return None
+
+def _rust_cfg(attributes: List[str]) -> List[str]:
+    """Predicates of the cfg attributes among attributes: ['feature = "std"']."""
+    predicates = []
+    for attribute in attributes:
+        if attribute.startswith("cfg") and attribute[3:].lstrip().startswith("("):
+            predicate = attribute[3:].strip()[1:-1]
+            predicates.append(" ".join(predicate.split()))
+    return predicates
+
+def _rust_test_attribute(node: Any) -> Optional[str]:
+    """The attribute marking a function as a test (test, tokio::test), if any."""
+    for attribute in _rust_attributes(node):
+        name = attribute.split("(", 1)[0].strip()
+        if name.rpartition("::")[2] in RUST_TEST_ATTRIBUTES:
+            return name
+    return None
+
+def _rust_type_name(node: Any) -> Optional[str]:
+    """Base name of an impl target: Foo for Foo, Foo<T>, a::Foo, &Foo."""
+    while node is not None:
+        if node.type == "type_identifier":
+            return _text(node)
+        if node.type == "generic_type":
+            node = node.child_by_field_name("type")
+        elif node.type == "scoped_type_identifier":
+            node = node.child_by_field_name("name")
+        elif node.type in ("reference_type", "pointer_type"):
+            node = node.child_by_field_name("type")
+        else:
+            return None
+    return None
+
+def _rust_type_path(node: Any) -> Tuple[Optional[str], Optional[str]]:
+    """(name, qualifier) of a trait or type: (Display, fmt) for fmt::Display<T>."""
+    while node is not None and node.type in ("generic_type", "reference_type", "pointer_type"):
+        node = node.child_by_field_name("type")
+    if node is None:
+        return None, None
+    if node.type == "scoped_type_identifier":
+        path = node.child_by_field_name("path")
+        return _text(node.child_by_field_name("name")) or None, _text(path) or None
+    if node.type == "type_identifier":
+        return _text(node), None
+    return None, None
+
+def _rust_type_parameters(node: Any) -> List[str]:
+    """Names of the type parameters of an impl or item: T and U for <'a, T: Clone, U>."""
+    names = []
+    for child in _named_children(node) if node is not None else []:
+        if child.type == "type_identifier":
+            names.append(_text(child))
+        elif child.type in ("constrained_type_parameter", "optional_type_parameter"):
+            name = child.child_by_field_name("left") or child.child_by_field_name("name")
+            if name is not None and name.type == "type_identifier":
+                names.append(_text(name))
+    return names
+
+# Macros that panic when reached
+_RUST_PANIC_MACROS = {"panic", "unreachable", "todo", "unimplemented"}
+
+# Methods that panic on None/Err
+_RUST_PANIC_METHODS = {"unwrap", "expect", "unwrap_err", "expect_err"}
+
+def _rust_abi(node: Any) -> Optional[str]:
+    """ABI of an extern modifier ("C" for extern "C"), or None without one."""
+    for child in node.children if node is not None else []:
+        if child.type == "extern_modifier":
+            literal = next((sub for sub in _named_children(child) if sub.type == "string_literal"), None)
+            return _text(literal).strip('"') if literal is not None else "C"
+        if child.type == "function_modifiers":
+            return _rust_abi(child)
+    return None
+
+def _rust_doc_line(text: str, inner: bool) -> Optional[str]:
+    """Body of a doc comment, or None for a comment that isn't an (inner or outer) doc."""
+    line, block = ("//!", "/*!") if inner else ("///", "/**")
+    # //// and /*** start plain comments
+    if text.startswith(line) and not text.startswith("////"):
+        body = text[3:]
+        return body[1:] if body.startswith(" ") else body
+    if text.startswith(block) and text.endswith("*/") and not text.startswith("/***") and text != "/**/":
+        lines = []
+        for raw in text[3:-2].splitlines():
+            raw = raw.strip()
+            raw = raw[1:] if raw.startswith("*") else raw
+            lines.append(raw[1:] if raw.startswith(" ") else raw)
+        return "\n".join(lines).strip("\n")
+    return None
+
+def _rust_doc(node: Any) -> Optional[str]:
+    """The outer doc comments directly above an item, past its attributes."""
+    lines = []
+    sibling = node.prev_sibling
+    while sibling is not None and sibling.type in ("line_comment", "block_comment", "attribute_item"):
+        if sibling.type != "attribute_item":
+            body = _rust_doc_line(_text(sibling).rstrip("\n"), inner=False)
+            if body is not None:
+                lines.append(body)
+        sibling = sibling.prev_sibling
+    return "\n".join(reversed(lines)) if lines else None
+
+class _RustExtractor(_Extractor):
+    language = "rust"
+
+    def __init__(self):
+        super().__init__()
+        self.safety: List[Dict[str, Any]] = []
+        self.module_docs: Dict[str, List[str]] = {}
+        self.module_cfg: Dict[str, List[str]] = {}
+
+    def document(self, name: Optional[str], node: Any) -> None:
+        """Attach the doc comment above node to the definition just recorded."""
+        doc = _rust_doc(node) if name else None
+        if doc:
+            self.definitions[-1]["doc"] = doc
+
+    def site(self, kind: str, node: Any, scope: _Scope, **extra) -> None:
+        self.safety.append({
+            "kind": kind,
+            "line": node.start_point[0] + 1,
+            "column": node.start_point[1],
+            "scope": scope.path,
+            **extra
+        })
+
+    def result(self) -> Dict[str, Any]:
+        result = super().result()
+        result["safety"] = self.safety
+        if self.module_docs:
+            result["module_docs"] = {scope: "\n".join(lines) for scope, lines in self.module_docs.items()}
+        if self.module_cfg:
+            result["module_cfg"] = self.module_cfg
+        return result
+
+    def is_exported(self, name: str, node: Any) -> bool:
+        return any(child.type == "visibility_modifier" for child in node.children)
+
+    def handle(self, node: Any, scope: _Scope) -> bool:
+        t = node.type
+        if t in ("line_comment", "block_comment"):
+            body = _rust_doc_line(_text(node).rstrip("\n"), inner=True)
+            if body is not None:
+                self.module_docs.setdefault(scope.path, []).append(body)
+            return True
+        if t == "unsafe_block":
+            self.site("unsafe_block", node, scope)
+            return False
+        if t == "pointer_type":
+            self.site("raw_pointer", node, scope, detail=_text(node))
+            return False
+        if t == "foreign_mod_item":
+            abi = _rust_abi(node)
+            self.site("extern_block", node, scope, detail=abi)
+            body = node.child_by_field_name("body")
+            for item in _named_children(body) if body is not None else []:
+                if item.type in ("function_signature_item", "static_item"):
+                    self.site("ffi_declaration", item, scope, name=_text(item.child_by_field_name("name")), detail=abi)
+            return False
+        if t in _RUST_ITEMS:
+            kind = _RUST_ITEMS[t]
+            if kind == "function" and scope.kind in ("impl", "trait"):
+                kind = "method"
+            name_node = node.child_by_field_name("name")
+            name = self.define(name_node, kind, node, scope)
+            self.document(name, node)
+            cfg = _rust_cfg(_rust_attributes(node)) if name else []
+            if cfg:
+                self.definitions[-1]["cfg"] = cfg
+            if t == "function_item" and name:
+                test = _rust_test_attribute(node)
+                if test is not None:
+                    self.definitions[-1]["test"] = test
+            if t == "function_item":
+                modifiers = next((child for child in node.children if child.type == "function_modifiers"), None)
+                if modifiers is not None and any(child.type == "unsafe" for child in modifiers.children):
+                    self.site("unsafe_fn", node, scope, name=name)
+                if name and modifiers is not None and any(child.type == "async" for child in modifiers.children):
+                    self.definitions[-1]["async"] = True
+                abi = _rust_abi(node)
+                if abi is not None:
+                    self.site("extern_fn", node, scope, name=name, detail=abi)
+            elif kind == "trait" and any(child.type == "unsafe" for child in node.children):
+                self.site("unsafe_trait", node, scope, name=name)
+            if kind in ("function", "method"):
+                inner = self.enter(scope, name, kind)
+                parameters = node.child_by_field_name("parameters")
+                if parameters is not None:
+                    for parameter in _named_children(parameters):
+                        if parameter.type == "self_parameter":
+                            inner.locals.add("self")
+                            continue
+                        inner.locals.update(_rust_pattern_names(parameter.child_by_field_name("pattern")))
+                        kind_node = parameter.child_by_field_name("type")
+                        if kind_node is not None:
+                            self.visit(kind_node, scope)
+                for part in ("type_parameters", "return_type", "body"):
+                    sub = node.child_by_field_name(part)
+                    if sub is not None:
+                        self.visit(sub, inner if part == "body" else scope)
+                return True
+            if kind == "module" and name:
+                # mod foo; loads foo.rs, foo/mod.rs or a #[path] file; mod foo { } is inline
+                self.definitions[-1]["inline"] = node.child_by_field_name("body") is not None
+                path = _rust_path_attribute(node)
+                if path:
+                    self.definitions[-1]["path"] = path
+                if "test" in cfg:
+                    self.definitions[-1]["cfg_test"] = True
+            if kind in ("struct", "enum", "trait", "module"):
+                inner = self.enter(scope, name, kind)
+                body = node.child_by_field_name("body")
+                if kind == "enum" and body is not None:
+                    for variant in _named_children(body):
+                        if variant.type == "enum_variant":
+                            variant_name = self.define(variant.child_by_field_name("name"), "variant", variant, inner, exported=self.is_exported(name, node))
+                            self.document(variant_name, variant)
+                            self.visit_children(variant, scope, skip=(variant.child_by_field_name("name"),))
+                    return True
+                self.visit_children(node, inner if kind in ("trait", "module") else scope, skip=(name_node,))
+                return True
+            if kind == "macro":
+                return True
+            self.visit_children(node, scope, skip=(name_node,))
+            return True
+        if t == "impl_item":
+            target = node.child_by_field_name("type")
+            trait = node.child_by_field_name("trait")
+            owner = _rust_type_name(target)
+            if any(child.type == "unsafe" for child in node.children):
+                self.site("unsafe_impl", node, scope, name=" for ".join(_text(part) for part in (trait, target) if part is not None))
+            self.visit(target, scope)
+            if trait is not None:
+                self.visit(trait, scope)
+            inner = self.enter(scope, owner, "impl") if owner else scope.child(None, "impl")
+            start = len(self.definitions)
+            body = node.child_by_field_name("body")
+            if body is not None:
+                self.visit(body, inner)
+            trait_name, trait_path = _rust_type_path(trait)
+            type_name, type_path = _rust_type_path(target)
+            generics = _rust_type_parameters(node.child_by_field_name("type_parameters"))
+            methods = []
+            for definition in self.definitions[start:]:
+                if definition["kind"] == "method" and definition["scope"] == inner.path:
+                    methods.append(definition["name"])
+                    if trait_name:
+                        definition["trait"] = f"{trait_path}::{trait_name}" if trait_path else trait_name
+            self.impls.append({
+                "type": type_name,
+                "type_path": type_path,
+                "type_text": _text(target),
+                "trait": trait_name,
+                "trait_path": trait_path,
+                # impl !Send for T
+                "negative": trait is not None and any(child.type == "!" for child in node.children),
+                "generics": generics,
+                # impl<T: Display> ToString for T covers every type meeting the bounds
+                "blanket": bool(type_name) and type_path is None and type_name in generics,
+                "methods": methods,
+                "scope": scope.path,
+                "cfg": _rust_cfg(_rust_attributes(node)),
+                "line": node.start_point[0] + 1,
+                "end_line": node.end_point[0] + 1
+            })
+            return True
+        if t == "use_declaration":
+            start = len(self.imports)
+            self._use(node.child_by_field_name("argument"), "", node)
+            public = self.is_exported("", node)
+            for entry in self.imports[start:]:
+                entry["scope"] = scope.path
+                if public:
+                    entry["public"] = True
+            return True
+        if t == "extern_crate_declaration":
+            name = node.child_by_field_name("name")
+            alias = node.child_by_field_name("alias")
+            self.add_import(_text(name), None, _text(alias or name), node, crate=True)
+            return True
+        if t == "let_declaration":
+            self.bind(scope, _rust_pattern_names(node.child_by_field_name("pattern")))
+            for part in ("type", "value", "alternative"):
+                sub = node.child_by_field_name(part)
+                if sub is not None:
+                    self.visit(sub, scope)
+            return True
+        if t in ("for_expression",):
+            pattern = node.child_by_field_name("pattern")
+            self.bind(scope, _rust_pattern_names(pattern))
+            self.visit_children(node, scope, skip=(pattern,))
+            return True
+        if t in ("let_condition", "match_pattern"):
+            pattern = node.child_by_field_name("pattern") or (node.children[0] if node.children else None)
+            self.bind(scope, _rust_pattern_names(pattern))
+            rest = [child for child in _named_children(node) if child != pattern]
+            for child in rest:
+                self.visit(child, scope)
+            return True
+        if t == "closure_expression":
+            inner = scope.child(None, "lambda")
+            parameters = node.child_by_field_name("parameters")
+            if parameters is not None:
+                for parameter in _named_children(parameters):
+                    inner.locals.update(_rust_pattern_names(parameter.child_by_field_name("pattern") or parameter))
+            self.visit(node.child_by_field_name("body"), inner)
+            return True
+        if t == "call_expression":
+            function = node.child_by_field_name("function")
+            if function is not None and function.type == "field_expression":
+                method = _text(function.child_by_field_name("field"))
+                if method in _RUST_PANIC_METHODS:
+                    kind = "unwrap" if method.startswith("unwrap") else "expect"
+                    self.site(kind, function.child_by_field_name("field"), scope, detail=method)
+            self._member(function, scope, "call")
+            self.visit(node.child_by_field_name("arguments"), scope.offloading(function))
+            return True
+        if t == "macro_invocation":
+            macro = _text(node.child_by_field_name("macro")).rpartition("::")[2]
+            if macro in _RUST_PANIC_MACROS:
+                self.site("panic", node, scope, detail=f"{macro}!")
+            self._member(node.child_by_field_name("macro"), scope, "macro")
+            for child in _named_children(node):
+                if child.type == "token_tree":
+                    self.visit(child, scope)
+            return True
+        if t in ("scoped_identifier", "scoped_type_identifier", "field_expression", "generic_function"):
+            self._member(node, scope, "type" if t == "scoped_type_identifier" else "name")
+            return True
+        if t in ("field_initializer", "shorthand_field_initializer"):
+            value = node.child_by_field_name("value")
+            if value is not None:
+                self.visit(value, scope)
+            elif t == "shorthand_field_initializer":
+                self.visit_children(node, scope)
+            return True
+        if t in ("field_declaration",):
+            kind_node = node.child_by_field_name("type")
+            if kind_node is not None:
+                self.visit(kind_node, scope)
+            return True
+        if t == "inner_attribute_item":
+            text = _text(node).strip()
+            cfg = _rust_cfg([text[3:-1].strip()]) if text.startswith("#![") and text.endswith("]") else []
+            if cfg:
+                self.module_cfg.setdefault(scope.path, []).extend(cfg)
+            return True
+        if t in ("lifetime", "label", "attribute_item", "line_comment", "block_comment"):
+            return True
+        return False
+
+    def _use(self, node: Any, prefix: str, anchor: Any) -> None:
+        if node is None:
+            return
+        t = node.type
+        join = lambda a, b: f"{a}::{b}" if a and b else (a or b)
+        if t in ("identifier", "crate", "self", "super", "scoped_identifier", "metavariable"):
+            path = join(prefix, _text(node))
+            parts = path.split("::")
+            if parts[-1] == "self":
+                # use a::{self} imports module a
+                parts = parts[:-1]
+                self.add_import("::".join(parts), None, parts[-1] if parts else None, anchor)
+            elif len(parts) == 1:
+                self.add_import(parts[0], None, parts[0], anchor)
+            else:
+                self.add_import("::".join(parts[:-1]), parts[-1], parts[-1], anchor)
+        elif t == "use_as_clause":
+            path = join(prefix, _text(node.child_by_field_name("path")))
+            alias = _text(node.child_by_field_name("alias"))
+            parts = path.split("::")
+            if alias == "_":
+                return
+            if len(parts) == 1:
+                self.add_import(parts[0], None, alias, anchor)
+            else:
+                self.add_import("::".join(parts[:-1]), parts[-1], alias, anchor)
+        elif t == "scoped_use_list":
+            path = node.child_by_field_name("path")
+            self._use(node.child_by_field_name("list"), join(prefix, _text(path)) if path is not None else prefix, anchor)
+        elif t == "use_list":
+            for child in _named_children(node):
+                self._use(child, prefix, anchor)
+        elif t == "use_wildcard":
+            path = next((child for child in _named_children(node)), None)
+            self.add_import(join(prefix, _text(path)) if path is not None else prefix, "*", None, anchor)
+
+    def _member(self, node: Any, scope: _Scope, kind: str) -> None:
+        if node is None:
+            return
+        t = node.type
+        if t in ("identifier", "type_identifier"):
+            self.reference(node, scope, kind=kind)
+        elif t in ("scoped_identifier", "scoped_type_identifier"):
+            path = node.child_by_field_name("path")
+            name = node.child_by_field_name("name")
+            qualifier = _dotted(path) if path is not None else None
+            if path is not None and path.type == "generic_type":
+                qualifier = _rust_type_name(path)
+                self.visit(path, scope)
+            self.reference(name, scope, qualifier=qualifier, kind=kind)
+            if path is not None and qualifier is not None:
+                head = path
+                while head.type in ("scoped_identifier", "scoped_type_identifier") and head.child_by_field_name("path") is not None:
+                    head = head.child_by_field_name("path")
+                if head.type in ("identifier", "type_identifier"):
+                    self.reference(head, scope)
+        elif t == "field_expression":
+            value = node.child_by_field_name("value")
+            field_node = node.child_by_field_name("field")
+            qualifier = _dotted(value)
+            if field_node is not None and field_node.type == "field_identifier":
+                self.reference(field_node, scope, qualifier=qualifier, kind=kind)
+            if qualifier is None:
+                self.visit(value, scope)
+            elif value.type == "identifier":
+                self.reference(value, scope)
+        elif t == "generic_function":
+            self._member(node.child_by_field_name("function"), scope, kind)
+            self.visit(node.child_by_field_name("type_arguments"), scope)
+        else:
+            self.visit(node, scope)
+
+    def leaf(self, node: Any, scope: _Scope) -> None:
+        if node.type in ("identifier", "type_identifier"):
+            self.reference(node, scope, kind="type" if node.type == "type_identifier" else "name")
+
+_EXTRACTORS: Dict[str, Callable[[str], _Extractor]] = {
+    "python": lambda language: _PythonExtractor(),
+    "javascript": _JavaScriptExtractor,
+    "jsx": _JavaScriptExtractor,
+    "typescript": _JavaScriptExtractor,
+    "tsx": _JavaScriptExtractor,
+    "go": lambda language: _GoExtractor(),
+    "rust": lambda language: _RustExtractor()
+}
+
+def grammar_for(language: Optional[str], file_path: Optional[str] = None) -> Optional[str]:
+    """Tree-sitter grammar to re-parse a file with, or None if symbols aren't extracted."""
+    if not language:
+        return None
+    if language == "typescript" and file_path and file_path.endswith(".tsx"):
+        return "tsx"
+    return SYMBOL_LANGUAGES.get(language)
+
+def extract_symbols(language: str, content: str, tree: Any = None, file_path: Optional[str] = None) -> Optional[Dict[str, Any]]:
+    """Definitions, references and imports of one file; None for unsupported languages.
+
+    tree is the file's tree-sitter tree if the parser kept it; otherwise the
+    content is parsed here.
+    """
+    grammar = grammar_for(language, file_path)
+    if grammar is None:
+        return None
+    if tree is None:
+        from tree_sitter_language_pack import get_parser
+        tree = get_parser(grammar).parse(content.encode("utf-8"))
+    extractor = _EXTRACTORS[language](language)
+    return extractor.run(tree.root_node)
+
+__all__ = [
+    "SYMBOL_LANGUAGES",
+    "CLASS_SCOPES",
+    "RUST_TEST_ATTRIBUTES",
+    "grammar_for",
+    "extract_symbols"
+]
@@ -348,11 +348,17 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
         Args:
             language_id: Language identifier
         """
-        super().__init__(language_id)
-        self.parser_type = ParserType.TREE_SITTER
+        BaseParserInterface.__init__(self, language_id, FileType.CODE, ParserType.TREE_SITTER)
+        AIParserInterface.__init__(
+            self,
+            language_id=language_id,
+            file_type=FileType.CODE,
+            capabilities={AICapability.CODE_UNDERSTANDING}
+        )
         self.language = None
         self.parser = None
         self.query_registry = None
+        self._metrics: Dict[str, Any] = {}
         self._component_id = f"tree_sitter_parser_{language_id}"
         
         # Register with shutdown handler for proper cleanup
@@ -369,33 +375,24 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
             bool: True if initialization was successful
         """
         try:
-            # Import tree-sitter and language-specific modules
-            from tree_sitter import Parser
-            
             # Convert language ID to format used by tree-sitter-languages
             normalized_language_id = self._normalize_language_id()
-            
+
             # Get language
             await self._load_tree_sitter_language(normalized_language_id)
             if not self.language:
                 return False
-                
-            # Create parser
-            self.parser = Parser()
-            self.parser.set_language(self.language)
-            
+
+            # Create parser already bound to the language
+            self.parser = get_parser(normalized_language_id)
+
             # Create query registry
             self.query_registry = QueryPatternRegistry(self.language_id)
             await self.query_registry.initialize()
-            
+
             # Register with health monitor
-            from utils.health_monitor import register_component
-            register_component(self._component_id, self._report_health)
-            
-            # Register with error audit
-            from utils.error_handling import register_error_boundary
-            register_error_boundary(f"tree_sitter_{self.language_id}", self._handle_error)
-            
+            global_health_monitor.register_component(self._component_id, health_check=self._report_health)
+
             await log(f"Tree-sitter parser initialized for {self.language_id}", level="info")
             return True
             
@@ -405,7 +402,11 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
         except Exception as e:
             await log(f"Error initializing tree-sitter parser: {e}", level="error")
             return False
-    
+
+    async def initialize(self) -> bool:
+        """Initialize the parser (see _initialize)."""
+        return await self._initialize()
+
     async def _report_health(self) -> Dict[str, Any]:
         """Report health metrics for the parser.
         
@@ -665,44 +666,39 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
         try:
             import difflib
             
+            # Tree-sitter works on UTF-8 byte offsets
+            old_bytes = old_source.encode('utf-8')
+            new_bytes = new_source.encode('utf-8')
+            
+            def point_at(data: bytes, offset: int) -> Tuple[int, int]:
+                row = data.count(b'\n', 0, offset)
+                return (row, offset - (data.rfind(b'\n', 0, offset) + 1))
+            
+            def advance(point: Tuple[int, int], text: bytes) -> Tuple[int, int]:
+                newlines = text.count(b'\n')
+                if newlines:
+                    return (point[0] + newlines, len(text) - text.rfind(b'\n') - 1)
+                return (point[0], point[1] + len(text))
+            
             # Get changes using difflib
-            matcher = difflib.SequenceMatcher(None, old_source, new_source)
+            matcher = difflib.SequenceMatcher(None, old_bytes, new_bytes)
             edits = []
             
             for op, old_start, old_end, new_start, new_end in matcher.get_opcodes():
-                if op == 'replace' or op == 'delete' or op == 'insert':
-                    # Calculate line/column positions for old_start
-                    old_start_line = old_source.count('\n', 0, old_start)
-                    if old_start_line > 0:
-                        last_nl = old_source.rfind('\n', 0, old_start)
-                        old_start_col = old_start - last_nl - 1
-                    else:
-                        old_start_col = old_start
-                    
-                    # Calculate line/column positions for old_end
-                    old_end_line = old_source.count('\n', 0, old_end)
-                    if old_end_line > 0:
-                        last_nl = old_source.rfind('\n', 0, old_end)
-                        old_end_col = old_end - last_nl - 1
-                    else:
-                        old_end_col = old_end
-                    
-                    # Calculate line/column positions for new_end
-                    new_end_line = new_source.count('\n', 0, new_end)
-                    if new_end_line > 0:
-                        last_nl = new_source.rfind('\n', 0, new_end)
-                        new_end_col = new_end - last_nl - 1
-                    else:
-                        new_end_col = new_end
-                    
-                    edits.append({
-                        'start_byte': old_start,
-                        'old_end_byte': old_end,
-                        'new_end_byte': new_end,
-                        'start_point': (old_start_line, old_start_col),
-                        'old_end_point': (old_end_line, old_end_col),
-                        'new_end_point': (new_end_line, new_end_col)
-                    })
+                if op == 'equal':
+                    continue
+                # Edits are applied to the tree one after another, so each is
+                # expressed relative to the earlier ones: everything before
+                # new_start already matches the new source
+                start_point = point_at(new_bytes, new_start)
+                edits.append({
+                    'start_byte': new_start,
+                    'old_end_byte': new_start + (old_end - old_start),
+                    'new_end_byte': new_end,
+                    'start_point': start_point,
+                    'old_end_point': advance(start_point, old_bytes[old_start:old_end]),
+                    'new_end_point': point_at(new_bytes, new_end)
+                })
             
             return edits
             
@@ -710,7 +706,14 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
             await log(f"Error tracking edits: {e}", level="error")
             return []
 
-    async def parse_incremental(self, source_code: str, old_tree=None, file_path: Optional[str] = None) -> ParserResult:
+    async def parse_incremental(
+        self,
+        source_code: str,
+        old_tree=None,
+        file_path: Optional[str] = None,
+        old_source: Optional[str] = None,
+        extract_features: bool = True
+    ) -> ParserResult:
         """Parse source code with incremental parsing support.
         
         This enhanced method uses tree-sitter's incremental parsing capabilities to
@@ -720,6 +723,9 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
             source_code: The source code to parse
             old_tree: Optional previous tree for incremental parsing
             file_path: Optional file path for error reporting
+            old_source: Source old_tree was parsed from; needed to compute edits
+                when the tree object cannot carry it
+            extract_features: Set to False when only the tree is needed
             
         Returns:
             ParserResult: The parsing result
@@ -732,7 +738,7 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
                     file_type=self.file_type,
                     parser_type=self.parser_type,
                     language=self.language_id,
-                    errors=[str(e) for e in self._error_audit.errors]
+                    errors=[f"Tree-sitter language not available: {self.language_id}"]
                 )
         
         start_time = time.time()
@@ -740,7 +746,8 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
         try:
             async with AsyncErrorBoundary(f"incremental_parse_{self.language_id}"):
                 # Store the old source if we have an old tree
-                old_source = getattr(old_tree, '_source', None) if old_tree else None
+                if old_tree and old_source is None:
+                    old_source = getattr(old_tree, '_source', None)
                 
                 # If we have both old tree and old source, track edits and apply them
                 if old_tree and old_source and hasattr(old_tree, 'edit'):
@@ -762,17 +769,26 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
                 bytes_source = bytes(source_code, "utf8")
                 tree = self.parser.parse(bytes_source, old_tree)
                 
-                # Store source with tree for future incremental parsing
-                setattr(tree, '_source', source_code)
-                
                 if not tree or not tree.root_node:
                     raise ProcessingError(f"Incremental parsing failed for {file_path or 'unknown file'}")
                 
+                # Store source with tree for future incremental parsing; native
+                # tree objects reject new attributes, callers then pass old_source
+                try:
+                    setattr(tree, '_source', source_code)
+                except AttributeError:
+                    pass
+                
                 # Convert to our AST format
                 ast = self._convert_node(tree.root_node)
                 
-                # Extract features
-                features = await self._extract_features(ast, source_code)
+                # Extract features; a failure here should not discard the tree
+                features = ExtractedFeatures()
+                if extract_features:
+                    try:
+                        features = await self._extract_features(ast, source_code)
+                    except Exception as e:
+                        await log(f"Feature extraction failed for {file_path or 'unknown file'}: {e}", level="warning")
                 
                 # Calculate parse time
                 parse_time = time.time() - start_time
@@ -806,7 +822,6 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
                 )
                 
                 # Update metrics
-                self._metrics = self._metrics or {}
                 self._metrics.setdefault("parse_times", []).append(parse_time)
                 
                 return result
@@ -821,6 +836,24 @@ class TreeSitterParser(BaseParserInterface, AIParserInterface):
                 errors=[str(e)]
             )
 
+    async def parse(self, source_code: str) -> Optional[ParserResult]:
+        """Parse source code from scratch."""
+        return await self.parse_incremental(source_code)
+
+    async def validate(self, source_code: str) -> PatternValidationResult:
+        """Validate source code by reporting tree-sitter syntax errors."""
+        start_time = time.time()
+        result = await self.parse_incremental(source_code, extract_features=False)
+        errors = [
+            error['message'] if isinstance(error, dict) else str(error)
+            for error in result.errors
+        ]
+        return PatternValidationResult(
+            is_valid=result.success and not errors,
+            errors=errors,
+            validation_time=time.time() - start_time
+        )
+
     def _find_error_nodes(self, node):
         """Find all error nodes in the tree."""
         error_nodes = []
@@ -2037,6 +2070,21 @@ TS_QUERIES = {{
         except Exception as e:
             await log(f"Error cleaning up tree-sitter parser: {e}", level="error")
 
+    async def _cleanup(self) -> None:
+        """Clean up resources used by the parser (see cleanup)."""
+        await self.cleanup()
+
+    async def process_with_ai(self, source_code: str, context: AIContext) -> AIProcessingResult:
+        """AI processing is provided by the unified parser, not the raw tree-sitter layer."""
+        return AIProcessingResult(
+            success=False,
+            response="AI processing is not available on TreeSitterParser; use the unified parser"
+        )
+
+    async def learn_from_code(self, source_code: str, context: AIContext) -> List[Dict[str, Any]]:
+        """Pattern learning is provided by the unified parser, not the raw tree-sitter layer."""
+        return []
+
     async def record_error(self, error: Exception, operation: str, context: Dict[str, Any] = None) -> None:
         """Record an error that occurred during parsing operations.
         
@@ -6,7 +6,7 @@ enums, dataclasses, and type aliases.
 
 from typing import Dict, Any, List, Optional, Union, Set, Callable
 from enum import Enum, auto
-from dataclasses import dataclass, field
+from dataclasses import dataclass, field, asdict
 # Remove circular import
 # from parsers.language_mapping import normalize_language_name, normalize_and_check_tree_sitter_support
 import asyncio
@@ -821,6 +821,9 @@ class ExtractedFeatures:
     documentation: Documentation = field(default_factory=Documentation)
     metrics: ComplexityMetrics = field(default_factory=ComplexityMetrics)
 
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
 @dataclass
 class AIContext:
     """Context for AI processing."""
@@ -859,6 +862,8 @@ class ParserResult:
     warnings: List[str] = field(default_factory=list)
     metrics: ComplexityMetrics = field(default_factory=ComplexityMetrics)
     metadata: Dict[str, Any] = field(default_factory=dict)
+    tree: Optional[Any] = None  # Raw tree-sitter tree, kept for incremental re-parsing
+    parse_time: float = 0.0
 
 @dataclass
 class PatternInfo:
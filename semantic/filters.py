@@ -0,0 +1,182 @@
+"""[5.3] Search filters and query syntax.
+
+Flow:
+1. Query Syntax:
+   - field:value terms in a query are filters, the rest is the search text:
+     lang:rust path:src/** kind:function repo:foo -path:tests
+   - A leading - excludes; values may be comma-separated (lang:rust,go)
+     or quoted (path:"docs/user guide/**")
+   - Fields: lang (language), path (file), kind, repo; other text with a
+     colon (std::fs, http://...) stays search text
+
+2. Structured Filters:
+   - The API and CLI build the same SearchFilters from parameters, merged
+     with the ones in the query
+
+3. SQL:
+   - where_clause() turns filters into a WHERE clause with $N parameters,
+     so they apply inside the vector and full-text queries [5.2] rather
+     than to their results
+   - path globs become regular expressions: ** crosses directories, * and ?
+     do not; a pattern without / matches at any depth, one with / from the
+     repository root, and a plain name also matches everything below it
+   - Stored paths are absolute, so globs match the path relative to the
+     repository's root_path (recorded when it is indexed [6.5.12]); a row
+     outside every known root is matched on its full path
+   - On repo_docs, kind matches doc_type and lang does not apply
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+FIELDS = ("lang", "path", "kind", "repo")
+
+_ALIASES = {"language": "lang", "file": "path"}
+
+_FILTER = re.compile(r'(?<!\S)(-?)(lang|language|path|file|kind|repo):("[^"]*"|[^\s"]+)')
+
+# Filterable column per field and table; repo is matched through repository ids
+_COLUMNS = {
+    "code_chunks": {"lang": "language", "path": "file_path", "kind": "kind"},
+    "repo_docs": {"path": "file_path", "kind": "doc_type"}
+}
+
+# Repositories whose root holds a row's file, per table
+_ROOTS = {
+    "code_chunks": "repositories r WHERE r.id = code_chunks.repo_id",
+    "repo_docs": (
+        "repositories r JOIN repo_doc_relations rel ON rel.repo_id = r.id "
+        "WHERE rel.doc_id = repo_docs.id"
+    )
+}
+
+def relative_path(table: str) -> str:
+    """SQL for a row's file path relative to its repository root, or the full path outside it."""
+    path = f"{table}.file_path"
+    return (
+        f"coalesce((SELECT substr({path}, length(r.root_path) + 2) FROM {_ROOTS[table]} "
+        f"AND substr({path}, 1, length(r.root_path) + 1) = r.root_path || '/' LIMIT 1), {path})"
+    )
+
+@dataclass
+class SearchFilters:
+    """Values to include and to exclude per field."""
+    include: Dict[str, List[str]] = field(default_factory=dict)
+    exclude: Dict[str, List[str]] = field(default_factory=dict)
+
+    def add(self, name: str, values: Any, negate: bool = False) -> 'SearchFilters':
+        """Add values (a list, or a comma-separated string) for a field."""
+        name = _ALIASES.get(name, name)
+        if name not in FIELDS:
+            raise ValueError(f"Unknown search filter: {name}")
+        if isinstance(values, str):
+            values = values.split(",")
+        values = [str(value).strip() for value in values or [] if str(value).strip()]
+        if values:
+            target = self.exclude if negate else self.include
+            target.setdefault(name, [])
+            target[name].extend(value for value in values if value not in target[name])
+        return self
+
+    def merge(self, other: Optional['SearchFilters']) -> 'SearchFilters':
+        """A copy with other's values added."""
+        merged = SearchFilters()
+        for filters in (self, other or SearchFilters()):
+            for name, values in filters.include.items():
+                merged.add(name, values)
+            for name, values in filters.exclude.items():
+                merged.add(name, values, negate=True)
+        return merged
+
+    def map_values(self, name: str, convert) -> 'SearchFilters':
+        """Rewrite one field's values in place (e.g. to normalize language names)."""
+        for target in (self.include, self.exclude):
+            if name in target:
+                target[name] = list(dict.fromkeys(convert(value) for value in target[name]))
+        return self
+
+    def __bool__(self) -> bool:
+        return bool(self.include or self.exclude)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"include": self.include, "exclude": self.exclude}
+
+def parse_query(query: str) -> Tuple[str, SearchFilters]:
+    """[5.3.1] Split a query into its search text and its filters."""
+    filters = SearchFilters()
+    for match in _FILTER.finditer(query or ""):
+        value = match.group(3)
+        if value.startswith('"'):
+            value = value[1:-1]
+        filters.add(match.group(2), value, negate=bool(match.group(1)))
+    text = " ".join(_FILTER.sub(" ", query or "").split())
+    return text, filters
+
+def glob_regex(pattern: str) -> str:
+    """A path glob as a regular expression valid in Python and PostgreSQL."""
+    anchored = "/" in pattern.strip("/")
+    pattern = pattern.strip("/") or "**"
+    parts, i = [], 0
+    while i < len(pattern):
+        if pattern.startswith("**/", i):
+            parts.append("(.*/)?")
+            i += 3
+        elif pattern.startswith("**", i):
+            parts.append(".*")
+            i += 2
+        elif pattern[i] == "*":
+            parts.append("[^/]*")
+            i += 1
+        elif pattern[i] == "?":
+            parts.append("[^/]")
+            i += 1
+        else:
+            parts.append(re.escape(pattern[i]))
+            i += 1
+    prefix = "^" if anchored else "(^|/)"
+    # Without wildcards a pattern names a file or a directory and everything below it
+    suffix = "$" if any(char in pattern for char in "*?") else "(/|$)"
+    return prefix + "".join(parts) + suffix
+
+def where_clause(filters: Optional[SearchFilters], table: str, start: int = 1) -> Tuple[Optional[str], List[Any]]:
+    """[5.3.2] SQL conditions for filters on code_chunks or repo_docs, numbered from $start."""
+    conditions: List[str] = []
+    params: List[Any] = []
+    if not filters:
+        return None, params
+
+    def placeholder(value: Any) -> str:
+        params.append(value)
+        return f"${start + len(params) - 1}"
+
+    columns = _COLUMNS[table]
+    for name in FIELDS:
+        for negate, values in ((False, filters.include.get(name)), (True, filters.exclude.get(name))):
+            if not values:
+                continue
+            if name == "repo":
+                refs = placeholder(values)
+                repo_ids = f"SELECT id FROM repositories WHERE repo_name = ANY({refs}::text[]) OR CAST(id AS TEXT) = ANY({refs}::text[])"
+                clause = (
+                    f"repo_id IN ({repo_ids})" if table == "code_chunks"
+                    else f"id IN (SELECT doc_id FROM repo_doc_relations WHERE repo_id IN ({repo_ids}))"
+                )
+            elif name not in columns:
+                continue
+            elif name == "path":
+                path = relative_path(table)
+                clause = " OR ".join(f"{path} ~ {placeholder(glob_regex(value))}" for value in values)
+            else:
+                clause = f"coalesce({columns[name]}, '') = ANY({placeholder(values)}::text[])"
+            conditions.append(f"NOT ({clause})" if negate else f"({clause})")
+    return " AND ".join(conditions) or None, params
+
+__all__ = [
+    "FIELDS",
+    "SearchFilters",
+    "parse_query",
+    "glob_regex",
+    "relative_path",
+    "where_clause"
+]
@@ -0,0 +1,104 @@
+"""[5.2] Identifier-aware lexical search and rank fusion.
+
+Flow:
+1. Tokens:
+   - Words are split at underscores, camelCase humps and digit runs
+     (parseHTTPResponse -> parse, http, response); each compound also keeps
+     a joined token (parsehttpresponse), so parse_config, parseConfig and
+     ParseConfig all match each other
+   - search_text() is the token string stored next to each code chunk and
+     doc; the full-text index [6.6] is built over it
+
+2. Lexical Ranking:
+   - The storage backend's text_search [6.9] ranks rows matching any query
+     term: ts_rank_cd on PostgreSQL, BM25 on the embedded store
+
+3. Fusion:
+   - Vector, lexical and graph rankings are merged with reciprocal rank
+     fusion: each list adds weight / (k + rank) for every result it holds
+   - Every result keeps its fused score and, per source, its rank, raw
+     score and contribution; lexical hits also list the terms they matched
+"""
+
+import re
+from typing import Any, Callable, Dict, Hashable, List, Optional, Sequence
+
+# Conventional RRF constant; larger values flatten the difference between ranks
+RRF_K = 60
+
+_WORD = re.compile(r"[A-Za-z0-9_]+")
+_PART = re.compile(r"[A-Z]+(?=[A-Z][a-z])|[A-Z]?[a-z]+|[A-Z]+|[0-9]+")
+_MAX_TOKEN = 64
+
+def identifier_tokens(text: str) -> List[str]:
+    """Lowercase tokens of text, in order: the parts of each word, then the joined word."""
+    tokens = []
+    for word in _WORD.findall(text or ""):
+        parts = [part.lower() for part in _PART.findall(word)]
+        tokens.extend(part for part in parts if len(part) > 1)
+        if len(parts) > 1:
+            joined = "".join(parts)
+            if len(joined) <= _MAX_TOKEN:
+                tokens.append(joined)
+    return tokens
+
+def search_text(*texts: Optional[str]) -> str:
+    """The token string stored for full-text search."""
+    return " ".join(token for text in texts if text for token in identifier_tokens(text))
+
+def query_terms(query: str) -> List[str]:
+    """Distinct tokens of a search query."""
+    return list(dict.fromkeys(identifier_tokens(query)))
+
+def matched_terms(terms: Sequence[str], *texts: Optional[str]) -> List[str]:
+    """The query terms that occur in texts."""
+    present = set(identifier_tokens(" ".join(text for text in texts if text)))
+    return [term for term in terms if term in present]
+
+def reciprocal_rank_fusion(
+    rankings: Dict[str, List[Dict[str, Any]]],
+    key: Callable[[Dict[str, Any]], Hashable],
+    k: int = RRF_K,
+    weights: Optional[Dict[str, float]] = None
+) -> List[Dict[str, Any]]:
+    """[5.2.1] Merge ranked result lists by reciprocal rank fusion.
+
+    Each result gets score (the fused score) and score_details, which holds
+    k and, per source it appeared in, its 1-based rank, the source's own
+    score and what that rank contributed. The first list a result appears in
+    supplies its fields.
+    """
+    weights = weights or {}
+    fused: Dict[Hashable, Dict[str, Any]] = {}
+    for source, results in rankings.items():
+        weight = weights.get(source, 1.0)
+        seen = set()
+        for rank, result in enumerate(results, start=1):
+            result_key = key(result)
+            if result_key in seen:
+                continue
+            seen.add(result_key)
+            entry = fused.get(result_key)
+            if entry is None:
+                entry = dict(result)
+                entry["score"] = 0.0
+                entry["score_details"] = {"k": k, "sources": {}}
+                fused[result_key] = entry
+            contribution = weight / (k + rank)
+            detail = {"rank": rank, "contribution": contribution}
+            for field in ("similarity", "text_score", "matched_terms"):
+                if field in result:
+                    detail[field] = result[field]
+            entry["score"] += contribution
+            entry["score_details"]["sources"][source] = detail
+    # Ties keep the order of the first ranking that holds them
+    return sorted(fused.values(), key=lambda entry: -entry["score"])
+
+__all__ = [
+    "RRF_K",
+    "identifier_tokens",
+    "search_text",
+    "query_terms",
+    "matched_terms",
+    "reciprocal_rank_fusion"
+]
@@ -2,8 +2,12 @@
 
 Flow:
 1. Search Capabilities:
-   - Code search with vector similarity
-   - Doc search with vector similarity
+   - Code search over function-level chunks [1.14]
+   - Doc search
+   - Vector similarity and identifier-aware full-text rankings merged by
+     reciprocal rank fusion [5.2], with the score explained per result
+   - Filters from the query syntax or structured parameters [5.3] apply
+     inside the queries; pages come from one stable fused ranking
    - Graph-enhanced results
    
 2. Integration Points:
@@ -18,12 +22,14 @@ Flow:
 """
 
 from db.psql import query
-from typing import List, Dict, Optional, Set, Any
+from typing import Any, Callable, Dict, List, Optional, Set, Tuple
 import torch
 from utils.logger import log, ErrorSeverity
 from utils.cache import cache_coordinator
 from embedding.embedding_models import code_embedder, doc_embedder
 from ai_tools.graph_capabilities import graph_analysis
+from semantic.lexical import RRF_K, matched_terms, query_terms, reciprocal_rank_fusion
+from semantic.filters import SearchFilters, parse_query, where_clause
 from parsers.types import (
     FileType,
     ParserResult,
@@ -32,7 +38,6 @@ from parsers.types import (
 )
 from parsers.models import FileClassification
 from parsers.language_mapping import normalize_language_name
-from tree_sitter_language_pack import get_binding, get_language, get_parser, SupportedLanguage
 from utils.error_handling import (
     handle_async_errors,
     handle_errors,
@@ -42,7 +47,13 @@ from utils.error_handling import (
 )
 from utils.shutdown import register_shutdown_handler
 import asyncio
-import os
+
+_CHUNK_COLUMNS = ("id", "repo_id", "file_path", "language", "kind", "symbol", "parent_symbol",
+                  "start_line", "end_line", "part", "content")
+_DOC_COLUMNS = ("id", "file_path", "doc_type", "content", "related_code_path", "metadata")
+
+# How deep each ranking is read; results past it are not returned
+_MAX_RESULTS = 1000
 
 class SearchEngine:
     """[5.1] Handles all search operations combining vector and graph-based search."""
@@ -53,30 +64,19 @@ class SearchEngine:
         self._pending_tasks: Set[asyncio.Task] = set()
         self._lock = asyncio.Lock()
         self._graph_sync = None
+        self._storage = None
         self._vector_store = None
         self._search_config = None
         self._cache = None
     
-    async def _get_language_for_file(self, file_path: str) -> Optional[str]:
-        """Get normalized language name for a file."""
-        _, ext = os.path.splitext(file_path)
-        if not ext:
-            return None
-            
-        try:
-            language = normalize_language_name(ext[1:].lower())
-            if language in SupportedLanguage.__args__:
-                return language
-        except:
-            pass
-        return None
-    
     async def ensure_initialized(self):
         """Ensure the instance is properly initialized before use."""
         if not self._initialized:
             raise ProcessingError("SearchEngine not initialized. Use create() to initialize.")
         if not self._graph_sync:
             raise ProcessingError("Graph sync not initialized")
+        if not self._storage:
+            raise ProcessingError("Storage backend not initialized")
         if not self._vector_store:
             raise ProcessingError("Vector store not initialized")
         if not self._cache:
@@ -94,11 +94,14 @@ class SearchEngine:
                 severity=ErrorSeverity.CRITICAL
             ):
                 # Initialize components
-                from db.graph_sync import GraphSync
-                instance._graph_sync = await GraphSync.create()
+                from db.graph_sync import get_graph_sync
+                instance._graph_sync = await get_graph_sync()
+                
+                from db.storage import get_storage_backend
+                instance._storage = await get_storage_backend()
                 
                 from semantic.vector_store import VectorStore
-                instance._vector_store = await VectorStore.create()
+                instance._vector_store = await VectorStore.create("search_results")
                 
                 # Initialize cache
                 from utils.cache import UnifiedCache, cache_coordinator
@@ -155,7 +158,7 @@ class SearchEngine:
                 self._pending_tasks.difference_update({vector_task, graph_task})
             
             # Combine and rank results
-            results = await self._combine_results(vector_results, graph_results)
+            results = await self._combine_results({"vector": vector_results or [], "graph": graph_results or []})
             
             # Cache results
             task = asyncio.create_task(self._cache.set_async(cache_key, results))
@@ -167,26 +170,22 @@ class SearchEngine:
             
             return results
     
-    async def _combine_results(self, vector_results: List[Dict], graph_results: List[Dict]) -> List[Dict]:
-        """Combine and rank search results."""
-        combined = []
-        seen = set()
-        
-        # Process vector results first (they usually have better relevance)
-        for result in vector_results:
-            key = (result.get("file"), result.get("line"))
-            if key not in seen:
-                seen.add(key)
-                combined.append(result)
-        
-        # Add unique graph results
-        for result in graph_results:
-            key = (result.get("file"), result.get("line"))
-            if key not in seen:
-                seen.add(key)
-                combined.append(result)
-        
-        return combined
+    async def _combine_results(
+        self,
+        rankings: Dict[str, List[Dict[str, Any]]],
+        key: Callable[[Dict[str, Any]], Any] = lambda result: (result.get("file"), result.get("line"))
+    ) -> List[Dict[str, Any]]:
+        """Merge ranked result lists by reciprocal rank fusion [5.2.1].
+
+        The search config may set rrf_k, weights per source, and max_results
+        (how deep each ranking is read).
+        """
+        return reciprocal_rank_fusion(
+            rankings,
+            key=key,
+            k=self._search_config.get("rrf_k", RRF_K),
+            weights=self._search_config.get("weights")
+        )
     
     async def cleanup(self):
         """Clean up search engine resources."""
@@ -210,8 +209,9 @@ class SearchEngine:
             if self._vector_store:
                 await self._vector_store.cleanup()
             
-            if self._graph_sync:
-                await self._graph_sync.cleanup()
+            # Graph sync and storage are shared singletons with their own shutdown handlers
+            self._graph_sync = None
+            self._storage = None
             
             # Unregister from health monitoring
             from utils.health_monitor import global_health_monitor
@@ -223,147 +223,169 @@ class SearchEngine:
             await log(f"Error cleaning up search engine: {e}", level="error")
             raise ProcessingError(f"Failed to cleanup search engine: {e}")
 
+    async def _rank(
+        self,
+        table: str,
+        query_embedding: Any,
+        terms: List[str],
+        where: Optional[str],
+        params: List[Any]
+    ) -> List[Dict[str, Any]]:
+        """Ids of the rows matching where, by fused vector and lexical rank.
+
+        Both rankings are read max_results deep whatever page is asked for,
+        so the fused order, and with it every page, is the same on each call.
+        Without a query embedding, rows are listed by path.
+        """
+        depth = self._search_config.get("max_results", _MAX_RESULTS)
+        if query_embedding is None:
+            return await self._storage.fetch(
+                f"SELECT id FROM {table}{f' WHERE {where}' if where else ''} ORDER BY file_path, id LIMIT {int(depth)};",
+                *params
+            )
+        vector_results = await self._storage.vector_search(
+            table,
+            query_embedding,
+            columns=("id",),
+            where=where,
+            params=params,
+            limit=depth,
+            tiebreak="id"
+        )
+        lexical_results = await self._storage.text_search(
+            table,
+            terms,
+            columns=("id",),
+            where=where,
+            params=params,
+            limit=depth,
+            tiebreak="id"
+        ) if terms else []
+        return await self._combine_results(
+            {"vector": vector_results or [], "lexical": lexical_results or []},
+            key=lambda result: result["id"]
+        )
+    
+    async def _hydrate(self, table: str, columns: Tuple[str, ...], ranked: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+        """Full rows for a window of ranked ids, in rank order."""
+        if not ranked:
+            return []
+        rows = await self._storage.fetch(
+            f"SELECT {', '.join(columns)} FROM {table} WHERE id = ANY($1::int[]);",
+            [result["id"] for result in ranked]
+        )
+        by_id = {row["id"]: row for row in rows}
+        return [{**by_id[result["id"]], **result} for result in ranked if result["id"] in by_id]
+    
+    @staticmethod
+    def _filters(query: str, filters: Optional[SearchFilters]) -> Tuple[str, SearchFilters]:
+        """Search text and filters of a query, merged with structured filters."""
+        text, parsed = parse_query(query)
+        return text, parsed.merge(filters).map_values("lang", normalize_language_name)
+    
+    @staticmethod
+    def _explain_terms(result: Dict[str, Any], terms: List[str], *texts: Optional[str]) -> None:
+        lexical = result.get("score_details", {}).get("sources", {}).get("lexical")
+        if lexical is not None:
+            lexical["matched_terms"] = matched_terms(terms, *texts)
+    
     @handle_async_errors(error_types=ProcessingError)
-    async def search_code(self, query: str, language: Optional[str] = None, **kwargs) -> List[Dict[str, Any]]:
-        """Search code using vector similarity."""
+    async def search_code(
+        self,
+        query: str,
+        language: Optional[str] = None,
+        repo_id: Optional[int] = None,
+        limit: int = 10,
+        offset: int = 0,
+        filters: Optional[SearchFilters] = None
+    ) -> List[Dict[str, Any]]:
+        """Search code chunks by vector similarity and identifier terms, fused by rank.
+
+        The query may hold filters (lang:rust path:src/** kind:function
+        repo:foo -path:tests, see semantic/filters.py [5.3]); they, filters,
+        language and repo_id all apply inside the queries. Each result is one
+        function, class or method with its file, line span and symbol, plus
+        score and score_details from reciprocal rank fusion [5.2.1]: per
+        source (vector, lexical), its rank, similarity or text_score, and for
+        lexical hits the matched terms.
+        """
         if not self._initialized:
             await self.ensure_initialized()
             
         try:
-            # If language is provided, normalize it
+            text, filters = self._filters(query, filters)
             if language:
-                language = normalize_language_name(language)
-                if language not in SupportedLanguage.__args__:
-                    language = None
+                filters.add("lang", normalize_language_name(language))
+            if repo_id is not None:
+                filters.add("repo", str(repo_id))
+            languages = filters.include.get("lang", [])
             
             # Get query embedding
             query_embedding = await code_embedder.embed_code(
-                query,
-                language or "unknown",
+                text,
+                languages[0] if len(languages) == 1 else "unknown",
                 context={"is_query": True}
-            )
-            
-            # Search vector store
-            results = await self._vector_store.search_code(
-                query_embedding,
-                language=language,
-                **kwargs
-            )
+            ) if text else None
             
-            # Enhance results with tree-sitter parsing
-            enhanced_results = []
+            # Search the storage backend
+            terms = query_terms(text)
+            where, params = where_clause(filters, "code_chunks")
+            ranked = await self._rank("code_chunks", query_embedding, terms, where, params)
+            results = await self._hydrate("code_chunks", _CHUNK_COLUMNS, ranked[offset:offset + limit])
             for result in results:
-                file_path = result.get("file")
-                if not file_path:
-                    continue
-                    
-                # Get language for file
-                file_language = await self._get_language_for_file(file_path)
-                if not file_language:
-                    enhanced_results.append(result)
-                    continue
-                    
-                # Get parser for language
-                parser = get_parser(file_language)
-                if not parser:
-                    enhanced_results.append(result)
-                    continue
-                    
-                # Parse snippet
-                snippet = result.get("snippet", "")
-                if not snippet:
-                    enhanced_results.append(result)
-                    continue
-                    
-                tree = parser.parse(bytes(snippet, "utf8"))
-                if not tree:
-                    enhanced_results.append(result)
-                    continue
-                    
-                # Get unified parser
-                unified = await get_unified_parser()
-                
-                # Extract features
-                features = await unified.extract_features(tree, snippet, file_language)
-                
-                # Add features to result
-                result["features"] = features.to_dict()
-                enhanced_results.append(result)
+                result["file"] = result["file_path"]
+                result["line"] = result["start_line"]
+                result["snippet"] = result.pop("content")
+                self._explain_terms(result, terms, result["file_path"], result.get("symbol"), result["snippet"])
             
-            return enhanced_results
+            return results
         except Exception as e:
+            # Raised, not returned empty, so callers can tell a failure from no matches
             await log(f"Error searching code: {e}", level="error")
-            return []
+            raise
 
     @handle_async_errors(error_types=ProcessingError)
-    async def search_docs(self, query: str, **kwargs) -> List[Dict[str, Any]]:
-        """Search documentation using vector similarity."""
+    async def search_docs(
+        self,
+        query: str,
+        repo_id: Optional[int] = None,
+        limit: int = 10,
+        offset: int = 0,
+        filters: Optional[SearchFilters] = None
+    ) -> List[Dict[str, Any]]:
+        """Search documentation by vector similarity and identifier terms, fused by rank.
+
+        Filters work as in search_code; kind matches the doc type and lang
+        does not apply.
+        """
         if not self._initialized:
             await self.ensure_initialized()
             
         try:
+            text, filters = self._filters(query, filters)
+            if repo_id is not None:
+                filters.add("repo", str(repo_id))
+            
             # Get query embedding
             query_embedding = await doc_embedder.embed_text(
-                query,
+                text,
                 context={"is_query": True}
-            )
-            
-            # Search vector store
-            results = await self._vector_store.search_docs(
-                query_embedding,
-                **kwargs
-            )
+            ) if text else None
             
-            # Enhance results with tree-sitter parsing for code blocks
-            enhanced_results = []
+            # Search the storage backend
+            terms = query_terms(text)
+            where, params = where_clause(filters, "repo_docs")
+            ranked = await self._rank("repo_docs", query_embedding, terms, where, params)
+            results = await self._hydrate("repo_docs", _DOC_COLUMNS, ranked[offset:offset + limit])
             for result in results:
-                # Extract code blocks from doc content
-                code_blocks = self._extract_code_blocks(result.get("content", ""))
-                
-                # Process each code block
-                processed_blocks = []
-                for block in code_blocks:
-                    language = block.get("language")
-                    if not language:
-                        processed_blocks.append(block)
-                        continue
-                        
-                    # Normalize language
-                    language = normalize_language_name(language)
-                    if language not in SupportedLanguage.__args__:
-                        processed_blocks.append(block)
-                        continue
-                        
-                    # Get parser for language
-                    parser = get_parser(language)
-                    if not parser:
-                        processed_blocks.append(block)
-                        continue
-                        
-                    # Parse code block
-                    tree = parser.parse(bytes(block["content"], "utf8"))
-                    if not tree:
-                        processed_blocks.append(block)
-                        continue
-                        
-                    # Get unified parser
-                    unified = await get_unified_parser()
-                    
-                    # Extract features
-                    features = await unified.extract_features(tree, block["content"], language)
-                    
-                    # Add features to block
-                    block["features"] = features.to_dict()
-                    processed_blocks.append(block)
-                
-                # Add processed blocks to result
-                result["code_blocks"] = processed_blocks
-                enhanced_results.append(result)
+                self._explain_terms(result, terms, result["file_path"], result["content"])
+                result["code_blocks"] = self._extract_code_blocks(result.get("content", ""))
             
-            return enhanced_results
+            return results
         except Exception as e:
+            # Raised, not returned empty, so callers can tell a failure from no matches
             await log(f"Error searching docs: {e}", level="error")
-            return []
+            raise
 
     def _extract_code_blocks(self, content: str) -> List[Dict[str, Any]]:
         """Extract code blocks from markdown content."""
@@ -427,7 +449,7 @@ async def search_docs_common(query_embedding: torch.Tensor, repo_id: Optional[in
         vector_literal = _search_engine._to_pgvector(query_embedding.tolist())
         
         base_sql = """
-        SELECT rd.id, rd.file_path, rd.content, rd.doc_type,
+        SELECT rd.id, rd.file_path, rd.content, rd.doc_type, rd.related_code_path, rd.metadata,
                rd.embedding <=> $1::vector AS similarity
         FROM repo_docs rd
         """
@@ -0,0 +1,102 @@
+"""Tests for function-level code chunks (indexer/code_chunks.py [1.14])."""
+
+import pytest
+
+from indexer.code_chunks import (
+    _Block,
+    blocks_from_extractor,
+    blocks_from_symbols,
+    build_chunks,
+    file_embedding
+)
+
+SOURCE = """import os
+
+CONSTANT = 1
+
+class Parser:
+    \"\"\"Parses things.\"\"\"
+
+    def parse(self, text):
+        return text.split()
+
+    def reset(self):
+        pass
+
+def main():
+    Parser().parse("a b")
+"""
+
+SYMBOLS = {"definitions": [
+    {"name": "Parser", "kind": "class", "line": 5, "end_line": 12, "scope": ""},
+    {"name": "parse", "kind": "method", "line": 8, "end_line": 9, "scope": "Parser"},
+    {"name": "reset", "kind": "method", "line": 11, "end_line": 12, "scope": "Parser"},
+    {"name": "main", "kind": "function", "line": 14, "end_line": 15, "scope": ""},
+    {"name": "CONSTANT", "kind": "variable", "line": 3, "end_line": 3, "scope": ""}
+]}
+
+def _spans(chunks):
+    return [(chunk["kind"], chunk["symbol"], chunk["start_line"], chunk["end_line"], chunk["part"]) for chunk in chunks]
+
+def test_blocks_from_symbols_keeps_chunk_kinds():
+    blocks = blocks_from_symbols(SYMBOLS)
+    assert [(block.kind, block.symbol, block.parent_symbol) for block in blocks] == [
+        ("class", "Parser", None),
+        ("method", "Parser.parse", "Parser"),
+        ("method", "Parser.reset", "Parser"),
+        ("function", "main", None)
+    ]
+
+def test_class_keeps_its_own_lines_and_module_gets_the_rest():
+    chunks = build_chunks(SOURCE, blocks_from_symbols(SYMBOLS), max_lines=60, max_chars=2000, overlap=0)
+    assert _spans(chunks) == [
+        ("module", None, 1, 3, 0),
+        ("class", "Parser", 5, 6, 0),
+        ("method", "Parser.parse", 8, 9, 0),
+        ("method", "Parser.reset", 11, 12, 0),
+        ("function", "main", 14, 15, 0)
+    ]
+    assert chunks[1]["content"] == 'class Parser:\n    """Parses things."""'
+    assert chunks[0]["parent_symbol"] is None and chunks[2]["parent_symbol"] == "Parser"
+
+@pytest.mark.parametrize("max_lines, max_chars, overlap, expected", [
+    (4, 2000, 0, [(1, 4), (5, 8), (9, 10)]),
+    (4, 2000, 1, [(1, 4), (4, 7), (7, 10)]),
+    (4, 2000, 9, [(1, 4), (2, 5), (3, 6), (4, 7), (5, 8), (6, 9), (7, 10)]),
+    (60, 15, 0, [(1, 2), (3, 4), (5, 6), (7, 8), (9, 10)]),
+    (60, 2000, 0, [(1, 10)]),
+])
+def test_windows(max_lines, max_chars, overlap, expected):
+    source = "\n".join(f"line {n}" for n in range(1, 11))
+    blocks = [_Block("function", 1, 10, "f")]
+    chunks = build_chunks(source, blocks, max_lines=max_lines, max_chars=max_chars, overlap=overlap)
+    assert [(chunk["start_line"], chunk["end_line"]) for chunk in chunks] == expected
+    assert [chunk["part"] for chunk in chunks] == list(range(len(expected)))
+
+def test_blank_edges_are_trimmed_and_out_of_range_blocks_dropped():
+    source = "\n\nx = 1\n\n"
+    chunks = build_chunks(source, [_Block("function", 3, 40, "f")], max_lines=60, max_chars=2000, overlap=0)
+    assert _spans(chunks) == [("module", None, 3, 3, 0)]
+    assert build_chunks("", [], max_lines=60, max_chars=2000, overlap=0) == []
+
+def test_blocks_from_extractor_names_blocks_and_dedupes_spans():
+    start = SOURCE.index("    def parse")
+    end = SOURCE.index("    def reset") - 1
+    extracted = [
+        {"type": "function", "start": start, "end": end},
+        {"type": "method", "start": start, "end": end},
+        {"type": "comment", "start": 0, "end": 9}
+    ]
+    blocks = blocks_from_extractor(SOURCE, extracted, SYMBOLS)
+    assert [(block.kind, block.symbol, block.start_line, block.end_line) for block in blocks] == [
+        ("method", "Parser.parse", 8, 9)
+    ]
+
+@pytest.mark.parametrize("embeddings, expected", [
+    ([[1.0, 3.0], [3.0, 5.0]], [2.0, 4.0]),
+    ([[1.0, 2.0], None], [1.0, 2.0]),
+    ([], None),
+    ([None], None),
+])
+def test_file_embedding_is_mean_of_chunks(embeddings, expected):
+    assert file_embedding([{"embedding": embedding} for embedding in embeddings]) == expected
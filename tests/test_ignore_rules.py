@@ -0,0 +1,119 @@
+"""Tests for gitignore-compatible ignore rules (indexer/ignore_rules.py [1.4])."""
+
+import pytest
+
+from indexer.ignore_rules import IgnoreRules, parse_rule
+
+ROOT = "/home/tests/repo"
+
+@pytest.mark.parametrize("line, path, is_dir, matches", [
+    ("*.log", "a.log", False, True),
+    ("*.log", "logs/deep/a.log", False, True),
+    ("*.log", "a.log.txt", False, False),
+    ("build/", "build", True, True),
+    ("build/", "build", False, False),
+    ("build/", "src/build", True, True),
+    ("/build", "build", False, True),
+    ("/build", "src/build", False, False),
+    ("doc/*.md", "doc/a.md", False, True),
+    ("doc/*.md", "doc/api/a.md", False, False),
+    ("doc/*.md", "src/doc/a.md", False, False),
+    ("**/cache", "cache", True, True),
+    ("**/cache", "a/b/cache", True, True),
+    ("out/**", "out/a/b.txt", False, True),
+    ("out/**", "out", True, False),
+    ("a/**/b", "a/b", False, True),
+    ("a/**/b", "a/x/y/b", False, True),
+    ("a/**/b", "x/a/b", False, False),
+    ("file?.txt", "file1.txt", False, True),
+    ("file?.txt", "file10.txt", False, False),
+    ("[abc].py", "b.py", False, True),
+    ("[!abc].py", "b.py", False, False),
+    ("[!abc].py", "d.py", False, True),
+    ("\\#notes", "#notes", False, True),
+    ("trailing   ", "trailing", False, True),
+])
+def test_parse_rule_matches(line, path, is_dir, matches):
+    assert parse_rule(line, "test", 1).matches(path, is_dir) == matches
+
+@pytest.mark.parametrize("line", ["", "\n", "# comment", "   ", "!", "/", "!/"])
+def test_parse_rule_skips_blanks_and_comments(line):
+    assert parse_rule(line, "test", 1) is None
+
+@pytest.mark.parametrize("line, negated, dir_only", [
+    ("*.log", False, False),
+    ("!keep.log", True, False),
+    ("build/", False, True),
+    ("!build/", True, True),
+])
+def test_parse_rule_flags(line, negated, dir_only):
+    rule = parse_rule(line, "test", 3)
+    assert (rule.pattern, rule.negated, rule.dir_only, rule.line) == (line, negated, dir_only, 3)
+
+def test_rule_base_limits_it_to_its_directory():
+    rule = parse_rule("/gen", "sub/.gitignore", 1, base="sub")
+    assert rule.matches("sub/gen", False)
+    assert not rule.matches("gen", False)
+    assert not rule.matches("other/sub/gen", False)
+
+def _rules(files):
+    contents = {f"{ROOT}/{name}": content for name, content in files.items()}
+    return IgnoreRules(ROOT, read_file=contents.get)
+
+@pytest.mark.parametrize("files, path, ignored", [
+    # Last matching rule wins
+    ({".gitignore": "*.log\n!keep.log\n"}, "keep.log", False),
+    ({".gitignore": "*.log\n!keep.log\n"}, "drop.log", True),
+    ({".gitignore": "!keep.log\n*.log\n"}, "keep.log", True),
+    # Deeper ignore files override shallower ones
+    ({".gitignore": "*.log\n", "sub/.gitignore": "!*.log\n"}, "sub/a.log", False),
+    ({".gitignore": "*.log\n", "sub/.gitignore": "!*.log\n"}, "a.log", True),
+    # .repoanalyzerignore overrides .gitignore in the same directory
+    ({".gitignore": "*.tsv\n", ".repoanalyzerignore": "!data.tsv\n"}, "data.tsv", False),
+    # An excluded directory keeps its contents excluded
+    ({".gitignore": "build/\n!build/keep.txt\n"}, "build/keep.txt", True),
+    ({".gitignore": "build/\n", "build/.gitignore": "!keep.txt\n"}, "build/keep.txt", True),
+    # Unless the contents, not the directory, were excluded
+    ({".gitignore": "build/*\n!build/keep.txt\n"}, "build/keep.txt", False),
+    ({".gitignore": "build/*\n!build/keep.txt\n"}, "build/other.txt", True),
+    # Anchoring
+    ({".gitignore": "/todo.txt\n"}, "todo.txt", True),
+    ({".gitignore": "/todo.txt\n"}, "docs/todo.txt", False),
+    ({"docs/.gitignore": "/todo.txt\n"}, "docs/todo.txt", True),
+    ({"docs/.gitignore": "todo.txt\n"}, "docs/api/todo.txt", True),
+    ({"docs/.gitignore": "todo.txt\n"}, "todo.txt", False),
+    # Double asterisks
+    ({".gitignore": "**/fixtures/*.json\n"}, "a/b/fixtures/x.json", True),
+    ({".gitignore": "src/**/gen\n"}, "src/a/b/gen/x.py", True),
+    ({".gitignore": "src/**/gen\n"}, "lib/gen/x.py", False),
+    # Configured patterns apply below every ignore file
+    ({}, "pkg/node_modules/x.js", True),
+    ({".gitignore": "!*.log\n"}, "a.log", False),
+    ({}, "src/main.py", False),
+])
+def test_is_ignored(files, path, ignored):
+    assert _rules(files).is_ignored(f"{ROOT}/{path}") == ignored
+
+def test_match_reports_deciding_rule_and_path():
+    rules = _rules({".gitignore": "# build output\nbuild/\n"})
+    decided = rules.match(f"{ROOT}/build/out/a.o")
+    assert decided.ignored
+    assert decided.matched == "build"
+    assert (decided.rule.source, decided.rule.line) == (f"{ROOT}/.gitignore", 2)
+    assert rules.match(f"{ROOT}/src/a.py") is None
+    assert rules.match(ROOT) is None
+
+def test_re_included_parent_explains_its_contents():
+    rules = _rules({".gitignore": "vendor/*\n!vendor/lib/\n"})
+    decided = rules.match(f"{ROOT}/vendor/lib/a.py")
+    assert not decided.ignored
+    assert decided.matched == "vendor/lib"
+
+def test_invalidate_rereads_a_directory():
+    files = {f"{ROOT}/.gitignore": "*.bak\n"}
+    rules = IgnoreRules(ROOT, read_file=files.get)
+    assert rules.is_ignored(f"{ROOT}/a.bak")
+    files[f"{ROOT}/.gitignore"] = ""
+    assert rules.is_ignored(f"{ROOT}/a.bak")
+    rules.invalidate(ROOT)
+    assert not rules.is_ignored(f"{ROOT}/a.bak")
@@ -0,0 +1,123 @@
+"""Tests for JavaScript/TypeScript module resolution (indexer/js_modules.py [1.13])."""
+
+import asyncio
+import json
+
+import pytest
+
+from indexer.js_modules import is_builtin, load_js_resolver, parse_jsonc
+
+ROOT = "/home/tests/web"
+
+@pytest.mark.parametrize("content, expected", [
+    ('{"a": 1}', {"a": 1}),
+    ('{\n  // line comment\n  "a": 1, /* block */ "b": [1, 2,],\n}', {"a": 1, "b": [1, 2]}),
+    ('/* multi\nline */ {"a": {"b": true,},}', {"a": {"b": True}}),
+    ('{"url": "http://example.com/*x*/"}', {"url": "http://example.com/*x*/"}),
+    ('{"text": "a,}", "list": ["b,]"]}', {"text": "a,}", "list": ["b,]"]}),
+    ('{"quote": "say \\"hi\\" // not a comment",}', {"quote": 'say "hi" // not a comment'}),
+])
+def test_parse_jsonc(content, expected):
+    assert parse_jsonc(content) == expected
+
+def test_parse_jsonc_rejects_invalid_json():
+    with pytest.raises(json.JSONDecodeError):
+        parse_jsonc('{"a": }')
+
+CONFIGS = {
+    "tsconfig.base.json": """{
+        // Shared options
+        "compilerOptions": {
+            "baseUrl": ".",
+            "paths": {
+                "@app/*": ["src/app/*"],
+                "@app/widgets/*": ["src/widgets/*"],
+                "@config": ["src/config/index.ts"],
+            },
+        },
+    }""",
+    "tsconfig.json": '{"extends": "./tsconfig.base", "include": ["src"]}',
+    "packages/core/package.json": json.dumps({
+        "name": "@acme/core",
+        "exports": {
+            ".": {"types": "./dist/index.d.ts", "import": "./dist/index.js"},
+            "./utils/*": "./dist/utils/*.js"
+        }
+    }),
+    "packages/ui/package.json": json.dumps({"name": "ui", "main": "lib/index.js"}),
+    "packages/ui/tsconfig.json": '{"compilerOptions": {"paths": {"~/*": ["./*"]}}}'
+}
+
+FILES = [
+    "src/main.ts",
+    "src/legacy.js",
+    "src/app/util.ts",
+    "src/app/widgets/index.tsx",
+    "src/widgets/button.tsx",
+    "src/config/index.ts",
+    "packages/core/src/index.ts",
+    "packages/core/src/utils/str.ts",
+    "packages/ui/lib/index.js",
+    "packages/ui/button.tsx"
+]
+
+@pytest.fixture
+def resolver():
+    configs = {f"{ROOT}/{name}": content for name, content in CONFIGS.items()}
+
+    async def reader(path):
+        return configs.get(path)
+
+    files = [f"{ROOT}/{name}" for name in FILES]
+    return asyncio.run(load_js_resolver(ROOT, files, reader))
+
+@pytest.mark.parametrize("importer, spec, expected", [
+    # Relative specifiers and probing
+    ("src/main.ts", "./app/util", "src/app/util.ts"),
+    ("src/main.ts", "./app/util.js", "src/app/util.ts"),
+    ("src/main.ts", "./app/widgets", "src/app/widgets/index.tsx"),
+    ("src/app/widgets/index.tsx", "../util", "src/app/util.ts"),
+    ("src/main.ts", "./legacy.js", "src/legacy.js"),
+    ("src/main.ts", "./missing", None),
+    # tsconfig paths from the extended config: exact, then the longest prefix
+    ("src/main.ts", "@app/util", "src/app/util.ts"),
+    ("src/main.ts", "@app/widgets", "src/app/widgets/index.tsx"),
+    ("src/main.ts", "@app/widgets/button", "src/widgets/button.tsx"),
+    ("src/main.ts", "@config", "src/config/index.ts"),
+    # baseUrl
+    ("src/main.ts", "src/legacy", "src/legacy.js"),
+    # The nearest tsconfig decides
+    ("packages/ui/lib/index.js", "~/button", "packages/ui/button.tsx"),
+    ("src/main.ts", "~/button", None),
+    # Workspace packages: exports fall back from dist/ to src/, else main
+    ("src/main.ts", "@acme/core", "packages/core/src/index.ts"),
+    ("src/main.ts", "@acme/core/utils/str", "packages/core/src/utils/str.ts"),
+    ("src/main.ts", "@acme/core/internal", None),
+    ("src/main.ts", "ui", "packages/ui/lib/index.js"),
+    ("src/main.ts", "ui/button", "packages/ui/button.tsx"),
+    # External
+    ("src/main.ts", "react", None),
+    ("src/main.ts", "fs", None),
+    ("src/main.ts", "node:fs", None),
+    ("src/main.ts", "", None),
+])
+def test_resolve(resolver, importer, spec, expected):
+    files = {f"{ROOT}/{name}" for name in FILES}
+    found = resolver.resolve(f"{ROOT}/{importer}", spec, files)
+    assert found == (f"{ROOT}/{expected}" if expected else None)
+
+def test_loads_configs_and_packages(resolver):
+    assert resolver.configs[ROOT].base_url == ROOT
+    assert resolver.configs[ROOT].paths["@app/*"] == [f"{ROOT}/src/app/*"]
+    assert resolver.configs[f"{ROOT}/packages/ui"].paths == {"~/*": [f"{ROOT}/packages/ui/*"]}
+    assert sorted(resolver.packages) == ["@acme/core", "ui"]
+
+@pytest.mark.parametrize("spec, builtin", [
+    ("fs", True),
+    ("fs/promises", True),
+    ("node:test", True),
+    ("react", False),
+    ("./fs", False),
+])
+def test_is_builtin(spec, builtin):
+    assert is_builtin(spec) == builtin
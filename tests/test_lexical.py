@@ -0,0 +1,86 @@
+"""Tests for identifier tokens and rank fusion (semantic/lexical.py [5.2])."""
+
+import pytest
+
+from semantic.lexical import (
+    RRF_K,
+    identifier_tokens,
+    matched_terms,
+    query_terms,
+    reciprocal_rank_fusion,
+    search_text
+)
+
+@pytest.mark.parametrize("text, tokens", [
+    ("spawn_blocking", ["spawn", "blocking", "spawnblocking"]),
+    ("HTTPServerError", ["http", "server", "error", "httpservererror"]),
+    ("getID", ["get", "id", "getid"]),
+    ("parseJSON2Value", ["parse", "json", "value", "parsejson2value"]),
+    ("std::fs::read", ["std", "fs", "read"]),
+    ("v2", ["v2"]),
+    ("x", []),
+    ("a_b", ["ab"]),
+    ("", []),
+    (None, []),
+    ("a" * 70 + "Bcd", ["a" * 70, "bcd"]),
+])
+def test_identifier_tokens(text, tokens):
+    assert identifier_tokens(text) == tokens
+
+def test_search_text_skips_missing_texts():
+    assert search_text("readFile", None, "", "x y2") == "read file readfile y2"
+
+def test_query_terms_are_distinct():
+    assert query_terms("read read_file") == ["read", "file", "readfile"]
+
+def test_matched_terms_keep_query_order():
+    assert matched_terms(["write", "file", "read"], "readFile", None) == ["file", "read"]
+
+def _key(result):
+    return result["id"]
+
+def test_rrf_sums_contributions_across_sources():
+    fused = reciprocal_rank_fusion({
+        "vector": [{"id": "a", "similarity": 0.9}, {"id": "b", "similarity": 0.8}],
+        "lexical": [{"id": "b", "text_score": 2.5, "matched_terms": ["x"]}, {"id": "c", "text_score": 1.0}]
+    }, key=_key)
+    assert [result["id"] for result in fused] == ["b", "a", "c"]
+    b = fused[0]
+    assert b["score"] == pytest.approx(1 / (RRF_K + 2) + 1 / (RRF_K + 1))
+    assert b["score_details"] == {"k": RRF_K, "sources": {
+        "vector": {"rank": 2, "contribution": pytest.approx(1 / (RRF_K + 2)), "similarity": 0.8},
+        "lexical": {"rank": 1, "contribution": pytest.approx(1 / (RRF_K + 1)), "text_score": 2.5, "matched_terms": ["x"]}
+    }}
+    # The first list a result appears in supplies its fields
+    assert b["similarity"] == 0.8 and "text_score" not in b
+
+@pytest.mark.parametrize("k, weights, order", [
+    (60, None, ["a", "x"]),
+    (60, {"lexical": 2.0}, ["x", "a"]),
+    (1, {"vector": 0.0}, ["x", "a"]),
+])
+def test_rrf_weights(k, weights, order):
+    fused = reciprocal_rank_fusion({
+        "vector": [{"id": "a"}, {"id": "x"}],
+        "lexical": [{"id": "x"}, {"id": "a"}, {"id": "z"}]
+    }, key=_key, k=k, weights=weights)
+    assert [result["id"] for result in fused][:2] == order
+
+def test_rrf_ties_keep_first_ranking_order():
+    fused = reciprocal_rank_fusion({
+        "vector": [{"id": "a"}, {"id": "b"}],
+        "lexical": [{"id": "b"}, {"id": "a"}]
+    }, key=_key)
+    assert [result["id"] for result in fused] == ["a", "b"]
+    assert fused[0]["score"] == pytest.approx(fused[1]["score"])
+
+def test_rrf_counts_a_duplicate_once_per_source():
+    fused = reciprocal_rank_fusion({"vector": [{"id": "a"}, {"id": "a"}, {"id": "b"}]}, key=_key)
+    assert [(result["id"], result["score_details"]["sources"]["vector"]["rank"]) for result in fused] == [("a", 1), ("b", 3)]
+    assert fused[0]["score"] == pytest.approx(1 / (RRF_K + 1))
+
+def test_rrf_does_not_modify_its_input():
+    result = {"id": "a"}
+    reciprocal_rank_fusion({"vector": [result]}, key=_key)
+    assert result == {"id": "a"}
+    assert reciprocal_rank_fusion({}, key=_key) == []
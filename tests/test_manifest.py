@@ -0,0 +1,77 @@
+"""Tests for index planning against the manifest (indexer/manifest.py [1.3])."""
+
+import asyncio
+
+import pytest
+
+import indexer.manifest as manifest
+from indexer.manifest import embedding_version, file_hash, parser_version, plan_indexing
+
+def _entry(digest):
+    return {"content_hash": digest, "parser_version": parser_version(), "embedding_model": embedding_version()}
+
+@pytest.fixture
+def repo(tmp_path, monkeypatch):
+    (tmp_path / ".git").mkdir()
+    (tmp_path / ".gitignore").write_text("build/\n*.gen.py\n")
+    for name in ("src/a.py", "src/b.py", "src/c.gen.py", "build/out.py"):
+        path = tmp_path / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(f"# {name}\n")
+    root = str(tmp_path)
+    entries = {
+        f"{root}/src/a.py": _entry(file_hash(f"{root}/src/a.py")),
+        f"{root}/src/b.py": _entry("stale"),
+        f"{root}/src/c.gen.py": _entry("x"),
+        f"{root}/build/out.py": _entry("x"),
+        f"{root}/src/gone.py": _entry("x"),
+        "/elsewhere/gone.py": _entry("x")
+    }
+
+    async def load_manifest(repo_id):
+        return entries
+
+    monkeypatch.setattr(manifest, "load_manifest", load_manifest)
+    return root
+
+def test_plan_splits_changed_unchanged_and_removed(repo):
+    plan = asyncio.run(plan_indexing(1, repo, [f"{repo}/src/a.py", f"{repo}/src/b.py"]))
+    assert plan.changed == [f"{repo}/src/b.py"]
+    assert plan.unchanged == [f"{repo}/src/a.py"]
+    # Newly ignored files go like deleted ones; paths outside root stay
+    assert plan.removed == [f"{repo}/build/out.py", f"{repo}/src/c.gen.py", f"{repo}/src/gone.py"]
+    assert plan.hashes[f"{repo}/src/a.py"] == file_hash(f"{repo}/src/a.py")
+
+def test_full_reindexes_everything(repo):
+    plan = asyncio.run(plan_indexing(1, repo, [f"{repo}/src/a.py"], full=True))
+    assert plan.changed == [f"{repo}/src/a.py"] and plan.unchanged == []
+
+@pytest.mark.parametrize("removed_under, removed", [
+    (["build"], ["build/out.py"]),
+    (["src/gone.py"], ["src/gone.py"]),
+    (["src/a.py"], []),
+    ([], []),
+])
+def test_removed_under_limits_removals(repo, removed_under, removed):
+    plan = asyncio.run(plan_indexing(1, repo, [], removed_under=[f"{repo}/{path}" for path in removed_under]))
+    assert plan.removed == [f"{repo}/{path}" for path in removed]
+
+class _Source:
+    """A commit holding src/a.py and src/c.gen.py, whose ignore files exclude *.gen.py."""
+
+    def __init__(self, root):
+        self.paths = {f"{root}/src/a.py", f"{root}/src/c.gen.py"}
+
+    async def hash_files(self, paths):
+        return {path: "h" for path in paths}
+
+    def exists(self, path):
+        return path in self.paths
+
+    def is_ignored(self, path):
+        return path.endswith(".gen.py")
+
+def test_source_supplies_existence_and_ignore_rules(repo):
+    plan = asyncio.run(plan_indexing(1, repo, [f"{repo}/src/a.py"], source=_Source(repo)))
+    assert plan.changed == [f"{repo}/src/a.py"]
+    assert plan.removed == [f"{repo}/build/out.py", f"{repo}/src/b.py", f"{repo}/src/c.gen.py", f"{repo}/src/gone.py"]
@@ -0,0 +1,228 @@
+"""Tests for manifest and lockfile parsing (indexer/package_deps.py [1.12])."""
+
+import json
+
+import pytest
+
+from indexer.package_deps import normalize_name, parse_lockfile, parse_manifest, parse_requirement
+
+def _rows(declarations):
+    return [(d.ecosystem, d.name, d.constraint, d.kind, d.version) for d in declarations]
+
+@pytest.mark.parametrize("line, expected", [
+    ("requests", ("requests", None)),
+    ("requests>=2.0,<3", ("requests", ">=2.0,<3")),
+    ("uvicorn[standard]==0.30.1", ("uvicorn", "==0.30.1")),
+    ("pywin32>=306; sys_platform == 'win32'", ("pywin32", ">=306")),
+    ("numpy  # arrays", ("numpy", None)),
+    ("pkg @ https://example.com/pkg.whl", ("pkg", "https://example.com/pkg.whl")),
+    ("-r base.txt", None),
+    ("--index-url https://example.com", None),
+    ("./local/pkg", None),
+    ("https://example.com/pkg.whl", None),
+    ("# comment", None),
+    ("", None),
+])
+def test_parse_requirement(line, expected):
+    assert parse_requirement(line) == expected
+
+def test_requirements_txt_kind_follows_file_name():
+    content = "requests==2.31.0\n-e .\nflake8>=6\n"
+    assert _rows(parse_manifest("requirements.txt", content, "requirements.txt")) == [
+        ("pypi", "requests", "==2.31.0", "runtime", "2.31.0"),
+        ("pypi", "flake8", ">=6", "runtime", None)
+    ]
+    assert [d.kind for d in parse_manifest("requirements-dev.txt", content, "requirements-dev.txt")] == ["dev", "dev"]
+
+def test_pyproject():
+    content = """
+[build-system]
+requires = ["setuptools>=61"]
+
+[project]
+dependencies = ["httpx==0.27.0", "pydantic>=2"]
+
+[project.optional-dependencies]
+fast = ["orjson"]
+
+[dependency-groups]
+test = ["pytest>=8", {include-group = "lint"}]
+
+[tool.poetry.dependencies]
+python = "^3.10"
+rich = "^13.0"
+local = {path = "../local"}
+extra = {version = "1.0", optional = true}
+
+[tool.poetry.group.docs.dependencies]
+mkdocs = {git = "https://github.com/mkdocs/mkdocs"}
+"""
+    assert _rows(parse_manifest("pyproject.toml", content, "pyproject.toml")) == [
+        ("pypi", "httpx", "==0.27.0", "runtime", "0.27.0"),
+        ("pypi", "pydantic", ">=2", "runtime", None),
+        ("pypi", "orjson", None, "optional", None),
+        ("pypi", "pytest", ">=8", "dev", None),
+        ("pypi", "setuptools", ">=61", "build", None),
+        ("pypi", "rich", "^13.0", "runtime", None),
+        ("pypi", "extra", "1.0", "optional", None),
+        ("pypi", "mkdocs", "git https://github.com/mkdocs/mkdocs", "dev", None)
+    ]
+
+def test_cargo_toml():
+    content = """
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = "1"
+core = { path = "../core" }
+rand_core = { package = "rand-core", version = "0.6", optional = true }
+anyhow = { workspace = true }
+tracing = { git = "https://github.com/tokio-rs/tracing", tag = "v0.1" }
+
+[dev-dependencies]
+proptest = "1"
+
+[build-dependencies]
+cc = "1.0"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[workspace.dependencies]
+anyhow = "1.0"
+"""
+    declarations = parse_manifest("Cargo.toml", content, "crates/app/Cargo.toml")
+    assert _rows(declarations) == [
+        ("cargo", "serde", "1.0", "runtime", None),
+        ("cargo", "tokio", "1", "runtime", None),
+        ("cargo", "rand-core", "0.6", "optional", None),
+        ("cargo", "anyhow", None, "runtime", None),
+        ("cargo", "tracing", "git https://github.com/tokio-rs/tracing#v0.1", "runtime", None),
+        ("cargo", "libc", "0.2", "runtime", None),
+        ("cargo", "proptest", "1", "dev", None),
+        ("cargo", "cc", "1.0", "build", None),
+        ("cargo", "anyhow", "1.0", "runtime", None)
+    ]
+    assert declarations[2].imports == ["rand_core"]
+    assert {d.manifest for d in declarations} == {"crates/app/Cargo.toml"}
+
+def test_package_json():
+    content = json.dumps({
+        "dependencies": {"react": "18.2.0", "@acme/core": "workspace:*", "local": "file:../local"},
+        "devDependencies": {"typescript": "^5.4.0"},
+        "peerDependencies": {"react-dom": ">=18"},
+        "optionalDependencies": {"fsevents": "~2.3.0"}
+    })
+    assert _rows(parse_manifest("package.json", content, "package.json")) == [
+        ("npm", "react", "18.2.0", "runtime", None),
+        ("npm", "typescript", "^5.4.0", "dev", None),
+        ("npm", "react-dom", ">=18", "peer", None),
+        ("npm", "fsevents", "~2.3.0", "optional", None)
+    ]
+
+def test_go_mod():
+    content = """module example.com/app
+
+go 1.22
+
+require github.com/pkg/errors v0.9.1
+
+require (
+\tgolang.org/x/sync v0.7.0
+\t// a comment
+\tgolang.org/x/text v0.14.0 // indirect
+)
+"""
+    assert _rows(parse_manifest("go.mod", content, "go.mod")) == [
+        ("go", "github.com/pkg/errors", "v0.9.1", "runtime", "v0.9.1"),
+        ("go", "golang.org/x/sync", "v0.7.0", "runtime", "v0.7.0"),
+        ("go", "golang.org/x/text", "v0.14.0", "indirect", "v0.14.0")
+    ]
+
+@pytest.mark.parametrize("file_name, content, expected", [
+    ("Cargo.lock", """
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.60"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+""", {"serde": {"1.0.200"}, "syn": {"1.0.109", "2.0.60"}}),
+    ("poetry.lock", """
+[[package]]
+name = "requests"
+version = "2.31.0"
+
+[[package]]
+name = "idna"
+version = "3.7"
+""", {"requests": {"2.31.0"}, "idna": {"3.7"}}),
+    ("package-lock.json", json.dumps({"packages": {
+        "": {"name": "app"},
+        "node_modules/react": {"version": "18.2.0"},
+        "node_modules/@babel/core": {"version": "7.24.0"},
+        "node_modules/a/node_modules/react": {"version": "17.0.2"}
+    }}), {"react": {"18.2.0"}, "@babel/core": {"7.24.0"}}),
+    ("package-lock.json", json.dumps({"dependencies": {
+        "lodash": {"version": "4.17.21"}
+    }}), {"lodash": {"4.17.21"}}),
+    ("yarn.lock", """# yarn lockfile v1
+
+"@babel/core@^7.0.0", "@babel/core@^7.24.0":
+  version "7.24.0"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz"
+
+lodash@^4.17.0:
+  version "4.17.21"
+""", {"@babel/core": {"7.24.0"}, "lodash": {"4.17.21"}}),
+    ("yarn.lock", """__metadata:
+  version: 8
+
+"react@npm:^18.2.0":
+  version: 18.2.0
+""", {"react": {"18.2.0"}}),
+    ("pnpm-lock.yaml", """lockfileVersion: '6.0'
+
+dependencies:
+  react:
+    specifier: ^18.2.0
+    version: 18.2.0
+
+packages:
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-x}
+
+  /@babel/core@7.24.0(supports-color@8.1.1):
+    resolution: {integrity: sha512-y}
+""", {"react": {"18.2.0"}, "@babel/core": {"7.24.0"}}),
+    ("pnpm-lock.yaml", """lockfileVersion: '9.0'
+
+packages:
+
+  'react@18.2.0':
+    resolution: {integrity: sha512-x}
+""", {"react": {"18.2.0"}}),
+])
+def test_parse_lockfile(file_name, content, expected):
+    assert {name: set(versions) for name, versions in parse_lockfile(file_name, content).items()} == expected
+
+@pytest.mark.parametrize("ecosystem, name, normalized", [
+    ("pypi", "Flask_SQLAlchemy", "flask-sqlalchemy"),
+    ("pypi", "zope.interface", "zope-interface"),
+    ("npm", "@Scope/Name", "@Scope/Name"),
+    ("cargo", "serde_json", "serde_json"),
+])
+def test_normalize_name(ecosystem, name, normalized):
+    assert normalize_name(ecosystem, name) == normalized
@@ -0,0 +1,68 @@
+"""Tests for the multi-process parse stage (indexer/parse_pool.py [2.6])."""
+
+import asyncio
+from concurrent.futures import Executor, Future
+from concurrent.futures.process import BrokenProcessPool
+
+import pytest
+
+import indexer.parse_pool as parse_pool
+from indexer.parse_pool import ParsedFile, ParsePool
+
+class _Executor(Executor):
+    """Parses every file it is given, or fails them all as a pool whose worker died."""
+
+    def __init__(self, broken=False):
+        self.broken = broken
+        self.submitted = []
+        self.shut_down = False
+
+    def submit(self, fn, *args, **kwargs):
+        self.submitted.append(args[0])
+        future = Future()
+        if self.broken:
+            future.set_exception(BrokenProcessPool("A child process terminated abruptly"))
+        else:
+            future.set_result(ParsedFile(file_path=args[0], language="python"))
+        return future
+
+    def shutdown(self, wait=True, *, cancel_futures=False):
+        self.shut_down = True
+
+async def _log(message, level="info", context=None):
+    pass
+
+@pytest.fixture
+def pool(monkeypatch):
+    monkeypatch.setattr(parse_pool, "log", _log)
+    pool = ParsePool()
+    pool._initialized = True
+    pool._workers = 1
+    pool._executor = _Executor(broken=True)
+    monkeypatch.setattr(pool, "_new_executor", lambda: _Executor())
+    return pool
+
+def test_dead_worker_fails_its_file_and_restarts_the_pool(pool):
+    broken = pool._executor
+    files = ["/repo/a.py", "/repo/b.py", "/repo/c.py"]
+    stored = []
+
+    async def read(path):
+        return "x = 1\n"
+
+    async def write(parsed, content):
+        stored.append(parsed.file_path)
+
+    stats = asyncio.run(pool.run(files, read, write))
+    assert broken.submitted == ["/repo/a.py"] and broken.shut_down
+    assert stats["failed_files"] == ["/repo/a.py"]
+    assert stats["errors"]["/repo/a.py"].startswith("Parser process died")
+    assert stored == ["/repo/b.py", "/repo/c.py"]
+    assert pool._executor.submitted == ["/repo/b.py", "/repo/c.py"]
+
+def test_replacing_an_already_replaced_pool_is_a_no_op(pool):
+    broken = pool._executor
+    asyncio.run(pool._replace_executor(broken))
+    replacement = pool._executor
+    asyncio.run(pool._replace_executor(broken))
+    assert pool._executor is replacement and replacement is not broken
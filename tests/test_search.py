@@ -0,0 +1,97 @@
+"""Tests for code and doc search over the storage backend (semantic/search.py [5.0])."""
+
+import asyncio
+
+import pytest
+
+import semantic.search as search
+from semantic.search import SearchEngine
+
+CHUNKS = [
+    {"id": 1, "repo_id": 1, "file_path": "/repo/cmd/main.go", "language": "go", "kind": "function",
+     "symbol": "main", "parent_symbol": None, "start_line": 3, "end_line": 9, "part": 0,
+     "content": "func main() {\n\tserve()\n}"},
+    {"id": 2, "repo_id": 1, "file_path": "/repo/src/server.cpp", "language": "cpp", "kind": "function",
+     "symbol": "serve", "parent_symbol": None, "start_line": 10, "end_line": 20, "part": 0,
+     "content": "void serve() {}"},
+    {"id": 3, "repo_id": 1, "file_path": "/repo/Cargo.toml", "language": "toml", "kind": "module",
+     "symbol": None, "parent_symbol": None, "start_line": 1, "end_line": 4, "part": 0,
+     "content": "[package]\nname = \"serve\""}
+]
+
+DOCS = [
+    {"id": 7, "file_path": "/repo/README.md", "doc_type": "markdown", "related_code_path": None, "metadata": None,
+     "content": "Run the server:\n```go\nserve()\n```\n"}
+]
+
+class _Storage:
+    """Vector ranking in row order, lexical ranking of the rows named serve."""
+
+    def __init__(self, rows):
+        self.rows = rows
+
+    async def vector_search(self, table, embedding, **kwargs):
+        return [{"id": row["id"], "similarity": 1.0 - n / 10} for n, row in enumerate(self.rows)]
+
+    async def text_search(self, table, terms, **kwargs):
+        return [{"id": row["id"], "text_score": 1.0} for row in self.rows if row.get("symbol") == "serve"]
+
+    async def fetch(self, sql, ids):
+        return [dict(row) for row in self.rows if row["id"] in ids]
+
+class _Embedder:
+    async def embed_code(self, text, language, context=None):
+        return [1.0, 0.0]
+
+    async def embed_text(self, text, context=None):
+        return [1.0, 0.0]
+
+async def _log(message, level="info", context=None):
+    pass
+
+@pytest.fixture
+def engine(monkeypatch):
+    # The global logger's writer task outlives asyncio.run
+    monkeypatch.setattr(search, "log", _log)
+    monkeypatch.setattr(search, "code_embedder", _Embedder())
+    monkeypatch.setattr(search, "doc_embedder", _Embedder())
+    return _engine
+
+def _engine(rows):
+    engine = SearchEngine()
+    engine._initialized = True
+    engine._search_config = {}
+    engine._storage = _Storage(rows)
+    return engine
+
+def test_search_code_returns_hits_in_any_language(engine):
+    results = asyncio.run(engine(CHUNKS).search_code("serve"))
+    assert [(result["file"], result["language"]) for result in results] == [
+        ("/repo/src/server.cpp", "cpp"),
+        ("/repo/cmd/main.go", "go"),
+        ("/repo/Cargo.toml", "toml")
+    ]
+    main = results[1]
+    assert (main["line"], main["symbol"], main["snippet"]) == (3, "main", CHUNKS[0]["content"])
+    assert set(main["score_details"]["sources"]) == {"vector"}
+    assert set(results[0]["score_details"]["sources"]) == {"vector", "lexical"}
+
+def test_search_code_pages_the_fused_ranking(engine):
+    results = asyncio.run(engine(CHUNKS).search_code("serve", limit=1, offset=1))
+    assert [result["file"] for result in results] == ["/repo/cmd/main.go"]
+
+def test_search_docs_extracts_code_blocks(engine):
+    results = asyncio.run(engine(DOCS).search_docs("serve"))
+    assert [result["file_path"] for result in results] == ["/repo/README.md"]
+    assert results[0]["code_blocks"] == [{"language": "go", "content": "serve()"}]
+
+def test_search_code_raises_storage_errors(engine):
+
+    class _Failing(_Storage):
+        async def vector_search(self, table, embedding, **kwargs):
+            raise RuntimeError("connection lost")
+
+    failing = engine(CHUNKS)
+    failing._storage = _Failing(CHUNKS)
+    with pytest.raises(RuntimeError):
+        asyncio.run(failing.search_code("serve"))
@@ -0,0 +1,119 @@
+"""Tests for search filter parsing and SQL (semantic/filters.py [5.3])."""
+
+import re
+import sqlite3
+
+import pytest
+
+from db.embedded_store import SqliteDialect, _regexp
+from semantic.filters import SearchFilters, glob_regex, parse_query, where_clause
+
+ROOT = "/home/tests/repo"
+
+@pytest.mark.parametrize("query, text, include, exclude", [
+    ("spawn_blocking", "spawn_blocking", {}, {}),
+    ("lang:rust spawn", "spawn", {"lang": ["rust"]}, {}),
+    ("language:rust,go file:src/**", "", {"lang": ["rust", "go"], "path": ["src/**"]}, {}),
+    ("-path:tests kind:function parse", "parse", {"kind": ["function"]}, {"path": ["tests"]}),
+    ('path:"docs/user guide/**" intro', "intro", {"path": ["docs/user guide/**"]}, {}),
+    ("repo:foo repo:foo x", "x", {"repo": ["foo"]}, {}),
+    ("std::fs::read http://example.com", "std::fs::read http://example.com", {}, {}),
+    ("a-path:tests", "a-path:tests", {}, {}),
+])
+def test_parse_query(query, text, include, exclude):
+    parsed_text, filters = parse_query(query)
+    assert parsed_text == text
+    assert filters.include == include
+    assert filters.exclude == exclude
+
+def test_unknown_filter_is_rejected():
+    with pytest.raises(ValueError):
+        SearchFilters().add("owner", "me")
+
+@pytest.mark.parametrize("pattern, path, matches", [
+    ("src/**", "src/lib/a.py", True),
+    ("src/**", "lib/src/a.py", False),
+    ("src/*.rs", "src/main.rs", True),
+    ("src/*.rs", "src/bin/main.rs", False),
+    ("src/**/*.rs", "src/main.rs", True),
+    ("src/**/*.rs", "src/bin/main.rs", True),
+    ("*.py", "a.py", True),
+    ("*.py", "pkg/a.py", True),
+    ("*.py", "a.pyc", False),
+    ("tests", "tests/test_a.py", True),
+    ("tests", "pkg/tests/test_a.py", True),
+    ("tests", "mytests/a.py", False),
+    ("tests", "tests.py", False),
+    ("/src/", "src/a.py", True),
+    ("a?.py", "ab.py", True),
+    ("a?.py", "a/.py", False),
+    ("a.py", "axpy", False),
+])
+def test_glob_regex(pattern, path, matches):
+    assert (re.search(glob_regex(pattern), path) is not None) == matches
+
+@pytest.fixture
+def store():
+    conn = sqlite3.connect(":memory:")
+    conn.create_function("regexp", 2, _regexp, deterministic=True)
+    conn.executescript("""
+        CREATE TABLE repositories (id INTEGER PRIMARY KEY, repo_name TEXT, root_path TEXT);
+        CREATE TABLE code_chunks (id INTEGER PRIMARY KEY, repo_id INTEGER, file_path TEXT, language TEXT, kind TEXT);
+        CREATE TABLE repo_docs (id INTEGER PRIMARY KEY, file_path TEXT, doc_type TEXT);
+        CREATE TABLE repo_doc_relations (repo_id INTEGER, doc_id INTEGER);
+    """)
+    conn.executemany("INSERT INTO repositories VALUES (?, ?, ?)", [
+        (1, "repo", ROOT),
+        (2, "other", None)
+    ])
+    conn.executemany("INSERT INTO code_chunks VALUES (?, ?, ?, ?, ?)", [
+        (1, 1, f"{ROOT}/src/lib/a.py", "python", "function"),
+        (2, 1, f"{ROOT}/src/main.rs", "rust", "function"),
+        (3, 1, f"{ROOT}/tests/test_a.py", "python", "function"),
+        (4, 1, f"{ROOT}/README.py", "python", "module"),
+        (5, 2, "/elsewhere/src/b.py", "python", "class")
+    ])
+    conn.executemany("INSERT INTO repo_docs VALUES (?, ?, ?)", [
+        (1, f"{ROOT}/docs/guide.md", "markdown"),
+        (2, f"{ROOT}/src/a.py", "docstring")
+    ])
+    conn.executemany("INSERT INTO repo_doc_relations VALUES (?, ?)", [(1, 1), (1, 2)])
+    yield conn
+    conn.close()
+
+def _ids(conn, table, filters):
+    where, params = where_clause(filters, table)
+    sql = SqliteDialect.translate(f"SELECT id FROM {table} WHERE {where or 'true'} ORDER BY id")
+    return [row[0] for row in conn.execute(sql, [SqliteDialect.param(p) for p in params])]
+
+@pytest.mark.parametrize("query, ids", [
+    ("path:src/**", [1, 2]),
+    ("path:src/*.rs", [2]),
+    ("-path:tests", [1, 2, 4, 5]),
+    ("path:tests", [3]),
+    ("lang:rust", [2]),
+    ("-lang:rust kind:function", [1, 3]),
+    ("repo:repo kind:module,class", [4]),
+    ("repo:2", [5]),
+    ("path:*.py -path:tests", [1, 4, 5]),
+])
+def test_where_clause_code_chunks(store, query, ids):
+    _, filters = parse_query(query)
+    assert _ids(store, "code_chunks", filters) == ids
+
+@pytest.mark.parametrize("query, ids", [
+    ("path:docs/**", [1]),
+    ("kind:docstring", [2]),
+    ("lang:rust", [1, 2]),
+    ("-path:src", [1]),
+])
+def test_where_clause_repo_docs(store, query, ids):
+    _, filters = parse_query(query)
+    assert _ids(store, "repo_docs", filters) == ids
+
+def test_where_clause_numbers_parameters_from_start():
+    _, filters = parse_query("lang:rust path:src/**")
+    where, params = where_clause(filters, "code_chunks", start=3)
+    assert "$3" in where and "$4" in where and "$1" not in where
+    assert params[0] == ["rust"]
+    assert where_clause(SearchFilters(), "code_chunks") == (None, [])
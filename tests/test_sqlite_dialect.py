@@ -0,0 +1,87 @@
+"""Tests for the PostgreSQL-to-SQLite rewrites of the embedded backend (db/embedded_store.py [6.10])."""
+
+import sqlite3
+
+import pytest
+
+from db.embedded_store import SqliteDialect, _rank, _regexp
+
+@pytest.mark.parametrize("sql, expected", [
+    ("id SERIAL PRIMARY KEY", "id INTEGER PRIMARY KEY AUTOINCREMENT"),
+    ("id BIGSERIAL PRIMARY KEY", "id INTEGER PRIMARY KEY AUTOINCREMENT"),
+    ("at TIMESTAMP WITH TIME ZONE", "at TIMESTAMP"),
+    ("metadata JSONB", "metadata TEXT"),
+    ("embedding VECTOR(768)", "embedding TEXT"),
+    ("names TEXT[]", "names TEXT"),
+    ("SELECT $1::text, $2::int[]", "SELECT ?1, ?2"),
+    ("WHERE id = ANY($3::int[])", "WHERE id IN (SELECT value FROM json_each(?3))"),
+    ("WHERE a = $1 AND b = $12", "WHERE a = ?1 AND b = ?12"),
+    ("UPDATE t SET updated_at = NOW()", "UPDATE t SET updated_at = CURRENT_TIMESTAMP"),
+    ("WHERE name ILIKE $1", "WHERE name LIKE ?1"),
+    ("WHERE file_path ~ $1", "WHERE file_path REGEXP ?1"),
+    ("SELECT GREATEST(a, b), LEAST(a, b)", "SELECT MAX(a, b), MIN(a, b)"),
+    ("DROP TABLE code_chunks CASCADE;", "DROP TABLE code_chunks;"),
+])
+def test_translate(sql, expected):
+    assert SqliteDialect.translate(sql) == expected
+
+@pytest.mark.parametrize("sql", [
+    "CREATE INDEX idx ON code_chunks USING ivfflat (embedding vector_cosine_ops)",
+    "CREATE INDEX idx ON code_chunks USING gin (to_tsvector('simple', search_text))",
+    "CREATE EXTENSION IF NOT EXISTS vector",
+    "SET statement_timeout = 0",
+])
+def test_translate_skips_unsupported_statements(sql):
+    assert SqliteDialect.translate(sql) is None
+
+@pytest.mark.parametrize("sql, table, column", [
+    ("ALTER TABLE code_chunks ADD COLUMN IF NOT EXISTS search_text TEXT", "code_chunks", "search_text"),
+    ("alter table repositories add column if not exists root_path TEXT", "repositories", "root_path"),
+])
+def test_add_column_if_not_exists(sql, table, column):
+    assert SqliteDialect.ADD_COLUMN.match(SqliteDialect.translate(sql)).groups() == (table, column)
+
+def test_split_drops_comments_and_empty_statements():
+    script = """
+    CREATE TABLE a (id INTEGER); -- first
+    ;
+    CREATE TABLE b (id INTEGER);
+    """
+    assert SqliteDialect.split(script) == ["CREATE TABLE a (id INTEGER)", "CREATE TABLE b (id INTEGER)"]
+
+@pytest.mark.parametrize("value, expected", [
+    (True, 1),
+    (None, None),
+    ("text", "text"),
+    ([1, 2], "[1, 2]"),
+    (("a", "b"), '["a", "b"]'),
+    ({"k": 1}, '{"k": 1}'),
+])
+def test_param(value, expected):
+    assert SqliteDialect.param(value) == expected
+
+def test_translated_statements_run_on_sqlite():
+    conn = sqlite3.connect(":memory:")
+    conn.create_function("regexp", 2, _regexp, deterministic=True)
+    conn.execute(SqliteDialect.translate(
+        "CREATE TABLE t (id SERIAL PRIMARY KEY, path TEXT NOT NULL, meta JSONB, at TIMESTAMP WITH TIME ZONE)"
+    ))
+    conn.executemany(
+        SqliteDialect.translate("INSERT INTO t (path, meta, at) VALUES ($1, $2::jsonb, NOW())"),
+        [("src/a.py", '{}'), ("src/b.rs", '{}'), ("tests/c.py", None)]
+    )
+    sql = SqliteDialect.translate(
+        "SELECT id FROM t WHERE id = ANY($1::int[]) AND path ~ $2 AND path ILIKE $3 ORDER BY id"
+    )
+    params = [SqliteDialect.param(p) for p in ([1, 2, 3], r"\.py$", "SRC/%")]
+    assert [row[0] for row in conn.execute(sql, params)] == [1]
+    conn.close()
+
+@pytest.mark.parametrize("ids, expected", [
+    ([3, None, 1], [1, 3, None]),
+    (["b", None, "a"], ["a", "b", None]),
+])
+def test_rank_sorts_null_tiebreaks_last(ids, expected):
+    rows = [{"id": i, "score": 1.0} for i in ids]
+    _rank(rows, "score", "id")
+    assert [row["id"] for row in rows] == expected
@@ -16,6 +16,7 @@ from utils.shutdown import execute_shutdown_handlers
 from utils.error_handling import AsyncErrorBoundary, ProcessingError, ErrorSeverity, ErrorAudit
 from utils.health_monitor import global_health_monitor, ComponentStatus
 from db.connection import connection_manager
+from db.storage import get_storage_backend, is_embedded
 from db.neo4j_ops import create_schema_indexes_and_constraints, get_neo4j_tools
 from db.transaction import get_transaction_coordinator, initialize_transaction_coordinator
 
@@ -142,8 +143,8 @@ async def _initialize_components():
 
 async def _initialize_database_components():
     """Initialize database components in proper order."""
-    # Initialize connection manager first
-    await connection_manager.initialize()
+    # Open the configured storage backend first
+    backend = await get_storage_backend()
     
     # Initialize transaction coordinator
     await initialize_transaction_coordinator()
@@ -152,6 +153,13 @@ async def _initialize_database_components():
     from db.psql import initialize as init_psql
     await init_psql()
     
+    if is_embedded():
+        # Tables live in the local file; there is no Neo4j to set up
+        from db.schema import get_schema_manager
+        schema_manager = await get_schema_manager()
+        await schema_manager.create_all_tables()
+        return
+    
     # Initialize Neo4j tools and schema
     neo4j_tools = await get_neo4j_tools()
     await create_schema_indexes_and_constraints()
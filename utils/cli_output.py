@@ -0,0 +1,129 @@
+"""Command line output formatting.
+
+Flow:
+1. Output Formats:
+   - json: A single JSON document on stdout for scripts
+   - table: Aligned plain-text columns for humans
+
+2. Integration Points:
+   - index.py subcommands return plain dicts/lists which are rendered here
+   - Log output goes to stderr so stdout stays machine-readable
+"""
+
+import json
+import sys
+from datetime import date, datetime
+from typing import Any, Dict, List, Optional, Sequence, TextIO
+
+OUTPUT_FORMATS = ("json", "table")
+
+# Exit codes returned by the CLI subcommands
+EXIT_OK = 0
+EXIT_ERROR = 1
+EXIT_USAGE = 2
+EXIT_NO_RESULTS = 3
+
+def json_default(value: Any) -> Any:
+    """Serialize values json does not handle natively."""
+    if isinstance(value, (datetime, date)):
+        return value.isoformat()
+    if isinstance(value, (set, frozenset, tuple)):
+        return list(value)
+    if hasattr(value, "to_dict"):
+        return value.to_dict()
+    if hasattr(value, "tolist"):
+        return value.tolist()
+    return str(value)
+
+def _cell(value: Any, max_width: int) -> str:
+    """Render a single table cell on one line."""
+    if value is None:
+        text = ""
+    elif isinstance(value, (dict, list)):
+        text = json.dumps(value, default=json_default)
+    else:
+        text = str(value)
+    text = " ".join(text.split())
+    if len(text) > max_width:
+        text = text[:max_width - 3] + "..."
+    return text
+
+def format_table(
+    rows: Sequence[Dict[str, Any]],
+    columns: Optional[List[str]] = None,
+    max_width: int = 60
+) -> str:
+    """Format a list of dicts as an aligned text table."""
+    if not rows:
+        return "(no results)"
+    if columns:
+        columns = [col for col in columns if any(col in row for row in rows)]
+    if not columns:
+        columns = []
+        for row in rows:
+            for key in row.keys():
+                if key not in columns:
+                    columns.append(key)
+
+    cells = [[_cell(row.get(col), max_width) for col in columns] for row in rows]
+    widths = [
+        max(len(col), *(len(line[i]) for line in cells))
+        for i, col in enumerate(columns)
+    ]
+
+    lines = [
+        "  ".join(col.upper().ljust(widths[i]) for i, col in enumerate(columns)),
+        "  ".join("-" * width for width in widths)
+    ]
+    for line in cells:
+        lines.append("  ".join(value.ljust(widths[i]) for i, value in enumerate(line)).rstrip())
+    return "\n".join(lines)
+
+def emit(
+    data: Any,
+    output_format: str = "json",
+    columns: Optional[List[str]] = None,
+    stream: Optional[TextIO] = None
+) -> None:
+    """Write a command result to stdout in the requested format.
+
+    Dict results are rendered as a two column key/value table, lists of
+    dicts as one row per item.
+    """
+    stream = stream or sys.stdout
+    if output_format == "json":
+        stream.write(json.dumps(data, indent=2, default=json_default))
+    elif isinstance(data, dict):
+        rows = [{"key": key, "value": value} for key, value in data.items()]
+        stream.write(format_table(rows, ["key", "value"]))
+    elif isinstance(data, list):
+        rows = [row if isinstance(row, dict) else {"value": row} for row in data]
+        stream.write(format_table(rows, columns))
+    else:
+        stream.write(str(data))
+    stream.write("\n")
+    stream.flush()
+
+def emit_error(
+    message: str,
+    output_format: str = "json",
+    code: int = EXIT_ERROR,
+    stream: Optional[TextIO] = None
+) -> None:
+    """Report a command failure in the requested format."""
+    if output_format == "json":
+        emit({"error": message, "exit_code": code}, "json", stream=stream)
+    else:
+        sys.stderr.write(f"error: {message}\n")
+
+__all__ = [
+    "OUTPUT_FORMATS",
+    "EXIT_OK",
+    "EXIT_ERROR",
+    "EXIT_USAGE",
+    "EXIT_NO_RESULTS",
+    "json_default",
+    "format_table",
+    "emit",
+    "emit_error"
+]
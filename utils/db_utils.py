@@ -13,6 +13,7 @@ from utils.logger import log, logger
 from db.psql import query
 from db.schema import create_all_tables
 from db.neo4j_ops import run_query  # Import the Neo4j query function
+from db.storage import get_storage_backend, is_embedded
 from utils.error_handling import handle_async_errors, AsyncErrorBoundary
 
 # Create the argument parser at module level
@@ -64,6 +65,12 @@ async def clean_neo4j():
     log("Cleaning Neo4j database...", level="info")
     async with AsyncErrorBoundary("cleaning neo4j"):
         try:
+            if is_embedded():
+                # The embedded graph lives in the SQLite file, not in Neo4j
+                backend = await get_storage_backend()
+                await backend.clear_graph()
+                log("Embedded graph cleaned.", level="info")
+                return True
             # Use the Neo4j driver to clean the database
             task = asyncio.create_task(run_query("MATCH (n) DETACH DELETE n"))
             await task
@@ -96,7 +96,7 @@ class EnhancedLogger:
                 format='%(asctime)s - %(levelname)s - %(message)s',
                 handlers=[
                     logging.FileHandler(log_file),
-                    logging.StreamHandler(sys.stdout)
+                    logging.StreamHandler(sys.stderr)
                 ]
             )
         except Exception as e:
@@ -0,0 +1,12 @@
+"""TOML parsing across Python versions.
+
+tomllib joined the standard library in 3.11; older interpreters use the
+tomli backport, which has the same API.
+"""
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+__all__ = ["tomllib"]
@@ -1,12 +1,18 @@
-"""File watcher implementation."""
+"""File watcher implementation.
+
+Created, modified, deleted and moved files are debounced and coalesced into
+batches; each batch is indexed by process_file_batch() with one projection
+refresh, and patterns of removed files are cleaned up.
+"""
 
 import os
 from watchdog.observers import Observer
-from watchdog.events import FileSystemEventHandler, FileModifiedEvent
+from watchdog.events import FileSystemEventHandler
 from utils.logger import log
-from indexer.unified_indexer import ProcessingCoordinator
+from indexer.unified_indexer import ProcessingCoordinator, process_file_batch
 from indexer.clone_and_index import get_or_create_repo
 from indexer.file_utils import is_binary_file, is_processable_file, get_relative_path
+from indexer.ignore_rules import invalidate_ignore_file
 from parsers.types import FileType
 from parsers.models import FileClassification
 from parsers.language_mapping import normalize_language_name
@@ -14,6 +20,8 @@ from parsers.file_classification import classify_file
 from tree_sitter_language_pack import get_binding, get_language, get_parser, SupportedLanguage
 from db.upsert_ops import UpsertCoordinator
 from db.neo4j_ops import get_graph_sync
+from db.pattern_storage import get_pattern_storage
+from config.config import indexing_config
 from utils.error_handling import (
     handle_async_errors, 
     AsyncErrorBoundary, 
@@ -21,7 +29,7 @@ from utils.error_handling import (
     ProcessingError
 )
 from utils.shutdown import register_shutdown_handler
-from typing import Callable, Dict, Any, Optional, Set
+from typing import Awaitable, Callable, Dict, Any, List, Optional, Set
 import asyncio
 from ai_tools.reference_repository_learning import ReferenceRepositoryLearning
 
@@ -38,15 +46,31 @@ async def get_or_init_graph_sync():
         _graph_sync = await get_graph_sync()
     return _graph_sync
 
+# Coalesced state of a path within a batch
+CHANGED = "changed"
+DELETED = "deleted"
+
 class AsyncFileHandler(FileSystemEventHandler):
-    """Asynchronous file event handler."""
+    """Asynchronous file event handler.
+    
+    Watchdog calls the on_* methods from its observer thread. Events are
+    handed to the event loop and coalesced per path (the last event wins; a
+    move deletes the source and changes the destination). Once events settle
+    for IndexingConfig.watch_debounce seconds, on_batch receives the changed
+    and deleted paths; batches never overlap.
+    """
     
     def __init__(self):
         """Private constructor - use create() instead."""
         super().__init__()
         self._initialized = False
         self._pending_tasks: Set[asyncio.Task] = set()
-        self.on_change = None
+        self._flush_lock = asyncio.Lock()
+        self._loop: Optional[asyncio.AbstractEventLoop] = None
+        self._changes: Dict[str, str] = {}
+        self._first_event: Optional[float] = None
+        self._timer: Optional[asyncio.TimerHandle] = None
+        self.on_batch = None
     
     async def ensure_initialized(self):
         """Ensure the instance is properly initialized before use."""
@@ -55,10 +79,11 @@ class AsyncFileHandler(FileSystemEventHandler):
         return True
     
     @classmethod
-    async def create(cls, on_change: Callable[[str], None]) -> 'AsyncFileHandler':
+    async def create(cls, on_batch: Callable[[List[str], List[str]], Awaitable[Any]]) -> 'AsyncFileHandler':
         """Async factory method to create and initialize an AsyncFileHandler instance."""
         instance = cls()
-        instance.on_change = on_change
+        instance.on_batch = on_batch
+        instance._loop = asyncio.get_running_loop()
         
         try:
             async with AsyncErrorBoundary(
@@ -89,42 +114,96 @@ class AsyncFileHandler(FileSystemEventHandler):
             await instance.cleanup()
             raise ProcessingError(f"Failed to initialize file handler: {e}")
     
+    def on_created(self, event):
+        """Handle file and directory creation events."""
+        self._post(CHANGED, event.src_path)
+    
     def on_modified(self, event):
         """Handle file modification events."""
-        if isinstance(event, FileModifiedEvent):
-            # Create task for change handling
-            task = asyncio.create_task(self._handle_change(event.src_path))
-            self._pending_tasks.add(task)
-            task.add_done_callback(lambda t: self._pending_tasks.remove(t) if t in self._pending_tasks else None)
+        # A directory is "modified" whenever an entry changes; the entry has its own event
+        if not event.is_directory:
+            self._post(CHANGED, event.src_path)
+    
+    def on_deleted(self, event):
+        """Handle file and directory deletion events."""
+        self._post(DELETED, event.src_path)
+    
+    def on_moved(self, event):
+        """Handle renames: the old path is gone and the new one has content."""
+        self._post(DELETED, event.src_path)
+        self._post(CHANGED, event.dest_path)
     
-    async def _handle_change(self, file_path: str):
-        """Handle file change asynchronously."""
+    def _post(self, kind: str, path: str) -> None:
+        """Hand an event from the observer thread to the event loop."""
+        if self._loop is None or not self._initialized:
+            return
+        try:
+            self._loop.call_soon_threadsafe(self._record, kind, os.path.abspath(path))
+        except RuntimeError:
+            # The loop is closed; we are shutting down
+            pass
+    
+    def _track(self, coro) -> asyncio.Task:
+        task = asyncio.create_task(coro)
+        self._pending_tasks.add(task)
+        task.add_done_callback(self._pending_tasks.discard)
+        return task
+    
+    def _record(self, kind: str, path: str) -> None:
+        """Coalesce an event and (re)arm the debounce timer."""
+        # Edited ignore files take effect for the next event
+        if invalidate_ignore_file(path):
+            self._track(log(f"Ignore rules changed: {path}", level="info"))
+            return
+        
+        self._changes[path] = kind
+        now = self._loop.time()
+        if self._first_event is None:
+            self._first_event = now
+        if self._timer is not None:
+            self._timer.cancel()
+        
+        # Flush once events settle, but don't let a steady stream postpone it forever
+        if len(self._changes) >= indexing_config.watch_batch_size:
+            delay = 0
+        else:
+            delay = min(
+                indexing_config.watch_debounce,
+                max(self._first_event + indexing_config.watch_max_delay - now, 0)
+            )
+        self._timer = self._loop.call_later(delay, self._schedule_flush)
+    
+    def _schedule_flush(self) -> None:
+        self._timer = None
+        self._track(self._flush())
+    
+    async def _flush(self) -> None:
+        """Pass the pending events to on_batch as one batch."""
         if not self._initialized:
             await self.ensure_initialized()
+        
+        async with self._flush_lock:
+            if not self._changes:
+                return
+            changes, self._changes = self._changes, {}
+            self._first_event = None
+            changed = sorted(path for path, kind in changes.items() if kind == CHANGED)
+            deleted = sorted(path for path, kind in changes.items() if kind == DELETED)
             
-        async with AsyncErrorBoundary("handle_file_change"):
-            try:
-                # Check if file is processable
-                if not is_processable_file(file_path):
-                    return
-                    
-                # Get normalized language
-                _, ext = os.path.splitext(file_path)
-                if not ext:
-                    return
-                    
-                language = normalize_language_name(ext[1:].lower())
-                if language not in SupportedLanguage.__args__:
-                    return
-                    
-                # Process file change
-                await self.on_change(file_path)
-            except Exception as e:
-                await log(f"Error handling file change: {e}", level="error")
+            async with AsyncErrorBoundary("handle_file_batch"):
+                try:
+                    await log(f"File events: {len(changed)} changed, {len(deleted)} deleted", level="debug")
+                    await self.on_batch(changed, deleted)
+                except Exception as e:
+                    await log(f"Error handling file batch: {e}", level="error")
     
     async def cleanup(self):
         """Clean up file handler resources."""
         try:
+            if self._timer is not None:
+                self._timer.cancel()
+                self._timer = None
+            
             # Cancel all pending tasks
             if self._pending_tasks:
                 for task in self._pending_tasks:
@@ -196,15 +275,20 @@ class DirectoryWatcher:
             await instance.cleanup()
             raise ProcessingError(f"Failed to initialize directory watcher: {e}")
     
-    async def watch_directory(self, path: str, repo_id: int, on_change: Callable[[str], None]) -> None:
-        """Watch a directory for file changes."""
+    async def watch_directory(
+        self,
+        path: str,
+        repo_id: int,
+        on_batch: Callable[[List[str], List[str]], Awaitable[Any]]
+    ) -> None:
+        """Watch a directory, passing batches of (changed, deleted) paths to on_batch."""
         if not self._initialized:
             await self.ensure_initialized()
             
         async with AsyncErrorBoundary("watch_directory"):
             try:
                 # Create and initialize handler
-                self._handler = await AsyncFileHandler.create(on_change)
+                self._handler = await AsyncFileHandler.create(on_batch)
                 
                 # Schedule and start observer
                 self._observer.schedule(self._handler, path, recursive=True)
@@ -312,56 +396,24 @@ def is_pattern_relevant_file(file_path: str) -> bool:
         return False
 
 @handle_async_errors(error_types=(Exception,))
-async def handle_file_change(file_path: str, repo_id: int):
-    """Handle a file change event."""
-    try:
-        # Get normalized language
-        _, ext = os.path.splitext(file_path)
-        if not ext:
-            return
-            
-        language = normalize_language_name(ext[1:].lower())
-        if language not in SupportedLanguage.__args__:
-            return
-            
-        # Get parser for language
-        parser = get_parser(language)
-        if not parser:
-            return
-            
-        # Read file content
-        content = await async_read_file(file_path)
-        if not content:
-            return
-            
-        # Parse content
-        tree = parser.parse(bytes(content, "utf8"))
-        if not tree:
-            return
-            
-        # Get unified parser
-        unified = await get_unified_parser()
-        
-        # Process file
-        result = await unified.process_file(
-            file_path,
-            content,
-            language,
-            tree
-        )
-        
-        if result:
-            # Update database
-            coordinator = UpsertCoordinator()
-            await coordinator.upsert_file_data(repo_id, file_path, result)
-            
-            # Update graph
-            graph_sync = await get_graph_sync()
-            await graph_sync.update_file(repo_id, file_path, result)
-            
-            await log(f"Processed changed file: {file_path}", level="info")
-    except Exception as e:
-        await log(f"Error handling file change: {e}", level="error")
+async def handle_file_batch(repo_path: str, repo_id: int, changed: List[str], deleted: List[str]) -> Optional[Dict[str, Any]]:
+    """Index a batch of file events and clean up after removed files."""
+    result = await process_file_batch(repo_path, repo_id, changed, deleted)
+    if result is None:
+        return None
+    
+    # Patterns found in removed files go with them
+    if result["removed_files"]:
+        pattern_storage = await get_pattern_storage()
+        for file_path in result["removed_files"]:
+            await pattern_storage.update_patterns_for_file(repo_id, file_path, [])
+    
+    await log(
+        f"Reindexed {result['indexed']} file(s), removed {result['removed']}, "
+        f"{result['unchanged']} unchanged, {result['failed']} failed",
+        level="info"
+    )
+    return result
 
 async def start_file_watcher(path: str = ".") -> None:
     """Start the file watcher in the current directory."""
@@ -378,13 +430,13 @@ async def start_file_watcher(path: str = ".") -> None:
             
         await log(f"Starting file watcher for repository: {repo_name} (id: {repo_id})")
         
-        # Create a handler function that creates tasks
-        def handle_change(file_path):
-            return asyncio.create_task(handle_file_change(file_path, repo_id))
+        repo_path = os.path.abspath(path)
+        async def handle_batch(changed, deleted):
+            return await handle_file_batch(repo_path, repo_id, changed, deleted)
         
         # Get directory watcher instance and start watching
         watcher = await get_directory_watcher()
-        await watcher.watch_directory(path, repo_id, handle_change)
+        await watcher.watch_directory(repo_path, repo_id, handle_batch)
 
 # Export watch_directory as an alias for start_file_watcher
 watch_directory = start_file_watcher